@@ -96,6 +96,10 @@ pub enum Subcommand {
 	/// Remove the whole chain.
 	PurgeChain(sc_cli::PurgeChainCmd),
 
+	/// Database utilities.
+	#[command(subcommand)]
+	Db(sc_cli::DbSubcommand),
+
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
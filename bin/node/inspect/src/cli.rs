@@ -59,4 +59,23 @@ pub enum InspectSubCmd {
 		#[arg(value_name = "BLOCK:INDEX or BYTES")]
 		input: String,
 	},
+	/// Walk the state at a block and print the storage usage of every pallet and storage item.
+	///
+	/// Keys are attributed using the storage entries of the runtime metadata. Keys of child tries
+	/// are attributed to the storage item whose value references the child trie.
+	Storage {
+		/// Block at which to inspect the state.
+		///
+		/// Can be either a block hash (no 0x prefix) or a number. Defaults to the best block.
+		#[arg(long, value_name = "HASH or NUMBER")]
+		at: Option<String>,
+
+		/// Number of keys with the largest values to print out.
+		#[arg(long, default_value_t = 10)]
+		top: usize,
+
+		/// Print the report as JSON instead of tables.
+		#[arg(long)]
+		json: bool,
+	},
 }
@@ -20,11 +20,14 @@
 
 use crate::{
 	cli::{InspectCmd, InspectSubCmd},
-	Inspector,
+	storage::{inspect_storage, StorageLayout},
+	BlockAddressFor, Inspector,
 };
 use sc_cli::{CliConfiguration, ImportParams, Result, SharedParams};
+use sc_client_api::{CallExecutor, ExecutionStrategy, ExecutorProvider, HeaderBackend};
 use sc_executor::NativeElseWasmExecutor;
 use sc_service::{new_full_client, Configuration, NativeExecutionDispatch};
+use sp_core::traits::CallContext;
 use sp_runtime::traits::Block;
 use std::str::FromStr;
 
@@ -45,21 +48,54 @@ impl InspectCmd {
 		);
 
 		let client = new_full_client::<B, RA, _>(&config, None, executor)?;
-		let inspect = Inspector::<B>::new(client);
 
 		match &self.command {
 			InspectSubCmd::Block { input } => {
 				let input = input.parse()?;
-				let res = inspect.block(input).map_err(|e| format!("{}", e))?;
+				let res = Inspector::<B>::new(client).block(input).map_err(|e| format!("{}", e))?;
 				println!("{}", res);
 				Ok(())
 			},
 			InspectSubCmd::Extrinsic { input } => {
 				let input = input.parse()?;
-				let res = inspect.extrinsic(input).map_err(|e| format!("{}", e))?;
+				let res =
+					Inspector::<B>::new(client).extrinsic(input).map_err(|e| format!("{}", e))?;
 				println!("{}", res);
 				Ok(())
 			},
+			InspectSubCmd::Storage { at, top, json } => {
+				let hash = match at {
+					Some(at) => match at.parse::<BlockAddressFor<B>>()? {
+						BlockAddressFor::<B>::Hash(hash) => hash,
+						BlockAddressFor::<B>::Number(number) => client
+							.hash(number)?
+							.ok_or_else(|| format!("Could not find block {:?}", number))?,
+						BlockAddressFor::<B>::Bytes(_) =>
+							return Err("Expected a block hash or number for --at".into()),
+					},
+					None => client.info().best_hash,
+				};
+
+				let metadata = client.executor().call(
+					hash,
+					"Metadata_metadata",
+					&[],
+					ExecutionStrategy::NativeElseWasm,
+					CallContext::Offchain,
+				)?;
+				let layout = StorageLayout::from_metadata(&metadata).map_err(|e| e.to_string())?;
+				let state = client.state_at(hash)?;
+				let report = inspect_storage(&state, &layout, *top).map_err(|e| e.to_string())?;
+
+				if *json {
+					let json = serde_json::to_string_pretty(&report)
+						.map_err(|e| format!("Could not serialize the report: {}", e))?;
+					println!("{}", json);
+				} else {
+					println!("{}", report);
+				}
+				Ok(())
+			},
 		}
 	}
 }
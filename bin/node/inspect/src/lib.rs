@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! A CLI extension for substrate node, adding sub-command to pretty print debug info
-//! about blocks and extrinsics.
+//! about blocks, extrinsics and the storage layout.
 //!
 //! The blocks and extrinsics can either be retrieved from the database (on-chain),
 //! or a raw SCALE-encoding can be provided.
@@ -26,6 +26,7 @@
 
 pub mod cli;
 pub mod command;
+pub mod storage;
 
 use codec::{Decode, Encode};
 use sc_client_api::BlockBackend;
@@ -89,6 +90,12 @@ pub enum Error {
 	/// Given block has not been found.
 	#[error("{0}")]
 	NotFound(String),
+	/// The runtime metadata is not supported.
+	#[error("Invalid runtime metadata: {0}")]
+	Metadata(String),
+	/// Error walking the state trie.
+	#[error("Trie error: {0}")]
+	Trie(String),
 }
 
 /// A helper trait to access block headers and bodies.
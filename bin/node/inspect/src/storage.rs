@@ -0,0 +1,525 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Inspection of the storage layout of a runtime.
+//!
+//! Every key of the state is attributed to a pallet and storage item by matching it against the
+//! hashed prefixes of the storage entries in the runtime metadata. The keys of a child trie are
+//! attributed to the storage item whose value references the child trie, either by its storage key
+//! or by its root.
+
+use crate::Error;
+use codec::{Codec, Decode};
+use comfy_table::Table;
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use hash_db::HashDBRef;
+use serde::Serialize;
+use sp_core::{
+	hashing::twox_128,
+	hexdisplay::HexDisplay,
+	storage::{
+		well_known_keys::{is_default_child_storage_key, DEFAULT_CHILD_STORAGE_KEY_PREFIX},
+		ChildInfo,
+	},
+	Hasher,
+};
+use sp_state_machine::backend::AsTrieBackend;
+use sp_trie::{
+	DBValue, HashDBT, KeySpacedDB, LayoutV1, NodePlan, Trie, TrieDBBuilder, TrieDBNodeIterator,
+	ValuePlan,
+};
+use std::{
+	cmp::Reverse,
+	collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap},
+	fmt,
+};
+use trie_db::node::NodeHandlePlan;
+
+/// Pseudo pallet of well-known keys, such as `:code`.
+const WELL_KNOWN: &str = "<well-known>";
+/// Pseudo pallet of child tries that are not referenced by any storage item.
+const CHILD_TRIE: &str = "<child trie>";
+/// Child storage keys shorter than this are too ambiguous to be searched for in values.
+const MIN_REFERENCE_LEN: usize = 16;
+/// Pseudo pallet and item of trie nodes that are shared between multiple storage items.
+const SHARED: &str = "<shared>";
+/// Pseudo pallet and item of keys that are not in the metadata.
+const UNKNOWN: &str = "<unknown>";
+
+/// Maps hashed storage prefixes to the names of their pallet and storage item.
+#[derive(Debug, Default)]
+pub struct StorageLayout {
+	pallets: HashMap<[u8; 16], String>,
+	items: HashMap<[u8; 32], (String, String)>,
+}
+
+impl StorageLayout {
+	/// Create a new layout from `(pallet prefix, storage item name)` pairs.
+	pub fn new(items: impl IntoIterator<Item = (String, String)>) -> Self {
+		let mut layout = Self::default();
+		for (pallet, item) in items {
+			let pallet_prefix = twox_128(pallet.as_bytes());
+			let mut item_prefix = [0u8; 32];
+			item_prefix[..16].copy_from_slice(&pallet_prefix);
+			item_prefix[16..].copy_from_slice(&twox_128(item.as_bytes()));
+
+			layout.pallets.insert(pallet_prefix, pallet.clone());
+			layout.items.insert(item_prefix, (pallet, item));
+		}
+		layout
+	}
+
+	/// Create a new layout from the SCALE encoded result of `Metadata_metadata`.
+	pub fn from_metadata(mut encoded: &[u8]) -> Result<Self, Error> {
+		let bytes = Vec::<u8>::decode(&mut encoded)?;
+		let metadata = RuntimeMetadataPrefixed::decode(&mut &bytes[..])?;
+		let pallets = match metadata.1 {
+			RuntimeMetadata::V14(metadata) => metadata.pallets,
+			_ => return Err(Error::Metadata("only metadata V14 is supported".into())),
+		};
+
+		Ok(Self::new(pallets.into_iter().filter_map(|p| p.storage).flat_map(|storage| {
+			let prefix = storage.prefix;
+			storage.entries.into_iter().map(move |entry| (prefix.clone(), entry.name))
+		})))
+	}
+
+	/// The pallet and storage item of `key`.
+	fn attribute(&self, key: &[u8]) -> (String, String) {
+		if let Some(names) = self.lookup(key) {
+			return names
+		}
+		if key.starts_with(b":") {
+			return (WELL_KNOWN.into(), String::from_utf8_lossy(key).into_owned())
+		}
+		(UNKNOWN.into(), UNKNOWN.into())
+	}
+
+	/// The pallet and storage item of a trie node at `path`, as far as it is known.
+	fn attribute_path(&self, path: &[u8]) -> (String, String) {
+		self.lookup(path).unwrap_or_else(|| (SHARED.into(), SHARED.into()))
+	}
+
+	/// The pallet and storage item of the prefixes of `key`.
+	fn lookup(&self, key: &[u8]) -> Option<(String, String)> {
+		if let Some(names) = key.get(..32).and_then(|prefix| self.items.get(prefix)) {
+			return Some(names.clone())
+		}
+		let pallet = key.get(..16).and_then(|prefix| self.pallets.get(prefix))?;
+		let item = if key.len() >= 32 { UNKNOWN } else { SHARED };
+		Some((pallet.clone(), item.into()))
+	}
+}
+
+/// The child tries of a state and the storage items they belong to.
+struct ChildTries {
+	/// The storage key, root and owning storage item of every child trie.
+	tries: Vec<(Vec<u8>, Vec<u8>, (String, String))>,
+	/// Maps the storage keys and roots to the index of their child trie.
+	references: HashMap<Vec<u8>, usize>,
+}
+
+impl ChildTries {
+	/// Collect the child tries of the trie at `root` and attribute each of them to the first
+	/// storage item whose value contains its storage key or root.
+	///
+	/// Child tries that are not referenced by any value are grouped under
+	/// `:child_storage:default:`.
+	fn collect<H>(
+		db: &dyn HashDBRef<H, DBValue>,
+		root: &H::Out,
+		layout: &StorageLayout,
+	) -> Result<Self, Error>
+	where
+		H: Hasher,
+		H::Out: Codec + Ord,
+	{
+		let trie = TrieDBBuilder::<LayoutV1<H>>::new(db, root).build();
+		let unreferenced = || {
+			(
+				CHILD_TRIE.to_string(),
+				String::from_utf8_lossy(DEFAULT_CHILD_STORAGE_KEY_PREFIX).into(),
+			)
+		};
+
+		let mut child_tries = Self { tries: Vec::new(), references: HashMap::new() };
+		let mut iter = trie.iter().map_err(|e| Error::Trie(e.to_string()))?;
+		iter.seek(DEFAULT_CHILD_STORAGE_KEY_PREFIX)
+			.map_err(|e| Error::Trie(e.to_string()))?;
+		for item in iter {
+			let (key, root) = item.map_err(|e| Error::Trie(e.to_string()))?;
+			if !is_default_child_storage_key(&key) {
+				break
+			}
+			let storage_key = key[DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..].to_vec();
+			let index = child_tries.tries.len();
+			if storage_key.len() >= MIN_REFERENCE_LEN {
+				child_tries.references.insert(storage_key.clone(), index);
+			}
+			child_tries.references.insert(root.clone(), index);
+			child_tries.tries.push((storage_key, root, unreferenced()));
+		}
+		if child_tries.tries.is_empty() {
+			return Ok(child_tries)
+		}
+
+		let lengths = child_tries.references.keys().map(Vec::len).collect::<BTreeSet<_>>();
+		let mut owned = vec![false; child_tries.tries.len()];
+		for item in trie.iter().map_err(|e| Error::Trie(e.to_string()))? {
+			let (key, value) = item.map_err(|e| Error::Trie(e.to_string()))?;
+			if is_default_child_storage_key(&key) {
+				continue
+			}
+			for len in &lengths {
+				for window in value.windows(*len) {
+					match child_tries.references.get(window) {
+						Some(&index) if !owned[index] => {
+							owned[index] = true;
+							child_tries.tries[index].2 = layout.attribute(&key);
+						},
+						_ => (),
+					}
+				}
+			}
+		}
+
+		Ok(child_tries)
+	}
+
+	/// The pallet and storage item of the child trie whose root is stored at `key` of the top
+	/// trie, if `key` is the root of a child trie.
+	fn attribute_root(&self, key: &[u8]) -> Option<(String, String)> {
+		let storage_key = key.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX)?;
+		self.tries
+			.iter()
+			.find(|(k, _, _)| k == storage_key)
+			.map(|(_, _, names)| names.clone())
+	}
+}
+
+/// The storage usage of a single storage item.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ItemStats {
+	/// The pallet of the storage item.
+	pub pallet: String,
+	/// The name of the storage item.
+	pub item: String,
+	/// The number of keys.
+	pub keys: u64,
+	/// The total size of all keys in bytes.
+	pub key_bytes: u64,
+	/// The total size of all values in bytes.
+	pub value_bytes: u64,
+	/// The size of all trie nodes on the path to the values, excluding the values themselves.
+	pub trie_overhead: u64,
+}
+
+/// A single key and the size of its value.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct KeyStats {
+	/// The size of the value in bytes.
+	pub value_bytes: u64,
+	/// The pallet of the key.
+	pub pallet: String,
+	/// The storage item of the key.
+	pub item: String,
+	/// The hex encoded key.
+	pub key: String,
+	/// The hex encoded storage key of the child trie containing the key, if any.
+	pub child_trie: Option<String>,
+}
+
+/// The storage usage of a state, per pallet and storage item.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageReport {
+	/// The usage per storage item, the largest first.
+	pub items: Vec<ItemStats>,
+	/// The keys with the largest values, the largest first.
+	pub largest_keys: Vec<KeyStats>,
+}
+
+/// Walk the entire state, including all child tries, and collect its storage usage.
+///
+/// The `largest` keys with the biggest values are included in the report.
+pub fn inspect_storage<H, S>(
+	state: &S,
+	layout: &StorageLayout,
+	largest: usize,
+) -> Result<StorageReport, Error>
+where
+	H: Hasher,
+	H::Out: Codec + Ord,
+	S: AsTrieBackend<H>,
+{
+	let backend = state.as_trie_backend();
+	let db: &dyn HashDBT<H, DBValue> = backend.essence();
+	let mut collector =
+		Collector { items: BTreeMap::new(), largest_keys: BinaryHeap::new(), largest };
+
+	let child_tries = ChildTries::collect(&db, backend.root(), layout)?;
+	// The root of a child trie is attributed like the keys of the child trie itself.
+	let attribute = |key: &[u8], complete: bool| {
+		if !complete {
+			layout.attribute_path(key)
+		} else {
+			child_tries.attribute_root(key).unwrap_or_else(|| layout.attribute(key))
+		}
+	};
+	walk_trie(&db, backend.root(), attribute, None, &mut collector)?;
+
+	for (storage_key, root, names) in &child_tries.tries {
+		let root = H::Out::decode(&mut &root[..])?;
+		let child_info = ChildInfo::new_default(storage_key);
+		let child_db = KeySpacedDB::new(&db, child_info.keyspace());
+		walk_trie(&child_db, &root, |_, _| names.clone(), Some(storage_key), &mut collector)?;
+	}
+
+	let mut items = collector.items.into_values().collect::<Vec<_>>();
+	items.sort_by_key(|i| Reverse(i.value_bytes + i.trie_overhead));
+	let mut largest_keys =
+		collector.largest_keys.into_iter().map(|Reverse(key)| key).collect::<Vec<_>>();
+	largest_keys.sort_by(|a, b| b.cmp(a));
+
+	Ok(StorageReport { items, largest_keys })
+}
+
+/// Accumulates the statistics while walking the tries.
+struct Collector {
+	items: BTreeMap<(String, String), ItemStats>,
+	/// Min-heap of the largest keys seen so far.
+	largest_keys: BinaryHeap<Reverse<KeyStats>>,
+	largest: usize,
+}
+
+impl Collector {
+	fn item(&mut self, (pallet, item): (String, String)) -> &mut ItemStats {
+		self.items.entry((pallet.clone(), item.clone())).or_insert_with(|| ItemStats {
+			pallet,
+			item,
+			..Default::default()
+		})
+	}
+
+	fn add_key(&mut self, names: (String, String), key: &[u8], value: &[u8], child: Option<&[u8]>) {
+		let stats = self.item(names.clone());
+		stats.keys += 1;
+		stats.key_bytes += key.len() as u64;
+		stats.value_bytes += value.len() as u64;
+
+		if self.largest == 0 {
+			return
+		}
+		let is_larger = self
+			.largest_keys
+			.peek()
+			.map_or(true, |Reverse(smallest)| smallest.value_bytes < value.len() as u64);
+		if self.largest_keys.len() < self.largest || is_larger {
+			self.largest_keys.push(Reverse(KeyStats {
+				value_bytes: value.len() as u64,
+				pallet: names.0,
+				item: names.1,
+				key: format!("0x{}", HexDisplay::from(&key)),
+				child_trie: child.map(|c| format!("0x{}", HexDisplay::from(&c))),
+			}));
+			if self.largest_keys.len() > self.largest {
+				self.largest_keys.pop();
+			}
+		}
+	}
+}
+
+/// Walk all keys and nodes of the trie at `root`, attributing them with `attribute`.
+///
+/// `attribute` is called with either a complete key or the path to a trie node.
+fn walk_trie<H>(
+	db: &dyn HashDBRef<H, DBValue>,
+	root: &H::Out,
+	attribute: impl Fn(&[u8], bool) -> (String, String),
+	child: Option<&[u8]>,
+	collector: &mut Collector,
+) -> Result<(), Error>
+where
+	H: Hasher,
+	H::Out: Codec + Ord,
+{
+	let trie = TrieDBBuilder::<LayoutV1<H>>::new(db, root).build();
+
+	for item in trie.iter().map_err(|e| Error::Trie(e.to_string()))? {
+		let (key, value) = item.map_err(|e| Error::Trie(e.to_string()))?;
+		collector.add_key(attribute(&key, true), &key, &value, child);
+	}
+
+	for node in TrieDBNodeIterator::new(&trie).map_err(|e| Error::Trie(e.to_string()))? {
+		let (mut path, _, node) = node.map_err(|e| Error::Trie(e.to_string()))?;
+		let data = node.data();
+		// Inline children are visited on their own, so only the bytes of this node that are
+		// neither an inline child nor an inline value count as its overhead.
+		let inline_len = |child: &NodeHandlePlan| match child {
+			NodeHandlePlan::Inline(range) => range.len(),
+			NodeHandlePlan::Hash(_) => 0,
+		};
+		let inline_children = |children: &[Option<NodeHandlePlan>]| {
+			children.iter().flatten().map(inline_len).sum::<usize>()
+		};
+		let (partial, value, inline_children) = match node.node_plan() {
+			NodePlan::Leaf { partial, value } => (Some(partial), Some(value), 0),
+			NodePlan::NibbledBranch { partial, value, children } =>
+				(Some(partial), value.as_ref(), inline_children(children)),
+			NodePlan::Extension { partial, child } => (Some(partial), None, inline_len(child)),
+			NodePlan::Branch { value, children } =>
+				(None, value.as_ref(), inline_children(children)),
+			NodePlan::Empty => (None, None, 0),
+		};
+		if let Some(partial) = partial {
+			path.append_partial(partial.build(data).right());
+		}
+
+		let inline_value = match value {
+			Some(ValuePlan::Inline(range)) => range.len(),
+			_ => 0,
+		};
+		let overhead = data.len().saturating_sub(inline_children + inline_value);
+
+		let (key, complete) = path.as_prefix();
+		let names = attribute(key, value.is_some() && complete.is_none());
+		collector.item(names).trie_overhead += overhead as u64;
+	}
+
+	Ok(())
+}
+
+impl fmt::Display for StorageReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut table = Table::new();
+		table.set_header([
+			"Pallet",
+			"Item",
+			"Keys",
+			"Key bytes",
+			"Value bytes",
+			"Trie overhead",
+			"Total bytes",
+		]);
+		for item in &self.items {
+			table.add_row([
+				item.pallet.clone(),
+				item.item.clone(),
+				item.keys.to_string(),
+				item.key_bytes.to_string(),
+				item.value_bytes.to_string(),
+				item.trie_overhead.to_string(),
+				(item.value_bytes + item.trie_overhead).to_string(),
+			]);
+		}
+		writeln!(f, "{}", table)?;
+
+		if !self.largest_keys.is_empty() {
+			let mut table = Table::new();
+			table.set_header(["Pallet", "Item", "Key", "Child trie", "Value bytes"]);
+			for key in &self.largest_keys {
+				table.add_row([
+					key.pallet.clone(),
+					key.item.clone(),
+					key.key.clone(),
+					key.child_trie.clone().unwrap_or_default(),
+					key.value_bytes.to_string(),
+				]);
+			}
+			writeln!(f, "Largest keys:\n{}", table)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{storage::ChildInfo, Blake2Hasher};
+	use sp_runtime::StateVersion;
+
+	fn item_key(pallet: &str, item: &str, suffix: &[u8]) -> Vec<u8> {
+		[&twox_128(pallet.as_bytes())[..], &twox_128(item.as_bytes())[..], suffix].concat()
+	}
+
+	fn stats<'a>(report: &'a StorageReport, pallet: &str, item: &str) -> &'a ItemStats {
+		report
+			.items
+			.iter()
+			.find(|i| i.pallet == pallet && i.item == item)
+			.unwrap_or_else(|| panic!("{} {} not found", pallet, item))
+	}
+
+	#[test]
+	fn keys_are_attributed_to_storage_items() {
+		let layout = StorageLayout::new([
+			("System".to_string(), "Account".to_string()),
+			("System".to_string(), "Number".to_string()),
+			("Contracts".to_string(), "ContractInfoOf".to_string()),
+		]);
+		// Child tries are usually keyed by an id that the owning storage item stores.
+		let trie_id = [7; 32];
+		let child_info = ChildInfo::new_default(&trie_id);
+		let contract_info = [&[0x80][..], &trie_id, &[0; 8]].concat();
+		let orphan = ChildInfo::new_default(b"orphan");
+		let state = sp_state_machine::new_in_mem::<Blake2Hasher, sp_trie::PrefixedKey<_>>();
+		let state = state.update(
+			vec![
+				(
+					None,
+					vec![
+						(b":code".to_vec(), Some(vec![0; 100])),
+						(item_key("System", "Account", &[1; 32]), Some(vec![1; 80])),
+						(item_key("System", "Account", &[2; 32]), Some(vec![2; 10])),
+						(item_key("System", "Number", &[]), Some(vec![3; 4])),
+						(item_key("System", "Removed", &[]), Some(vec![4; 4])),
+						(b"unknown".to_vec(), Some(vec![5; 1])),
+						(
+							item_key("Contracts", "ContractInfoOf", &[1; 32]),
+							Some(contract_info.clone()),
+						),
+					],
+				),
+				(Some(child_info), vec![(b"child".to_vec(), Some(vec![6; 50]))]),
+				(Some(orphan), vec![(b"orphan".to_vec(), Some(vec![7; 5]))]),
+			],
+			StateVersion::V1,
+		);
+
+		let report = inspect_storage(&state, &layout, 2).unwrap();
+
+		let account = stats(&report, "System", "Account");
+		assert_eq!((account.keys, account.key_bytes, account.value_bytes), (2, 128, 90));
+		assert!(account.trie_overhead > 0);
+		assert_eq!(stats(&report, "System", "Number").value_bytes, 4);
+		assert_eq!(stats(&report, "System", UNKNOWN).value_bytes, 4);
+		assert_eq!(stats(&report, WELL_KNOWN, ":code").value_bytes, 100);
+		assert_eq!(stats(&report, UNKNOWN, UNKNOWN).value_bytes, 1);
+		// The child trie is attributed to the item referencing its storage key, together with the
+		// child root in the top trie.
+		let contracts = stats(&report, "Contracts", "ContractInfoOf");
+		assert_eq!(
+			(contracts.keys, contracts.value_bytes),
+			(3, contract_info.len() as u64 + 50 + 32)
+		);
+		let orphans = stats(&report, CHILD_TRIE, ":child_storage:default:");
+		assert_eq!((orphans.keys, orphans.value_bytes), (2, 5 + 32));
+
+		let largest = report.largest_keys.iter().map(|k| k.value_bytes).collect::<Vec<_>>();
+		assert_eq!(largest, vec![100, 80]);
+	}
+}
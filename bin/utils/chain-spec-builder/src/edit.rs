@@ -0,0 +1,319 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Operations on existing chain specs of any runtime.
+//!
+//! Chain specs are edited as JSON and their genesis is built as a [`GenericChainSpec`] with a JSON
+//! genesis config, so none of these operations needs the native runtime the chain spec was
+//! created with. Instead, the genesis storage is built through the `GenesisBuilder` api of the
+//! runtime embedded in the chain spec. All other fields, including any chain spec extensions, are
+//! kept as they are.
+
+use std::{fs, path::Path};
+
+use sc_chain_spec::{json_merge, GenericChainSpec, GenesisConfigBuilderRuntimeCaller};
+use sc_executor::WasmExecutor;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sp_core::{
+	bytes::{from_hex, to_hex},
+	storage::{well_known_keys, Storage, StorageChild},
+	traits::Externalities,
+};
+use sp_runtime::BuildStorage;
+use sp_state_machine::BasicExternalities;
+
+/// The genesis config of a runtime that is unknown to this tool.
+///
+/// It is edited as JSON, and its genesis storage is built by the `GenesisBuilder` api of the
+/// runtime code it contains in `system.code`, as FRAME runtimes do.
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UnknownGenesis(Value);
+
+impl BuildStorage for UnknownGenesis {
+	fn assimilate_storage(&self, storage: &mut Storage) -> Result<(), String> {
+		let code = self
+			.0
+			.get("system")
+			.and_then(|system| system.get("code"))
+			.and_then(Value::as_str)
+			.ok_or("The genesis config has no runtime code in `system.code` to build it with")?;
+		let code = from_hex(code).map_err(|e| format!("Invalid runtime code: {}", e))?;
+
+		let built = GenesisConfigBuilderRuntimeCaller::new(&code)
+			.get_storage_for_config(self.0.clone())
+			.map_err(|e| {
+				format!(
+					"The genesis config can only be built by a runtime implementing the \
+					 `GenesisBuilder` api: {}",
+					e
+				)
+			})?;
+		storage.top.extend(built.top);
+		for (key, child) in built.children_default {
+			storage
+				.children_default
+				.entry(key)
+				.or_insert_with(|| StorageChild {
+					data: Default::default(),
+					child_info: child.child_info,
+				})
+				.data
+				.extend(child.data);
+		}
+		storage.top.insert(well_known_keys::CODE.to_vec(), code);
+		Ok(())
+	}
+}
+
+/// A chain spec of any runtime, only used to build its genesis.
+type ChainSpec = GenericChainSpec<UnknownGenesis>;
+
+/// Convert `spec` to the raw format.
+pub fn convert_to_raw(spec: Value) -> Result<String, String> {
+	with_built_genesis(spec, true)
+}
+
+/// Merge `patch` into the genesis config of `spec`.
+///
+/// The patch is applied as a JSON merge patch, see [`json_merge`]. Raw chain specs cannot be
+/// patched since their genesis config is not known anymore.
+pub fn update_genesis(mut spec: Value, patch: Value) -> Result<String, String> {
+	let genesis = genesis_mut(&mut spec)?;
+	let config = if let Some(config) = genesis.get_mut("runtime") {
+		config
+	} else if let Some(Value::Object(inner)) = genesis.get_mut("runtimeGenesis") {
+		match inner.contains_key("config") {
+			true => inner.get_mut("config"),
+			false => inner.get_mut("patch"),
+		}
+		.ok_or("The `runtimeGenesis` has neither a `config` nor a `patch`")?
+	} else {
+		return Err("Only chain specs with a `runtime` or `runtimeGenesis` genesis config can be \
+					patched, patch the chain spec before converting it to raw"
+			.into())
+	};
+
+	json_merge(config, patch);
+	with_built_genesis(spec, false)
+}
+
+/// Replace the runtime code of `spec` with `code`.
+///
+/// A native genesis config is expected to keep the code in `system.code`, as FRAME runtimes do.
+pub fn update_code(mut spec: Value, code: &[u8]) -> Result<String, String> {
+	let code = Value::String(to_hex(code, false));
+	let genesis = genesis_mut(&mut spec)?;
+	let code_slot = if let Some(Value::Object(raw)) = genesis.get_mut("raw") {
+		raw.get_mut("top")
+			.and_then(Value::as_object_mut)
+			.map(|top| top.entry(to_hex(well_known_keys::CODE, false)).or_insert(Value::Null))
+	} else if let Some(Value::Object(inner)) = genesis.get_mut("runtimeGenesis") {
+		inner.get_mut("code")
+	} else if let Some(Value::Object(config)) = genesis.get_mut("runtime") {
+		config
+			.get_mut("system")
+			.and_then(Value::as_object_mut)
+			.and_then(|s| s.get_mut("code"))
+	} else {
+		None
+	};
+
+	*code_slot.ok_or("The chain spec has no runtime code to replace")? = code;
+	with_built_genesis(spec, false)
+}
+
+/// Verify that the genesis storage of the raw chain spec `raw` is built by its runtime.
+///
+/// The storage is built by calling the `GenesisBuilder` api of the runtime in `raw` with the
+/// genesis config of `source`, or with the default genesis config of the runtime if there is no
+/// `source`. Returns the genesis state root on success.
+pub fn verify(raw: &Value, source: Option<&Value>) -> Result<String, String> {
+	if genesis(raw)?.get("raw").is_none() {
+		return Err("Only raw chain specs can be verified".into())
+	}
+	let raw_storage = genesis_chain_spec(raw)?.build_storage()?;
+	let code = raw_storage
+		.top
+		.get(well_known_keys::CODE)
+		.ok_or("The raw chain spec has no runtime code")?;
+
+	let caller = GenesisConfigBuilderRuntimeCaller::new(code);
+	let mut built_storage = match source.map(genesis).transpose()? {
+		None => caller.get_storage_for_patch(json!({})),
+		Some(genesis) => match (genesis.get("runtime"), genesis.get("runtimeGenesis")) {
+			(Some(config), _) => caller.get_storage_for_config(config.clone()),
+			(None, Some(Value::Object(inner))) => match (inner.get("config"), inner.get("patch")) {
+				(Some(config), _) => caller.get_storage_for_config(config.clone()),
+				(None, Some(patch)) => caller.get_storage_for_patch(patch.clone()),
+				(None, None) =>
+					Err("The `runtimeGenesis` has neither a `config` nor a `patch`".into()),
+			},
+			_ => Err("The source chain spec must have a `runtime` or `runtimeGenesis` genesis \
+					  config"
+				.into()),
+		},
+	}?;
+	built_storage.top.insert(well_known_keys::CODE.to_vec(), code.clone());
+
+	let executor = WasmExecutor::<sp_io::SubstrateHostFunctions>::builder(Default::default())
+		.with_allow_missing_host_functions(true)
+		.build();
+	let state_version = sc_chain_spec::resolve_state_version_from_wasm(&raw_storage, &executor)
+		.map_err(|e| e.to_string())?;
+	let raw_root = BasicExternalities::new(raw_storage).storage_root(state_version);
+	let built_root = BasicExternalities::new(built_storage).storage_root(state_version);
+
+	if raw_root != built_root {
+		return Err(format!(
+			"Genesis state root mismatch: the raw chain spec has {}, but its runtime builds {}",
+			to_hex(&raw_root, false),
+			to_hex(&built_root, false),
+		))
+	}
+	Ok(to_hex(&raw_root, false))
+}
+
+/// Read the JSON file at `path`.
+pub fn read_json(path: &Path) -> Result<Value, String> {
+	let bytes =
+		fs::read(path).map_err(|e| format!("Error reading file `{}`: {}", path.display(), e))?;
+	serde_json::from_slice(&bytes)
+		.map_err(|e| format!("Error parsing file `{}`: {}", path.display(), e))
+}
+
+/// A chain spec with the genesis of `spec` and nothing else.
+fn genesis_chain_spec(spec: &Value) -> Result<ChainSpec, String> {
+	let genesis = genesis(spec)?;
+	let json = json!({ "name": "", "id": "", "bootNodes": [], "genesis": genesis });
+	ChainSpec::from_json_bytes(to_vec(&json)?)
+}
+
+/// Check that the genesis of `spec` is valid and return `spec` with its genesis in the canonical
+/// format, or in the raw format if `raw` is set.
+fn with_built_genesis(mut spec: Value, raw: bool) -> Result<String, String> {
+	let built = genesis_chain_spec(&spec)?.as_json(raw)?;
+	let mut built: Value = serde_json::from_str(&built)
+		.map_err(|e| format!("Error parsing generated spec json: {}", e))?;
+	spec["genesis"] = built["genesis"].take();
+	serde_json::to_string_pretty(&spec).map_err(|e| format!("Error generating spec json: {}", e))
+}
+
+fn to_vec(spec: &Value) -> Result<Vec<u8>, String> {
+	serde_json::to_vec(spec).map_err(|e| format!("Error generating spec json: {}", e))
+}
+
+fn genesis(spec: &Value) -> Result<&Map<String, Value>, String> {
+	spec.get("genesis")
+		.and_then(Value::as_object)
+		.ok_or_else(|| "The chain spec has no `genesis`".into())
+}
+
+fn genesis_mut(spec: &mut Value) -> Result<&mut Map<String, Value>, String> {
+	spec.get_mut("genesis")
+		.and_then(Value::as_object_mut)
+		.ok_or_else(|| "The chain spec has no `genesis`".into())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn spec(genesis: Value) -> Value {
+		json!({
+			"name": "Test",
+			"id": "test",
+			"chainType": "Live",
+			"bootNodes": [],
+			"telemetryEndpoints": null,
+			"protocolId": null,
+			"properties": null,
+			"relayChain": "rococo",
+			"codeSubstitutes": {},
+			"genesis": genesis,
+		})
+	}
+
+	fn parse(json: Result<String, String>) -> Value {
+		serde_json::from_str(&json.unwrap()).unwrap()
+	}
+
+	#[test]
+	fn update_genesis_merges_patch_and_keeps_extensions() {
+		let native = spec(json!({ "runtime": { "balances": { "balances": [] }, "sudo": {} } }));
+		let patched = parse(update_genesis(native, json!({ "balances": { "balances": [1] } })));
+		assert_eq!(
+			patched["genesis"]["runtime"],
+			json!({ "balances": { "balances": [1] }, "sudo": {} })
+		);
+		assert_eq!(patched["relayChain"], "rococo");
+
+		let runtime_genesis = spec(json!({ "runtimeGenesis": { "code": "0x00", "patch": {} } }));
+		let patched = parse(update_genesis(runtime_genesis, json!({ "sudo": { "key": "a" } })));
+		assert_eq!(
+			patched["genesis"]["runtimeGenesis"]["patch"],
+			json!({ "sudo": { "key": "a" } })
+		);
+
+		let raw = spec(json!({ "raw": { "top": {}, "childrenDefault": {} } }));
+		assert!(update_genesis(raw, json!({})).is_err());
+	}
+
+	#[test]
+	fn update_code_replaces_code() {
+		let raw =
+			spec(json!({ "raw": { "top": { "0x3a636f6465": "0x00" }, "childrenDefault": {} } }));
+		let updated = parse(update_code(raw, &[1, 2]));
+		assert_eq!(updated["genesis"]["raw"]["top"]["0x3a636f6465"], "0x0102");
+
+		let native = spec(json!({ "runtime": { "system": { "code": "0x00" } } }));
+		let updated = parse(update_code(native, &[1, 2]));
+		assert_eq!(updated["genesis"]["runtime"]["system"]["code"], "0x0102");
+
+		let native = spec(json!({ "runtime": { "balances": {} } }));
+		assert!(update_code(native, &[1, 2]).is_err());
+	}
+
+	#[test]
+	fn convert_to_raw_builds_native_genesis_with_embedded_runtime() {
+		let err = convert_to_raw(spec(json!({ "runtime": { "balances": {} } }))).unwrap_err();
+		assert!(err.contains("system.code"), "{}", err);
+
+		// The test runtime does not know the `system` config, so building it fails in the runtime.
+		let code = to_hex(substrate_test_runtime::wasm_binary_unwrap(), false);
+		let native = spec(json!({ "runtime": { "system": { "code": code } } }));
+		let err = convert_to_raw(native).unwrap_err();
+		assert!(err.contains("`GenesisBuilder` api"), "{}", err);
+	}
+
+	#[test]
+	fn verify_works() {
+		let code = substrate_test_runtime::wasm_binary_unwrap();
+		let patch = json!({ "balances": [[vec![1u8; 32], 100]] });
+		let source =
+			spec(json!({ "runtimeGenesis": { "code": to_hex(code, false), "patch": patch } }));
+		let raw = parse(convert_to_raw(source.clone()));
+		assert_eq!(raw["relayChain"], "rococo");
+
+		assert!(verify(&raw, Some(&source)).is_ok());
+		let err = verify(&raw, None).unwrap_err();
+		assert!(err.contains("Genesis state root mismatch"), "{}", err);
+		assert!(verify(&source, None).is_err());
+	}
+}
@@ -16,6 +16,8 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+mod edit;
+
 use std::{
 	fs,
 	path::{Path, PathBuf},
@@ -35,6 +37,8 @@ use sp_keystore::KeystorePtr;
 
 /// A utility to easily create a testnet chain spec definition with a given set
 /// of authorities and endowed accounts and/or generate random accounts.
+///
+/// Existing chain specs of any runtime can be converted to raw, edited and verified.
 #[derive(Parser)]
 #[command(rename_all = "kebab-case")]
 enum ChainSpecBuilder {
@@ -83,14 +87,65 @@ enum ChainSpecBuilder {
 		#[arg(long, short)]
 		keystore_path: Option<PathBuf>,
 	},
+	/// Convert an existing chain spec to the raw format.
+	///
+	/// The genesis storage is built by the runtime embedded in the chain spec.
+	ConvertToRaw {
+		/// The chain spec to convert.
+		input_chain_spec: PathBuf,
+		/// The path where the raw chain spec should be saved.
+		#[arg(long, short, default_value = "./chain_spec.json")]
+		chain_spec_path: PathBuf,
+	},
+	/// Patch the genesis config of an existing chain spec.
+	///
+	/// The patch is merged as a JSON merge patch: objects are merged recursively, any other value
+	/// replaces the existing one and `null` removes a field. Raw chain specs cannot be patched.
+	UpdateGenesis {
+		/// The chain spec to patch.
+		input_chain_spec: PathBuf,
+		/// The JSON file containing the patch.
+		#[arg(long, short)]
+		patch_path: PathBuf,
+		/// The path where the patched chain spec should be saved.
+		#[arg(long, short, default_value = "./chain_spec.json")]
+		chain_spec_path: PathBuf,
+	},
+	/// Replace the runtime code embedded in an existing chain spec.
+	UpdateCode {
+		/// The chain spec to update.
+		input_chain_spec: PathBuf,
+		/// The wasm blob of the new runtime.
+		#[arg(long, short)]
+		runtime_wasm_path: PathBuf,
+		/// The path where the updated chain spec should be saved.
+		#[arg(long, short, default_value = "./chain_spec.json")]
+		chain_spec_path: PathBuf,
+	},
+	/// Verify that the genesis state of a raw chain spec is built by its embedded runtime.
+	///
+	/// The genesis storage is built through the `GenesisBuilder` runtime api, either from the
+	/// genesis config of the `--source` chain spec or from the default genesis config of the
+	/// runtime. Its state root must match the one of the raw genesis storage.
+	Verify {
+		/// The raw chain spec to verify.
+		input_chain_spec: PathBuf,
+		/// The chain spec the raw chain spec was created from.
+		#[arg(long, short)]
+		source: Option<PathBuf>,
+	},
 }
 
 impl ChainSpecBuilder {
-	/// Returns the path where the chain spec should be saved.
-	fn chain_spec_path(&self) -> &Path {
+	/// Returns the path where the chain spec should be saved, if the command saves one.
+	fn chain_spec_path(&self) -> Option<&Path> {
 		match self {
-			ChainSpecBuilder::New { chain_spec_path, .. } => chain_spec_path.as_path(),
-			ChainSpecBuilder::Generate { chain_spec_path, .. } => chain_spec_path.as_path(),
+			ChainSpecBuilder::New { chain_spec_path, .. } => Some(chain_spec_path.as_path()),
+			ChainSpecBuilder::Generate { chain_spec_path, .. } => Some(chain_spec_path.as_path()),
+			ChainSpecBuilder::ConvertToRaw { chain_spec_path, .. } |
+			ChainSpecBuilder::UpdateGenesis { chain_spec_path, .. } |
+			ChainSpecBuilder::UpdateCode { chain_spec_path, .. } => Some(chain_spec_path.as_path()),
+			ChainSpecBuilder::Verify { .. } => None,
 		}
 	}
 }
@@ -100,7 +155,7 @@ fn genesis_constructor(
 	nominator_accounts: &[AccountId],
 	endowed_accounts: &[AccountId],
 	sudo_account: &AccountId,
-) -> chain_spec::GenesisConfig {
+) -> serde_json::Value {
 	let authorities = authority_seeds
 		.iter()
 		.map(AsRef::as_ref)
@@ -138,18 +193,17 @@ fn generate_chain_spec(
 
 	let sudo_account = parse_account(sudo_account)?;
 
-	let chain_spec = chain_spec::ChainSpec::from_genesis(
+	let chain_spec = chain_spec::ChainSpec::from_runtime_code(
 		"Custom",
 		"custom",
 		sc_chain_spec::ChainType::Live,
-		move || {
-			genesis_constructor(
-				&authority_seeds,
-				&nominator_accounts,
-				&endowed_accounts,
-				&sudo_account,
-			)
-		},
+		kitchensink_runtime::wasm_binary_unwrap(),
+		genesis_constructor(
+			&authority_seeds,
+			&nominator_accounts,
+			&endowed_accounts,
+			&sudo_account,
+		),
 		vec![],
 		None,
 		None,
@@ -237,7 +291,7 @@ fn main() -> Result<(), String> {
 	);
 
 	let builder = ChainSpecBuilder::parse();
-	let chain_spec_path = builder.chain_spec_path().to_path_buf();
+	let chain_spec_path = builder.chain_spec_path().map(Path::to_path_buf);
 
 	let (authority_seeds, nominator_accounts, endowed_accounts, sudo_account) = match builder {
 		ChainSpecBuilder::Generate { authorities, nominators, endowed, keystore_path, .. } => {
@@ -283,10 +337,40 @@ fn main() -> Result<(), String> {
 			sudo_account,
 			..
 		} => (authority_seeds, nominator_accounts, endowed_accounts, sudo_account),
+		ChainSpecBuilder::ConvertToRaw { input_chain_spec, chain_spec_path } =>
+			return write_chain_spec(
+				&chain_spec_path,
+				edit::convert_to_raw(edit::read_json(&input_chain_spec)?)?,
+			),
+		ChainSpecBuilder::UpdateGenesis { input_chain_spec, patch_path, chain_spec_path } => {
+			let json = edit::update_genesis(
+				edit::read_json(&input_chain_spec)?,
+				edit::read_json(&patch_path)?,
+			)?;
+			return write_chain_spec(&chain_spec_path, json)
+		},
+		ChainSpecBuilder::UpdateCode { input_chain_spec, runtime_wasm_path, chain_spec_path } => {
+			let code = fs::read(&runtime_wasm_path).map_err(|err| {
+				format!("Error reading runtime `{}`: {}", runtime_wasm_path.display(), err)
+			})?;
+			let json = edit::update_code(edit::read_json(&input_chain_spec)?, &code)?;
+			return write_chain_spec(&chain_spec_path, json)
+		},
+		ChainSpecBuilder::Verify { input_chain_spec, source } => {
+			let source = source.map(|source| edit::read_json(&source)).transpose()?;
+			let root = edit::verify(&edit::read_json(&input_chain_spec)?, source.as_ref())?;
+			println!("Genesis state root {} matches the embedded runtime", root);
+			return Ok(())
+		},
 	};
 
 	let json =
 		generate_chain_spec(authority_seeds, nominator_accounts, endowed_accounts, sudo_account)?;
 
-	fs::write(chain_spec_path, json).map_err(|err| err.to_string())
+	let chain_spec_path = chain_spec_path.expect("`New` and `Generate` save a chain spec; qed");
+	write_chain_spec(&chain_spec_path, json)
+}
+
+fn write_chain_spec(path: &Path, json: String) -> Result<(), String> {
+	fs::write(path, json).map_err(|err| err.to_string())
 }
@@ -0,0 +1,74 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reference signer for the remote keystore of a node, backed by a local keystore.
+
+use std::{
+	fs,
+	os::unix::{fs::PermissionsExt, net::UnixListener},
+	path::PathBuf,
+	sync::Arc,
+};
+
+use clap::Parser;
+use sc_keystore::LocalKeystore;
+use sp_core::crypto::SecretString;
+
+/// Serve the keys of a local keystore to a node over a Unix socket.
+#[derive(Debug, Parser)]
+#[command(rename_all = "kebab-case", version, about)]
+struct SignerCli {
+	/// Path of the Unix socket to listen on.
+	#[arg(long, value_name = "PATH")]
+	socket: PathBuf,
+
+	/// Path of the keystore. Keys are only kept in memory if it isn't given.
+	#[arg(long, value_name = "PATH")]
+	keystore_path: Option<PathBuf>,
+
+	/// File that contains the password used by the keystore.
+	#[arg(long, value_name = "PATH", requires = "keystore_path")]
+	password_filename: Option<PathBuf>,
+}
+
+fn main() -> Result<(), String> {
+	env_logger::init();
+	let cli = SignerCli::parse();
+
+	let keystore = match cli.keystore_path {
+		Some(path) => {
+			let password = cli
+				.password_filename
+				.map(|file| fs::read_to_string(file).map(SecretString::new))
+				.transpose()
+				.map_err(|e| format!("Error reading the password file: {}", e))?;
+			LocalKeystore::open(path, password)
+				.map_err(|e| format!("Error opening the keystore: {}", e))?
+		},
+		None => LocalKeystore::in_memory(),
+	};
+
+	let listener = UnixListener::bind(&cli.socket)
+		.map_err(|e| format!("Error binding to `{}`: {}", cli.socket.display(), e))?;
+	fs::set_permissions(&cli.socket, fs::Permissions::from_mode(0o600))
+		.map_err(|e| format!("Error restricting access to the socket: {}", e))?;
+	println!("Serving keystore requests on {}", cli.socket.display());
+
+	sc_keystore::remote::serve(listener, Arc::new(keystore))
+		.map_err(|e| format!("Error accepting connections: {}", e))
+}
@@ -19,13 +19,16 @@
 //! Substrate chain configurations.
 #![warn(missing_docs)]
 
-use crate::{extension::GetExtension, ChainType, Properties, RuntimeGenesis};
+use crate::{
+	extension::GetExtension, genesis_config_builder::GenesisConfigBuilderRuntimeCaller, ChainType,
+	Properties, RuntimeGenesis,
+};
 use sc_network::config::MultiaddrWithPeerId;
 use sc_telemetry::TelemetryEndpoints;
 use serde::{Deserialize, Serialize};
 use serde_json as json;
 use sp_core::{
-	storage::{ChildInfo, Storage, StorageChild, StorageData, StorageKey},
+	storage::{well_known_keys, ChildInfo, Storage, StorageChild, StorageData, StorageKey},
 	Bytes,
 };
 use sp_runtime::BuildStorage;
@@ -36,6 +39,7 @@ enum GenesisSource<G> {
 	Binary(Cow<'static, [u8]>),
 	Factory(Arc<dyn Fn() -> G + Send + Sync>),
 	Storage(Storage),
+	GenesisBuilderApi(RuntimeGenesisInner),
 }
 
 impl<G> Clone for GenesisSource<G> {
@@ -45,6 +49,7 @@ impl<G> Clone for GenesisSource<G> {
 			Self::Binary(ref d) => Self::Binary(d.clone()),
 			Self::Factory(ref f) => Self::Factory(f.clone()),
 			Self::Storage(ref s) => Self::Storage(s.clone()),
+			Self::GenesisBuilderApi(ref s) => Self::GenesisBuilderApi(s.clone()),
 		}
 	}
 }
@@ -104,6 +109,7 @@ impl<G: RuntimeGenesis> GenesisSource<G> {
 
 				Ok(Genesis::Raw(RawGenesis { top, children_default }))
 			},
+			Self::GenesisBuilderApi(inner) => Ok(Genesis::RuntimeGenesis(inner.clone())),
 		}
 	}
 }
@@ -112,6 +118,22 @@ impl<G: RuntimeGenesis, E> BuildStorage for ChainSpec<G, E> {
 	fn assimilate_storage(&self, storage: &mut Storage) -> Result<(), String> {
 		match self.genesis.resolve()? {
 			Genesis::Runtime(gc) => gc.assimilate_storage(storage),
+			Genesis::RuntimeGenesis(inner) => {
+				let built = inner.build_storage()?;
+				storage.top.extend(built.top);
+				for (key, child) in built.children_default {
+					storage
+						.children_default
+						.entry(key)
+						.or_insert_with(|| StorageChild {
+							data: Default::default(),
+							child_info: child.child_info,
+						})
+						.data
+						.extend(child.data);
+				}
+				Ok(())
+			},
 			Genesis::Raw(RawGenesis { top: map, children_default: children_map }) => {
 				storage.top.extend(map.into_iter().map(|(k, v)| (k.0, v.0)));
 				children_map.into_iter().for_each(|(k, v)| {
@@ -144,6 +166,61 @@ pub struct RawGenesis {
 	pub children_default: BTreeMap<StorageKey, GenesisStorage>,
 }
 
+impl From<Storage> for RawGenesis {
+	fn from(storage: Storage) -> Self {
+		let top = storage.top.into_iter().map(|(k, v)| (StorageKey(k), StorageData(v))).collect();
+		let children_default = storage
+			.children_default
+			.into_iter()
+			.map(|(sk, child)| {
+				(
+					StorageKey(sk),
+					child.data.into_iter().map(|(k, v)| (StorageKey(k), StorageData(v))).collect(),
+				)
+			})
+			.collect();
+
+		RawGenesis { top, children_default }
+	}
+}
+
+/// Runtime code with the JSON representation of its genesis config.
+///
+/// The genesis storage is built by calling the `GenesisBuilder` api of the runtime.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RuntimeGenesisInner {
+	/// Runtime wasm code, expected to implement the `GenesisBuilder` api.
+	code: Bytes,
+	/// The JSON genesis config or a patch on top of the default one.
+	#[serde(flatten)]
+	json_blob: RuntimeGenesisConfigJson,
+}
+
+/// The JSON blob of a [`RuntimeGenesisInner`].
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+enum RuntimeGenesisConfigJson {
+	/// The full genesis config, all fields must be given.
+	Config(json::Value),
+	/// A patch merged into the default genesis config of the runtime.
+	Patch(json::Value),
+}
+
+impl RuntimeGenesisInner {
+	fn build_storage(&self) -> Result<Storage, String> {
+		let caller = GenesisConfigBuilderRuntimeCaller::new(&self.code);
+		let mut storage = match self.json_blob {
+			RuntimeGenesisConfigJson::Config(ref config) =>
+				caller.get_storage_for_config(config.clone()),
+			RuntimeGenesisConfigJson::Patch(ref patch) =>
+				caller.get_storage_for_patch(patch.clone()),
+		}?;
+		storage.top.insert(well_known_keys::CODE.to_vec(), self.code.to_vec());
+		Ok(storage)
+	}
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
@@ -152,6 +229,8 @@ enum Genesis<G> {
 	Raw(RawGenesis),
 	/// State root hash of the genesis storage.
 	StateRootHash(StorageData),
+	/// Runtime code with a genesis config, built through the `GenesisBuilder` api.
+	RuntimeGenesis(RuntimeGenesisInner),
 }
 
 /// A configuration of a client. Does not include runtime storage initialization.
@@ -290,6 +369,45 @@ impl<G, E> ChainSpec<G, E> {
 		ChainSpec { client_spec, genesis: GenesisSource::Factory(Arc::new(constructor)) }
 	}
 
+	/// Create a spec whose genesis is built by the `GenesisBuilder` api of the given runtime code.
+	///
+	/// The `patch` is merged into the default genesis config of the runtime, so no native
+	/// genesis config type is needed.
+	pub fn from_runtime_code(
+		name: &str,
+		id: &str,
+		chain_type: ChainType,
+		code: &[u8],
+		patch: json::Value,
+		boot_nodes: Vec<MultiaddrWithPeerId>,
+		telemetry_endpoints: Option<TelemetryEndpoints>,
+		protocol_id: Option<&str>,
+		fork_id: Option<&str>,
+		properties: Option<Properties>,
+		extensions: E,
+	) -> Self {
+		let client_spec = ClientSpec {
+			name: name.to_owned(),
+			id: id.to_owned(),
+			chain_type,
+			boot_nodes,
+			telemetry_endpoints,
+			protocol_id: protocol_id.map(str::to_owned),
+			fork_id: fork_id.map(str::to_owned),
+			properties,
+			extensions,
+			consensus_engine: (),
+			genesis: Default::default(),
+			code_substitutes: BTreeMap::new(),
+		};
+
+		let genesis = GenesisSource::GenesisBuilderApi(RuntimeGenesisInner {
+			code: code.to_vec().into(),
+			json_blob: RuntimeGenesisConfigJson::Patch(patch),
+		});
+		ChainSpec { client_spec, genesis }
+	}
+
 	/// Type of the chain.
 	fn chain_type(&self) -> ChainType {
 		self.client_spec.chain_type.clone()
@@ -335,27 +453,8 @@ struct JsonContainer<G, E> {
 impl<G: RuntimeGenesis, E: serde::Serialize + Clone + 'static> ChainSpec<G, E> {
 	fn json_container(&self, raw: bool) -> Result<JsonContainer<G, E>, String> {
 		let genesis = match (raw, self.genesis.resolve()?) {
-			(true, Genesis::Runtime(g)) => {
-				let storage = g.build_storage()?;
-				let top =
-					storage.top.into_iter().map(|(k, v)| (StorageKey(k), StorageData(v))).collect();
-				let children_default = storage
-					.children_default
-					.into_iter()
-					.map(|(sk, child)| {
-						(
-							StorageKey(sk),
-							child
-								.data
-								.into_iter()
-								.map(|(k, v)| (StorageKey(k), StorageData(v)))
-								.collect(),
-						)
-					})
-					.collect();
-
-				Genesis::Raw(RawGenesis { top, children_default })
-			},
+			(true, Genesis::Runtime(g)) => Genesis::Raw(g.build_storage()?.into()),
+			(true, Genesis::RuntimeGenesis(inner)) => Genesis::Raw(inner.build_storage()?.into()),
 			(_, genesis) => genesis,
 		};
 		Ok(JsonContainer { client_spec: self.client_spec.clone(), genesis })
@@ -506,6 +605,32 @@ mod tests {
 		assert_eq!(spec.extensions().my_property, "Test Extension");
 	}
 
+	#[test]
+	fn runtime_genesis_is_built_by_the_runtime() {
+		let code = substrate_test_runtime::wasm_binary_unwrap();
+		let spec = TestSpec::from_runtime_code(
+			"Test",
+			"test",
+			ChainType::Development,
+			code,
+			json::json!({ "balances": [[vec![1u8; 32], 100]] }),
+			Vec::new(),
+			None,
+			None,
+			None,
+			None,
+			None,
+		);
+
+		let json = spec.as_json(false).unwrap();
+		let spec = TestSpec::from_json_bytes(json.into_bytes()).unwrap();
+		let storage = spec.build_storage().unwrap();
+		assert_eq!(storage.top.get(well_known_keys::CODE), Some(&code.to_vec()));
+
+		let raw: json::Value = json::from_str(&spec.as_json(true).unwrap()).unwrap();
+		assert_eq!(raw["genesis"]["raw"]["top"].as_object().unwrap().len(), storage.top.len());
+	}
+
 	#[test]
 	fn chain_spec_raw_output_should_be_deterministic() {
 		let mut spec = TestSpec2::from_json_bytes(Cow::Owned(
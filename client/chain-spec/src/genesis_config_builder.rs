@@ -0,0 +1,150 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A helper module for calling the `GenesisBuilder` api from arbitrary runtime wasm blobs.
+
+use codec::{Decode, Encode};
+use sc_executor::{error::Result, WasmExecutor};
+use serde_json::{from_slice, Value};
+use sp_core::{
+	storage::Storage,
+	traits::{CallContext, CodeExecutor, Externalities, FetchRuntimeCode, RuntimeCode},
+};
+use sp_genesis_builder::Result as BuildResult;
+use sp_state_machine::BasicExternalities;
+use std::borrow::Cow;
+
+/// A utility that facilitates calling the `GenesisBuilder` api from the runtime wasm code blob.
+pub struct GenesisConfigBuilderRuntimeCaller<'a> {
+	code: Cow<'a, [u8]>,
+	code_hash: Vec<u8>,
+	executor: WasmExecutor<sp_io::SubstrateHostFunctions>,
+}
+
+impl<'a> FetchRuntimeCode for GenesisConfigBuilderRuntimeCaller<'a> {
+	fn fetch_runtime_code(&self) -> Option<Cow<[u8]>> {
+		Some(self.code.as_ref().into())
+	}
+}
+
+impl<'a> GenesisConfigBuilderRuntimeCaller<'a> {
+	/// Creates new instance using the provided code blob.
+	///
+	/// This code is later referred to as `runtime`.
+	pub fn new(code: &'a [u8]) -> Self {
+		GenesisConfigBuilderRuntimeCaller {
+			code: code.into(),
+			code_hash: sp_core::blake2_256(code).to_vec(),
+			executor: WasmExecutor::<sp_io::SubstrateHostFunctions>::builder(Default::default())
+				.with_allow_missing_host_functions(true)
+				.build(),
+		}
+	}
+
+	fn call(&self, ext: &mut dyn Externalities, method: &str, data: &[u8]) -> Result<Vec<u8>> {
+		self.executor
+			.call(
+				ext,
+				&RuntimeCode { heap_pages: None, code_fetcher: self, hash: self.code_hash.clone() },
+				method,
+				data,
+				false,
+				CallContext::Offchain,
+			)
+			.0
+	}
+
+	/// Returns the default `GenesisConfig` provided by the `runtime`.
+	///
+	/// Calls `GenesisBuilder::create_default_config` in the `runtime`.
+	pub fn get_default_config(&self) -> core::result::Result<Value, String> {
+		let mut ext = BasicExternalities::new_empty();
+		let call_result = self
+			.call(&mut ext, "GenesisBuilder_create_default_config", &[])
+			.map_err(|e| format!("wasm call error {}", e))?;
+		let default_config = Vec::<u8>::decode(&mut &call_result[..])
+			.map_err(|e| format!("scale codec error: {}", e))?;
+		from_slice(&default_config[..])
+			.map_err(|e| format!("Invalid default genesis config returned by the runtime: {}", e))
+	}
+
+	/// Builds the storage from the given `GenesisConfig` JSON.
+	///
+	/// Calls `GenesisBuilder::build_config` in the `runtime`.
+	pub fn get_storage_for_config(&self, config: Value) -> core::result::Result<Storage, String> {
+		let mut ext = BasicExternalities::new_empty();
+		let call_result = self
+			.call(&mut ext, "GenesisBuilder_build_config", &config.to_string().encode())
+			.map_err(|e| format!("wasm call error {}", e))?;
+		BuildResult::decode(&mut &call_result[..])
+			.map_err(|e| format!("scale codec error: {}", e))?
+			.map_err(|e| format!("Building the genesis config failed: {}", e))?;
+		Ok(ext.into_storages())
+	}
+
+	/// Builds the storage from the default `GenesisConfig` of the `runtime` with the given patch
+	/// applied.
+	///
+	/// The patch is merged into the default config as a JSON merge patch, see
+	/// [`json_merge`](crate::json_merge).
+	pub fn get_storage_for_patch(&self, patch: Value) -> core::result::Result<Storage, String> {
+		let mut config = self.get_default_config()?;
+		crate::json_patch::merge(&mut config, patch);
+		self.get_storage_for_config(config)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::KeyedVec;
+	use serde_json::json;
+	use sp_core::storage::well_known_keys;
+
+	fn balance_key(who: [u8; 32]) -> Vec<u8> {
+		sp_core::blake2_256(&who.to_keyed_vec(b"balance:")).to_vec()
+	}
+
+	#[test]
+	fn get_default_config_works() {
+		let config =
+			GenesisConfigBuilderRuntimeCaller::new(substrate_test_runtime::wasm_binary_unwrap())
+				.get_default_config()
+				.unwrap();
+		assert_eq!(config, json!({ "authorities": [], "balances": [] }));
+	}
+
+	#[test]
+	fn get_storage_for_patch_works() {
+		let caller =
+			GenesisConfigBuilderRuntimeCaller::new(substrate_test_runtime::wasm_binary_unwrap());
+		let storage =
+			caller.get_storage_for_patch(json!({ "balances": [[vec![1u8; 32], 100]] })).unwrap();
+
+		assert_eq!(storage.top.get(&balance_key([1; 32])), Some(&100u64.encode()));
+		assert!(storage.top.get(well_known_keys::CODE).is_none());
+	}
+
+	#[test]
+	fn get_storage_for_config_rejects_invalid_config() {
+		let caller =
+			GenesisConfigBuilderRuntimeCaller::new(substrate_test_runtime::wasm_binary_unwrap());
+		let err = caller.get_storage_for_config(json!({ "unknown": 1 })).unwrap_err();
+		assert!(err.contains("Building the genesis config failed"), "{}", err);
+	}
+}
@@ -0,0 +1,73 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A helper module implementing the JSON merge patch, as described in
+//! [RFC 7386](https://www.rfc-editor.org/rfc/rfc7386).
+
+use serde_json::Value;
+
+/// Recursively merges `patch` into `target`.
+///
+/// Objects are merged key by key, `null` values in the patch remove the key from the target and
+/// every other value replaces the one in the target.
+pub fn merge(target: &mut Value, patch: Value) {
+	match patch {
+		Value::Object(patch) => {
+			if !target.is_object() {
+				*target = Value::Object(Default::default());
+			}
+			if let Value::Object(target) = target {
+				for (key, value) in patch {
+					if value.is_null() {
+						target.remove(&key);
+					} else {
+						merge(target.entry(key).or_insert(Value::Null), value);
+					}
+				}
+			}
+		},
+		patch => *target = patch,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn merge_replaces_nested_values() {
+		let mut target = json!({ "a": { "b": 1, "c": [1, 2] }, "d": "x" });
+		merge(&mut target, json!({ "a": { "c": [3] }, "e": true }));
+		assert_eq!(target, json!({ "a": { "b": 1, "c": [3] }, "d": "x", "e": true }));
+	}
+
+	#[test]
+	fn merge_removes_null_values() {
+		let mut target = json!({ "a": { "b": 1, "c": 2 }, "d": "x" });
+		merge(&mut target, json!({ "a": { "b": null }, "d": null }));
+		assert_eq!(target, json!({ "a": { "c": 2 } }));
+	}
+
+	#[test]
+	fn merge_replaces_non_objects() {
+		let mut target = json!([1, 2]);
+		merge(&mut target, json!({ "a": 1, "b": null }));
+		assert_eq!(target, json!({ "a": 1 }));
+	}
+}
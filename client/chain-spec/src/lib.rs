@@ -178,6 +178,8 @@
 mod chain_spec;
 mod extension;
 mod genesis;
+mod genesis_config_builder;
+mod json_patch;
 
 pub use self::{
 	chain_spec::{ChainSpec as GenericChainSpec, NoExtension},
@@ -186,6 +188,8 @@ pub use self::{
 		construct_genesis_block, resolve_state_version_from_wasm, BuildGenesisBlock,
 		GenesisBlockBuilder,
 	},
+	genesis_config_builder::GenesisConfigBuilderRuntimeCaller,
+	json_patch::merge as json_merge,
 };
 pub use sc_chain_spec_derive::{ChainSpecExtension, ChainSpecGroup};
 
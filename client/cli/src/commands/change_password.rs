@@ -0,0 +1,196 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of the `change-password` subcommand
+
+use super::key::open_local_keystore;
+use crate::{
+	params::secret_string_from_str, Error, KeystoreParams, OutputType, OutputTypeFlag,
+	SharedParams, SubstrateCli,
+};
+use clap::{ArgGroup, Parser};
+use serde_json::json;
+use sp_core::crypto::SecretString;
+use std::{
+	fs,
+	io::{self, Write},
+	path::PathBuf,
+};
+
+/// The `change-password` command
+///
+/// The password of the keystore is used when deriving the keys from their phrases, so changing it
+/// changes the public keys of all keys. Since this rotates the keys, it has to be acknowledged with
+/// `--rotate-keys`. The new public keys are printed and have to be registered on chain as new
+/// session keys.
+#[derive(Debug, Clone, Parser)]
+#[command(
+	name = "change-password",
+	about = "Change the password of the keystore of a node, which changes all of its keys."
+)]
+#[command(group(ArgGroup::new("new_password_source").required(true)))]
+pub struct ChangePasswordCmd {
+	/// Use interactive shell for entering the new password of the keystore.
+	#[arg(long, group = "new_password_source")]
+	pub new_password_interactive: bool,
+
+	/// The new password of the keystore.
+	#[arg(long, value_parser = secret_string_from_str, group = "new_password_source")]
+	pub new_password: Option<SecretString>,
+
+	/// File that contains the new password of the keystore.
+	#[arg(long, value_name = "PATH", group = "new_password_source")]
+	pub new_password_filename: Option<PathBuf>,
+
+	/// Remove the password of the keystore.
+	#[arg(long, group = "new_password_source")]
+	pub remove_password: bool,
+
+	/// Acknowledge that changing the password replaces all keys with new ones.
+	#[arg(long, required = true)]
+	pub rotate_keys: bool,
+
+	/// Skip interactive prompt by answering yes automatically.
+	#[arg(short = 'y')]
+	pub yes: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub keystore_params: KeystoreParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub output_scheme: OutputTypeFlag,
+}
+
+impl ChangePasswordCmd {
+	/// Run the command
+	pub fn run<C: SubstrateCli>(&self, cli: &C) -> Result<(), Error> {
+		let keystore = open_local_keystore(cli, &self.shared_params, &self.keystore_params)?;
+		let new_password = self.new_password()?;
+
+		if !self.yes {
+			print!(
+				"Are you sure to replace all {} keys of the keystore? The old keys can't be \
+				 restored. [y/N]: ",
+				keystore.all_keys()?.len()
+			);
+			io::stdout().flush().expect("failed to flush stdout");
+
+			let mut input = String::new();
+			io::stdin().read_line(&mut input)?;
+			let input = input.trim();
+
+			match input.chars().next() {
+				Some('y') | Some('Y') => {},
+				_ => {
+					println!("Aborted");
+					return Ok(())
+				},
+			}
+		}
+
+		let keys = keystore.change_password(new_password)?;
+
+		match self.output_scheme.output_type {
+			OutputType::Json => {
+				let json = keys
+					.iter()
+					.map(|key| {
+						json!({
+							"keyType": String::from_utf8_lossy(&key.key_type.0),
+							"publicKey": array_bytes::bytes2hex("0x", &key.public),
+							"newPublicKey": array_bytes::bytes2hex("0x", &key.new_public),
+						})
+					})
+					.collect::<Vec<_>>();
+				println!(
+					"{}",
+					serde_json::to_string_pretty(&json).expect("Json pretty print failed")
+				);
+			},
+			OutputType::Text => {
+				for key in &keys {
+					println!(
+						"Key type:            {}\n  \
+						 Public key (hex):  {}\n  \
+						 New public key:    {}",
+						String::from_utf8_lossy(&key.key_type.0),
+						array_bytes::bytes2hex("0x", &key.public),
+						array_bytes::bytes2hex("0x", &key.new_public),
+					);
+				}
+				if keys.iter().any(|key| key.public != key.new_public) {
+					eprintln!(
+						"The public keys changed, the session keys of the node have to be set again."
+					);
+				}
+			},
+		}
+
+		Ok(())
+	}
+
+	fn new_password(&self) -> Result<Option<SecretString>, Error> {
+		let password = if self.new_password_interactive {
+			Some(SecretString::new(rpassword::prompt_password("New keystore password: ")?))
+		} else if let Some(ref file) = self.new_password_filename {
+			Some(SecretString::new(fs::read_to_string(file)?))
+		} else {
+			self.new_password.clone()
+		};
+
+		Ok(password)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn new_password_is_required_once() {
+		assert!(ChangePasswordCmd::try_parse_from(["change-password", "--rotate-keys"]).is_err());
+		assert!(ChangePasswordCmd::try_parse_from([
+			"change-password",
+			"--rotate-keys",
+			"--new-password",
+			"password",
+			"--remove-password",
+		])
+		.is_err());
+
+		let cmd = ChangePasswordCmd::try_parse_from([
+			"change-password",
+			"--rotate-keys",
+			"--remove-password",
+		])
+		.unwrap();
+		assert!(cmd.new_password().unwrap().is_none());
+	}
+
+	#[test]
+	fn rotating_keys_must_be_acknowledged() {
+		assert!(
+			ChangePasswordCmd::try_parse_from(["change-password", "--remove-password"]).is_err()
+		);
+	}
+}
@@ -0,0 +1,63 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Database related CLI utilities
+
+use super::{migrate_db_cmd::MigrateDbCmd, offchain_storage_cmd::OffchainStorageSubcommand};
+use crate::{
+	error,
+	params::{DatabaseParams, SharedParams},
+	CliConfiguration,
+};
+use sc_service::DatabaseSource;
+use sp_runtime::traits::Block as BlockT;
+
+/// Database utilities for the cli.
+#[derive(Debug, clap::Subcommand)]
+pub enum DbSubcommand {
+	/// Copy the database to another database backend.
+	Migrate(MigrateDbCmd),
+	/// Inspect and manage the offchain storage.
+	#[command(subcommand)]
+	OffchainStorage(OffchainStorageSubcommand),
+}
+
+impl DbSubcommand {
+	/// Run the database subcommands
+	pub fn run<B: BlockT>(&self, database_config: DatabaseSource) -> error::Result<()> {
+		match self {
+			DbSubcommand::Migrate(cmd) => cmd.run::<B>(database_config),
+			DbSubcommand::OffchainStorage(cmd) => cmd.run::<B>(database_config),
+		}
+	}
+}
+
+impl CliConfiguration for DbSubcommand {
+	fn shared_params(&self) -> &SharedParams {
+		match self {
+			DbSubcommand::Migrate(cmd) => &cmd.shared_params,
+			DbSubcommand::OffchainStorage(cmd) => cmd.shared_params(),
+		}
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		match self {
+			DbSubcommand::Migrate(cmd) => Some(&cmd.database_params),
+			DbSubcommand::OffchainStorage(cmd) => Some(cmd.database_params()),
+		}
+	}
+}
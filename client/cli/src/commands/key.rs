@@ -18,10 +18,13 @@
 //! Key related CLI utilities
 
 use super::{
-	generate::GenerateCmd, generate_node_key::GenerateNodeKeyCmd, insert_key::InsertKeyCmd,
-	inspect_key::InspectKeyCmd, inspect_node_key::InspectNodeKeyCmd,
+	change_password::ChangePasswordCmd, generate::GenerateCmd,
+	generate_node_key::GenerateNodeKeyCmd, insert_key::InsertKeyCmd, inspect_key::InspectKeyCmd,
+	inspect_node_key::InspectNodeKeyCmd, list_keys::ListKeysCmd, remove_key::RemoveKeyCmd,
 };
-use crate::{Error, SubstrateCli};
+use crate::{Error, KeystoreParams, SharedParams, SubstrateCli};
+use sc_keystore::LocalKeystore;
+use sc_service::config::{BasePath, KeystoreConfig};
 
 /// Key utilities for the cli.
 #[derive(Debug, clap::Subcommand)]
@@ -41,6 +44,15 @@ pub enum KeySubcommand {
 
 	/// Insert a key to the keystore of a node.
 	Insert(InsertKeyCmd),
+
+	/// List the keys in the keystore of a node.
+	List(ListKeysCmd),
+
+	/// Remove a key from the keystore of a node.
+	Remove(RemoveKeyCmd),
+
+	/// Change the password of the keystore of a node, which changes all of its keys.
+	ChangePassword(ChangePasswordCmd),
 }
 
 impl KeySubcommand {
@@ -52,6 +64,31 @@ impl KeySubcommand {
 			KeySubcommand::Inspect(cmd) => cmd.run(),
 			KeySubcommand::Insert(cmd) => cmd.run(cli),
 			KeySubcommand::InspectNodeKey(cmd) => cmd.run(),
+			KeySubcommand::List(cmd) => cmd.run(cli),
+			KeySubcommand::Remove(cmd) => cmd.run(cli),
+			KeySubcommand::ChangePassword(cmd) => cmd.run(cli),
 		}
 	}
 }
+
+/// Open the local keystore of the node given by the shared and keystore parameters.
+pub(super) fn open_local_keystore<C: SubstrateCli>(
+	cli: &C,
+	shared_params: &SharedParams,
+	keystore_params: &KeystoreParams,
+) -> Result<LocalKeystore, Error> {
+	let base_path = shared_params
+		.base_path()?
+		.unwrap_or_else(|| BasePath::from_project("", "", &C::executable_name()));
+	let chain_id = shared_params.chain_id(shared_params.is_dev());
+	let chain_spec = cli.load_spec(&chain_id)?;
+	let config_dir = base_path.config_dir(chain_spec.id());
+
+	match keystore_params.keystore_config(&config_dir)? {
+		KeystoreConfig::Path { path, password } => Ok(LocalKeystore::open(path, password)?),
+		KeystoreConfig::Remote { .. } =>
+			Err(Error::Input("Keys of a remote keystore have to be managed by its signer".into())),
+		KeystoreConfig::InMemory =>
+			unreachable!("keystore_config never returns an in-memory keystore; qed"),
+	}
+}
@@ -0,0 +1,253 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of the `list` subcommand
+
+use super::key::open_local_keystore;
+use crate::{
+	with_crypto_scheme, CryptoScheme, Error, KeystoreParams, NetworkSchemeFlag, OutputType,
+	OutputTypeFlag, SharedParams, SubstrateCli,
+};
+use clap::{Parser, ValueEnum};
+use sc_keystore::LocalKeystore;
+use serde_json::json;
+use sp_core::crypto::{
+	unwrap_or_default_ss58_version, ByteArray, KeyTypeId, Ss58AddressFormat, Ss58Codec,
+};
+use sp_runtime::{traits::IdentifyAccount, MultiSigner};
+
+/// The `list` command
+#[derive(Debug, Clone, Parser)]
+#[command(name = "list", about = "List the keys in the keystore of a node.")]
+pub struct ListKeysCmd {
+	/// Only list keys of the given key type, examples: "gran", or "imon"
+	#[arg(long)]
+	key_type: Option<String>,
+
+	/// The hex encoded session keys of the node, as returned by `author_rotateKeys`.
+	///
+	/// If given, every key is marked whether it is part of these session keys.
+	#[arg(long, value_name = "HEX")]
+	session_keys: Option<String>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub keystore_params: KeystoreParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub network_scheme: NetworkSchemeFlag,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub output_scheme: OutputTypeFlag,
+}
+
+/// A key of the keystore.
+#[derive(Debug, PartialEq)]
+struct KeyInfo {
+	key_type: KeyTypeId,
+	public: Vec<u8>,
+	/// The crypto scheme and SS58 address of the key.
+	///
+	/// `None` if the key can't be derived with the password of the keystore.
+	scheme: Option<(CryptoScheme, String)>,
+	/// Whether the key is part of the given session keys.
+	in_session_keys: Option<bool>,
+}
+
+impl ListKeysCmd {
+	/// Run the command
+	pub fn run<C: SubstrateCli>(&self, cli: &C) -> Result<(), Error> {
+		let key_type = self
+			.key_type
+			.as_ref()
+			.map(|key_type| KeyTypeId::try_from(key_type.as_str()))
+			.transpose()
+			.map_err(|_| Error::KeyTypeInvalid)?;
+		let session_keys = self
+			.session_keys
+			.as_ref()
+			.map(|session_keys| array_bytes::hex2bytes(session_keys))
+			.transpose()
+			.map_err(Error::HexDataConversion)?;
+		let network = unwrap_or_default_ss58_version(self.network_scheme.network);
+
+		let keystore = open_local_keystore(cli, &self.shared_params, &self.keystore_params)?;
+		let keys = list_keys(&keystore, key_type, session_keys.as_deref(), network)?;
+
+		match self.output_scheme.output_type {
+			OutputType::Json => {
+				let json = keys.iter().map(KeyInfo::to_json).collect::<Vec<_>>();
+				println!(
+					"{}",
+					serde_json::to_string_pretty(&json).expect("Json pretty print failed")
+				);
+			},
+			OutputType::Text =>
+				for key in keys {
+					key.print();
+				},
+		}
+
+		Ok(())
+	}
+}
+
+impl KeyInfo {
+	fn to_json(&self) -> serde_json::Value {
+		json!({
+			"keyType": key_type_name(self.key_type),
+			"scheme": self.scheme.as_ref().map(|(scheme, _)| scheme_name(*scheme)),
+			"publicKey": array_bytes::bytes2hex("0x", &self.public),
+			"ss58Address": self.scheme.as_ref().map(|(_, address)| address),
+			"inSessionKeys": self.in_session_keys,
+		})
+	}
+
+	fn print(&self) {
+		println!(
+			"Key type:            {}\n  \
+			 Scheme:            {}\n  \
+			 Public key (hex):  {}\n  \
+			 SS58 Address:      {}",
+			key_type_name(self.key_type),
+			self.scheme.as_ref().map_or("unknown", |(scheme, _)| scheme_name(*scheme)),
+			array_bytes::bytes2hex("0x", &self.public),
+			self.scheme.as_ref().map_or("n/a", |(_, address)| address.as_str()),
+		);
+		if let Some(in_session_keys) = self.in_session_keys {
+			println!("  In session keys:   {}", if in_session_keys { "yes" } else { "no" });
+		}
+	}
+}
+
+/// Collect all keys of `keystore`, optionally only the ones of `key_type`.
+///
+/// The session keys are the concatenation of the public keys of a node, so a key is part of
+/// them if its public key is contained in `session_keys`.
+fn list_keys(
+	keystore: &LocalKeystore,
+	key_type: Option<KeyTypeId>,
+	session_keys: Option<&[u8]>,
+	network: Ss58AddressFormat,
+) -> Result<Vec<KeyInfo>, Error> {
+	let mut keys = keystore.all_keys()?;
+	keys.retain(|(t, _)| key_type.map_or(true, |key_type| *t == key_type));
+	keys.sort();
+	keys.dedup();
+
+	Ok(keys
+		.into_iter()
+		.map(|(key_type, public)| {
+			let scheme = CryptoScheme::value_variants().iter().find_map(|scheme| {
+				with_crypto_scheme!(*scheme, ss58_address(keystore, key_type, &public, network))
+					.map(|address| (*scheme, address))
+			});
+			let in_session_keys = session_keys
+				.map(|session_keys| session_keys.windows(public.len()).any(|k| k == &public[..]));
+			KeyInfo { key_type, public, scheme, in_session_keys }
+		})
+		.collect())
+}
+
+/// Returns the SS58 address of the key, if it is a key of the crypto `P`.
+fn ss58_address<P: sp_core::Pair>(
+	keystore: &LocalKeystore,
+	key_type: KeyTypeId,
+	public: &[u8],
+	network: Ss58AddressFormat,
+) -> Option<String>
+where
+	P::Public: Into<MultiSigner>,
+{
+	let public = P::Public::from_slice(public).ok()?;
+	keystore.key_pair_by_type::<P>(&public, key_type).ok()??;
+	Some(public.into().into_account().to_ss58check_with_version(network))
+}
+
+fn key_type_name(key_type: KeyTypeId) -> String {
+	std::str::from_utf8(&key_type.0)
+		.map(Into::into)
+		.unwrap_or_else(|_| array_bytes::bytes2hex("0x", &key_type.0))
+}
+
+fn scheme_name(scheme: CryptoScheme) -> &'static str {
+	match scheme {
+		CryptoScheme::Ed25519 => "ed25519",
+		CryptoScheme::Sr25519 => "sr25519",
+		CryptoScheme::Ecdsa => "ecdsa",
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::crypto::{SecretString, Ss58AddressFormatRegistry};
+	use sp_keystore::Keystore;
+	use tempfile::TempDir;
+
+	#[test]
+	fn list_keys_works() {
+		let path = TempDir::new().unwrap();
+		let keystore = LocalKeystore::open(path.path(), None).unwrap();
+		let network = Ss58AddressFormatRegistry::SubstrateAccount.into();
+
+		let babe = keystore.sr25519_generate_new(KeyTypeId(*b"babe"), None).unwrap();
+		let beefy = keystore.ecdsa_generate_new(KeyTypeId(*b"beef"), None).unwrap();
+		let session_keys = babe.to_raw_vec();
+
+		let keys = list_keys(&keystore, None, Some(&session_keys), network).unwrap();
+		assert_eq!(
+			keys,
+			vec![
+				KeyInfo {
+					key_type: KeyTypeId(*b"babe"),
+					public: babe.to_raw_vec(),
+					scheme: Some((
+						CryptoScheme::Sr25519,
+						MultiSigner::from(babe).into_account().to_ss58check_with_version(network)
+					)),
+					in_session_keys: Some(true),
+				},
+				KeyInfo {
+					key_type: KeyTypeId(*b"beef"),
+					public: beefy.to_raw_vec(),
+					scheme: Some((
+						CryptoScheme::Ecdsa,
+						MultiSigner::from(beefy).into_account().to_ss58check_with_version(network)
+					)),
+					in_session_keys: Some(false),
+				},
+			]
+		);
+
+		let keys = list_keys(&keystore, Some(KeyTypeId(*b"beef")), None, network).unwrap();
+		assert_eq!(keys.len(), 1);
+		assert_eq!(keys[0].in_session_keys, None);
+
+		// keys of a keystore opened with the wrong password have no known scheme
+		let keystore =
+			LocalKeystore::open(path.path(), Some(SecretString::new("password".into()))).unwrap();
+		let keys = list_keys(&keystore, None, None, network).unwrap();
+		assert!(keys.iter().all(|key| key.scheme.is_none()));
+	}
+}
@@ -0,0 +1,130 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	arg_enums::Database,
+	error,
+	params::{DatabaseParams, SharedParams},
+	CliConfiguration,
+};
+use clap::Parser;
+use sc_service::DatabaseSource;
+use sp_runtime::traits::Block as BlockT;
+use std::path::Path;
+
+/// The `db migrate` command used to copy the database to another database backend.
+///
+/// The node must not be running. The source database is left untouched, remove it once the node
+/// runs on the new database.
+#[derive(Debug, Clone, Parser)]
+pub struct MigrateDbCmd {
+	/// Database backend to copy the database to.
+	///
+	/// Only `paritydb` is supported, the database is copied from RocksDB.
+	#[arg(long, value_name = "DB", ignore_case = true, value_enum)]
+	pub to: Database,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl MigrateDbCmd {
+	/// Run the migrate command
+	pub fn run<B: BlockT>(&self, database_config: DatabaseSource) -> error::Result<()> {
+		if !matches!(self.to, Database::ParityDb | Database::ParityDbDeprecated) {
+			return Err(error::Error::Input(
+				"Only migrating from RocksDB to ParityDB is supported".into(),
+			))
+		}
+
+		let (rocksdb_path, paritydb_path, cache_size) = match database_config {
+			DatabaseSource::Auto { rocksdb_path, paritydb_path, cache_size } =>
+				(rocksdb_path, paritydb_path, cache_size),
+			#[cfg(feature = "rocksdb")]
+			DatabaseSource::RocksDb { path, cache_size } => {
+				let paritydb_path = sibling_paritydb_path(&path).ok_or_else(|| {
+					error::Error::Input(format!("Invalid database path {:?}", path))
+				})?;
+				(path, paritydb_path, cache_size)
+			},
+			_ => return Err(error::Error::Input("The source database must be RocksDB".into())),
+		};
+
+		if !rocksdb_path.exists() {
+			return Err(error::Error::Input(format!(
+				"No RocksDB database found at {:?}",
+				rocksdb_path
+			)))
+		}
+
+		migrate::<B>(&rocksdb_path, &paritydb_path, cache_size)
+	}
+}
+
+/// The ParityDB location next to the RocksDB database at `rocksdb_path`, which is
+/// `<base>/db/<role>`.
+#[cfg(feature = "rocksdb")]
+fn sibling_paritydb_path(rocksdb_path: &Path) -> Option<std::path::PathBuf> {
+	let role_dir = rocksdb_path.file_name()?;
+	let base_path = rocksdb_path.parent()?.parent()?;
+	Some(base_path.join("paritydb").join(role_dir))
+}
+
+#[cfg(feature = "rocksdb")]
+fn migrate<B: BlockT>(
+	rocksdb_path: &Path,
+	paritydb_path: &Path,
+	cache_size: usize,
+) -> error::Result<()> {
+	println!("Migrating {:?} to {:?}", rocksdb_path, paritydb_path);
+	let summary = sc_client_db::migration::migrate_rocksdb_to_parity_db::<B>(
+		rocksdb_path,
+		paritydb_path,
+		cache_size,
+	)?;
+
+	println!("Copied {} entries.", summary.entries.iter().sum::<u64>());
+	println!("Best block #{} ({}) matches.", summary.best.0, summary.best.1);
+	if let Some((number, hash)) = summary.finalized_state {
+		println!(
+			"State of block #{} ({}) matches its state root, {} nodes checked.",
+			number, hash, summary.verified_nodes
+		);
+	}
+	println!("Run the node with `--database paritydb` to use the new database.");
+	Ok(())
+}
+
+#[cfg(not(feature = "rocksdb"))]
+fn migrate<B: BlockT>(_: &Path, _: &Path, _: usize) -> error::Result<()> {
+	Err(error::Error::Input("RocksDB support is not enabled".into()))
+}
+
+impl CliConfiguration for MigrateDbCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,631 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Implementation of the `db offchain-storage` subcommands.
+//!
+//! All subcommands work on the persistent offchain storage, which holds both the values written
+//! by offchain workers and the entries written through offchain indexing. Keys and values are
+//! hex encoded. The storage has no type information, but `list` and `dump` decode the values as
+//! a type of the runtime metadata given with `--metadata` and `--value-type`.
+
+use crate::{
+	error,
+	params::{DatabaseParams, SharedParams},
+	OutputType, OutputTypeFlag,
+};
+use clap::{Args, Parser};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use parity_scale_codec::{Compact, Decode};
+use sc_client_api::backend::Backend as BackendT;
+use sc_client_db::{offchain::LocalStorage, Backend, BlocksPruning, DatabaseSettings};
+use sc_service::DatabaseSource;
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef, TypeDefPrimitive};
+use serde_json::{json, Value};
+use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
+use sp_runtime::traits::Block as BlockT;
+use std::{
+	fs,
+	io::{self, Write},
+	path::PathBuf,
+};
+
+/// Number of entries read from the database at once.
+const PAGE_SIZE: usize = 1000;
+
+/// Offchain storage inspection and management.
+#[derive(Debug, clap::Subcommand)]
+pub enum OffchainStorageSubcommand {
+	/// List the keys of the offchain storage, one page at a time.
+	List(ListOffchainStorageCmd),
+	/// Dump all entries of the offchain storage as JSON.
+	Dump(DumpOffchainStorageCmd),
+	/// Remove entries from the offchain storage.
+	Remove(RemoveOffchainStorageCmd),
+}
+
+impl OffchainStorageSubcommand {
+	/// Run the offchain storage subcommands
+	pub fn run<B: BlockT>(&self, database_config: DatabaseSource) -> error::Result<()> {
+		match self {
+			Self::List(cmd) => cmd.run::<B>(database_config),
+			Self::Dump(cmd) => cmd.run::<B>(database_config),
+			Self::Remove(cmd) => cmd.run::<B>(database_config),
+		}
+	}
+
+	/// The shared params of the subcommand.
+	pub fn shared_params(&self) -> &SharedParams {
+		match self {
+			Self::List(cmd) => &cmd.shared_params,
+			Self::Dump(cmd) => &cmd.shared_params,
+			Self::Remove(cmd) => &cmd.shared_params,
+		}
+	}
+
+	/// The database params of the subcommand.
+	pub fn database_params(&self) -> &DatabaseParams {
+		match self {
+			Self::List(cmd) => &cmd.database_params,
+			Self::Dump(cmd) => &cmd.database_params,
+			Self::Remove(cmd) => &cmd.database_params,
+		}
+	}
+}
+
+/// The `db offchain-storage list` command.
+#[derive(Debug, Clone, Parser)]
+pub struct ListOffchainStorageCmd {
+	/// Only list keys starting with this hex encoded prefix.
+	#[arg(long, value_name = "HEX")]
+	pub prefix: Option<String>,
+
+	/// List the keys following this hex encoded key.
+	#[arg(long, value_name = "HEX")]
+	pub start_key: Option<String>,
+
+	/// Maximum number of keys to list.
+	#[arg(long, value_name = "COUNT", default_value_t = 100)]
+	pub count: usize,
+
+	/// Print the values next to the keys.
+	#[arg(long)]
+	pub values: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub value_type: ValueTypeParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub output_type: OutputTypeFlag,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ListOffchainStorageCmd {
+	/// Run the command
+	pub fn run<B: BlockT>(&self, database_config: DatabaseSource) -> error::Result<()> {
+		let prefix = parse_hex(self.prefix.as_deref())?;
+		let start_key = self.start_key.as_deref().map(|key| parse_hex(Some(key))).transpose()?;
+
+		let decoder = self.value_type.decoder()?;
+
+		let storage = open_storage::<B>(database_config, true)?;
+		let entries = entries_paged(&storage, &prefix, start_key.as_deref(), self.count)?;
+
+		match self.output_type.output_type {
+			OutputType::Json => {
+				let entries = entries
+					.iter()
+					.map(|(key, value)| {
+						if self.values {
+							json!({ "key": to_hex(key), "value": decode_value(&decoder, value) })
+						} else {
+							json!(to_hex(key))
+						}
+					})
+					.collect::<Vec<_>>();
+				println!(
+					"{}",
+					serde_json::to_string_pretty(&entries).expect("Json pretty print failed")
+				);
+			},
+			OutputType::Text =>
+				for (key, value) in &entries {
+					if self.values {
+						match decode_value(&decoder, value) {
+							Value::String(value) => println!("{} {}", to_hex(key), value),
+							value => println!("{} {}", to_hex(key), value),
+						}
+					} else {
+						println!("{}", to_hex(key));
+					}
+				},
+		}
+
+		if let Some((last, _)) = entries.last().filter(|_| entries.len() == self.count) {
+			eprintln!("More keys may follow, continue with `--start-key {}`.", to_hex(last));
+		}
+		Ok(())
+	}
+}
+
+/// The `db offchain-storage dump` command.
+#[derive(Debug, Clone, Parser)]
+pub struct DumpOffchainStorageCmd {
+	/// Only dump entries whose keys start with this hex encoded prefix.
+	#[arg(long, value_name = "HEX")]
+	pub prefix: Option<String>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub value_type: ValueTypeParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl DumpOffchainStorageCmd {
+	/// Run the command
+	pub fn run<B: BlockT>(&self, database_config: DatabaseSource) -> error::Result<()> {
+		let prefix = parse_hex(self.prefix.as_deref())?;
+		let decoder = self.value_type.decoder()?;
+		let storage = open_storage::<B>(database_config, true)?;
+
+		let entries = all_entries(&storage, &prefix)?
+			.into_iter()
+			.map(
+				|(key, value)| json!({ "key": to_hex(&key), "value": decode_value(&decoder, &value) }),
+			)
+			.collect::<Vec<_>>();
+
+		let mut out = io::stdout();
+		serde_json::to_writer_pretty(&mut out, &entries)
+			.map_err(|e| format!("Error writing JSON: {}", e))?;
+		writeln!(out)?;
+		Ok(())
+	}
+}
+
+/// Parameters to decode the values of the offchain storage with.
+#[derive(Debug, Clone, Args)]
+pub struct ValueTypeParams {
+	/// File with the SCALE encoded runtime metadata, binary or hex encoded like returned by the
+	/// `state_getMetadata` RPC.
+	#[arg(long, value_name = "PATH", requires = "value_type")]
+	pub metadata: Option<PathBuf>,
+
+	/// The type of the runtime metadata to decode the values as, given by its path like
+	/// `pallet_example_offchain_worker::PricePayload` or by its id.
+	///
+	/// Values that can't be decoded are printed hex encoded.
+	#[arg(long, value_name = "TYPE", requires = "metadata")]
+	pub value_type: Option<String>,
+}
+
+impl ValueTypeParams {
+	/// The decoder of the values, if a value type is given.
+	fn decoder(&self) -> error::Result<Option<ValueDecoder>> {
+		match (&self.metadata, &self.value_type) {
+			(Some(metadata), Some(value_type)) =>
+				ValueDecoder::new(&fs::read(metadata)?, value_type).map(Some),
+			_ => Ok(None),
+		}
+	}
+}
+
+/// Decodes values as a type of the runtime metadata.
+struct ValueDecoder {
+	registry: PortableRegistry,
+	ty: u32,
+}
+
+impl ValueDecoder {
+	/// Create a decoder for the type `value_type` of the encoded `metadata`.
+	fn new(metadata: &[u8], value_type: &str) -> error::Result<Self> {
+		let hex = std::str::from_utf8(metadata)
+			.ok()
+			.and_then(|hex| array_bytes::hex2bytes(hex.trim().trim_matches('"')).ok());
+		let metadata = RuntimeMetadataPrefixed::decode(&mut hex.as_deref().unwrap_or(metadata))
+			.map_err(|e| error::Error::Input(format!("Invalid metadata: {}", e)))?;
+		let registry = match metadata.1 {
+			RuntimeMetadata::V14(metadata) => metadata.types,
+			_ => return Err(error::Error::Input("Only metadata V14 is supported".into())),
+		};
+
+		let ty = match value_type.parse::<u32>() {
+			Ok(id) => registry.resolve(id).map(|_| id),
+			Err(_) => registry
+				.types()
+				.iter()
+				.find(|ty| {
+					let segments = ty.ty().path().segments();
+					segments.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join("::") ==
+						value_type
+				})
+				.map(|ty| ty.id()),
+		}
+		.ok_or_else(|| {
+			error::Error::Input(format!("Type `{}` not found in the metadata", value_type))
+		})?;
+
+		Ok(Self { registry, ty })
+	}
+
+	/// Decode `value` as JSON.
+	fn decode(&self, mut value: &[u8]) -> Result<Value, String> {
+		let decoded = self.decode_type(self.ty, &mut value)?;
+		if !value.is_empty() {
+			return Err(format!("{} trailing bytes", value.len()))
+		}
+		Ok(decoded)
+	}
+
+	fn decode_type(&self, id: u32, input: &mut &[u8]) -> Result<Value, String> {
+		let ty = self.registry.resolve(id).ok_or_else(|| format!("Unknown type {}", id))?;
+		let decoded = match ty.type_def() {
+			TypeDef::Composite(composite) => self.decode_fields(composite.fields(), input)?,
+			TypeDef::Variant(variant) => {
+				let index = u8::decode(input).map_err(|e| e.to_string())?;
+				let variant = variant
+					.variants()
+					.iter()
+					.find(|variant| variant.index() == index)
+					.ok_or_else(|| format!("Unknown variant {} of type {}", index, id))?;
+				if variant.fields().is_empty() {
+					json!(variant.name())
+				} else {
+					json!({ variant.name(): self.decode_fields(variant.fields(), input)? })
+				}
+			},
+			TypeDef::Sequence(sequence) => {
+				let len = Compact::<u32>::decode(input).map_err(|e| e.to_string())?.0;
+				self.decode_items(sequence.type_param().id(), len, input)?
+			},
+			TypeDef::Array(array) =>
+				self.decode_items(array.type_param().id(), array.len(), input)?,
+			TypeDef::Tuple(tuple) => Value::Array(
+				tuple
+					.fields()
+					.iter()
+					.map(|field| self.decode_type(field.id(), input))
+					.collect::<Result<_, _>>()?,
+			),
+			TypeDef::Primitive(primitive) => decode_primitive(primitive, input)?,
+			TypeDef::Compact(compact) => self.decode_compact(compact.type_param().id(), input)?,
+			TypeDef::BitSequence(_) => return Err("Bit sequences are not supported".into()),
+		};
+		Ok(decoded)
+	}
+
+	/// Decode fields as an object if they are named, otherwise as an array or, for a single
+	/// field, as its value.
+	fn decode_fields(
+		&self,
+		fields: &[scale_info::Field<PortableForm>],
+		input: &mut &[u8],
+	) -> Result<Value, String> {
+		if fields.iter().all(|field| field.name().is_some()) {
+			let mut object = serde_json::Map::new();
+			for field in fields {
+				let name: &str = field.name().expect("all fields are named; qed").as_ref();
+				object.insert(name.into(), self.decode_type(field.ty().id(), input)?);
+			}
+			return Ok(Value::Object(object))
+		}
+
+		let mut values = fields
+			.iter()
+			.map(|field| self.decode_type(field.ty().id(), input))
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(if values.len() == 1 { values.remove(0) } else { Value::Array(values) })
+	}
+
+	fn decode_items(&self, id: u32, len: u32, input: &mut &[u8]) -> Result<Value, String> {
+		// Don't trust the length of sequences, sequences of zero sized items aren't supported.
+		if len as usize > input.len() {
+			return Err(format!("{} items don't fit into the value", len))
+		}
+		(0..len)
+			.map(|_| self.decode_type(id, input))
+			.collect::<Result<Vec<_>, _>>()
+			.map(Value::Array)
+	}
+
+	/// Decode a compact encoded integer, also when it is wrapped in single field composites.
+	fn decode_compact(&self, id: u32, input: &mut &[u8]) -> Result<Value, String> {
+		let ty = self.registry.resolve(id).ok_or_else(|| format!("Unknown type {}", id))?;
+		match ty.type_def() {
+			TypeDef::Composite(composite) if composite.fields().len() == 1 =>
+				self.decode_compact(composite.fields()[0].ty().id(), input),
+			TypeDef::Primitive(_) => {
+				let value = Compact::<u128>::decode(input).map_err(|e| e.to_string())?.0;
+				Ok(u64::try_from(value).map_or_else(|_| json!(value.to_string()), |v| json!(v)))
+			},
+			_ => Err(format!("Type {} can't be compact encoded", id)),
+		}
+	}
+}
+
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Value, String> {
+	fn decode<T: Decode>(input: &mut &[u8]) -> Result<T, String> {
+		T::decode(input).map_err(|e| e.to_string())
+	}
+
+	Ok(match primitive {
+		TypeDefPrimitive::Bool => json!(decode::<bool>(input)?),
+		TypeDefPrimitive::Char => {
+			let c = decode::<u32>(input)?;
+			json!(char::from_u32(c).ok_or_else(|| format!("Invalid char {}", c))?)
+		},
+		TypeDefPrimitive::Str => json!(decode::<String>(input)?),
+		TypeDefPrimitive::U8 => json!(decode::<u8>(input)?),
+		TypeDefPrimitive::U16 => json!(decode::<u16>(input)?),
+		TypeDefPrimitive::U32 => json!(decode::<u32>(input)?),
+		TypeDefPrimitive::U64 => json!(decode::<u64>(input)?),
+		TypeDefPrimitive::U128 => json!(decode::<u128>(input)?.to_string()),
+		TypeDefPrimitive::U256 => json!(to_hex(&decode::<[u8; 32]>(input)?)),
+		TypeDefPrimitive::I8 => json!(decode::<i8>(input)?),
+		TypeDefPrimitive::I16 => json!(decode::<i16>(input)?),
+		TypeDefPrimitive::I32 => json!(decode::<i32>(input)?),
+		TypeDefPrimitive::I64 => json!(decode::<i64>(input)?),
+		TypeDefPrimitive::I128 => json!(decode::<i128>(input)?.to_string()),
+		TypeDefPrimitive::I256 => json!(to_hex(&decode::<[u8; 32]>(input)?)),
+	})
+}
+
+/// Decode `value` with `decoder`, falling back to its hex encoding.
+fn decode_value(decoder: &Option<ValueDecoder>, value: &[u8]) -> Value {
+	match decoder.as_ref().map(|decoder| decoder.decode(value)) {
+		Some(Ok(decoded)) => decoded,
+		Some(Err(error)) => {
+			log::debug!("Failed to decode {}: {}", to_hex(value), error);
+			json!(to_hex(value))
+		},
+		None => json!(to_hex(value)),
+	}
+}
+
+/// The `db offchain-storage remove` command.
+#[derive(Debug, Clone, Parser)]
+#[command(group(clap::ArgGroup::new("entries").required(true).args(["key", "prefix"])))]
+pub struct RemoveOffchainStorageCmd {
+	/// The hex encoded key of the entry to remove.
+	#[arg(long, value_name = "HEX")]
+	pub key: Option<String>,
+
+	/// Remove all entries whose keys start with this hex encoded prefix.
+	#[arg(long, value_name = "HEX")]
+	pub prefix: Option<String>,
+
+	/// Skip interactive prompt by answering yes automatically.
+	#[arg(short = 'y')]
+	pub yes: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl RemoveOffchainStorageCmd {
+	/// Run the command
+	pub fn run<B: BlockT>(&self, database_config: DatabaseSource) -> error::Result<()> {
+		let keys = {
+			let storage = open_storage::<B>(database_config.clone(), true)?;
+			match (&self.key, &self.prefix) {
+				(Some(key), _) => {
+					let key = parse_hex(Some(key))?;
+					if storage.get(STORAGE_PREFIX, &key).is_none() {
+						return Err(error::Error::Input(format!(
+							"No offchain storage entry under key {}",
+							to_hex(&key)
+						)))
+					}
+					vec![key]
+				},
+				(None, prefix) => all_entries(&storage, &parse_hex(prefix.as_deref())?)?
+					.into_iter()
+					.map(|(key, _)| key)
+					.collect(),
+			}
+		};
+
+		if keys.is_empty() {
+			println!("No offchain storage entries to remove.");
+			return Ok(())
+		}
+
+		if !self.yes {
+			print!("Are you sure to remove {} offchain storage entries? [y/N]: ", keys.len());
+			io::stdout().flush().expect("failed to flush stdout");
+
+			let mut input = String::new();
+			io::stdin().read_line(&mut input)?;
+			let input = input.trim();
+
+			match input.chars().next() {
+				Some('y') | Some('Y') => {},
+				_ => {
+					println!("Aborted");
+					return Ok(())
+				},
+			}
+		}
+
+		let mut storage = open_storage::<B>(database_config, false)?;
+		for key in &keys {
+			storage.remove(STORAGE_PREFIX, key);
+		}
+		println!("{} offchain storage entries removed.", keys.len());
+		Ok(())
+	}
+}
+
+/// Open the offchain storage of the database at `database_config`.
+fn open_storage<B: BlockT>(
+	database_config: DatabaseSource,
+	read_only: bool,
+) -> error::Result<LocalStorage> {
+	let db_config = DatabaseSettings {
+		trie_cache_maximum_size: None,
+		state_pruning: None,
+		source: database_config,
+		blocks_pruning: BlocksPruning::KeepFinalized,
+	};
+	let backend = if read_only {
+		Backend::<B>::new_read_only(db_config)?
+	} else {
+		Backend::<B>::new(db_config, 0)?
+	};
+	Ok(backend
+		.offchain_storage()
+		.expect("the database backend has an offchain storage; qed"))
+}
+
+/// Read up to `count` entries whose keys start with `prefix`, following `start_key`.
+fn entries_paged(
+	storage: &LocalStorage,
+	prefix: &[u8],
+	start_key: Option<&[u8]>,
+	count: usize,
+) -> error::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+	storage.entries_paged(STORAGE_PREFIX, prefix, start_key, count).ok_or_else(|| {
+		error::Error::Input("The database backend does not support listing its entries".into())
+	})
+}
+
+/// Read all entries whose keys start with `prefix`.
+fn all_entries(storage: &LocalStorage, prefix: &[u8]) -> error::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+	let mut entries = Vec::new();
+	loop {
+		let start_key = entries.last().map(|(key, _): &(Vec<u8>, _)| key.as_slice());
+		let page = entries_paged(storage, prefix, start_key, PAGE_SIZE)?;
+		let done = page.len() < PAGE_SIZE;
+		entries.extend(page);
+		if done {
+			return Ok(entries)
+		}
+	}
+}
+
+fn parse_hex(hex: Option<&str>) -> error::Result<Vec<u8>> {
+	hex.map_or(Ok(Vec::new()), |hex| {
+		array_bytes::hex2bytes(hex).map_err(error::Error::HexDataConversion)
+	})
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+	array_bytes::bytes2hex("0x", bytes)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_runtime::testing::{Block as RawBlock, ExtrinsicWrapper};
+	use std::sync::Arc;
+
+	type Block = RawBlock<ExtrinsicWrapper<u64>>;
+
+	#[test]
+	fn list_and_remove_work() {
+		let db = Arc::new(sp_database::MemDb::new());
+		let mut storage = open_storage::<Block>(
+			DatabaseSource::Custom { db: db.clone(), require_create_flag: true },
+			false,
+		)
+		.unwrap();
+		let db = DatabaseSource::Custom { db, require_create_flag: false };
+		for key in [&b"a1"[..], b"a2", b"a3", b"b1"] {
+			storage.set(STORAGE_PREFIX, key, b"v");
+		}
+		storage.set(b"other", b"a4", b"v");
+
+		let keys = |entries: Vec<(Vec<u8>, Vec<u8>)>| {
+			entries.into_iter().map(|(key, _)| key).collect::<Vec<_>>()
+		};
+		assert_eq!(keys(entries_paged(&storage, b"a", None, 2).unwrap()), vec![b"a1", b"a2"]);
+		assert_eq!(keys(entries_paged(&storage, b"a", Some(b"a2"), 2).unwrap()), vec![b"a3"]);
+		assert_eq!(all_entries(&storage, b"").unwrap().len(), 4);
+
+		let cmd = RemoveOffchainStorageCmd::parse_from(["remove", "--prefix", "0x61", "-y"]);
+		cmd.run::<Block>(db.clone()).unwrap();
+		assert_eq!(keys(all_entries(&storage, b"").unwrap()), vec![b"b1"]);
+
+		let cmd = RemoveOffchainStorageCmd::parse_from(["remove", "--key", "0x6231", "-y"]);
+		cmd.run::<Block>(db.clone()).unwrap();
+		assert!(all_entries(&storage, b"").unwrap().is_empty());
+		assert!(RemoveOffchainStorageCmd::try_parse_from(["remove", "-y"]).is_err());
+	}
+
+	#[test]
+	fn values_are_decoded_with_metadata() {
+		use frame_metadata::{v14::ExtrinsicMetadata, RuntimeMetadataV14};
+		use parity_scale_codec::Encode;
+		use sp_runtime::Perbill;
+
+		type Tuple = (Perbill, Option<Vec<u8>>, Compact<u64>, bool);
+		let metadata: RuntimeMetadataPrefixed = RuntimeMetadataV14::new(
+			Vec::new(),
+			ExtrinsicMetadata {
+				ty: scale_info::meta_type::<()>(),
+				version: 4,
+				signed_extensions: Vec::new(),
+			},
+			scale_info::meta_type::<Tuple>(),
+		)
+		.into();
+		let metadata = metadata.encode();
+
+		let decoder = ValueDecoder::new(&metadata, "sp_arithmetic::per_things::Perbill").unwrap();
+		assert_eq!(decoder.decode(&Perbill::from_parts(5).encode()), Ok(json!(5)));
+		assert!(decoder.decode(&[0; 5]).is_err());
+		assert!(ValueDecoder::new(&metadata, "Unknown").is_err());
+
+		// The metadata can be hex encoded as well, and types given by id.
+		let decoder = ValueDecoder::new(to_hex(&metadata).as_bytes(), "0").unwrap();
+		let id = decoder
+			.registry
+			.types()
+			.iter()
+			.find(|ty| matches!(ty.ty().type_def(), TypeDef::Tuple(t) if t.fields().len() == 4))
+			.unwrap()
+			.id();
+		let decoder = ValueDecoder::new(&metadata, &id.to_string()).unwrap();
+		let value: Tuple = (Perbill::from_parts(7), Some(vec![1, 2]), Compact(1 << 40), true);
+		assert_eq!(
+			decode_value(&Some(decoder), &value.encode()),
+			json!([7, { "Some": [1, 2] }, 1u64 << 40, true]),
+		);
+		assert_eq!(decode_value(&None, &[1, 2]), json!("0x0102"));
+	}
+}
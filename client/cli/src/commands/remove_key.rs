@@ -0,0 +1,84 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of the `remove` subcommand
+
+use super::key::open_local_keystore;
+use crate::{Error, KeystoreParams, SharedParams, SubstrateCli};
+use clap::Parser;
+use sp_core::crypto::KeyTypeId;
+use std::io::{self, Write};
+
+/// The `remove` command
+#[derive(Debug, Clone, Parser)]
+#[command(name = "remove", about = "Remove a key from the keystore of a node.")]
+pub struct RemoveKeyCmd {
+	/// Key type, examples: "gran", or "imon"
+	#[arg(long)]
+	key_type: String,
+
+	/// The hex encoded public key of the key to remove.
+	#[arg(long, value_name = "HEX")]
+	public: String,
+
+	/// Skip interactive prompt by answering yes automatically.
+	#[arg(short = 'y')]
+	pub yes: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub keystore_params: KeystoreParams,
+}
+
+impl RemoveKeyCmd {
+	/// Run the command
+	pub fn run<C: SubstrateCli>(&self, cli: &C) -> Result<(), Error> {
+		let key_type =
+			KeyTypeId::try_from(self.key_type.as_str()).map_err(|_| Error::KeyTypeInvalid)?;
+		let public = array_bytes::hex2bytes(&self.public).map_err(Error::HexDataConversion)?;
+
+		let keystore = open_local_keystore(cli, &self.shared_params, &self.keystore_params)?;
+
+		if !self.yes {
+			print!("Are you sure to remove the {} key {}? [y/N]: ", self.key_type, self.public);
+			io::stdout().flush().expect("failed to flush stdout");
+
+			let mut input = String::new();
+			io::stdin().read_line(&mut input)?;
+			let input = input.trim();
+
+			match input.chars().next() {
+				Some('y') | Some('Y') => {},
+				_ => {
+					println!("Aborted");
+					return Ok(())
+				},
+			}
+		}
+
+		if keystore.remove(key_type, &public)? {
+			println!("{} key {} removed.", self.key_type, self.public);
+			Ok(())
+		} else {
+			Err(Error::Input(format!("No {} key {} in the keystore", self.key_type, self.public)))
+		}
+	}
+}
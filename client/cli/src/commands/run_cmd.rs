@@ -28,7 +28,7 @@ use crate::{
 use clap::Parser;
 use regex::Regex;
 use sc_service::{
-	config::{BasePath, PrometheusConfig, TransactionPoolOptions},
+	config::{BasePath, PrometheusConfig, RpcArchiveConfig, TransactionPoolOptions},
 	ChainSpec, Role,
 };
 use sc_telemetry::TelemetryEndpoints;
@@ -116,6 +116,15 @@ pub struct RunCmd {
 	#[arg(long)]
 	pub rpc_max_subscriptions_per_connection: Option<usize>,
 
+	/// Maximum number of results returned by a single descendant query of the `archive` RPC
+	/// methods.
+	#[arg(long, value_name = "COUNT")]
+	pub rpc_archive_max_descendant_responses: Option<usize>,
+
+	/// Maximum number of items queried by a single `archive_unstable_storage` call.
+	#[arg(long, value_name = "COUNT")]
+	pub rpc_archive_max_queried_items: Option<usize>,
+
 	/// Expose Prometheus exporter on all interfaces.
 	///
 	/// Default is local.
@@ -465,6 +474,18 @@ impl CliConfiguration for RunCmd {
 		Ok(self.rpc_max_subscriptions_per_connection)
 	}
 
+	fn rpc_archive(&self) -> Result<RpcArchiveConfig> {
+		let default = RpcArchiveConfig::default();
+		Ok(RpcArchiveConfig {
+			max_descendant_responses: self
+				.rpc_archive_max_descendant_responses
+				.unwrap_or(default.max_descendant_responses),
+			max_queried_items: self
+				.rpc_archive_max_queried_items
+				.unwrap_or(default.max_queried_items),
+		})
+	}
+
 	fn ws_max_out_buffer_capacity(&self) -> Result<Option<usize>> {
 		Ok(self.ws_max_out_buffer_capacity)
 	}
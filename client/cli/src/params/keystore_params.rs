@@ -31,8 +31,15 @@ const DEFAULT_KEYSTORE_CONFIG_PATH: &str = "keystore";
 /// Parameters of the keystore
 #[derive(Debug, Clone, Args)]
 pub struct KeystoreParams {
-	/// Specify custom URIs to connect to for keystore-services
-	#[arg(long)]
+	/// Specify custom URIs to connect to for keystore-services.
+	///
+	/// Only `unix://<PATH>` URIs of a signer listening on a Unix socket are supported, see
+	/// `sc_keystore::remote` for its protocol. All keys are kept by the signer then.
+	#[arg(
+		long,
+		value_name = "URI",
+		conflicts_with_all = &["keystore_path", "password_interactive", "password", "password_filename"]
+	)]
 	pub keystore_uri: Option<String>,
 
 	/// Specify custom keystore path.
@@ -69,6 +76,13 @@ pub fn secret_string_from_str(s: &str) -> std::result::Result<SecretString, Stri
 impl KeystoreParams {
 	/// Get the keystore configuration for the parameters
 	pub fn keystore_config(&self, config_dir: &Path) -> Result<KeystoreConfig> {
+		if let Some(ref uri) = self.keystore_uri {
+			let socket = uri.strip_prefix("unix://").ok_or_else(|| {
+				format!("Unsupported keystore URI `{}`, expected `unix://<PATH>`", uri)
+			})?;
+			return Ok(KeystoreConfig::Remote { socket: socket.into() })
+		}
+
 		let password = if self.password_interactive {
 			Some(SecretString::new(input_keystore_password()?))
 		} else if let Some(ref file) = self.password_filename {
@@ -25,7 +25,8 @@
 
 use clap::{ArgAction, Args};
 use sc_network::config::Role;
-use sc_service::config::OffchainWorkerConfig;
+use sc_service::config::{HttpHostFilter, OffchainHttpClientConfig, OffchainWorkerConfig};
+use std::{fs::File, io::BufReader, path::PathBuf, time::Duration};
 
 use crate::{error, OffchainWorkerEnabled};
 
@@ -50,6 +51,34 @@ pub struct OffchainWorkerParams {
 	/// DB during block import.
 	#[arg(long = "enable-offchain-indexing", value_name = "ENABLE_OFFCHAIN_INDEXING", default_value_t = false, action = ArgAction::Set)]
 	pub indexing_enabled: bool,
+
+	/// Only allow offchain workers to send HTTP requests to the given hosts.
+	///
+	/// A host also allows all of its subdomains.
+	#[arg(long, value_name = "HOST", num_args = 1.., conflicts_with = "offchain_http_deny")]
+	pub offchain_http_allow: Vec<String>,
+
+	/// Forbid offchain workers to send HTTP requests to the given hosts.
+	///
+	/// A host also forbids all of its subdomains. A forbidden IP address also forbids the hosts
+	/// resolving to it, but a forbidden host name can still be reached through other names or
+	/// its addresses. Use `--offchain-http-allow` to restrict the reachable hosts.
+	#[arg(long, value_name = "HOST", num_args = 1..)]
+	pub offchain_http_deny: Vec<String>,
+
+	/// File with PEM encoded root certificates offchain workers trust for HTTPS requests, in
+	/// addition to the native root certificates of the platform.
+	#[arg(long, value_name = "PATH")]
+	pub offchain_http_root_certificates: Option<PathBuf>,
+
+	/// Maximum size in bytes of the response body of an HTTP request of offchain workers.
+	#[arg(long, value_name = "BYTES")]
+	pub offchain_http_max_response_size: Option<u64>,
+
+	/// Maximum time in seconds an HTTP request of offchain workers may take, until its response
+	/// body was received.
+	#[arg(long, value_name = "SECONDS")]
+	pub offchain_http_timeout: Option<u64>,
 }
 
 impl OffchainWorkerParams {
@@ -63,6 +92,31 @@ impl OffchainWorkerParams {
 		};
 
 		let indexing_enabled = self.indexing_enabled;
-		Ok(OffchainWorkerConfig { enabled, indexing_enabled })
+
+		let host_filter = if !self.offchain_http_allow.is_empty() {
+			HttpHostFilter::Allow(self.offchain_http_allow.clone())
+		} else if !self.offchain_http_deny.is_empty() {
+			HttpHostFilter::Deny(self.offchain_http_deny.clone())
+		} else {
+			HttpHostFilter::Any
+		};
+		let root_certificates = match self.offchain_http_root_certificates {
+			Some(ref path) => {
+				let certificates = rustls_pemfile::certs(&mut BufReader::new(File::open(path)?))?;
+				if certificates.is_empty() {
+					return Err(format!("No certificates found in {}", path.display()).into())
+				}
+				certificates
+			},
+			None => Vec::new(),
+		};
+		let http_client = OffchainHttpClientConfig {
+			host_filter,
+			root_certificates,
+			max_response_body_size: self.offchain_http_max_response_size,
+			request_timeout: self.offchain_http_timeout.map(Duration::from_secs),
+		};
+
+		Ok(OffchainWorkerConfig { enabled, indexing_enabled, http_client })
 	}
 }
@@ -19,6 +19,7 @@
 use crate::error;
 use clap::Args;
 use sc_service::{BlocksPruning, PruningMode};
+use std::num::NonZeroU32;
 
 /// Parameters to define the pruning mode
 #[derive(Debug, Clone, PartialEq, Args)]
@@ -46,6 +47,11 @@ pub struct PruningParams {
 	///
 	///    Keep the state of the last number of finalized blocks.
 	///
+	///  - number:interval
+	///
+	///    Keep the state of the last number of finalized blocks and additionally the state of
+	///    every finalized block whose number is a multiple of interval. Requires ParityDB.
+	///
 	/// [default: 256]
 	#[arg(alias = "pruning", long, value_name = "PRUNING_MODE")]
 	pub state_pruning: Option<DatabasePruningMode>,
@@ -84,6 +90,12 @@ impl PruningParams {
 
 	/// Get the block pruning value from the parameters
 	pub fn blocks_pruning(&self) -> error::Result<BlocksPruning> {
+		if let DatabasePruningMode::CustomWithCheckpoints(..) = self.blocks_pruning {
+			return Err(error::Error::Input(
+				"Checkpoints are only supported for `--state-pruning`".into(),
+			))
+		}
+
 		Ok(self.blocks_pruning.into())
 	}
 }
@@ -101,6 +113,9 @@ pub enum DatabasePruningMode {
 	ArchiveCanonical,
 	/// Keep the data of the last number of finalized blocks.
 	Custom(u32),
+	/// Keep the data of the last number of finalized blocks and of every finalized block whose
+	/// number is a multiple of the given interval.
+	CustomWithCheckpoints(u32, NonZeroU32),
 }
 
 impl std::str::FromStr for DatabasePruningMode {
@@ -110,6 +125,15 @@ impl std::str::FromStr for DatabasePruningMode {
 		match input {
 			"archive" => Ok(Self::Archive),
 			"archive-canonical" => Ok(Self::ArchiveCanonical),
+			bc if bc.contains(':') => {
+				let (blocks, interval) = bc.split_once(':').expect("contains ':'; qed");
+				blocks
+					.parse()
+					.ok()
+					.zip(interval.parse().ok())
+					.map(|(blocks, interval)| Self::CustomWithCheckpoints(blocks, interval))
+					.ok_or_else(|| "Invalid pruning mode specified".to_string())
+			},
 			bc => bc
 				.parse()
 				.map_err(|_| "Invalid pruning mode specified".to_string())
@@ -124,6 +148,8 @@ impl Into<PruningMode> for DatabasePruningMode {
 			DatabasePruningMode::Archive => PruningMode::ArchiveAll,
 			DatabasePruningMode::ArchiveCanonical => PruningMode::ArchiveCanonical,
 			DatabasePruningMode::Custom(n) => PruningMode::blocks_pruning(n),
+			DatabasePruningMode::CustomWithCheckpoints(n, interval) =>
+				PruningMode::blocks_pruning_with_checkpoints(n, interval),
 		}
 	}
 }
@@ -133,7 +159,8 @@ impl Into<BlocksPruning> for DatabasePruningMode {
 		match self {
 			DatabasePruningMode::Archive => BlocksPruning::KeepAll,
 			DatabasePruningMode::ArchiveCanonical => BlocksPruning::KeepFinalized,
-			DatabasePruningMode::Custom(n) => BlocksPruning::Some(n),
+			DatabasePruningMode::Custom(n) | DatabasePruningMode::CustomWithCheckpoints(n, _) =>
+				BlocksPruning::Some(n),
 		}
 	}
 }
@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use clap::Args;
-use sc_service::config::TransactionPoolOptions;
+use sc_service::config::{PoolPriorityLane, PoolSenderLimit, TransactionPoolOptions};
 
 /// Parameters used to create the pool configuration.
 #[derive(Debug, Clone, Args)]
@@ -33,6 +33,53 @@ pub struct TransactionPoolParams {
 	/// How long a transaction is banned for, if it is considered invalid. Defaults to 1800s.
 	#[arg(long, value_name = "SECONDS")]
 	pub tx_ban_seconds: Option<u64>,
+
+	/// Maximum number of transactions of a single sender in the transaction pool.
+	///
+	/// The sender is identified by the tag a transaction provides with the nonce stripped, see
+	/// `--pool-sender-nonce-bytes`. Transactions exceeding the quota are evicted, future ones
+	/// first. Unlimited by default.
+	#[arg(long, value_name = "COUNT")]
+	pub pool_sender_limit: Option<usize>,
+
+	/// Number of trailing bytes of a provided tag that encode the nonce of the sender.
+	#[arg(long, value_name = "BYTES", default_value_t = 4)]
+	pub pool_sender_nonce_bytes: usize,
+
+	/// Reserve slots of the ready queue for transactions of at least the given priority.
+	///
+	/// Expects `MIN_PRIORITY:SLOTS` and can be passed multiple times. Transactions with a lower
+	/// priority are evicted when they would occupy the reserved slots.
+	#[arg(long, value_name = "MIN_PRIORITY:SLOTS", value_parser = parse_priority_lane)]
+	pub pool_priority_lane: Vec<PoolPriorityLane>,
+
+	/// Persist the transaction pool to the database.
+	///
+	/// Transactions are saved on shutdown and reimported on the next start, dropping the ones
+	/// that became invalid in the meantime.
+	#[arg(long)]
+	pub pool_persist: bool,
+
+	/// Additionally save the transaction pool every given number of seconds.
+	///
+	/// Only takes effect together with `--pool-persist`.
+	#[arg(long, value_name = "SECONDS", requires = "pool_persist")]
+	pub pool_persist_interval: Option<u64>,
+}
+
+/// Parse a priority lane given as `MIN_PRIORITY:SLOTS`.
+fn parse_priority_lane(input: &str) -> Result<PoolPriorityLane, String> {
+	let (min_priority, reserved) = input
+		.split_once(':')
+		.ok_or_else(|| format!("Expected MIN_PRIORITY:SLOTS, got {}", input))?;
+	Ok(PoolPriorityLane {
+		min_priority: min_priority
+			.parse()
+			.map_err(|e| format!("Invalid priority {}: {}", min_priority, e))?,
+		reserved: reserved
+			.parse()
+			.map_err(|e| format!("Invalid slot count {}: {}", reserved, e))?,
+	})
 }
 
 impl TransactionPoolParams {
@@ -49,6 +96,16 @@ impl TransactionPoolParams {
 		opts.future.count = self.pool_limit / factor;
 		opts.future.total_bytes = self.pool_kbytes * 1024 / factor;
 
+		// fairness
+		opts.sender = self
+			.pool_sender_limit
+			.map(|count| PoolSenderLimit { count, nonce_len: self.pool_sender_nonce_bytes });
+		opts.priority_lanes = self.pool_priority_lane.clone();
+
+		// persistence
+		opts.persist = self.pool_persist;
+		opts.persist_interval = self.pool_persist_interval.map(std::time::Duration::from_secs);
+
 		opts.ban_time = if let Some(ban_seconds) = self.tx_ban_seconds {
 			std::time::Duration::from_secs(ban_seconds)
 		} else if is_dev {
@@ -60,3 +117,54 @@ impl TransactionPoolParams {
 		opts
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+
+	#[derive(Parser)]
+	struct Cli {
+		#[clap(flatten)]
+		pool: TransactionPoolParams,
+	}
+
+	#[test]
+	fn priority_lanes_and_sender_limit_are_parsed() {
+		let cli = Cli::parse_from([
+			"",
+			"--pool-sender-limit",
+			"16",
+			"--pool-priority-lane",
+			"1000:64",
+			"--pool-priority-lane",
+			"5000:8",
+		]);
+		let opts = cli.pool.transaction_pool(false);
+
+		assert_eq!(opts.sender.map(|limit| (limit.count, limit.nonce_len)), Some((16, 4)));
+		assert_eq!(
+			opts.priority_lanes,
+			vec![
+				PoolPriorityLane { min_priority: 1000, reserved: 64 },
+				PoolPriorityLane { min_priority: 5000, reserved: 8 },
+			]
+		);
+		assert!(parse_priority_lane("1000").is_err());
+		assert!(parse_priority_lane("x:1").is_err());
+	}
+
+	#[test]
+	fn persistence_is_configured() {
+		let opts = Cli::parse_from([""]).pool.transaction_pool(false);
+		assert!(!opts.persist);
+
+		let opts = Cli::parse_from(["", "--pool-persist", "--pool-persist-interval", "60"])
+			.pool
+			.transaction_pool(false);
+		assert!(opts.persist);
+		assert_eq!(opts.persist_interval, Some(std::time::Duration::from_secs(60)));
+
+		assert!(Cli::try_parse_from(["", "--pool-persist-interval", "60"]).is_err());
+	}
+}
@@ -298,6 +298,7 @@ mod tests {
 				rpc_max_response_size: None,
 				rpc_id_provider: None,
 				rpc_max_subs_per_conn: None,
+				rpc_archive: Default::default(),
 				ws_max_out_buffer_capacity: None,
 				prometheus_config: None,
 				telemetry_endpoints: None,
@@ -0,0 +1,180 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Batched sealing of pool imports with an optional empty-block heartbeat.
+
+use crate::rpc::EngineCommand;
+use futures::{prelude::*, task::Poll};
+use futures_timer::Delay;
+use std::time::Duration;
+
+/// Configuration of the batch sealing engine.
+#[derive(Debug, Clone)]
+pub struct BatchSealConfig {
+	/// Seal as soon as this many transactions were imported since the last block.
+	pub max_transactions: usize,
+	/// Seal once this long has passed since the first transaction of a batch was imported.
+	pub max_wait: Duration,
+	/// Seal an empty block if no block was sealed for this long.
+	pub empty_block_interval: Option<Duration>,
+	/// Finalize the sealed blocks immediately.
+	pub finalize: bool,
+}
+
+struct BatchState<S> {
+	imports: stream::Fuse<S>,
+	config: BatchSealConfig,
+	pending: usize,
+	window: Option<Delay>,
+	heartbeat: Option<Delay>,
+}
+
+impl<S: Stream + Unpin> BatchState<S> {
+	/// Resolves to `Some(create_empty)` whenever a block should be sealed.
+	fn poll_seal(&mut self, cx: &mut std::task::Context) -> Poll<Option<bool>> {
+		loop {
+			match self.imports.poll_next_unpin(cx) {
+				Poll::Ready(Some(_)) => {
+					self.pending += 1;
+					if self.pending >= self.config.max_transactions {
+						return self.seal(false)
+					}
+					if self.window.is_none() {
+						self.window = Some(Delay::new(self.config.max_wait));
+					}
+				},
+				Poll::Ready(None) => return Poll::Ready(None),
+				Poll::Pending => break,
+			}
+		}
+
+		if self.window.as_mut().map_or(false, |window| window.poll_unpin(cx).is_ready()) {
+			return self.seal(false)
+		}
+		if self
+			.heartbeat
+			.as_mut()
+			.map_or(false, |heartbeat| heartbeat.poll_unpin(cx).is_ready())
+		{
+			return self.seal(true)
+		}
+		Poll::Pending
+	}
+
+	fn seal(&mut self, create_empty: bool) -> Poll<Option<bool>> {
+		self.pending = 0;
+		self.window = None;
+		self.heartbeat = self.config.empty_block_interval.map(Delay::new);
+		Poll::Ready(Some(create_empty))
+	}
+}
+
+/// Turns a stream of transaction pool imports into a stream of seal commands.
+///
+/// Imports are collected until either `max_transactions` are pending or `max_wait` passed since
+/// the first one, and then sealed into a single block. If `empty_block_interval` is set, an empty
+/// block is sealed whenever no block was sealed for that long.
+pub fn batch_seal_commands<S, Hash>(
+	imports: S,
+	config: BatchSealConfig,
+) -> impl Stream<Item = EngineCommand<Hash>>
+where
+	S: Stream + Unpin,
+{
+	let finalize = config.finalize;
+	let mut state = BatchState {
+		imports: imports.fuse(),
+		heartbeat: config.empty_block_interval.map(Delay::new),
+		config,
+		pending: 0,
+		window: None,
+	};
+
+	stream::poll_fn(move |cx| {
+		state.poll_seal(cx).map(|seal| {
+			seal.map(|create_empty| EngineCommand::SealNewBlock {
+				create_empty,
+				finalize,
+				parent_hash: None,
+				sender: None,
+			})
+		})
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::channel::mpsc;
+	use sp_core::H256;
+	use std::time::Instant;
+
+	fn config(max_transactions: usize, max_wait: u64, empty: Option<u64>) -> BatchSealConfig {
+		BatchSealConfig {
+			max_transactions,
+			max_wait: Duration::from_millis(max_wait),
+			empty_block_interval: empty.map(Duration::from_millis),
+			finalize: false,
+		}
+	}
+
+	fn create_empty(command: Option<EngineCommand<H256>>) -> bool {
+		match command {
+			Some(EngineCommand::SealNewBlock { create_empty, .. }) => create_empty,
+			_ => panic!("expected a seal command"),
+		}
+	}
+
+	#[tokio::test]
+	async fn seals_once_batch_is_full() {
+		let (imports, rx) = mpsc::unbounded::<()>();
+		let mut commands = Box::pin(batch_seal_commands::<_, H256>(rx, config(3, 60_000, None)));
+
+		for _ in 0..3 {
+			imports.unbounded_send(()).unwrap();
+		}
+		assert!(!create_empty(commands.next().await));
+
+		imports.unbounded_send(()).unwrap();
+		assert!(commands.next().now_or_never().is_none());
+	}
+
+	#[tokio::test]
+	async fn seals_partial_batch_after_max_wait() {
+		let (imports, rx) = mpsc::unbounded::<()>();
+		let mut commands = Box::pin(batch_seal_commands::<_, H256>(rx, config(100, 50, None)));
+
+		let started = Instant::now();
+		imports.unbounded_send(()).unwrap();
+		assert!(!create_empty(commands.next().await));
+		assert!(started.elapsed() >= Duration::from_millis(50));
+	}
+
+	#[tokio::test]
+	async fn seals_empty_blocks_when_idle() {
+		let (imports, rx) = mpsc::unbounded::<()>();
+		let mut commands =
+			Box::pin(batch_seal_commands::<_, H256>(rx, config(100, 60_000, Some(20))));
+
+		assert!(create_empty(commands.next().await));
+		assert!(create_empty(commands.next().await));
+
+		drop(imports);
+		assert!(commands.next().await.is_none());
+	}
+}
@@ -33,10 +33,52 @@ use sp_inherents::{InherentData, InherentDataProvider, InherentIdentifier};
 use sp_runtime::traits::{Block as BlockT, Zero};
 use sp_timestamp::{InherentType, INHERENT_IDENTIFIER};
 use std::{
-	sync::{atomic, Arc},
+	sync::{atomic, Arc, Mutex},
 	time::SystemTime,
 };
 
+/// Handle to move the time of the [`SlotTimestampProvider`] forward.
+///
+/// Adjustments are applied to the next block only, but carry over to the blocks built on top of
+/// it, since the provider derives its time from the slot of the best block. The handle is
+/// shared with [`ManualSeal`](crate::rpc::ManualSeal) to expose it over rpc.
+#[derive(Clone, Default)]
+pub struct TimeTravel(Arc<Mutex<Adjustment>>);
+
+#[derive(Default)]
+struct Adjustment {
+	// unix millisecond timestamp to use for the next block
+	next_timestamp: Option<u64>,
+	// milliseconds to add to the time of the next block
+	increase: u64,
+}
+
+impl TimeTravel {
+	/// Use the given unix millisecond timestamp for the next block.
+	///
+	/// The timestamp has to belong to a later slot than the best block, otherwise the block
+	/// will be rejected by the runtime.
+	pub fn set_next_timestamp(&self, unix_millis: u64) {
+		let mut adjustment = self.0.lock().expect("lock is never poisoned; qed");
+		*adjustment = Adjustment { next_timestamp: Some(unix_millis), increase: 0 };
+	}
+
+	/// Move the time of the next block forward by the given number of milliseconds.
+	pub fn increase_time(&self, millis: u64) {
+		let mut adjustment = self.0.lock().expect("lock is never poisoned; qed");
+		adjustment.increase = adjustment.increase.saturating_add(millis);
+	}
+
+	/// Applies and clears the pending adjustment.
+	fn apply(&self, unix_millis: u64) -> u64 {
+		let adjustment = std::mem::take(&mut *self.0.lock().expect("lock is never poisoned; qed"));
+		adjustment
+			.next_timestamp
+			.unwrap_or(unix_millis)
+			.saturating_add(adjustment.increase)
+	}
+}
+
 /// Provide duration since unix epoch in millisecond for timestamp inherent.
 /// Mocks the timestamp inherent to always produce a valid timestamp for the next slot.
 ///
@@ -122,6 +164,14 @@ impl SlotTimestampProvider {
 		Ok(time)
 	}
 
+	/// Apply the time adjustments requested through the given handle.
+	///
+	/// Should be called on the provider created for the next block, before it is used.
+	pub fn with_time_travel(self, time_travel: &TimeTravel) -> Self {
+		let unix_millis = time_travel.apply(self.unix_millis.into_inner());
+		Self { unix_millis: atomic::AtomicU64::new(unix_millis), ..self }
+	}
+
 	/// Get the current slot number
 	pub fn slot(&self) -> Slot {
 		Slot::from_timestamp(
@@ -159,3 +209,40 @@ impl InherentDataProvider for SlotTimestampProvider {
 		None
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn provider_at(unix_millis: u64) -> SlotTimestampProvider {
+		SlotTimestampProvider {
+			unix_millis: atomic::AtomicU64::new(unix_millis),
+			slot_duration: SlotDuration::from_millis(1000),
+		}
+	}
+
+	#[test]
+	fn time_travel_adjusts_next_block_only() {
+		let time_travel = TimeTravel::default();
+
+		time_travel.increase_time(5_000);
+		time_travel.increase_time(1_000);
+		let provider = provider_at(10_000).with_time_travel(&time_travel);
+		assert_eq!(provider.timestamp(), sp_timestamp::Timestamp::new(16_000));
+		assert_eq!(provider.slot(), Slot::from(16));
+
+		// the adjustment was consumed.
+		assert_eq!(
+			provider_at(10_000).with_time_travel(&time_travel).timestamp(),
+			sp_timestamp::Timestamp::new(10_000)
+		);
+
+		time_travel.increase_time(5_000);
+		time_travel.set_next_timestamp(42_000);
+		time_travel.increase_time(1_000);
+		assert_eq!(
+			provider_at(10_000).with_time_travel(&time_travel).timestamp(),
+			sp_timestamp::Timestamp::new(43_000)
+		);
+	}
+}
@@ -38,6 +38,7 @@ mod codes {
 	pub const CONSENSUS_ERROR: i32 = 14_000;
 	pub const INHERENTS_ERROR: i32 = 15_000;
 	pub const BLOCKCHAIN_ERROR: i32 = 16_000;
+	pub const NOT_SUPPORTED: i32 = 17_000;
 	pub const UNKNOWN_ERROR: i32 = 20_000;
 }
 
@@ -64,6 +65,9 @@ pub enum Error {
 	/// Supplied parent_hash doesn't exist in chain
 	#[error("Supplied parent_hash: {0} doesn't exist in chain")]
 	BlockNotFound(String),
+	/// The requested operation was not enabled for this engine
+	#[error("{0} is not supported by this node")]
+	NotSupported(&'static str),
 	/// Some string error
 	#[error("{0}")]
 	StringError(String),
@@ -100,6 +104,7 @@ impl Error {
 			ConsensusError(_) => codes::CONSENSUS_ERROR,
 			InherentError(_) => codes::INHERENTS_ERROR,
 			BlockchainError(_) => codes::BLOCKCHAIN_ERROR,
+			NotSupported(_) => codes::NOT_SUPPORTED,
 			SendError(_) | Canceled(_) => codes::SERVER_SHUTTING_DOWN,
 			_ => codes::UNKNOWN_ERROR,
 		}
@@ -32,17 +32,21 @@ use sp_inherents::CreateInherentDataProviders;
 use sp_runtime::{traits::Block as BlockT, ConsensusEngineId};
 use std::{marker::PhantomData, sync::Arc};
 
+mod batch_seal;
 mod error;
 mod finalize_block;
+mod revert_block;
 mod seal_block;
 
 pub mod consensus;
 pub mod rpc;
 
 pub use self::{
+	batch_seal::{batch_seal_commands, BatchSealConfig},
 	consensus::ConsensusDataProvider,
 	error::Error,
 	finalize_block::{finalize_block, FinalizeBlockParams},
+	revert_block::{revert_block, RevertBlockParams},
 	rpc::{CreatedBlock, EngineCommand},
 	seal_block::{seal_block, SealBlockParams, MAX_PROPOSAL_DURATION},
 };
@@ -83,7 +87,7 @@ where
 }
 
 /// Params required to start the instant sealing authorship task.
-pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, CS, CIDP, P> {
+pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, CB, TP, SC, CS, CIDP, P> {
 	/// Block import instance for well. importing blocks.
 	pub block_import: BI,
 
@@ -93,6 +97,9 @@ pub struct ManualSealParams<B: BlockT, BI, E, C: ProvideRuntimeApi<B>, TP, SC, C
 	/// Client instance
 	pub client: Arc<C>,
 
+	/// Backend to revert blocks in, `EngineCommand::RevertTo` is rejected if not given.
+	pub backend: Option<Arc<CB>>,
+
 	/// Shared reference to the transaction pool.
 	pub pool: Arc<TP>,
 
@@ -142,12 +149,13 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 		mut block_import,
 		mut env,
 		client,
+		backend,
 		pool,
 		mut commands_stream,
 		select_chain,
 		consensus_data_provider,
 		create_inherent_data_providers,
-	}: ManualSealParams<B, BI, E, C, TP, SC, CS, CIDP, P>,
+	}: ManualSealParams<B, BI, E, C, CB, TP, SC, CS, CIDP, P>,
 ) where
 	B: BlockT + 'static,
 	BI: BlockImport<B, Error = sp_consensus::Error, Transaction = sp_api::TransactionFor<C, B>>
@@ -194,6 +202,17 @@ pub async fn run_manual_seal<B, BI, CB, E, C, TP, SC, CS, CIDP, P>(
 				})
 				.await
 			},
+			EngineCommand::RevertTo { hash, mut sender } => match backend {
+				Some(ref backend) =>
+					revert_block(RevertBlockParams {
+						hash,
+						sender,
+						client: client.clone(),
+						backend: backend.clone(),
+					})
+					.await,
+				None => rpc::send_result(&mut sender, Err(Error::NotSupported("Reverting blocks"))),
+			},
 		}
 	}
 }
@@ -240,6 +259,7 @@ pub async fn run_instant_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		block_import,
 		env,
 		client,
+		backend: None,
 		pool,
 		commands_stream,
 		select_chain,
@@ -294,6 +314,7 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 		block_import,
 		env,
 		client,
+		backend: None,
 		pool,
 		commands_stream,
 		select_chain,
@@ -303,6 +324,52 @@ pub async fn run_instant_seal_and_finalize<B, BI, CB, E, C, TP, SC, CIDP, P>(
 	.await
 }
 
+/// Runs the background authorship task for the batch seal engine.
+/// batch-seal collects the transactions imported into the transaction pool
+/// and seals them into a single block, see [`BatchSealConfig`] for when.
+pub async fn run_batch_seal<B, BI, CB, E, C, TP, SC, CIDP, P>(
+	InstantSealParams {
+		block_import,
+		env,
+		client,
+		pool,
+		select_chain,
+		consensus_data_provider,
+		create_inherent_data_providers,
+	}: InstantSealParams<B, BI, E, C, TP, SC, CIDP, P>,
+	config: BatchSealConfig,
+) where
+	B: BlockT + 'static,
+	BI: BlockImport<B, Error = sp_consensus::Error, Transaction = sp_api::TransactionFor<C, B>>
+		+ Send
+		+ Sync
+		+ 'static,
+	C: HeaderBackend<B> + Finalizer<B, CB> + ProvideRuntimeApi<B> + 'static,
+	CB: ClientBackend<B> + 'static,
+	E: Environment<B> + 'static,
+	E::Proposer: Proposer<B, Proof = P, Transaction = TransactionFor<C, B>>,
+	SC: SelectChain<B> + 'static,
+	TransactionFor<C, B>: 'static,
+	TP: TransactionPool<Block = B>,
+	CIDP: CreateInherentDataProviders<B, ()>,
+	P: Send + Sync + 'static,
+{
+	let commands_stream = batch_seal_commands(pool.import_notification_stream(), config);
+
+	run_manual_seal(ManualSealParams {
+		block_import,
+		env,
+		client,
+		backend: None,
+		pool,
+		commands_stream: Box::pin(commands_stream),
+		select_chain,
+		consensus_data_provider,
+		create_inherent_data_providers,
+	})
+	.await
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -393,6 +460,7 @@ mod tests {
 			block_import: client.clone(),
 			env,
 			client: client.clone(),
+			backend: None,
 			pool: pool.clone(),
 			commands_stream,
 			select_chain,
@@ -454,6 +522,7 @@ mod tests {
 			block_import: client.clone(),
 			env,
 			client: client.clone(),
+			backend: None,
 			pool: pool.clone(),
 			commands_stream,
 			select_chain,
@@ -539,6 +608,7 @@ mod tests {
 			block_import: client.clone(),
 			env,
 			client: client.clone(),
+			backend: None,
 			pool: pool.clone(),
 			commands_stream,
 			select_chain,
@@ -644,6 +714,7 @@ mod tests {
 			block_import: client.clone(),
 			env,
 			client: client.clone(),
+			backend: None,
 			pool: pool.clone(),
 			commands_stream,
 			select_chain,
@@ -670,4 +741,88 @@ mod tests {
 		let header = client.header(created_block.hash).unwrap().unwrap();
 		assert_eq!(header.number, 1);
 	}
+
+	#[tokio::test]
+	async fn manual_seal_revert_to() {
+		let builder = TestClientBuilder::new();
+		let backend = builder.backend();
+		let (client, select_chain) = builder.build_with_longest_chain();
+		let client = Arc::new(client);
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.info().genesis_hash;
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			api(),
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let env = ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None);
+
+		let (mut sink, commands_stream) = futures::channel::mpsc::channel(1024);
+		let future = run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env,
+			client: client.clone(),
+			backend: Some(backend),
+			pool: pool.clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: |_, _| async { Ok(()) },
+		});
+		std::thread::spawn(|| {
+			let rt = tokio::runtime::Runtime::new().unwrap();
+			rt.block_on(future);
+		});
+
+		let mut hashes = vec![];
+		for finalize in [true, false, false] {
+			let (tx, rx) = futures::channel::oneshot::channel();
+			sink.send(EngineCommand::SealNewBlock {
+				parent_hash: None,
+				sender: Some(tx),
+				create_empty: true,
+				finalize,
+			})
+			.await
+			.unwrap();
+			hashes.push(rx.await.unwrap().unwrap().hash);
+		}
+		assert_eq!(client.info().best_number, 3);
+
+		// blocks older than the finalized one can't be reverted.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::RevertTo { hash: genesis_hash, sender: Some(tx) })
+			.await
+			.unwrap();
+		assert_matches::assert_matches!(rx.await.unwrap(), Err(Error::StringError(_)));
+		assert_eq!(client.info().best_number, 3);
+
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::RevertTo { hash: hashes[0], sender: Some(tx) })
+			.await
+			.unwrap();
+		rx.await.unwrap().unwrap();
+		assert_eq!(client.info().best_hash, hashes[0]);
+
+		// new blocks are built on top of the reverted chain.
+		let (tx, rx) = futures::channel::oneshot::channel();
+		sink.send(EngineCommand::SealNewBlock {
+			parent_hash: None,
+			sender: Some(tx),
+			create_empty: true,
+			finalize: false,
+		})
+		.await
+		.unwrap();
+		let created_block = rx.await.unwrap().unwrap();
+		let header = client.header(created_block.hash).unwrap().unwrap();
+		assert_eq!(header.number, 2);
+		assert_eq!(header.parent_hash, hashes[0]);
+	}
 }
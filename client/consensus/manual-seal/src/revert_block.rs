@@ -0,0 +1,81 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Block reverting utilities
+
+use crate::{rpc, Error};
+use sc_client_api::backend::Backend as ClientBackend;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use std::sync::Arc;
+
+/// params for reverting blocks.
+pub struct RevertBlockParams<B: BlockT, C, CB> {
+	/// hash of the block to revert to, it becomes the new best block.
+	pub hash: <B as BlockT>::Hash,
+	/// sender to report errors/success to the rpc.
+	pub sender: rpc::Sender<()>,
+	/// header backend
+	pub client: Arc<C>,
+	/// backend the blocks are reverted in.
+	pub backend: Arc<CB>,
+}
+
+/// reverts the best chain back to the block with the given params.
+///
+/// The block has to be part of the best chain and must not be older than the last finalized
+/// block. Transactions of the reverted blocks are not resubmitted to the transaction pool.
+pub async fn revert_block<B, C, CB>(params: RevertBlockParams<B, C, CB>)
+where
+	B: BlockT,
+	C: HeaderBackend<B>,
+	CB: ClientBackend<B>,
+{
+	let RevertBlockParams { hash, mut sender, client, backend } = params;
+
+	match revert_to(hash, &*client, &*backend) {
+		Err(e) => {
+			log::warn!("Failed to revert to block {}: {}", hash, e);
+			rpc::send_result(&mut sender, Err(e))
+		},
+		Ok(reverted) => {
+			log::info!("⏪ Reverted {} blocks, new best block: {}", reverted, hash);
+			rpc::send_result(&mut sender, Ok(()))
+		},
+	}
+}
+
+fn revert_to<B, C, CB>(hash: B::Hash, client: &C, backend: &CB) -> Result<NumberFor<B>, Error>
+where
+	B: BlockT,
+	C: HeaderBackend<B>,
+	CB: ClientBackend<B>,
+{
+	let info = client.info();
+	let number = client.number(hash)?.ok_or_else(|| Error::BlockNotFound(hash.to_string()))?;
+
+	if client.hash(number)? != Some(hash) {
+		return Err(Error::StringError(format!("Block {} is not part of the best chain", hash)))
+	}
+	if number < info.finalized_number {
+		return Err(Error::StringError(format!("Block {} is older than the finalized block", hash)))
+	}
+
+	let (reverted, _) = backend.revert(info.best_number - number, false)?;
+	Ok(reverted)
+}
@@ -18,7 +18,7 @@
 
 //! RPC interface for the `ManualSeal` Engine.
 
-use crate::error::Error;
+use crate::{consensus::timestamp::TimeTravel, error::Error};
 use futures::{
 	channel::{mpsc, oneshot},
 	SinkExt,
@@ -62,6 +62,13 @@ pub enum EngineCommand<Hash> {
 		/// finalization justification
 		justification: Option<EncodedJustification>,
 	},
+	/// Tells the engine to revert the best chain back to the block with the supplied hash
+	RevertTo {
+		/// hash of the block, it becomes the new best block
+		hash: Hash,
+		/// sender to report errors/success to the rpc.
+		sender: Sender<()>,
+	},
 }
 
 /// RPC trait that provides methods for interacting with the manual-seal authorship task over rpc.
@@ -83,11 +90,24 @@ pub trait ManualSealApi<Hash> {
 		hash: Hash,
 		justification: Option<EncodedJustification>,
 	) -> RpcResult<bool>;
+
+	/// Instructs the manual-seal authorship task to revert all blocks on top of the given one
+	#[method(name = "engine_revertTo")]
+	async fn revert_to(&self, hash: Hash) -> RpcResult<bool>;
+
+	/// Sets the unix millisecond timestamp of the next block
+	#[method(name = "engine_setNextTimestamp")]
+	fn set_next_timestamp(&self, timestamp: u64) -> RpcResult<bool>;
+
+	/// Moves the timestamp of the next block forward by the given number of milliseconds
+	#[method(name = "engine_increaseTime")]
+	fn increase_time(&self, millis: u64) -> RpcResult<bool>;
 }
 
 /// A struct that implements the [`ManualSealApiServer`].
 pub struct ManualSeal<Hash> {
 	import_block_channel: mpsc::Sender<EngineCommand<Hash>>,
+	time_travel: Option<TimeTravel>,
 }
 
 /// return type of `engine_createBlock`
@@ -102,7 +122,22 @@ pub struct CreatedBlock<Hash> {
 impl<Hash> ManualSeal<Hash> {
 	/// Create new `ManualSeal` with the given reference to the client.
 	pub fn new(import_block_channel: mpsc::Sender<EngineCommand<Hash>>) -> Self {
-		Self { import_block_channel }
+		Self { import_block_channel, time_travel: None }
+	}
+
+	/// Enable `engine_setNextTimestamp` and `engine_increaseTime`.
+	///
+	/// The same handle has to be applied to the
+	/// [`SlotTimestampProvider`](crate::consensus::timestamp::SlotTimestampProvider) of every
+	/// block, see
+	/// [`with_time_travel`](crate::consensus::timestamp::SlotTimestampProvider::with_time_travel).
+	pub fn with_time_travel(mut self, time_travel: TimeTravel) -> Self {
+		self.time_travel = Some(time_travel);
+		self
+	}
+
+	fn time_travel(&self) -> Result<&TimeTravel, Error> {
+		self.time_travel.as_ref().ok_or(Error::NotSupported("Time travel"))
 	}
 }
 
@@ -144,6 +179,29 @@ impl<Hash: Send + 'static> ManualSealApiServer<Hash> for ManualSeal<Hash> {
 		sink.send(command).await?;
 		receiver.await.map(|_| true).map_err(|e| JsonRpseeError::to_call_error(e))
 	}
+
+	async fn revert_to(&self, hash: Hash) -> RpcResult<bool> {
+		let mut sink = self.import_block_channel.clone();
+		let (sender, receiver) = oneshot::channel();
+		let command = EngineCommand::RevertTo { hash, sender: Some(sender) };
+		sink.send(command).await?;
+
+		match receiver.await {
+			Ok(Ok(())) => Ok(true),
+			Ok(Err(e)) => Err(e.into()),
+			Err(e) => Err(JsonRpseeError::to_call_error(e)),
+		}
+	}
+
+	fn set_next_timestamp(&self, timestamp: u64) -> RpcResult<bool> {
+		self.time_travel()?.set_next_timestamp(timestamp);
+		Ok(true)
+	}
+
+	fn increase_time(&self, millis: u64) -> RpcResult<bool> {
+		self.time_travel()?.increase_time(millis);
+		Ok(true)
+	}
 }
 
 /// report any errors or successes encountered by the authorship task back
@@ -72,6 +72,8 @@ struct KeyTracker {
 	/// We track the total number of reads and writes to these keys,
 	/// not de-duplicated for repeats.
 	child_keys: LinkedHashMap<Vec<u8>, LinkedHashMap<Vec<u8>, TrackedStorageKey>>,
+	/// The size of the value that was read per key, keyed by the child trie (if any) and the key.
+	read_sizes: HashMap<(Option<Vec<u8>>, Vec<u8>), u32>,
 }
 
 /// State that manages the backend database reference. Allows runtime to control the database.
@@ -83,6 +85,8 @@ pub struct BenchmarkingState<B: BlockT> {
 	genesis: HashMap<Vec<u8>, (Vec<u8>, i32)>,
 	record: Cell<Vec<Vec<u8>>>,
 	key_tracker: Arc<Mutex<KeyTracker>>,
+	/// The size of the largest value read per key prefix, across all reported benchmark runs.
+	read_value_sizes: RefCell<HashMap<Vec<u8>, u32>>,
 	whitelist: RefCell<Vec<TrackedStorageKey>>,
 	proof_recorder: Option<sp_trie::recorder::Recorder<HashFor<B>>>,
 	proof_recorder_root: Cell<B::Hash>,
@@ -151,8 +155,10 @@ impl<B: BlockT> BenchmarkingState<B> {
 			key_tracker: Arc::new(Mutex::new(KeyTracker {
 				main_keys: Default::default(),
 				child_keys: Default::default(),
+				read_sizes: Default::default(),
 				enable_tracking,
 			})),
+			read_value_sizes: Default::default(),
 			whitelist: Default::default(),
 			proof_recorder: record_proof.then(Default::default),
 			proof_recorder_root: Cell::new(root),
@@ -211,6 +217,7 @@ impl<B: BlockT> BenchmarkingState<B> {
 		let mut key_tracker = self.key_tracker.lock();
 		key_tracker.main_keys = LinkedHashMap::new();
 		key_tracker.child_keys = LinkedHashMap::new();
+		key_tracker.read_sizes = HashMap::new();
 		key_tracker.add_whitelist(&self.whitelist.borrow());
 	}
 
@@ -222,9 +229,52 @@ impl<B: BlockT> BenchmarkingState<B> {
 		self.key_tracker.lock().add_write_key(childtrie, key);
 	}
 
+	fn add_read_size(&self, child_info: Option<&ChildInfo>, key: &[u8], value: Option<&[u8]>) {
+		if let Some(value) = value {
+			self.key_tracker.lock().add_read_size(
+				child_info.map(|c| c.storage_key()),
+				key,
+				value.len() as u32,
+			);
+		}
+	}
+
+	/// Track the size of a value that is accessed without being returned, e.g. by
+	/// `storage_hash`.
+	///
+	/// The value is read from a state without proof recorder, so the recorded proof size is not
+	/// affected by it.
+	fn add_unread_size(&self, child_info: Option<&ChildInfo>, key: &[u8]) -> Result<(), String> {
+		if !self.key_tracker.lock().enable_tracking {
+			return Ok(())
+		}
+		let db = match self.db.take() {
+			Some(db) => db,
+			None => return Ok(()),
+		};
+		self.db.set(Some(db.clone()));
+		let storage_db = Arc::new(StorageDb::<B> { db, _block: Default::default() });
+		let state = DbStateBuilder::<B>::new(storage_db, self.root.get()).build();
+		let value = match child_info {
+			Some(child_info) => state.child_storage(child_info, key)?,
+			None => state.storage(key)?,
+		};
+		self.add_read_size(child_info, key, value.as_deref());
+		Ok(())
+	}
+
 	fn all_trackers(&self) -> Vec<TrackedStorageKey> {
 		self.key_tracker.lock().all_trackers()
 	}
+
+	/// Take the size of the largest value that was read per key prefix since the last call.
+	///
+	/// Only the reads that were reported by `get_read_and_written_keys` are considered, which
+	/// excludes the setup of a benchmark. The prefixes are the same as the ones reported there,
+	/// and thus also cover the keys of child tries.
+	pub fn take_read_value_sizes(&self) -> HashMap<Vec<u8>, u32> {
+		self.read_value_sizes.take()
+	}
 }
 
 impl KeyTracker {
@@ -277,6 +327,19 @@ impl KeyTracker {
 		}
 	}
 
+	// Childtrie is identified by its storage key (i.e. `ChildInfo::storage_key`)
+	fn add_read_size(&mut self, childtrie: Option<&[u8]>, key: &[u8], size: u32) {
+		if !self.enable_tracking {
+			return
+		}
+
+		let read_size = self
+			.read_sizes
+			.entry((childtrie.map(|c| c.to_vec()), key.to_vec()))
+			.or_default();
+		*read_size = (*read_size).max(size);
+	}
+
 	// Childtrie is identified by its storage key (i.e. `ChildInfo::storage_key`)
 	fn add_write_key(&mut self, childtrie: Option<&[u8]>, key: &[u8]) {
 		if !self.enable_tracking {
@@ -349,11 +412,14 @@ impl<B: BlockT> StateBackend<HashFor<B>> for BenchmarkingState<B> {
 
 	fn storage(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
 		self.add_read_key(None, key);
-		self.state.borrow().as_ref().ok_or_else(state_err)?.storage(key)
+		let value = self.state.borrow().as_ref().ok_or_else(state_err)?.storage(key)?;
+		self.add_read_size(None, key, value.as_deref());
+		Ok(value)
 	}
 
 	fn storage_hash(&self, key: &[u8]) -> Result<Option<B::Hash>, Self::Error> {
 		self.add_read_key(None, key);
+		self.add_unread_size(None, key)?;
 		self.state.borrow().as_ref().ok_or_else(state_err)?.storage_hash(key)
 	}
 
@@ -363,11 +429,14 @@ impl<B: BlockT> StateBackend<HashFor<B>> for BenchmarkingState<B> {
 		key: &[u8],
 	) -> Result<Option<Vec<u8>>, Self::Error> {
 		self.add_read_key(Some(child_info.storage_key()), key);
-		self.state
+		let value = self
+			.state
 			.borrow()
 			.as_ref()
 			.ok_or_else(state_err)?
-			.child_storage(child_info, key)
+			.child_storage(child_info, key)?;
+		self.add_read_size(Some(child_info), key, value.as_deref());
+		Ok(value)
 	}
 
 	fn child_storage_hash(
@@ -376,6 +445,7 @@ impl<B: BlockT> StateBackend<HashFor<B>> for BenchmarkingState<B> {
 		key: &[u8],
 	) -> Result<Option<B::Hash>, Self::Error> {
 		self.add_read_key(Some(child_info.storage_key()), key);
+		self.add_unread_size(Some(child_info), key)?;
 		self.state
 			.borrow()
 			.as_ref()
@@ -385,6 +455,7 @@ impl<B: BlockT> StateBackend<HashFor<B>> for BenchmarkingState<B> {
 
 	fn exists_storage(&self, key: &[u8]) -> Result<bool, Self::Error> {
 		self.add_read_key(None, key);
+		self.add_unread_size(None, key)?;
 		self.state.borrow().as_ref().ok_or_else(state_err)?.exists_storage(key)
 	}
 
@@ -394,6 +465,7 @@ impl<B: BlockT> StateBackend<HashFor<B>> for BenchmarkingState<B> {
 		key: &[u8],
 	) -> Result<bool, Self::Error> {
 		self.add_read_key(Some(child_info.storage_key()), key);
+		self.add_unread_size(Some(child_info), key)?;
 		self.state
 			.borrow()
 			.as_ref()
@@ -572,6 +644,20 @@ impl<B: BlockT> StateBackend<HashFor<B>> for BenchmarkingState<B> {
 		// TODO: Refactor to enable full storage key transparency, where we can remove the
 		// `prefix_key_tracker`.
 		let mut prefix_key_tracker = LinkedHashMap::<Vec<u8>, (u32, u32, bool)>::new();
+		{
+			let key_tracker = self.key_tracker.lock();
+			let mut read_value_sizes = self.read_value_sizes.borrow_mut();
+			key_tracker.read_sizes.iter().for_each(|((childtrie, key), size)| {
+				// Only keys of the main trie can be whitelisted.
+				let whitelisted = childtrie.is_none() &&
+					key_tracker.main_keys.get(key).map_or(false, |t| t.whitelisted);
+				if !whitelisted {
+					let prefix = key[0..key.len().min(32)].to_vec();
+					let max_size = read_value_sizes.entry(prefix).or_default();
+					*max_size = (*max_size).max(*size);
+				}
+			});
+		}
 		self.all_trackers().iter().for_each(|tracker| {
 			if !tracker.whitelisted {
 				let prefix_length = tracker.key.len().min(32);
@@ -648,6 +734,7 @@ impl<Block: BlockT> std::fmt::Debug for BenchmarkingState<Block> {
 #[cfg(test)]
 mod test {
 	use crate::bench::BenchmarkingState;
+	use sp_core::storage::ChildInfo;
 	use sp_state_machine::backend::Backend as _;
 
 	fn hex(hex: &str) -> Vec<u8> {
@@ -708,4 +795,69 @@ mod test {
 			bench_state.wipe().unwrap();
 		}
 	}
+
+	#[test]
+	fn read_value_sizes_are_tracked_per_prefix() {
+		let storage = sp_runtime::Storage {
+			top: vec![
+				(vec![1; 40], vec![0; 10]),
+				(vec![1; 48], vec![0; 20]),
+				(vec![2; 40], vec![0; 30]),
+			]
+			.into_iter()
+			.collect(),
+			..sp_runtime::Storage::default()
+		};
+		let bench_state =
+			BenchmarkingState::<crate::tests::Block>::new(storage, None, false, true).unwrap();
+
+		// Reads before the tracker is reset are not reported.
+		bench_state.storage(&[2; 40]).unwrap();
+		bench_state.reset_read_write_count();
+		bench_state.storage(&[1; 40]).unwrap();
+		bench_state.storage(&[1; 48]).unwrap();
+		bench_state.storage(&[3; 40]).unwrap();
+		bench_state.get_read_and_written_keys();
+
+		let sizes = bench_state.take_read_value_sizes();
+		assert_eq!(sizes.len(), 1);
+		assert_eq!(sizes.get(&vec![1; 32]), Some(&20));
+		assert!(bench_state.take_read_value_sizes().is_empty());
+	}
+
+	#[test]
+	fn read_value_sizes_cover_all_reads() {
+		let child_info = ChildInfo::new_default(b"child");
+		let storage = sp_runtime::Storage {
+			top: vec![(vec![1; 40], vec![0; 10]), (vec![2; 40], vec![0; 20])]
+				.into_iter()
+				.collect(),
+			children_default: vec![(
+				child_info.storage_key().to_vec(),
+				sp_runtime::StorageChild {
+					data: vec![(vec![3; 40], vec![0; 30]), (vec![4; 40], vec![0; 40])]
+						.into_iter()
+						.collect(),
+					child_info: child_info.clone(),
+				},
+			)]
+			.into_iter()
+			.collect(),
+		};
+		let bench_state =
+			BenchmarkingState::<crate::tests::Block>::new(storage, None, false, true).unwrap();
+
+		bench_state.reset_read_write_count();
+		assert!(bench_state.exists_storage(&[1; 40]).unwrap());
+		assert!(bench_state.storage_hash(&[2; 40]).unwrap().is_some());
+		assert!(bench_state.child_storage(&child_info, &[3; 40]).unwrap().is_some());
+		assert!(bench_state.exists_child_storage(&child_info, &[4; 40]).unwrap());
+		bench_state.get_read_and_written_keys();
+
+		let sizes = bench_state.take_read_value_sizes();
+		assert_eq!(sizes.get(&vec![1; 32]), Some(&10));
+		assert_eq!(sizes.get(&vec![2; 32]), Some(&20));
+		assert_eq!(sizes.get(&vec![3; 32]), Some(&30));
+		assert_eq!(sizes.get(&vec![4; 32]), Some(&40));
+	}
 }
@@ -31,6 +31,7 @@
 pub mod offchain;
 
 pub mod bench;
+pub mod migration;
 
 mod children;
 mod parity_db;
@@ -65,7 +66,7 @@ use sc_client_api::{
 	utils::is_descendent_of,
 	IoInfo, MemoryInfo, MemorySize, UsageInfo,
 };
-use sc_state_db::{IsPruned, LastCanonicalized, StateDb};
+use sc_state_db::{Constraints, IsPruned, LastCanonicalized, StateDb};
 use sp_arithmetic::traits::Saturating;
 use sp_blockchain::{
 	Backend as _, CachedHeaderMetadata, Error as ClientError, HeaderBackend, HeaderMetadata,
@@ -1130,6 +1131,14 @@ impl<Block: BlockT> Backend<Block> {
 		Self::from_database(db as Arc<_>, canonicalization_delay, &db_config, needs_init)
 	}
 
+	/// Open an existing database without ever writing to it, e.g. to inspect or replay its blocks.
+	///
+	/// Anything that needs to write to the database, such as importing a block, fails.
+	pub fn new_read_only(db_config: DatabaseSettings) -> ClientResult<Self> {
+		let db = crate::utils::open_database_read_only(&db_config.source, DatabaseType::Full)?;
+		Self::from_database(db, 0, &db_config, false)
+	}
+
 	/// Create new memory-backed client backend for tests.
 	#[cfg(any(test, feature = "test-helpers"))]
 	pub fn new_test(blocks_pruning: u32, canonicalization_delay: u64) -> Self {
@@ -2289,6 +2298,8 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 							hash_to_revert,
 						);
 						self.storage.db.commit(transaction)?;
+						// Make sure the reverted block can be imported again.
+						self.blockchain.remove_header_metadata(removed_hash);
 
 						let is_best = number_to_revert < best_number;
 
@@ -2475,7 +2486,9 @@ impl<Block: BlockT> sc_client_api::backend::Backend<Block> for Backend<Block> {
 	fn requires_full_sync(&self) -> bool {
 		matches!(
 			self.storage.state_db.pruning_mode(),
-			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical
+			PruningMode::ArchiveAll |
+				PruningMode::ArchiveCanonical |
+				PruningMode::Constrained(Constraints { checkpoint_interval: Some(_), .. })
 		)
 	}
 
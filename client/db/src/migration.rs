@@ -0,0 +1,420 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Moving the content of a database to a database with reference counting.
+//!
+//! RocksDB stores state nodes under prefixed keys and lets the state database track how often
+//! a node was inserted. ParityDB stores them under their hash and counts the references itself.
+//! The migration copies every column, rewrites the state journals for the new keys and inserts
+//! each state node as many times as the pending pruning journals will release it. The result is
+//! verified by comparing the best and finalized headers of both databases and by checking every
+//! node of the last finalized state against the state root.
+
+use std::{cell::Cell, collections::HashMap, io, mem};
+
+use crate::{
+	columns,
+	utils::{self, meta_keys, NUM_COLUMNS},
+	Database, DbHash, Transaction, DB_HASH_LEN,
+};
+use codec::Decode;
+use hash_db::{HashDBRef, Hasher, Prefix};
+use kvdb::KeyValueDB;
+use sp_blockchain::{Error as ClientError, Result as ClientResult};
+use sp_core::storage::well_known_keys;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, HashFor, Header as HeaderT, NumberFor},
+};
+use sp_trie::{DBValue, LayoutV1, Trie, TrieDBBuilder};
+
+/// Number of changes written to the target database in one commit.
+const BATCH_SIZE: usize = 16 * 1024;
+
+/// Summary of a finished migration.
+#[derive(Debug, Clone)]
+pub struct MigrationSummary<Block: BlockT> {
+	/// Number of entries copied from each column of the source database.
+	pub entries: Vec<u64>,
+	/// Best block, with a matching header in both databases.
+	pub best: (NumberFor<Block>, Block::Hash),
+	/// Last finalized block with state, if any, whose state was checked against its state root.
+	pub finalized_state: Option<(NumberFor<Block>, Block::Hash)>,
+	/// Number of state trie nodes and values that were checked.
+	pub verified_nodes: u64,
+}
+
+/// Copy the RocksDB database at `rocksdb_path` to a new ParityDB database at `paritydb_path`.
+///
+/// The source database is not modified.
+#[cfg(feature = "rocksdb")]
+pub fn migrate_rocksdb_to_parity_db<Block: BlockT>(
+	rocksdb_path: &std::path::Path,
+	paritydb_path: &std::path::Path,
+	cache_size: usize,
+) -> ClientResult<MigrationSummary<Block>> {
+	if paritydb_path.join("metadata").exists() {
+		return Err(ClientError::Backend(format!(
+			"A ParityDB database already exists at {:?}",
+			paritydb_path
+		)))
+	}
+
+	let source =
+		utils::open_rocksdb_without_upgrade(rocksdb_path, utils::DatabaseType::Full, cache_size)?;
+	let target = crate::parity_db::open(paritydb_path, utils::DatabaseType::Full, true, false)
+		.map_err(|e| ClientError::Backend(e.to_string()))?;
+
+	migrate::<Block>(&source, &*target)
+}
+
+/// Copy the content of `source`, a database without reference counting, to the empty
+/// `target` database, which must support reference counting.
+pub fn migrate<Block: BlockT>(
+	source: &dyn KeyValueDB,
+	target: &dyn Database<DbHash>,
+) -> ClientResult<MigrationSummary<Block>> {
+	if !target.supports_ref_counting() {
+		return Err(ClientError::Backend(
+			"The target database does not support reference counting".into(),
+		))
+	}
+	if target.get(columns::META, meta_keys::GENESIS_HASH).is_some() {
+		return Err(ClientError::Backend("The target database is not empty".into()))
+	}
+
+	let sanitize = |key: &Vec<u8>| {
+		let mut key = key.clone();
+		target.sanitize_key(&mut key);
+		key
+	};
+	let journals =
+		sc_state_db::ref_counted_journals::<Block::Hash, Vec<u8>, _>(&SourceMeta(source), sanitize)
+			.map_err(|e| ClientError::Backend(format!("Error reading state journals: {:?}", e)))?;
+	let mut journal_records: HashMap<_, _> = journals.meta.inserted.into_iter().collect();
+
+	let mut entries = vec![0; NUM_COLUMNS as usize];
+	let mut batch = Batch::new(target);
+	for column in 0..NUM_COLUMNS {
+		// the value of a ref counted entry and its counter, which is stored under the key with
+		// a zero byte appended and therefore immediately follows it.
+		let mut counted: Option<(Vec<u8>, DBValue)> = None;
+		for item in source.iter(column) {
+			let (key, value) = item.map_err(db_err)?;
+			entries[column as usize] += 1;
+			match column {
+				columns::STATE => {
+					if key.len() < DB_HASH_LEN {
+						return Err(ClientError::Backend(format!(
+							"Unexpected state key {}",
+							sp_core::hexdisplay::HexDisplay::from(&key.as_ref())
+						)))
+					}
+					let references = journals.references.get(key.as_ref()).copied().unwrap_or(1);
+					let key = sanitize(&key.to_vec());
+					for _ in 0..references {
+						batch.set(column, &key, value.clone())?;
+					}
+				},
+				columns::TRANSACTION => match counted.take() {
+					Some((counted_key, counted_value))
+						if key.len() == counted_key.len() + 1 &&
+							key.starts_with(&counted_key) &&
+							key.last() == Some(&0) =>
+					{
+						let counter = <[u8; 4]>::try_from(value.as_slice())
+							.map(u32::from_le_bytes)
+							.map_err(|_| {
+								ClientError::Backend(format!(
+									"Unexpected counter len {}",
+									value.len()
+								))
+							})?;
+						for _ in 0..counter {
+							batch.set(column, &counted_key, counted_value.clone())?;
+						}
+					},
+					previous => {
+						if let Some((counted_key, counted_value)) = previous {
+							batch.set(column, &counted_key, counted_value)?;
+						}
+						counted = Some((key.to_vec(), value));
+					},
+				},
+				columns::STATE_META => {
+					let value = journal_records.remove(key.as_ref()).unwrap_or(value);
+					batch.set(column, &key, value)?;
+				},
+				_ => batch.set(column, &key, value)?,
+			}
+		}
+		if let Some((counted_key, counted_value)) = counted {
+			batch.set(column, &counted_key, counted_value)?;
+		}
+	}
+	batch.flush()?;
+
+	let (best, finalized_state, verified_nodes) = verify::<Block>(source, target)?;
+	Ok(MigrationSummary { entries, best, finalized_state, verified_nodes })
+}
+
+/// Compare the best and finalized headers of both databases and check the last finalized
+/// state of the target database against its state root.
+fn verify<Block: BlockT>(
+	source: &dyn KeyValueDB,
+	target: &dyn Database<DbHash>,
+) -> ClientResult<((NumberFor<Block>, Block::Hash), Option<(NumberFor<Block>, Block::Hash)>, u64)> {
+	let meta = utils::read_meta::<Block>(target, columns::HEADER)?;
+	if meta.genesis_hash == Default::default() {
+		// nothing was imported
+		return Ok(((meta.best_number, meta.best_hash), None, 0))
+	}
+
+	for hash in [meta.best_hash, meta.finalized_hash] {
+		let header = utils::read_header::<Block>(
+			target,
+			columns::KEY_LOOKUP,
+			columns::HEADER,
+			BlockId::Hash(hash),
+		)?;
+		let source_header = match source.get(columns::KEY_LOOKUP, hash.as_ref()).map_err(db_err)? {
+			Some(lookup_key) => source.get(columns::HEADER, &lookup_key).map_err(db_err)?,
+			None => None,
+		}
+		.map(|header| Block::Header::decode(&mut &header[..]))
+		.transpose()
+		.map_err(|_| ClientError::Backend("Error decoding header".into()))?;
+		match header {
+			Some(header) if header.hash() == hash && Some(&header) == source_header.as_ref() => (),
+			_ =>
+				return Err(ClientError::Backend(format!(
+					"Header of block {} does not match the source database",
+					hash
+				))),
+		}
+	}
+
+	let mut verified_nodes = 0;
+	if let Some((hash, number)) = meta.finalized_state {
+		let header = utils::read_header::<Block>(
+			target,
+			columns::KEY_LOOKUP,
+			columns::HEADER,
+			BlockId::Hash(hash),
+		)?
+		.ok_or_else(|| ClientError::UnknownBlock(hash.to_string()))?;
+		let db = VerifyingDb { db: target, nodes: Cell::new(0) };
+		verify_trie::<HashFor<Block>>(&db, header.state_root(), true).map_err(|e| {
+			ClientError::Backend(format!(
+				"State of block #{} ({}) is incomplete: {}",
+				number, hash, e
+			))
+		})?;
+		verified_nodes = db.nodes.get();
+	}
+
+	Ok((
+		(meta.best_number, meta.best_hash),
+		meta.finalized_state.map(|(hash, number)| (number, hash)),
+		verified_nodes,
+	))
+}
+
+/// Iterate over all entries of the trie with the given root, and of its child tries when
+/// `with_children` is set.
+fn verify_trie<H: Hasher>(
+	db: &VerifyingDb,
+	root: &H::Out,
+	with_children: bool,
+) -> Result<(), String> {
+	let trie = TrieDBBuilder::<LayoutV1<H>>::new(db, root).build();
+	for item in trie.iter().map_err(|e| format!("{:?}", e))? {
+		let (key, value) = item.map_err(|e| format!("{:?}", e))?;
+		if with_children && key.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+			let mut child_root = H::Out::default();
+			if value.len() != child_root.as_ref().len() {
+				return Err(format!("Invalid child trie root {:?}", value))
+			}
+			child_root.as_mut().copy_from_slice(&value);
+			verify_trie::<H>(db, &child_root, false)?;
+		}
+	}
+	Ok(())
+}
+
+/// Read access to the state column that only returns values matching their hash.
+struct VerifyingDb<'a> {
+	db: &'a dyn Database<DbHash>,
+	nodes: Cell<u64>,
+}
+
+impl<'a, H: Hasher> HashDBRef<H, DBValue> for VerifyingDb<'a> {
+	fn get(&self, key: &H::Out, _prefix: Prefix) -> Option<DBValue> {
+		// A node that does not match its hash is reported as missing.
+		let value = self.db.get(columns::STATE, key.as_ref()).filter(|v| H::hash(v) == *key)?;
+		self.nodes.set(self.nodes.get() + 1);
+		Some(value)
+	}
+
+	fn contains(&self, key: &H::Out, prefix: Prefix) -> bool {
+		HashDBRef::<H, DBValue>::get(self, key, prefix).is_some()
+	}
+}
+
+/// State database metadata of the source database.
+struct SourceMeta<'a>(&'a dyn KeyValueDB);
+
+impl<'a> sc_state_db::MetaDb for SourceMeta<'a> {
+	type Error = io::Error;
+
+	fn get_meta(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+		self.0.get(columns::STATE_META, key)
+	}
+}
+
+/// Changes written to the target database in commits of `BATCH_SIZE`.
+struct Batch<'a> {
+	db: &'a dyn Database<DbHash>,
+	transaction: Transaction<DbHash>,
+	len: usize,
+}
+
+impl<'a> Batch<'a> {
+	fn new(db: &'a dyn Database<DbHash>) -> Self {
+		Batch { db, transaction: Transaction::new(), len: 0 }
+	}
+
+	fn set(&mut self, column: u32, key: &[u8], value: DBValue) -> ClientResult<()> {
+		self.transaction.set_from_vec(column, key, value);
+		self.len += 1;
+		if self.len >= BATCH_SIZE {
+			self.flush()?;
+		}
+		Ok(())
+	}
+
+	fn flush(&mut self) -> ClientResult<()> {
+		self.len = 0;
+		self.db
+			.commit(mem::take(&mut self.transaction))
+			.map_err(|e| ClientError::Backend(e.to_string()))
+	}
+}
+
+fn db_err(err: io::Error) -> ClientError {
+	ClientError::Backend(format!("{}", err))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		tests::{insert_header, Block},
+		Backend, BlocksPruning, DatabaseSettings, DatabaseSource, PruningMode,
+	};
+	use kvdb::{DBKeyValue, DBTransaction};
+	use sc_client_api::backend::Backend as _;
+	use sp_blockchain::HeaderBackend;
+	use sp_core::H256;
+	use sp_state_machine::{backend::Backend as _, IterArgs};
+	use std::sync::Arc;
+
+	/// Memory database that stays accessible after being handed to a `Backend`.
+	struct SharedDb(Arc<kvdb_memorydb::InMemory>);
+
+	impl KeyValueDB for SharedDb {
+		fn get(&self, col: u32, key: &[u8]) -> io::Result<Option<DBValue>> {
+			self.0.get(col, key)
+		}
+
+		fn get_by_prefix(&self, col: u32, prefix: &[u8]) -> io::Result<Option<DBValue>> {
+			self.0.get_by_prefix(col, prefix)
+		}
+
+		fn write(&self, transaction: DBTransaction) -> io::Result<()> {
+			self.0.write(transaction)
+		}
+
+		fn iter<'a>(&'a self, col: u32) -> Box<dyn Iterator<Item = io::Result<DBKeyValue>> + 'a> {
+			self.0.iter(col)
+		}
+
+		fn iter_with_prefix<'a>(
+			&'a self,
+			col: u32,
+			prefix: &'a [u8],
+		) -> Box<dyn Iterator<Item = io::Result<DBKeyValue>> + 'a> {
+			self.0.iter_with_prefix(col, prefix)
+		}
+	}
+
+	fn backend(db: Arc<dyn Database<DbHash>>, require_create_flag: bool) -> Backend<Block> {
+		let settings = DatabaseSettings {
+			trie_cache_maximum_size: None,
+			state_pruning: Some(PruningMode::blocks_pruning(2)),
+			source: DatabaseSource::Custom { db, require_create_flag },
+			blocks_pruning: BlocksPruning::KeepAll,
+		};
+		Backend::new(settings, 0).unwrap()
+	}
+
+	fn import_finalized(backend: &Backend<Block>, hashes: &mut Vec<H256>, count: usize) {
+		for _ in 0..count {
+			let parent = hashes.last().copied().unwrap_or_default();
+			let hash =
+				insert_header(backend, hashes.len() as u64, parent, None, Default::default());
+			backend.finalize_block(hash, None).unwrap();
+			hashes.push(hash);
+		}
+	}
+
+	fn state_entries(backend: &Backend<Block>, hash: H256) -> usize {
+		let state = backend.state_at(hash).unwrap();
+		state.pairs(IterArgs::default()).unwrap().map(|pair| pair.unwrap()).count()
+	}
+
+	#[test]
+	fn migrates_to_parity_db() {
+		let source = Arc::new(kvdb_memorydb::create(NUM_COLUMNS));
+		let mut hashes = Vec::new();
+		{
+			let db = sp_database::as_database(SharedDb(source.clone()));
+			let backend = backend(db, true);
+			import_finalized(&backend, &mut hashes, 6);
+		}
+
+		let path = tempfile::tempdir().unwrap();
+		let target =
+			crate::parity_db::open(path.path(), utils::DatabaseType::Full, true, false).unwrap();
+		let summary = migrate::<Block>(&SharedDb(source), &*target).unwrap();
+		assert_eq!(summary.best, (5, hashes[5]));
+		assert_eq!(summary.finalized_state, Some((5, hashes[5])));
+		assert!(summary.verified_nodes > 0);
+		assert!(migrate::<Block>(&kvdb_memorydb::create(NUM_COLUMNS), &*target).is_err());
+
+		// the pruning window continues with reference counting
+		let backend = backend(target, false);
+		assert_eq!(backend.blockchain().info().best_hash, hashes[5]);
+		assert_eq!(state_entries(&backend, hashes[5]), 6);
+		import_finalized(&backend, &mut hashes, 4);
+		assert!(backend.state_at(hashes[5]).is_err());
+		for number in 8..10 {
+			assert_eq!(state_entries(&backend, hashes[number]), number + 1);
+		}
+	}
+}
@@ -110,6 +110,25 @@ impl sp_core::offchain::OffchainStorage for LocalStorage {
 		}
 		is_set
 	}
+
+	fn entries_paged(
+		&self,
+		prefix: &[u8],
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		count: usize,
+	) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+		let full_prefix = concatenate_prefix_and_key(prefix, key_prefix);
+		let start = start_key.map(|start| concatenate_prefix_and_key(prefix, start));
+		let entries = self
+			.db
+			.iter_prefix(columns::OFFCHAIN, &full_prefix, start.as_deref())?
+			.map(|(key, value)| (key[prefix.len()..].to_vec(), value))
+			.skip_while(|(key, _)| Some(key.as_slice()) == start_key)
+			.take(count)
+			.collect();
+		Some(entries)
+	}
 }
 
 /// Concatenate the prefix and key to create an offchain key in the db.
@@ -147,4 +166,26 @@ mod tests {
 		assert_eq!(storage.get(prefix, key), Some(b"asd".to_vec()));
 		assert!(storage.locks.lock().is_empty(), "Locks map should be empty!");
 	}
+
+	#[test]
+	fn should_list_entries_paged() {
+		let mut storage = LocalStorage::new_test();
+		let prefix = b"prefix";
+
+		storage.set(prefix, b"a1", b"1");
+		storage.set(prefix, b"a2", b"2");
+		storage.set(prefix, b"a3", b"3");
+		storage.set(prefix, b"b1", b"4");
+		storage.set(b"other", b"a4", b"5");
+
+		assert_eq!(
+			storage.entries_paged(prefix, b"a", None, 2),
+			Some(vec![(b"a1".to_vec(), b"1".to_vec()), (b"a2".to_vec(), b"2".to_vec())]),
+		);
+		assert_eq!(
+			storage.entries_paged(prefix, b"a", Some(b"a2"), 2),
+			Some(vec![(b"a3".to_vec(), b"3".to_vec())]),
+		);
+		assert_eq!(storage.entries_paged(prefix, b"", None, 10).map(|e| e.len()), Some(4));
+	}
 }
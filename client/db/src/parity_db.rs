@@ -20,7 +20,7 @@ use crate::{
 	utils::{DatabaseType, NUM_COLUMNS},
 };
 /// A `Database` adapter for parity-db.
-use sp_database::{error::DatabaseError, Change, ColumnId, Database, Transaction};
+use sp_database::{error::DatabaseError, Change, ColumnId, Database, PairsIter, Transaction};
 
 struct DbAdapter(parity_db::Db);
 
@@ -33,13 +33,8 @@ fn handle_err<T>(result: parity_db::Result<T>) -> T {
 	}
 }
 
-/// Wrap parity-db database into a trait object that implements `sp_database::Database`
-pub fn open<H: Clone + AsRef<[u8]>>(
-	path: &std::path::Path,
-	db_type: DatabaseType,
-	create: bool,
-	upgrade: bool,
-) -> parity_db::Result<std::sync::Arc<dyn Database<H>>> {
+/// The parity-db options of a database of the given type.
+fn options(path: &std::path::Path, db_type: DatabaseType) -> parity_db::Options {
 	let mut config = parity_db::Options::with_columns(path, NUM_COLUMNS as u8);
 
 	match db_type {
@@ -67,9 +62,29 @@ pub fn open<H: Clone + AsRef<[u8]>>(
 			tx_col.ref_counted = true;
 			tx_col.preimage = true;
 			tx_col.uniform = true;
+
+			// Index the offchain column by key, so that its entries can be listed. parity-db can't
+			// change the index of an existing column, so databases created before keep theirs.
+			let offchain_btree_index =
+				parity_db::Options::load_metadata(path).ok().flatten().map_or(true, |meta| {
+					meta.columns.get(columns::OFFCHAIN as usize).map_or(false, |c| c.btree_index)
+				});
+			config.columns[columns::OFFCHAIN as usize].btree_index = offchain_btree_index;
 		},
 	}
 
+	config
+}
+
+/// Wrap parity-db database into a trait object that implements `sp_database::Database`
+pub fn open<H: Clone + AsRef<[u8]>>(
+	path: &std::path::Path,
+	db_type: DatabaseType,
+	create: bool,
+	upgrade: bool,
+) -> parity_db::Result<std::sync::Arc<dyn Database<H>>> {
+	let config = options(path, db_type);
+
 	if upgrade {
 		log::info!("Upgrading database metadata.");
 		if let Some(meta) = parity_db::Options::load_metadata(path)? {
@@ -86,6 +101,15 @@ pub fn open<H: Clone + AsRef<[u8]>>(
 	Ok(std::sync::Arc::new(DbAdapter(db)))
 }
 
+/// Open an existing parity-db database without writing to it.
+pub fn open_read_only<H: Clone + AsRef<[u8]>>(
+	path: &std::path::Path,
+	db_type: DatabaseType,
+) -> parity_db::Result<std::sync::Arc<dyn Database<H>>> {
+	let db = parity_db::Db::open_read_only(&options(path, db_type))?;
+	Ok(std::sync::Arc::new(DbAdapter(db)))
+}
+
 fn ref_counted_column(col: u32) -> bool {
 	col == columns::TRANSACTION || col == columns::STATE
 }
@@ -159,4 +183,59 @@ impl<H: Clone + AsRef<[u8]>> Database<H> for DbAdapter {
 	fn sanitize_key(&self, key: &mut Vec<u8>) {
 		let _prefix = key.drain(0..key.len() - crate::DB_HASH_LEN);
 	}
+
+	fn iter_prefix<'a>(
+		&'a self,
+		col: ColumnId,
+		prefix: &'a [u8],
+		start: Option<&'a [u8]>,
+	) -> Option<PairsIter<'a>> {
+		// Only columns with a btree index can be iterated.
+		let mut iter = self.0.iter(col as u8).ok()?;
+		handle_err(iter.seek(start.map_or(prefix, |start| start.max(prefix))));
+		Some(Box::new(
+			std::iter::from_fn(move || handle_err(iter.next()))
+				.take_while(move |(key, _)| key.starts_with(prefix)),
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+
+	#[test]
+	fn offchain_entries_can_be_iterated() {
+		let dir = tempfile::tempdir().unwrap();
+		let db = open::<H256>(dir.path(), DatabaseType::Full, true, false).unwrap();
+
+		let mut tx = Transaction::new();
+		for key in [&b"a1"[..], b"a2", b"a3", b"b1"] {
+			tx.set(columns::OFFCHAIN, key, key);
+		}
+		db.commit(tx).unwrap();
+
+		let keys = |start: Option<&[u8]>| {
+			db.iter_prefix(columns::OFFCHAIN, b"a", start)
+				.unwrap()
+				.map(|(key, _)| key)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(keys(None), vec![b"a1", b"a2", b"a3"]);
+		assert_eq!(keys(Some(b"a2")), vec![b"a2", b"a3"]);
+		assert!(keys(Some(b"b")).is_empty());
+		assert!(db.iter_prefix(columns::HEADER, b"", None).is_none());
+	}
+
+	#[test]
+	fn databases_without_offchain_index_can_be_opened() {
+		let dir = tempfile::tempdir().unwrap();
+		let mut config = options(dir.path(), DatabaseType::Full);
+		config.columns[columns::OFFCHAIN as usize].btree_index = false;
+		drop(parity_db::Db::open_or_create(&config).unwrap());
+
+		let db = open::<H256>(dir.path(), DatabaseType::Full, false, false).unwrap();
+		assert!(db.iter_prefix(columns::OFFCHAIN, b"", None).is_none());
+	}
 }
@@ -51,6 +51,8 @@ pub enum UpgradeError {
 	UnsupportedVersion(u32),
 	/// Database version comes from future version of the client.
 	FutureDatabaseVersion(u32),
+	/// Database version must be upgraded before the database can be used without upgrading it.
+	OutdatedDatabaseVersion(u32),
 	/// Invalid justification block.
 	DecodingJustificationBlock,
 	/// Common io error.
@@ -78,6 +80,13 @@ impl fmt::Display for UpgradeError {
 			UpgradeError::FutureDatabaseVersion(version) => {
 				write!(f, "Database version comes from future version of the client: {}", version)
 			},
+			UpgradeError::OutdatedDatabaseVersion(version) => {
+				write!(
+					f,
+					"Database version must be upgraded by starting the node on it first: {}",
+					version
+				)
+			},
 			UpgradeError::DecodingJustificationBlock => {
 				write!(f, "Decodoning justification block failed")
 			},
@@ -110,6 +119,16 @@ pub fn upgrade_db<Block: BlockT>(db_path: &Path, db_type: DatabaseType) -> Upgra
 	Ok(())
 }
 
+/// Ensure that the database has the current version, without upgrading it.
+pub fn ensure_current_version(db_path: &Path) -> UpgradeResult<()> {
+	match current_version(db_path)? {
+		CURRENT_VERSION => Ok(()),
+		db_version if db_version > CURRENT_VERSION =>
+			Err(UpgradeError::FutureDatabaseVersion(db_version)),
+		db_version => Err(UpgradeError::OutdatedDatabaseVersion(db_version)),
+	}
+}
+
 /// Migration from version1 to version2:
 /// 1) the number of columns has changed from 11 to 12;
 /// 2) transactions column is added;
@@ -242,6 +261,25 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn ensure_current_version_does_not_upgrade() {
+		let db_dir = tempfile::TempDir::new().unwrap();
+		assert!(matches!(
+			ensure_current_version(db_dir.path()),
+			Err(UpgradeError::MissingDatabaseVersionFile)
+		));
+
+		create_db(db_dir.path(), Some(CURRENT_VERSION - 1));
+		assert!(matches!(
+			ensure_current_version(db_dir.path()),
+			Err(UpgradeError::OutdatedDatabaseVersion(version)) if version == CURRENT_VERSION - 1
+		));
+		assert_eq!(current_version(db_dir.path()).unwrap(), CURRENT_VERSION - 1);
+
+		create_db(db_dir.path(), Some(CURRENT_VERSION));
+		ensure_current_version(db_dir.path()).unwrap();
+	}
+
 	#[test]
 	fn upgrade_to_4_works() {
 		let db_type = DatabaseType::Full;
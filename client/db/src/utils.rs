@@ -25,7 +25,7 @@ use log::{debug, info};
 
 use crate::{Database, DatabaseSource, DbHash};
 use codec::Decode;
-use sp_database::Transaction;
+use sp_database::{ColumnId, PairsIter, Transaction};
 use sp_runtime::{
 	generic::BlockId,
 	traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedFrom, UniqueSaturatedInto, Zero},
@@ -216,6 +216,124 @@ fn open_database_at<Block: BlockT>(
 	Ok(db)
 }
 
+/// Opens the configured database without ever writing to it.
+///
+/// Unlike [`open_database`], the database is neither migrated nor upgraded and must already exist.
+/// A RocksDB database is opened as a secondary instance, thus it can be read while a node is
+/// running on top of it. Committing anything to the returned database fails.
+pub fn open_database_read_only(db_source: &DatabaseSource, db_type: DatabaseType) -> OpenDbResult {
+	let db = match &db_source {
+		DatabaseSource::ParityDb { path } =>
+			ReadOnlyDatabase::new(open_parity_db_read_only(path, db_type)?),
+		#[cfg(feature = "rocksdb")]
+		DatabaseSource::RocksDb { path, cache_size } =>
+			open_kvdb_rocksdb_read_only(path, db_type, *cache_size)?,
+		DatabaseSource::Custom { db, .. } => ReadOnlyDatabase::new(db.clone()),
+		DatabaseSource::Auto { paritydb_path, rocksdb_path, cache_size } => {
+			// check if rocksdb exists first, if not, open paritydb
+			match open_kvdb_rocksdb_read_only(rocksdb_path, db_type, *cache_size) {
+				Ok(db) => db,
+				Err(OpenDbError::NotEnabled(_)) | Err(OpenDbError::DoesNotExist) =>
+					ReadOnlyDatabase::new(open_parity_db_read_only(paritydb_path, db_type)?),
+				Err(as_is) => return Err(as_is),
+			}
+		},
+	};
+
+	check_database_type(&db, db_type)?;
+	Ok(Arc::new(db))
+}
+
+/// A database that rejects any non-empty commit.
+struct ReadOnlyDatabase {
+	db: Arc<dyn Database<DbHash>>,
+	// Declared after `db`, so that the directory is only removed once the database is closed.
+	_secondary_dir: Option<SecondaryDir>,
+}
+
+impl ReadOnlyDatabase {
+	fn new(db: Arc<dyn Database<DbHash>>) -> Self {
+		Self { db, _secondary_dir: None }
+	}
+}
+
+/// The directory of a RocksDB secondary instance, removed when dropped.
+struct SecondaryDir(std::path::PathBuf);
+
+#[cfg(feature = "rocksdb")]
+impl SecondaryDir {
+	/// A new directory below the temporary directory, unique to this process.
+	fn new() -> Self {
+		static NEXT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+		let n = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+		Self(std::env::temp_dir().join(format!(
+			"substrate-db-secondary-{}-{}",
+			std::process::id(),
+			n
+		)))
+	}
+}
+
+impl Drop for SecondaryDir {
+	fn drop(&mut self) {
+		if let Err(e) = fs::remove_dir_all(&self.0) {
+			if e.kind() != io::ErrorKind::NotFound {
+				debug!(
+					target: "db",
+					"Failed to remove the secondary database directory {:?}: {}",
+					self.0,
+					e,
+				);
+			}
+		}
+	}
+}
+
+impl Database<DbHash> for ReadOnlyDatabase {
+	fn commit(&self, transaction: Transaction<DbHash>) -> sp_database::error::Result<()> {
+		if transaction.0.is_empty() {
+			return Ok(())
+		}
+		Err(sp_database::error::DatabaseError(Box::new(io::Error::new(
+			io::ErrorKind::PermissionDenied,
+			"database is opened read-only",
+		))))
+	}
+
+	fn get(&self, col: ColumnId, key: &[u8]) -> Option<Vec<u8>> {
+		self.db.get(col, key)
+	}
+
+	fn contains(&self, col: ColumnId, key: &[u8]) -> bool {
+		self.db.contains(col, key)
+	}
+
+	fn value_size(&self, col: ColumnId, key: &[u8]) -> Option<usize> {
+		self.db.value_size(col, key)
+	}
+
+	fn with_get(&self, col: ColumnId, key: &[u8], f: &mut dyn FnMut(&[u8])) {
+		self.db.with_get(col, key, f)
+	}
+
+	fn supports_ref_counting(&self) -> bool {
+		self.db.supports_ref_counting()
+	}
+
+	fn sanitize_key(&self, key: &mut Vec<u8>) {
+		self.db.sanitize_key(key)
+	}
+
+	fn iter_prefix<'a>(
+		&'a self,
+		col: ColumnId,
+		prefix: &'a [u8],
+		start: Option<&'a [u8]>,
+	) -> Option<PairsIter<'a>> {
+		self.db.iter_prefix(col, prefix, start)
+	}
+}
+
 #[derive(Debug)]
 pub enum OpenDbError {
 	// constructed only when rocksdb and paritydb are disabled
@@ -293,6 +411,10 @@ fn open_parity_db<Block: BlockT>(path: &Path, db_type: DatabaseType, create: boo
 	}
 }
 
+fn open_parity_db_read_only(path: &Path, db_type: DatabaseType) -> OpenDbResult {
+	Ok(crate::parity_db::open_read_only(path, db_type)?)
+}
+
 #[cfg(any(feature = "rocksdb", test))]
 fn open_kvdb_rocksdb<Block: BlockT>(
 	path: &Path,
@@ -309,9 +431,85 @@ fn open_kvdb_rocksdb<Block: BlockT>(
 	}
 
 	// and now open database assuming that it has the latest version
-	let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(NUM_COLUMNS);
+	let mut db_config = rocksdb_config(path, db_type, cache_size);
 	db_config.create_if_missing = create;
 
+	let db = kvdb_rocksdb::Database::open(&db_config, path)?;
+	// write database version only after the database is succesfully opened
+	crate::upgrade::update_version(path)?;
+	Ok(sp_database::as_database(db))
+}
+
+/// Open the existing RocksDB database at the given location, without wrapping it into the
+/// `Database` trait.
+///
+/// Unlike the databases opened by the node, the database is neither upgraded nor is its version
+/// file written, and opening it fails if it does not have the current version.
+#[cfg(any(feature = "rocksdb", test))]
+pub(crate) fn open_rocksdb_without_upgrade(
+	path: &Path,
+	db_type: DatabaseType,
+	cache_size: usize,
+) -> Result<kvdb_rocksdb::Database, OpenDbError> {
+	ensure_rocksdb_version(path)?;
+
+	let mut db_config = rocksdb_config(path, db_type, cache_size);
+	db_config.create_if_missing = false;
+
+	Ok(kvdb_rocksdb::Database::open(&db_config, path)?)
+}
+
+/// Open the existing RocksDB database at the given location as a secondary instance, which never
+/// writes to it.
+#[cfg(any(feature = "rocksdb", test))]
+fn open_kvdb_rocksdb_read_only(
+	path: &Path,
+	db_type: DatabaseType,
+	cache_size: usize,
+) -> Result<ReadOnlyDatabase, OpenDbError> {
+	// a secondary instance does not report a missing database as such.
+	ensure_rocksdb_version(path)?;
+
+	let secondary_dir = SecondaryDir::new();
+	let mut db_config = rocksdb_config(path, db_type, cache_size);
+	db_config.create_if_missing = false;
+	// the secondary instance only keeps its info logs there.
+	db_config.secondary = Some(secondary_dir.0.clone());
+
+	let db = kvdb_rocksdb::Database::open(&db_config, path)?;
+	Ok(ReadOnlyDatabase { db: sp_database::as_database(db), _secondary_dir: Some(secondary_dir) })
+}
+
+#[cfg(not(any(feature = "rocksdb", test)))]
+fn open_kvdb_rocksdb_read_only(
+	_path: &Path,
+	_db_type: DatabaseType,
+	_cache_size: usize,
+) -> Result<ReadOnlyDatabase, OpenDbError> {
+	Err(OpenDbError::NotEnabled("with-kvdb-rocksdb"))
+}
+
+/// Ensure that the RocksDB database at the given location has the current version, without
+/// upgrading it. A database without a version file is reported as not existing.
+#[cfg(any(feature = "rocksdb", test))]
+fn ensure_rocksdb_version(path: &Path) -> Result<(), OpenDbError> {
+	match crate::upgrade::ensure_current_version(path) {
+		Ok(()) => Ok(()),
+		Err(crate::upgrade::UpgradeError::MissingDatabaseVersionFile) =>
+			Err(OpenDbError::DoesNotExist),
+		Err(err) => Err(io::Error::new(io::ErrorKind::Other, err.to_string()).into()),
+	}
+}
+
+/// The RocksDB configuration of a database of the given type.
+#[cfg(any(feature = "rocksdb", test))]
+fn rocksdb_config(
+	path: &Path,
+	db_type: DatabaseType,
+	cache_size: usize,
+) -> kvdb_rocksdb::DatabaseConfig {
+	let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(NUM_COLUMNS);
+
 	let mut memory_budget = std::collections::HashMap::new();
 	match db_type {
 		DatabaseType::Full => {
@@ -336,11 +534,7 @@ fn open_kvdb_rocksdb<Block: BlockT>(
 		},
 	}
 	db_config.memory_budget = memory_budget;
-
-	let db = kvdb_rocksdb::Database::open(&db_config, path)?;
-	// write database version only after the database is succesfully opened
-	crate::upgrade::update_version(path)?;
-	Ok(sp_database::as_database(db))
+	db_config
 }
 
 #[cfg(not(any(feature = "rocksdb", test)))]
@@ -824,4 +1018,68 @@ mod tests {
 			assert!(db_res.is_ok(), "Existing parity database should be reopened");
 		}
 	}
+
+	#[cfg(feature = "rocksdb")]
+	#[test]
+	fn test_open_database_read_only() {
+		let db_dir = tempfile::TempDir::new().unwrap();
+		let db_path = db_dir.path().to_owned();
+		let paritydb_path = db_path.join("paritydb");
+		let rocksdb_path = db_path.join("rocksdb_path");
+		let auto = DatabaseSource::Auto {
+			paritydb_path: paritydb_path.clone(),
+			rocksdb_path: rocksdb_path.clone(),
+			cache_size: 128,
+		};
+		let secondary_dirs = || {
+			let prefix = format!("substrate-db-secondary-{}-", std::process::id());
+			fs::read_dir(std::env::temp_dir())
+				.unwrap()
+				.filter(|entry| {
+					entry.as_ref().unwrap().file_name().to_string_lossy().starts_with(&prefix)
+				})
+				.count()
+		};
+
+		// it should neither open nor create a missing database
+		{
+			let db_res = open_database_read_only(&auto, DatabaseType::Full);
+			assert!(db_res.is_err(), "Missing database should not be opened");
+			assert!(!paritydb_path.exists() && !rocksdb_path.exists());
+		}
+
+		{
+			let db_res = open_database::<Block>(
+				&DatabaseSource::RocksDb { path: rocksdb_path.clone(), cache_size: 128 },
+				DatabaseType::Full,
+				true,
+			);
+			assert!(db_res.is_ok(), "New rocksdb database should be created");
+		}
+
+		// it should open the existing rocksdb database as a secondary instance, and reject writes
+		{
+			let db = open_database_read_only(&auto, DatabaseType::Full).unwrap();
+			assert_eq!(secondary_dirs(), 1);
+			assert_eq!(
+				db.get(COLUMN_META, meta_keys::TYPE),
+				Some(DatabaseType::Full.as_str().as_bytes().to_vec())
+			);
+			assert!(db.commit(Transaction::new()).is_ok());
+			let mut transaction = Transaction::new();
+			transaction.set(COLUMN_META, b"key", b"value");
+			assert!(db.commit(transaction).is_err(), "Read-only database should reject writes");
+		}
+		// the secondary instance should clean up after itself
+		assert_eq!(secondary_dirs(), 0);
+
+		// it should refuse to open a database of another version, without upgrading it
+		{
+			let version_file = rocksdb_path.join("db_version");
+			fs::write(&version_file, "3").unwrap();
+			let db_res = open_database_read_only(&auto, DatabaseType::Full);
+			assert!(db_res.is_err(), "Outdated database should not be opened");
+			assert_eq!(fs::read_to_string(&version_file).unwrap(), "3");
+		}
+	}
 }
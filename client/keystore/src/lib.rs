@@ -25,7 +25,13 @@ use std::io;
 
 /// Local keystore implementation
 mod local;
-pub use local::LocalKeystore;
+pub use local::{LocalKeystore, RederivedKey};
+
+/// Remote keystore implementation
+#[cfg(unix)]
+pub mod remote;
+#[cfg(unix)]
+pub use remote::RemoteKeystore;
 
 /// Keystore error.
 #[derive(Debug, thiserror::Error)]
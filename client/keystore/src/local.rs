@@ -19,6 +19,8 @@
 
 use parking_lot::RwLock;
 use sp_application_crypto::{AppCrypto, AppPair, IsWrappedBy};
+#[cfg(feature = "bls-experimental")]
+use sp_core::bls381;
 use sp_core::{
 	crypto::{ByteArray, ExposeSecret, KeyTypeId, Pair as CorePair, SecretString},
 	ecdsa, ed25519, sr25519,
@@ -64,6 +66,44 @@ impl LocalKeystore {
 		self.0.read().key_pair::<Pair>(public)
 	}
 
+	/// Get a key pair of the given crypto for the given public key and key type.
+	///
+	/// Returns `Ok(None)` if the key doesn't exist, `Ok(Some(_))` if the key exists and
+	/// `Err(_)` when something failed, e.g. the key is not of the given crypto.
+	pub fn key_pair_by_type<Pair: CorePair>(
+		&self,
+		public: &Pair::Public,
+		key_type: KeyTypeId,
+	) -> Result<Option<Pair>> {
+		self.0.read().key_pair_by_type::<Pair>(public, key_type)
+	}
+
+	/// Returns the key type and raw public key of all keys in the keystore.
+	pub fn all_keys(&self) -> Result<Vec<(KeyTypeId, Vec<u8>)>> {
+		self.0.read().raw_keys()
+	}
+
+	/// Remove the key with the given public key and key type.
+	///
+	/// Returns `Ok(false)` if there was no such key.
+	pub fn remove(&self, key_type: KeyTypeId, public: &[u8]) -> Result<bool> {
+		self.0.write().remove(key_type, public)
+	}
+
+	/// Change the password of the keystore.
+	///
+	/// As the password is used to derive the keys from their phrases, the key pairs can not be
+	/// kept: all keys stored on disk are derived again with the new password and stored under their
+	/// new public key, i.e. the keys are rotated. Returns the old and the new public key of every
+	/// stored key.
+	///
+	/// Nothing is changed if any of the keys can not be derived with the current password. The
+	/// files of all new keys are written before any old key is removed, so the phrases are never
+	/// lost if writing fails.
+	pub fn change_password(&self, password: Option<SecretString>) -> Result<Vec<RederivedKey>> {
+		self.0.write().change_password(password)
+	}
+
 	fn public_keys<T: CorePair>(&self, key_type: KeyTypeId) -> Vec<T::Public> {
 		self.0
 			.read()
@@ -203,6 +243,33 @@ impl Keystore for LocalKeystore {
 		Ok(sig)
 	}
 
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<bls381::Public> {
+		self.public_keys::<bls381::Pair>(key_type)
+	}
+
+	/// Generate a new pair compatible with the 'bls381' signature scheme.
+	///
+	/// If the `[seed]` is `Some` then the key will be ephemeral and stored in memory.
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<bls381::Public, TraitError> {
+		self.generate_new::<bls381::Pair>(key_type, seed)
+	}
+
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &bls381::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<bls381::Signature>, TraitError> {
+		self.sign::<bls381::Pair>(key_type, public, msg)
+	}
+
 	fn insert(
 		&self,
 		key_type: KeyTypeId,
@@ -229,6 +296,26 @@ impl Into<KeystorePtr> for LocalKeystore {
 	}
 }
 
+/// The maximum length of a file name on most file systems.
+///
+/// Keys with a longer file name, like bls12-381 keys, are stored in a directory named after the
+/// first `MAX_FILE_NAME_LEN` characters of the file name.
+const MAX_FILE_NAME_LEN: usize = 255;
+
+/// A key that was derived again by [`LocalKeystore::change_password`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RederivedKey {
+	/// The key type of the key.
+	pub key_type: KeyTypeId,
+	/// The public key before changing the password.
+	pub public: Vec<u8>,
+	/// The public key after changing the password.
+	pub new_public: Vec<u8>,
+}
+
+/// Derives the public key of a phrase again with a new password, see [`rederive`].
+type RederiveFn = fn(&str, &[u8], Option<&str>, Option<&str>) -> Option<Vec<u8>>;
+
 /// A local key store.
 ///
 /// Stores key pairs in a file system store + short lived key pairs in memory.
@@ -309,6 +396,9 @@ impl KeystoreInner {
 
 	/// Write the given `data` to `file`.
 	fn write_to_file(file: PathBuf, data: &str) -> Result<()> {
+		if let Some(dir) = file.parent() {
+			fs::create_dir_all(dir)?;
+		}
 		let mut file = File::create(file)?;
 
 		#[cfg(target_family = "unix")]
@@ -384,33 +474,132 @@ impl KeystoreInner {
 		let mut buf = self.path.as_ref()?.clone();
 		let key_type = array_bytes::bytes2hex("", &key_type.0);
 		let key = array_bytes::bytes2hex("", public);
-		buf.push(key_type + key.as_str());
+		let name = key_type + key.as_str();
+		if name.len() > MAX_FILE_NAME_LEN {
+			let (dir, file) = name.split_at(MAX_FILE_NAME_LEN);
+			buf.push(dir);
+			buf.push(file);
+		} else {
+			buf.push(name);
+		}
 		Some(buf)
 	}
 
+	/// Remove the key with the given public key and key type from memory and the file system.
+	fn remove(&mut self, key_type: KeyTypeId, public: &[u8]) -> Result<bool> {
+		let mut removed = self.additional.remove(&(key_type, public.to_vec())).is_some();
+
+		if let Some(path) = self.key_file_path(public, key_type).filter(|p| p.exists()) {
+			fs::remove_file(&path)?;
+			removed = true;
+
+			// remove the directory holding the rest of a long file name once it is empty
+			if let Some(dir) = path.parent().filter(|dir| Some(*dir) != self.path.as_deref()) {
+				if fs::read_dir(dir)?.next().is_none() {
+					fs::remove_dir(dir)?;
+				}
+			}
+		}
+
+		Ok(removed)
+	}
+
+	/// Derive all keys on disk again with the given password.
+	///
+	/// See [`LocalKeystore::change_password`].
+	fn change_password(&mut self, password: Option<SecretString>) -> Result<Vec<RederivedKey>> {
+		let rederive_with: &[RederiveFn] = &[
+			rederive::<sr25519::Pair>,
+			rederive::<ed25519::Pair>,
+			rederive::<ecdsa::Pair>,
+			#[cfg(feature = "bls-experimental")]
+			rederive::<bls381::Pair>,
+		];
+		let new_password = password.as_ref().map(|p| p.expose_secret().as_str());
+
+		let mut changed = Vec::new();
+		for (key_type, public) in self.raw_keys()? {
+			// Keys created from a seed only live in memory and don't use the password.
+			if self.get_additional_pair(&public, key_type).is_some() {
+				continue
+			}
+			let phrase = match self.key_phrase_by_type(&public, key_type)? {
+				Some(phrase) => phrase,
+				None => continue,
+			};
+			let new_public = rederive_with
+				.iter()
+				.find_map(|rederive| rederive(&phrase, &public, self.password(), new_password))
+				.ok_or(Error::PublicKeyMismatch)?;
+			changed.push((key_type, public, new_public, phrase));
+		}
+
+		let rotated = changed
+			.iter()
+			.filter(|(_, public, new_public, _)| public != new_public)
+			.collect::<Vec<_>>();
+		let mut written = Vec::new();
+		for (key_type, _, new_public, phrase) in &rotated {
+			if let Err(e) = self.insert(*key_type, phrase, new_public) {
+				for (key_type, new_public) in written {
+					let _ = self.remove(key_type, new_public);
+				}
+				return Err(e)
+			}
+			written.push((*key_type, new_public.as_slice()));
+		}
+		for (key_type, public, _, _) in rotated {
+			self.remove(*key_type, public)?;
+		}
+		self.password = password;
+
+		Ok(changed
+			.into_iter()
+			.map(|(key_type, public, new_public, _)| RederivedKey { key_type, public, new_public })
+			.collect())
+	}
+
 	/// Returns a list of raw public keys filtered by `KeyTypeId`
 	fn raw_public_keys(&self, key_type: KeyTypeId) -> Result<Vec<Vec<u8>>> {
-		let mut public_keys: Vec<Vec<u8>> = self
-			.additional
-			.keys()
-			.into_iter()
-			.filter_map(|k| if k.0 == key_type { Some(k.1.clone()) } else { None })
-			.collect();
+		self.raw_keys().map(|keys| {
+			keys.into_iter()
+				.filter_map(|(t, public)| (t == key_type).then_some(public))
+				.collect()
+		})
+	}
+
+	/// Returns a list of `(KeyTypeId, raw public key)` of all keys.
+	fn raw_keys(&self) -> Result<Vec<(KeyTypeId, Vec<u8>)>> {
+		let mut keys: Vec<(KeyTypeId, Vec<u8>)> = self.additional.keys().cloned().collect();
 
 		if let Some(path) = &self.path {
 			for entry in fs::read_dir(&path)? {
 				let entry = entry?;
 				let path = entry.path();
 
-				// skip directories and non-unicode file names (hex is unicode)
-				if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-					match array_bytes::hex2bytes(name) {
+				// skip non-unicode file names (hex is unicode)
+				let name = match path.file_name().and_then(|n| n.to_str()) {
+					Some(name) => name.to_owned(),
+					None => continue,
+				};
+				let names = if path.is_dir() {
+					// skip directories that don't hold the rest of a long file name
+					if name.len() != MAX_FILE_NAME_LEN {
+						continue
+					}
+					fs::read_dir(&path)?
+						.filter_map(|e| Some(name.clone() + e.ok()?.file_name().to_str()?))
+						.collect()
+				} else {
+					vec![name]
+				};
+
+				for name in names {
+					match array_bytes::hex2bytes(&name) {
 						Ok(ref hex) if hex.len() > 4 => {
-							if hex[0..4] != key_type.0 {
-								continue
-							}
-							let public = hex[4..].to_vec();
-							public_keys.push(public);
+							let mut key_type = [0u8; 4];
+							key_type.copy_from_slice(&hex[0..4]);
+							keys.push((KeyTypeId(key_type), hex[4..].to_vec()));
 						},
 						_ => continue,
 					}
@@ -418,7 +607,7 @@ impl KeystoreInner {
 			}
 		}
 
-		Ok(public_keys)
+		Ok(keys)
 	}
 
 	/// Get a key pair for the given public key.
@@ -434,6 +623,24 @@ impl KeystoreInner {
 	}
 }
 
+/// Derive the public key of `phrase` with `new_password`.
+///
+/// Returns `None` if `phrase` doesn't derive `public` with `password` using the crypto of `Pair`.
+fn rederive<Pair: CorePair>(
+	phrase: &str,
+	public: &[u8],
+	password: Option<&str>,
+	new_password: Option<&str>,
+) -> Option<Vec<u8>> {
+	let pair = Pair::from_string(phrase, password).ok()?;
+	if pair.public().as_slice() != public {
+		return None
+	}
+	Pair::from_string(phrase, new_password)
+		.ok()
+		.map(|pair| pair.public().to_raw_vec())
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -629,6 +836,73 @@ mod tests {
 		assert_eq!(store.sr25519_public_keys(TEST_KEY_TYPE).len(), 2);
 	}
 
+	#[test]
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_keys_are_stored_and_sign() {
+		let temp_dir = TempDir::new().unwrap();
+		let store = LocalKeystore::open(temp_dir.path(), None).unwrap();
+		let public = store.bls381_generate_new(TEST_KEY_TYPE, None).unwrap();
+
+		drop(store);
+		let store = LocalKeystore::open(temp_dir.path(), None).unwrap();
+		assert_eq!(store.bls381_public_keys(TEST_KEY_TYPE), vec![public]);
+
+		let sig = store.bls381_sign(TEST_KEY_TYPE, &public, b"message").unwrap().unwrap();
+		assert!(bls381::Pair::verify(&sig, b"message", &public));
+	}
+
+	#[test]
+	fn remove_works() {
+		let temp_dir = TempDir::new().unwrap();
+		let store = LocalKeystore::open(temp_dir.path(), None).unwrap();
+
+		let public = store.sr25519_generate_new(TEST_KEY_TYPE, None).unwrap();
+		let ephemeral = store.sr25519_generate_new(SR25519, Some("//Alice")).unwrap();
+		let mut keys = store.all_keys().unwrap();
+		keys.sort();
+		assert_eq!(
+			keys,
+			vec![(SR25519, ephemeral.to_raw_vec()), (TEST_KEY_TYPE, public.to_raw_vec())]
+		);
+
+		assert!(store.remove(TEST_KEY_TYPE, public.as_ref()).unwrap());
+		assert!(!store.remove(TEST_KEY_TYPE, public.as_ref()).unwrap());
+		assert!(store.remove(SR25519, ephemeral.as_ref()).unwrap());
+		assert!(store.all_keys().unwrap().is_empty());
+		assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+	}
+
+	#[test]
+	fn change_password_works() {
+		let temp_dir = TempDir::new().unwrap();
+		let store = LocalKeystore::open(temp_dir.path(), None).unwrap();
+
+		let sr25519_public = store.sr25519_generate_new(TEST_KEY_TYPE, None).unwrap();
+		let ecdsa_public = store.ecdsa_generate_new(TEST_KEY_TYPE, None).unwrap();
+
+		let password = SecretString::from_str("password").unwrap();
+		let changed = store.change_password(Some(password.clone())).unwrap();
+		assert_eq!(changed.len(), 2);
+		assert!(!store.sr25519_public_keys(TEST_KEY_TYPE).contains(&sr25519_public));
+
+		let store = LocalKeystore::open(temp_dir.path(), Some(password)).unwrap();
+		for RederivedKey { key_type, public, new_public } in changed {
+			assert_eq!(key_type, TEST_KEY_TYPE);
+			if public == sr25519_public.to_raw_vec() {
+				let new_public = sp_core::sr25519::Public::from_slice(&new_public).unwrap();
+				assert!(store.sr25519_sign(key_type, &new_public, b"msg").unwrap().is_some());
+			} else {
+				assert_eq!(public, ecdsa_public.to_raw_vec());
+				let new_public = ecdsa::Public::from_slice(&new_public).unwrap();
+				assert!(store.ecdsa_sign(key_type, &new_public, b"msg").unwrap().is_some());
+			}
+		}
+
+		// the keys can't be derived with the wrong current password
+		let store = LocalKeystore::open(temp_dir.path(), None).unwrap();
+		assert!(matches!(store.change_password(None), Err(Error::PublicKeyMismatch)));
+	}
+
 	#[test]
 	#[cfg(target_family = "unix")]
 	fn uses_correct_file_permissions_on_unix() {
@@ -0,0 +1,778 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Remote keystore implementation
+//!
+//! [`RemoteKeystore`] forwards every keystore operation to a signer running in another process,
+//! so the secret keys never have to be loaded by the node. The signer side is implemented by
+//! [`serve`] on top of any [`Keystore`], the `keystore-signer` binary uses it to serve a
+//! [`LocalKeystore`](crate::LocalKeystore).
+//!
+//! # Protocol
+//!
+//! The node connects to the Unix socket of the signer and sends one [`Request`] per line, encoded
+//! as JSON. The signer answers every request with one [`Response`] line, in order. Byte strings
+//! are `0x` prefixed hex strings, key types and crypto types are arrays of their 4 bytes and
+//! signatures are SCALE encoded. Signing a message with the sr25519 `babe` key `0xd435…`:
+//!
+//! ```text
+//! {"method":"sign","params":{"key_type":[98,97,98,101],"crypto":[115,114,50,53],"public":"0xd435…","message":"0x1234"}}
+//! {"Ok":{"signature":"0x5e29…"}}
+//! ```
+//!
+//! If the key is unknown to the signer, it answers with `{"Ok":{"signature":null}}`, errors are
+//! returned as a [`RemoteError`], e.g. `{"Err":"unavailable"}`. Messages longer than
+//! [`MAX_MESSAGE_LEN`] are rejected by both sides.
+//!
+//! VRF transcripts can only be signed if their label and the labels of their items are known to
+//! the signer, see [`TRANSCRIPT_LABELS`].
+//!
+//! # Latency
+//!
+//! The [`Keystore`] api is synchronous, so every operation of a [`RemoteKeystore`] blocks the
+//! calling thread until the signer answered, for up to 30 seconds if the signer hangs. Since
+//! consensus engines call the keystore from their async tasks while authoring and voting, every
+//! round trip to the signer delays them by as much, and a hanging signer stalls them. The signer
+//! should thus run on the same machine as the node.
+
+use crate::Result;
+use codec::Decode;
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+#[cfg(feature = "bls-experimental")]
+use sp_core::bls381;
+use sp_core::{
+	crypto::{ByteArray, CryptoTypeId, KeyTypeId},
+	ecdsa, ed25519, sr25519, Bytes,
+};
+use sp_keystore::{
+	vrf::{VRFSignature, VRFTranscriptData, VRFTranscriptValue},
+	Error as TraitError, Keystore, KeystorePtr,
+};
+use std::{
+	io::{self, BufRead, BufReader, Read, Write},
+	os::unix::net::{UnixListener, UnixStream},
+	path::{Path, PathBuf},
+	thread,
+	time::Duration,
+};
+
+/// How long to wait for the signer to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The maximum length of a single message in bytes, including the newline.
+pub const MAX_MESSAGE_LEN: u64 = 1024 * 1024;
+
+/// The VRF transcript labels the signer can sign with.
+///
+/// The transcripts of [`VRFTranscriptData`] need `'static` labels, so the labels received from the
+/// node are resolved against this table instead of allocating them for the lifetime of the signer.
+pub const TRANSCRIPT_LABELS: &[&str] =
+	&["BABE", "slot number", "current epoch", "chain randomness"];
+
+const LOG_TARGET: &str = "keystore";
+
+/// A request from the node to the signer.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "method", content = "params")]
+pub enum Request {
+	/// Return the public keys of the `crypto` scheme for `key_type`.
+	///
+	/// Answered with [`Reply::PublicKeys`].
+	PublicKeys {
+		/// The key type of the keys.
+		key_type: KeyTypeId,
+		/// The crypto scheme of the keys.
+		crypto: CryptoTypeId,
+	},
+	/// Generate a new key pair of the `crypto` scheme for `key_type`.
+	///
+	/// Answered with [`Reply::Public`].
+	GenerateNew {
+		/// The key type of the new key.
+		key_type: KeyTypeId,
+		/// The crypto scheme of the new key.
+		crypto: CryptoTypeId,
+		/// Derive an ephemeral key from this secret URI instead of generating a random one.
+		seed: Option<String>,
+	},
+	/// Sign `message` with the key `public` of the `crypto` scheme.
+	///
+	/// Answered with [`Reply::Signature`].
+	Sign {
+		/// The key type of the key.
+		key_type: KeyTypeId,
+		/// The crypto scheme of the key.
+		crypto: CryptoTypeId,
+		/// The public key of the key.
+		public: Bytes,
+		/// The message to sign.
+		message: Bytes,
+	},
+	/// Sign the pre-hashed `message` with the ecdsa key `public`.
+	///
+	/// Answered with [`Reply::Signature`].
+	EcdsaSignPrehashed {
+		/// The key type of the key.
+		key_type: KeyTypeId,
+		/// The public key of the key.
+		public: Bytes,
+		/// The 32 byte hash to sign.
+		message: Bytes,
+	},
+	/// Generate a VRF signature with the sr25519 key `public` for a transcript.
+	///
+	/// Answered with [`Reply::VrfSignature`].
+	Sr25519VrfSign {
+		/// The key type of the key.
+		key_type: KeyTypeId,
+		/// The public key of the key.
+		public: Bytes,
+		/// The label of the transcript.
+		label: Bytes,
+		/// The labeled items of the transcript.
+		items: Vec<(String, VRFTranscriptValue)>,
+	},
+	/// Insert the secret key `suri` for `public`.
+	///
+	/// Answered with [`Reply::Inserted`].
+	Insert {
+		/// The key type of the key.
+		key_type: KeyTypeId,
+		/// The secret URI of the key.
+		suri: String,
+		/// The public key of the key.
+		public: Bytes,
+	},
+	/// Return the raw public keys of all schemes for `key_type`.
+	///
+	/// Answered with [`Reply::PublicKeys`].
+	Keys {
+		/// The key type of the keys.
+		key_type: KeyTypeId,
+	},
+	/// Check if the signer has all of the given keys.
+	///
+	/// Answered with [`Reply::HasKeys`].
+	HasKeys {
+		/// The raw public keys and their key types.
+		keys: Vec<(Bytes, KeyTypeId)>,
+	},
+}
+
+/// A successful answer of the signer to a [`Request`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reply {
+	/// The requested raw public keys.
+	PublicKeys(Vec<Bytes>),
+	/// The raw public key of a generated key.
+	Public(Bytes),
+	/// The SCALE encoded signature, `None` if the key doesn't exist.
+	Signature(Option<Bytes>),
+	/// The VRF output and proof, `None` if the key doesn't exist.
+	VrfSignature(Option<(Bytes, Bytes)>),
+	/// The key was inserted.
+	Inserted,
+	/// Whether all keys exist.
+	HasKeys(bool),
+}
+
+/// An error answer of the signer to a [`Request`], mirroring [`sp_keystore::Error`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemoteError {
+	/// The key type or crypto scheme is not supported.
+	KeyNotSupported(KeyTypeId),
+	/// The request was invalid.
+	Validation(String),
+	/// The keystore is unavailable.
+	Unavailable,
+	/// Any other error.
+	Other(String),
+}
+
+impl From<TraitError> for RemoteError {
+	fn from(error: TraitError) -> Self {
+		match error {
+			TraitError::KeyNotSupported(id) => Self::KeyNotSupported(id),
+			TraitError::ValidationError(e) => Self::Validation(e),
+			TraitError::Unavailable => Self::Unavailable,
+			TraitError::Other(e) => Self::Other(e),
+		}
+	}
+}
+
+impl From<RemoteError> for TraitError {
+	fn from(error: RemoteError) -> Self {
+		match error {
+			RemoteError::KeyNotSupported(id) => Self::KeyNotSupported(id),
+			RemoteError::Validation(e) => Self::ValidationError(e),
+			RemoteError::Unavailable => Self::Unavailable,
+			RemoteError::Other(e) => Self::Other(e),
+		}
+	}
+}
+
+/// The answer of the signer to a [`Request`].
+pub type Response = std::result::Result<Reply, RemoteError>;
+
+/// A keystore that forwards all operations to a signer listening on a Unix socket.
+pub struct RemoteKeystore {
+	path: PathBuf,
+	connection: Mutex<Option<BufReader<UnixStream>>>,
+}
+
+impl RemoteKeystore {
+	/// Connect to the signer listening on the Unix socket at `path`.
+	pub fn open<T: Into<PathBuf>>(path: T) -> Result<Self> {
+		let path = path.into();
+		let connection = connect(&path)?;
+		Ok(Self { path, connection: Mutex::new(Some(connection)) })
+	}
+
+	/// Send `request` to the signer and wait for its answer.
+	///
+	/// Requests are sent one at a time. If the connection fails it is dropped and reestablished
+	/// for the next request, so the signer can be restarted while the node is running.
+	pub fn request(&self, request: &Request) -> std::result::Result<Reply, TraitError> {
+		let mut connection = self.connection.lock();
+		let response = match connection.take() {
+			Some(stream) => Ok(stream),
+			None => connect(&self.path),
+		}
+		.and_then(|mut stream| {
+			write_message(stream.get_mut(), request)?;
+			let response = read_message::<Response>(&mut stream)?
+				.ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+			*connection = Some(stream);
+			Ok(response)
+		});
+
+		match response {
+			Ok(response) => response.map_err(Into::into),
+			Err(e) => {
+				log::warn!(
+					target: LOG_TARGET,
+					"Request to the signer at {} failed: {}",
+					self.path.display(),
+					e,
+				);
+				Err(TraitError::Unavailable)
+			},
+		}
+	}
+
+	fn public_keys<T: ByteArray>(&self, key_type: KeyTypeId, crypto: CryptoTypeId) -> Vec<T> {
+		match self.request(&Request::PublicKeys { key_type, crypto }) {
+			Ok(Reply::PublicKeys(keys)) =>
+				keys.iter().filter_map(|k| T::from_slice(k).ok()).collect(),
+			Ok(reply) => {
+				log::warn!(target: LOG_TARGET, "{}", unexpected(reply));
+				Vec::new()
+			},
+			Err(_) => Vec::new(),
+		}
+	}
+
+	fn generate_new<T: ByteArray>(
+		&self,
+		key_type: KeyTypeId,
+		crypto: CryptoTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<T, TraitError> {
+		let seed = seed.map(Into::into);
+		match self.request(&Request::GenerateNew { key_type, crypto, seed })? {
+			Reply::Public(public) => T::from_slice(&public).map_err(|_| invalid("public key")),
+			reply => Err(unexpected(reply)),
+		}
+	}
+
+	fn sign<T: Decode>(
+		&self,
+		key_type: KeyTypeId,
+		crypto: CryptoTypeId,
+		public: &[u8],
+		message: &[u8],
+	) -> std::result::Result<Option<T>, TraitError> {
+		self.sign_with(key_type, crypto, public, message)?
+			.map(|s| T::decode(&mut &s[..]).map_err(|_| invalid("signature")))
+			.transpose()
+	}
+}
+
+impl Keystore for RemoteKeystore {
+	fn sr25519_public_keys(&self, key_type: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys(key_type, sr25519::CRYPTO_ID)
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<sr25519::Public, TraitError> {
+		self.generate_new(key_type, sr25519::CRYPTO_ID, seed)
+	}
+
+	fn sr25519_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<sr25519::Signature>, TraitError> {
+		self.sign(key_type, sr25519::CRYPTO_ID, public.as_ref(), msg)
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> std::result::Result<Option<VRFSignature>, TraitError> {
+		let request = Request::Sr25519VrfSign {
+			key_type,
+			public: public.to_raw_vec().into(),
+			label: transcript_data.label.to_vec().into(),
+			items: transcript_data.items.into_iter().map(|(l, v)| (l.into(), v)).collect(),
+		};
+		match self.request(&request)? {
+			Reply::VrfSignature(Some((output, proof))) => Ok(Some(VRFSignature {
+				output: schnorrkel::vrf::VRFOutput::from_bytes(&output)
+					.map_err(|_| invalid("VRF output"))?,
+				proof: schnorrkel::vrf::VRFProof::from_bytes(&proof)
+					.map_err(|_| invalid("VRF proof"))?,
+			})),
+			Reply::VrfSignature(None) => Ok(None),
+			reply => Err(unexpected(reply)),
+		}
+	}
+
+	fn ed25519_public_keys(&self, key_type: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys(key_type, ed25519::CRYPTO_ID)
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ed25519::Public, TraitError> {
+		self.generate_new(key_type, ed25519::CRYPTO_ID, seed)
+	}
+
+	fn ed25519_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &ed25519::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<ed25519::Signature>, TraitError> {
+		self.sign(key_type, ed25519::CRYPTO_ID, public.as_ref(), msg)
+	}
+
+	fn ecdsa_public_keys(&self, key_type: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys(key_type, ecdsa::CRYPTO_ID)
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<ecdsa::Public, TraitError> {
+		self.generate_new(key_type, ecdsa::CRYPTO_ID, seed)
+	}
+
+	fn ecdsa_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<ecdsa::Signature>, TraitError> {
+		self.sign(key_type, ecdsa::CRYPTO_ID, public.as_ref(), msg)
+	}
+
+	fn ecdsa_sign_prehashed(
+		&self,
+		key_type: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> std::result::Result<Option<ecdsa::Signature>, TraitError> {
+		let request = Request::EcdsaSignPrehashed {
+			key_type,
+			public: public.to_raw_vec().into(),
+			message: msg.to_vec().into(),
+		};
+		match self.request(&request)? {
+			Reply::Signature(signature) => signature
+				.map(|s| ecdsa::Signature::decode(&mut &s[..]).map_err(|_| invalid("signature")))
+				.transpose(),
+			reply => Err(unexpected(reply)),
+		}
+	}
+
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<bls381::Public> {
+		self.public_keys(key_type, bls381::CRYPTO_ID)
+	}
+
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> std::result::Result<bls381::Public, TraitError> {
+		self.generate_new(key_type, bls381::CRYPTO_ID, seed)
+	}
+
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &bls381::Public,
+		msg: &[u8],
+	) -> std::result::Result<Option<bls381::Signature>, TraitError> {
+		self.sign(key_type, bls381::CRYPTO_ID, public.as_ref(), msg)
+	}
+
+	fn insert(
+		&self,
+		key_type: KeyTypeId,
+		suri: &str,
+		public: &[u8],
+	) -> std::result::Result<(), ()> {
+		let request =
+			Request::Insert { key_type, suri: suri.into(), public: public.to_vec().into() };
+		match self.request(&request) {
+			Ok(Reply::Inserted) => Ok(()),
+			_ => Err(()),
+		}
+	}
+
+	fn keys(&self, key_type: KeyTypeId) -> std::result::Result<Vec<Vec<u8>>, TraitError> {
+		match self.request(&Request::Keys { key_type })? {
+			Reply::PublicKeys(keys) => Ok(keys.into_iter().map(|k| k.0).collect()),
+			reply => Err(unexpected(reply)),
+		}
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let keys = public_keys.iter().map(|(k, t)| (k.clone().into(), *t)).collect();
+		matches!(self.request(&Request::HasKeys { keys }), Ok(Reply::HasKeys(true)))
+	}
+
+	/// Forwarded to the signer as is, so it signs with any scheme the signer supports.
+	fn sign_with(
+		&self,
+		id: KeyTypeId,
+		crypto_id: CryptoTypeId,
+		public: &[u8],
+		msg: &[u8],
+	) -> std::result::Result<Option<Vec<u8>>, TraitError> {
+		let request = Request::Sign {
+			key_type: id,
+			crypto: crypto_id,
+			public: public.to_vec().into(),
+			message: msg.to_vec().into(),
+		};
+		match self.request(&request)? {
+			Reply::Signature(signature) => Ok(signature.map(|s| s.0)),
+			reply => Err(unexpected(reply)),
+		}
+	}
+}
+
+/// Serve the requests of the nodes connecting to `listener` with `keystore`.
+///
+/// Every connection is served by its own thread. Only returns if accepting a connection failed.
+pub fn serve(listener: UnixListener, keystore: KeystorePtr) -> io::Result<()> {
+	for stream in listener.incoming() {
+		let stream = stream?;
+		let keystore = keystore.clone();
+		thread::spawn(move || {
+			if let Err(e) = serve_connection(stream, &*keystore) {
+				log::debug!(target: LOG_TARGET, "Connection to the signer failed: {}", e);
+			}
+		});
+	}
+	Ok(())
+}
+
+fn serve_connection(stream: UnixStream, keystore: &dyn Keystore) -> io::Result<()> {
+	let mut writer = stream.try_clone()?;
+	let mut reader = BufReader::new(stream);
+	while let Some(request) = read_message(&mut reader)? {
+		write_message(&mut writer, &handle_request(keystore, request))?;
+	}
+	Ok(())
+}
+
+/// Answer `request` with `keystore`.
+pub fn handle_request(keystore: &dyn Keystore, request: Request) -> Response {
+	fn to_bytes<T: ByteArray>(keys: Vec<T>) -> Vec<Bytes> {
+		keys.into_iter().map(|k| k.to_raw_vec().into()).collect()
+	}
+
+	let reply = match request {
+		Request::PublicKeys { key_type, crypto } => Reply::PublicKeys(match crypto {
+			sr25519::CRYPTO_ID => to_bytes(keystore.sr25519_public_keys(key_type)),
+			ed25519::CRYPTO_ID => to_bytes(keystore.ed25519_public_keys(key_type)),
+			ecdsa::CRYPTO_ID => to_bytes(keystore.ecdsa_public_keys(key_type)),
+			#[cfg(feature = "bls-experimental")]
+			bls381::CRYPTO_ID => to_bytes(keystore.bls381_public_keys(key_type)),
+			_ => return Err(RemoteError::KeyNotSupported(key_type)),
+		}),
+		Request::GenerateNew { key_type, crypto, seed } => {
+			let seed = seed.as_deref();
+			let public = match crypto {
+				sr25519::CRYPTO_ID => keystore.sr25519_generate_new(key_type, seed)?.to_raw_vec(),
+				ed25519::CRYPTO_ID => keystore.ed25519_generate_new(key_type, seed)?.to_raw_vec(),
+				ecdsa::CRYPTO_ID => keystore.ecdsa_generate_new(key_type, seed)?.to_raw_vec(),
+				#[cfg(feature = "bls-experimental")]
+				bls381::CRYPTO_ID => keystore.bls381_generate_new(key_type, seed)?.to_raw_vec(),
+				_ => return Err(RemoteError::KeyNotSupported(key_type)),
+			};
+			Reply::Public(public.into())
+		},
+		Request::Sign { key_type, crypto, public, message } => Reply::Signature(
+			keystore.sign_with(key_type, crypto, &public, &message)?.map(Into::into),
+		),
+		Request::EcdsaSignPrehashed { key_type, public, message } => {
+			let public = ecdsa::Public::from_slice(&public).map_err(|_| invalid("public key"))?;
+			let message = <[u8; 32]>::try_from(&message[..]).map_err(|_| invalid("message"))?;
+			let signature = keystore.ecdsa_sign_prehashed(key_type, &public, &message)?;
+			Reply::Signature(signature.map(|s| s.0.to_vec().into()))
+		},
+		Request::Sr25519VrfSign { key_type, public, label, items } => {
+			let public = sr25519::Public::from_slice(&public).map_err(|_| invalid("public key"))?;
+			let transcript_data = VRFTranscriptData {
+				label: transcript_label(&label)?.as_bytes(),
+				items: items
+					.into_iter()
+					.map(|(l, v)| Ok((transcript_label(l.as_bytes())?, v)))
+					.collect::<std::result::Result<_, RemoteError>>()?,
+			};
+			let signature = keystore.sr25519_vrf_sign(key_type, &public, transcript_data)?;
+			Reply::VrfSignature(
+				signature.map(|s| {
+					(s.output.to_bytes().to_vec().into(), s.proof.to_bytes().to_vec().into())
+				}),
+			)
+		},
+		Request::Insert { key_type, suri, public } => {
+			keystore
+				.insert(key_type, &suri, &public)
+				.map_err(|()| RemoteError::Other("Failed to insert the key".into()))?;
+			Reply::Inserted
+		},
+		Request::Keys { key_type } =>
+			Reply::PublicKeys(keystore.keys(key_type)?.into_iter().map(Into::into).collect()),
+		Request::HasKeys { keys } => {
+			let keys = keys.into_iter().map(|(k, t)| (k.0, t)).collect::<Vec<_>>();
+			Reply::HasKeys(keystore.has_keys(&keys))
+		},
+	};
+	Ok(reply)
+}
+
+/// The entry of [`TRANSCRIPT_LABELS`] matching `label`.
+fn transcript_label(label: &[u8]) -> std::result::Result<&'static str, RemoteError> {
+	TRANSCRIPT_LABELS
+		.iter()
+		.copied()
+		.find(|l| l.as_bytes() == label)
+		.ok_or_else(|| {
+			RemoteError::Validation(format!(
+				"Unknown VRF transcript label {}",
+				String::from_utf8_lossy(label)
+			))
+		})
+}
+
+fn connect(path: &Path) -> io::Result<BufReader<UnixStream>> {
+	let stream = UnixStream::connect(path)?;
+	stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+	stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+	Ok(BufReader::new(stream))
+}
+
+fn write_message<T: Serialize>(stream: &mut impl Write, message: &T) -> io::Result<()> {
+	let mut line = serde_json::to_vec(message)?;
+	line.push(b'\n');
+	if line.len() as u64 > MAX_MESSAGE_LEN {
+		return Err(io::Error::new(io::ErrorKind::InvalidInput, "Message is too long"))
+	}
+	stream.write_all(&line)?;
+	stream.flush()
+}
+
+/// Read the next message, `None` if the connection was closed.
+fn read_message<T: DeserializeOwned>(stream: &mut impl BufRead) -> io::Result<Option<T>> {
+	let mut line = String::new();
+	let len = stream.by_ref().take(MAX_MESSAGE_LEN).read_line(&mut line)?;
+	if len == 0 {
+		return Ok(None)
+	}
+	if !line.ends_with('\n') && len as u64 == MAX_MESSAGE_LEN {
+		return Err(io::Error::new(io::ErrorKind::InvalidData, "Message is too long"))
+	}
+	Ok(Some(serde_json::from_str(&line)?))
+}
+
+fn invalid(what: &str) -> TraitError {
+	TraitError::ValidationError(format!("Invalid {}", what))
+}
+
+fn unexpected(reply: Reply) -> TraitError {
+	TraitError::Other(format!("Unexpected reply from the signer: {:?}", reply))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::LocalKeystore;
+	use sp_core::{
+		crypto::Pair,
+		testing::{ECDSA, ED25519, SR25519},
+	};
+	use sp_keystore::vrf::make_transcript;
+	use std::sync::Arc;
+	use tempfile::TempDir;
+
+	fn start_signer(dir: &TempDir) -> (RemoteKeystore, Arc<LocalKeystore>) {
+		let path = dir.path().join("signer.sock");
+		let local = Arc::new(LocalKeystore::open(dir.path().join("keystore"), None).unwrap());
+		let listener = UnixListener::bind(&path).unwrap();
+		let keystore = local.clone();
+		thread::spawn(move || serve(listener, keystore));
+		(RemoteKeystore::open(path).unwrap(), local)
+	}
+
+	#[test]
+	fn keys_are_generated_and_used_by_the_signer() {
+		let dir = TempDir::new().unwrap();
+		let (remote, local) = start_signer(&dir);
+		let msg = b"message";
+
+		let public = remote.sr25519_generate_new(SR25519, None).unwrap();
+		assert_eq!(local.sr25519_public_keys(SR25519), vec![public]);
+		assert_eq!(remote.sr25519_public_keys(SR25519), vec![public]);
+		let signature = remote.sr25519_sign(SR25519, &public, msg).unwrap().unwrap();
+		assert!(sr25519::Pair::verify(&signature, msg, &public));
+
+		let public = remote.ed25519_generate_new(ED25519, Some("//Alice")).unwrap();
+		assert_eq!(public, ed25519::Pair::from_string("//Alice", None).unwrap().public());
+		let signature = remote.ed25519_sign(ED25519, &public, msg).unwrap().unwrap();
+		assert!(ed25519::Pair::verify(&signature, msg, &public));
+
+		let public = remote.ecdsa_generate_new(ECDSA, None).unwrap();
+		let hash = sp_core::blake2_256(msg);
+		let signature = remote.ecdsa_sign_prehashed(ECDSA, &public, &hash).unwrap().unwrap();
+		assert!(ecdsa::Pair::verify_prehashed(&signature, &hash, &public));
+		let signature = remote.sign_with(ECDSA, ecdsa::CRYPTO_ID, public.as_ref(), msg).unwrap();
+		assert!(ecdsa::Pair::verify(
+			&Decode::decode(&mut &signature.unwrap()[..]).unwrap(),
+			msg,
+			&public
+		));
+
+		assert_eq!(remote.keys(ECDSA).unwrap(), vec![public.to_raw_vec()]);
+		assert!(remote.has_keys(&[(public.to_raw_vec(), ECDSA)]));
+		assert!(!remote.has_keys(&[(public.to_raw_vec(), SR25519)]));
+	}
+
+	#[test]
+	fn vrf_sign_works() {
+		let dir = TempDir::new().unwrap();
+		let (remote, _) = start_signer(&dir);
+		let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+		let transcript_data = || VRFTranscriptData {
+			label: b"BABE",
+			items: vec![("slot number", VRFTranscriptValue::U64(1))],
+		};
+
+		let result = remote.sr25519_vrf_sign(SR25519, &pair.public(), transcript_data());
+		assert!(result.unwrap().is_none());
+
+		remote.insert(SR25519, "//Alice", pair.public().as_ref()).unwrap();
+		let signature = remote
+			.sr25519_vrf_sign(SR25519, &pair.public(), transcript_data())
+			.unwrap()
+			.unwrap();
+
+		let public = schnorrkel::PublicKey::from_bytes(pair.public().as_ref()).unwrap();
+		let (inout, _) = public
+			.vrf_verify(make_transcript(transcript_data()), &signature.output, &signature.proof)
+			.unwrap();
+		assert_eq!(inout.to_output(), signature.output);
+
+		let unknown = VRFTranscriptData { label: b"Test", items: Vec::new() };
+		assert!(matches!(
+			remote.sr25519_vrf_sign(SR25519, &pair.public(), unknown),
+			Err(TraitError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn long_messages_are_rejected() {
+		let mut message = vec![b' '; MAX_MESSAGE_LEN as usize];
+		message.extend_from_slice(b"null\n");
+		let result = read_message::<()>(&mut &message[..]);
+		assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+		let result = read_message::<()>(&mut &b"null\n"[..]);
+		assert!(result.unwrap().is_some());
+	}
+
+	#[test]
+	fn errors_are_forwarded() {
+		let dir = TempDir::new().unwrap();
+		let (remote, _) = start_signer(&dir);
+
+		let unknown = CryptoTypeId(*b"none");
+		assert!(matches!(
+			remote.sign_with(SR25519, unknown, &[0; 32], b"message"),
+			Err(TraitError::KeyNotSupported(SR25519))
+		));
+		assert!(matches!(
+			remote.request(&Request::GenerateNew {
+				key_type: SR25519,
+				crypto: unknown,
+				seed: None
+			}),
+			Err(TraitError::KeyNotSupported(SR25519))
+		));
+		assert!(matches!(
+			remote.sr25519_generate_new(SR25519, Some("invalid seed")),
+			Err(TraitError::ValidationError(_))
+		));
+	}
+
+	#[test]
+	fn reconnects_to_restarted_signer() {
+		let dir = TempDir::new().unwrap();
+		let path = dir.path().join("signer.sock");
+		let listener = UnixListener::bind(&path).unwrap();
+		let remote = RemoteKeystore::open(&path).unwrap();
+
+		// The signer accepted the connection but quit before answering.
+		drop(listener.accept().unwrap());
+		assert!(matches!(remote.keys(SR25519), Err(TraitError::Unavailable)));
+
+		let keystore: KeystorePtr = Arc::new(LocalKeystore::in_memory());
+		thread::spawn(move || serve(listener, keystore));
+		assert_eq!(remote.keys(SR25519).unwrap(), Vec::<Vec<u8>>::new());
+	}
+}
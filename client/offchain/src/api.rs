@@ -21,7 +21,7 @@ use std::{collections::HashSet, str::FromStr, sync::Arc, thread::sleep};
 use crate::NetworkProvider;
 use codec::{Decode, Encode};
 use futures::Future;
-pub use http::SharedClient;
+pub use http::{HttpClientConfig, HttpHostFilter, SharedClient};
 use libp2p::{Multiaddr, PeerId};
 use sp_core::{
 	offchain::{
@@ -426,7 +426,7 @@ mod tests {
 	fn offchain_api() -> (Api, AsyncApi) {
 		sp_tracing::try_init_simple();
 		let mock = Arc::new(TestNetwork());
-		let shared_client = SharedClient::new();
+		let shared_client = SharedClient::new(Default::default());
 
 		AsyncApi::new(mock, false, shared_client)
 	}
@@ -31,37 +31,214 @@ use crate::api::timestamp;
 use bytes::buf::{Buf, Reader};
 use fnv::FnvHashMap;
 use futures::{channel::mpsc, future, prelude::*};
-use hyper::{client, Body, Client as HyperClient};
+use futures_timer::Delay;
+use hyper::{
+	client::{
+		self,
+		connect::dns::{GaiResolver, Name},
+	},
+	service::Service,
+	Body, Client as HyperClient,
+};
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use once_cell::sync::Lazy;
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_core::offchain::{HttpError, HttpRequestId, HttpRequestStatus, Timestamp};
 use std::{
 	fmt,
-	io::Read as _,
+	io::{self, Read as _},
+	net::{IpAddr, SocketAddr},
 	pin::Pin,
 	sync::Arc,
 	task::{Context, Poll},
+	time::Duration,
 };
 
 const LOG_TARGET: &str = "offchain-worker::http";
 
+type HttpClient = HyperClient<HttpsConnector<client::HttpConnector<FilteringResolver>>, Body>;
+type LazyHttpClient = Lazy<HttpClient, Box<dyn FnOnce() -> HttpClient + Send>>;
+
+/// The hosts offchain workers are allowed to send HTTP requests to.
+///
+/// A host in the list matches itself and all of its subdomains, e.g. `example.com` matches
+/// `api.example.com` as well. IP addresses only match themselves, in any notation.
+///
+/// A deny list is enforced against the host of a request and, for denied IP addresses, against
+/// every address the host resolves to. Denying a host name does not deny the addresses it
+/// resolves to though, so a host name can still be reached through any other name or address
+/// pointing to it. Only an allow list restricts the hosts that can be reached.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum HttpHostFilter {
+	/// Any host may be contacted.
+	#[default]
+	Any,
+	/// Only the given hosts may be contacted.
+	Allow(Vec<String>),
+	/// All hosts but the given ones may be contacted.
+	Deny(Vec<String>),
+}
+
+impl HttpHostFilter {
+	/// Returns whether a request to `host` is allowed.
+	pub fn allows(&self, host: Option<&str>) -> bool {
+		let matches = |hosts: &[String], host: &str| {
+			let host = host.trim_start_matches('[').trim_end_matches(']').to_ascii_lowercase();
+			let ip = parse_ip(&host);
+			hosts.iter().any(|allowed| {
+				let allowed = allowed.to_ascii_lowercase();
+				match (ip, parse_ip(&allowed)) {
+					(Some(ip), Some(allowed)) => ip == allowed,
+					_ =>
+						host == allowed ||
+							host.strip_suffix(&allowed).map_or(false, |sub| sub.ends_with('.')),
+				}
+			})
+		};
+
+		match self {
+			Self::Any => true,
+			Self::Allow(hosts) => host.map_or(false, |host| matches(hosts, host)),
+			Self::Deny(hosts) => host.map_or(true, |host| !matches(hosts, host)),
+		}
+	}
+
+	/// Returns whether a host may be contacted at `address`, one of the addresses it resolved to.
+	pub fn allows_address(&self, address: IpAddr) -> bool {
+		match self {
+			Self::Deny(hosts) => {
+				let address = canonical_ip(address);
+				!hosts.iter().any(|host| parse_ip(host) == Some(address))
+			},
+			Self::Any | Self::Allow(_) => true,
+		}
+	}
+}
+
+/// Parse an IP address, optionally enclosed in brackets, see [`canonical_ip`].
+fn parse_ip(host: &str) -> Option<IpAddr> {
+	host.trim_start_matches('[')
+		.trim_end_matches(']')
+		.parse()
+		.ok()
+		.map(canonical_ip)
+}
+
+/// `ip`, with IPv4-mapped IPv6 addresses replaced by the IPv4 address they map to.
+fn canonical_ip(ip: IpAddr) -> IpAddr {
+	match ip {
+		IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or(IpAddr::V6(ip), IpAddr::V4),
+		ip => ip,
+	}
+}
+
+/// Resolves host names like the default resolver of hyper, but drops the addresses the host filter
+/// doesn't allow.
+#[derive(Clone)]
+struct FilteringResolver {
+	inner: GaiResolver,
+	host_filter: HttpHostFilter,
+}
+
+impl Service<Name> for FilteringResolver {
+	type Response = std::vec::IntoIter<SocketAddr>;
+	type Error = io::Error;
+	type Future = Pin<Box<dyn Future<Output = io::Result<Self::Response>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut Context) -> Poll<io::Result<()>> {
+		self.inner.poll_ready(cx)
+	}
+
+	fn call(&mut self, name: Name) -> Self::Future {
+		let host_filter = self.host_filter.clone();
+		let resolving = self.inner.call(name);
+		Box::pin(async move {
+			let addresses = resolving
+				.await?
+				.filter(|address| host_filter.allows_address(address.ip()))
+				.collect::<Vec<_>>();
+			if addresses.is_empty() {
+				return Err(io::Error::new(
+					io::ErrorKind::PermissionDenied,
+					"Host does not resolve to an allowed address",
+				))
+			}
+			Ok(addresses.into_iter())
+		})
+	}
+}
+
+/// Configuration of the HTTP client used by offchain workers.
+#[derive(Debug, Clone, Default)]
+pub struct HttpClientConfig {
+	/// The hosts requests may be sent to.
+	pub host_filter: HttpHostFilter,
+	/// DER encoded root certificates to trust in addition to the native ones of the platform.
+	pub root_certificates: Vec<Vec<u8>>,
+	/// The maximum size of a response body in bytes.
+	///
+	/// Reading a larger body fails with [`HttpError::Invalid`].
+	pub max_response_body_size: Option<u64>,
+	/// The maximum time from dispatching a request until its response body was received.
+	///
+	/// Requests that take longer fail with [`HttpError::IoError`].
+	pub request_timeout: Option<Duration>,
+}
+
 /// Wrapper struct used for keeping the hyper_rustls client running.
 #[derive(Clone)]
-pub struct SharedClient(Arc<Lazy<HyperClient<HttpsConnector<client::HttpConnector>, Body>>>);
+pub struct SharedClient {
+	client: Arc<LazyHttpClient>,
+	config: Arc<HttpClientConfig>,
+}
 
 impl SharedClient {
-	pub fn new() -> Self {
-		Self(Arc::new(Lazy::new(|| {
-			let connector = HttpsConnectorBuilder::new()
-				.with_native_roots()
+	/// Create a client respecting the given `config`.
+	pub fn new(config: HttpClientConfig) -> Self {
+		let root_certificates = config.root_certificates.clone();
+		let host_filter = config.host_filter.clone();
+		Self {
+			client: Arc::new(Lazy::new(Box::new(move || {
+				let resolver = FilteringResolver { inner: GaiResolver::new(), host_filter };
+				let mut http = client::HttpConnector::new_with_resolver(resolver);
+				http.enforce_http(false);
+				let connector = if root_certificates.is_empty() {
+					HttpsConnectorBuilder::new().with_native_roots()
+				} else {
+					HttpsConnectorBuilder::new().with_tls_config(tls_config(&root_certificates))
+				}
 				.https_or_http()
 				.enable_http1()
 				.enable_http2()
-				.build();
-			HyperClient::builder().build(connector)
-		})))
+				.wrap_connector(http);
+				HyperClient::builder().build(connector)
+			}))),
+			config: Arc::new(config),
+		}
+	}
+}
+
+/// Build a TLS configuration trusting the native and the given root certificates.
+fn tls_config(root_certificates: &[Vec<u8>]) -> rustls::ClientConfig {
+	let mut roots = rustls::RootCertStore::empty();
+	match rustls_native_certs::load_native_certs() {
+		Ok(certs) =>
+			for cert in certs {
+				let _ = roots.add(&rustls::Certificate(cert.0));
+			},
+		Err(error) =>
+			tracing::warn!(target: LOG_TARGET, ?error, "Failed to load native root certificates"),
+	}
+	for cert in root_certificates {
+		if let Err(error) = roots.add(&rustls::Certificate(cert.clone())) {
+			tracing::warn!(target: LOG_TARGET, ?error, "Ignoring invalid root certificate");
+		}
 	}
+
+	rustls::ClientConfig::builder()
+		.with_safe_defaults()
+		.with_root_certificates(roots)
+		.with_no_client_auth()
 }
 
 /// Creates a pair of [`HttpApi`] and [`HttpWorker`].
@@ -76,10 +253,17 @@ pub fn http(shared_client: SharedClient) -> (HttpApi, HttpWorker) {
 		// writing runtime code with hardcoded IDs.
 		next_id: HttpRequestId(rand::random::<u16>() % 2000),
 		requests: FnvHashMap::default(),
+		config: shared_client.config,
 	};
 
-	let engine =
-		HttpWorker { to_api, from_api, http_client: shared_client.0, requests: Vec::new() };
+	let engine = HttpWorker {
+		to_api,
+		from_api,
+		http_client: shared_client.client,
+		requests: Vec::new(),
+		request_timeout: api.config.request_timeout,
+		timeouts: FnvHashMap::default(),
+	};
 
 	(api, engine)
 }
@@ -98,6 +282,8 @@ pub struct HttpApi {
 	next_id: HttpRequestId,
 	/// List of HTTP requests in preparation or in progress.
 	requests: FnvHashMap<HttpRequestId, HttpApiRequest>,
+	/// Restrictions on the requests.
+	config: Arc<HttpClientConfig>,
 }
 
 /// One active request within `HttpApi`.
@@ -112,7 +298,7 @@ enum HttpApiRequest {
 	/// A request has been dispatched but the worker notified us of an error. We report this
 	/// failure to the user as an `IoError` and remove the request from the list as soon as
 	/// possible.
-	Fail(hyper::Error),
+	Fail(WorkerError),
 }
 
 /// A request within `HttpApi` that has received a response.
@@ -130,10 +316,12 @@ struct HttpApiRequestRp {
 	/// Elements extracted from the channel are first put into `current_read_chunk`.
 	/// If the channel produces an error, then that is translated into an `IoError` and the request
 	/// is removed from the list.
-	body: stream::Fuse<mpsc::Receiver<Result<hyper::body::Bytes, hyper::Error>>>,
+	body: stream::Fuse<mpsc::Receiver<Result<hyper::body::Bytes, WorkerError>>>,
 	/// Chunk that has been extracted from the channel and that is currently being read.
 	/// Reading data from the response should read from this field in priority.
 	current_read_chunk: Option<Reader<hyper::body::Bytes>>,
+	/// Number of bytes of the body that were received so far.
+	body_size: u64,
 }
 
 impl HttpApi {
@@ -159,6 +347,19 @@ impl HttpApi {
 				return Err(())
 			},
 		};
+
+		// Requests to hosts that aren't allowed are never dispatched. As they are unknown to all
+		// other methods, the runtime sees them as invalid.
+		if !self.config.host_filter.allows(request.uri().host()) {
+			tracing::warn!(
+				target: LOG_TARGET,
+				id = %new_id.0,
+				%uri,
+				"Request to a host that is not allowed",
+			);
+			return Ok(new_id)
+		}
+
 		self.requests
 			.insert(new_id, HttpApiRequest::NotDispatched(request, body_sender));
 
@@ -433,6 +634,7 @@ impl HttpApi {
 									headers,
 									body: body.fuse(),
 									current_read_chunk: None,
+									body_size: 0,
 								}),
 							);
 						},
@@ -542,7 +744,18 @@ impl HttpApi {
 
 			if let future::MaybeDone::Done(next_body) = next_body {
 				match next_body {
-					Some(Ok(chunk)) => response.current_read_chunk = Some(chunk.reader()),
+					Some(Ok(chunk)) => {
+						response.body_size += chunk.len() as u64;
+						if self
+							.config
+							.max_response_body_size
+							.map_or(false, |max| response.body_size > max)
+						{
+							tracing::warn!(target: LOG_TARGET, id = %request_id.0, "Response body is too large");
+							return Err(HttpError::Invalid)
+						}
+						response.current_read_chunk = Some(chunk.reader())
+					},
 					Some(Err(_)) => return Err(HttpError::IoError),
 					None => return Ok(0), // eof
 				}
@@ -606,17 +819,26 @@ enum WorkerToApi {
 		/// the next item.
 		/// Can also be used to send an error, in case an error happend on the HTTP socket. After
 		/// an error is sent, the channel will close.
-		body: mpsc::Receiver<Result<hyper::body::Bytes, hyper::Error>>,
+		body: mpsc::Receiver<Result<hyper::body::Bytes, WorkerError>>,
 	},
 	/// A request has failed because of an error. The request is then no longer valid.
 	Fail {
 		/// The ID that was passed to the worker.
 		id: HttpRequestId,
 		/// Error that happened.
-		error: hyper::Error,
+		error: WorkerError,
 	},
 }
 
+/// Error of a request in the worker.
+#[derive(Debug)]
+enum WorkerError {
+	/// The HTTP client failed.
+	Hyper(hyper::Error),
+	/// The request took longer than [`HttpClientConfig::request_timeout`].
+	Timeout,
+}
+
 /// Must be continuously polled for the [`HttpApi`] to properly work.
 pub struct HttpWorker {
 	/// Used to sends messages to the `HttpApi`.
@@ -624,9 +846,13 @@ pub struct HttpWorker {
 	/// Used to receive messages from the `HttpApi`.
 	from_api: TracingUnboundedReceiver<ApiToWorker>,
 	/// The engine that runs HTTP requests.
-	http_client: Arc<Lazy<HyperClient<HttpsConnector<client::HttpConnector>, Body>>>,
+	http_client: Arc<LazyHttpClient>,
 	/// HTTP requests that are being worked on by the engine.
 	requests: Vec<(HttpRequestId, HttpWorkerRequest)>,
+	/// See [`HttpClientConfig::request_timeout`].
+	request_timeout: Option<Duration>,
+	/// Fires when a request in `requests` reaches its timeout.
+	timeouts: FnvHashMap<HttpRequestId, Delay>,
 }
 
 /// HTTP request being processed by the worker.
@@ -638,7 +864,7 @@ enum HttpWorkerRequest {
 		/// Body to read `Chunk`s from. Only used if the channel is ready to accept data.
 		body: hyper::Body,
 		/// Channel to the [`HttpApi`] where we send the chunks to.
-		tx: mpsc::Sender<Result<hyper::body::Bytes, hyper::Error>>,
+		tx: mpsc::Sender<Result<hyper::body::Bytes, WorkerError>>,
 	},
 }
 
@@ -655,6 +881,30 @@ impl Future for HttpWorker {
 		// We remove each element from `requests` one by one and add them back only if necessary.
 		for n in (0..me.requests.len()).rev() {
 			let (id, request) = me.requests.swap_remove(n);
+			let timed_out = me
+				.timeouts
+				.get_mut(&id)
+				.map_or(false, |timeout| Future::poll(Pin::new(timeout), cx).is_ready());
+			if timed_out {
+				tracing::debug!(target: LOG_TARGET, id = %id.0, "Request timed out");
+				match request {
+					HttpWorkerRequest::Dispatched(_) => {
+						let error = WorkerError::Timeout;
+						let _ = me.to_api.unbounded_send(WorkerToApi::Fail { id, error });
+					},
+					HttpWorkerRequest::ReadBody { body, mut tx } => match tx.poll_ready(cx) {
+						Poll::Ready(Ok(())) => {
+							let _ = tx.start_send(Err(WorkerError::Timeout));
+						},
+						Poll::Ready(Err(_)) => {},
+						// Wait until the error can be sent.
+						Poll::Pending =>
+							me.requests.push((id, HttpWorkerRequest::ReadBody { body, tx })),
+					},
+				}
+				continue
+			}
+
 			match request {
 				HttpWorkerRequest::Dispatched(mut future) => {
 					// Check for an HTTP response from the Internet.
@@ -665,6 +915,7 @@ impl Future for HttpWorker {
 						},
 						Poll::Ready(Ok(response)) => response,
 						Poll::Ready(Err(error)) => {
+							let error = WorkerError::Hyper(error);
 							let _ = me.to_api.unbounded_send(WorkerToApi::Fail { id, error });
 							continue // don't insert the request back
 						},
@@ -707,7 +958,7 @@ impl Future for HttpWorker {
 							cx.waker().wake_by_ref(); // reschedule in order to continue reading
 						},
 						Poll::Ready(Some(Err(err))) => {
-							let _ = tx.start_send(Err(err));
+							let _ = tx.start_send(Err(WorkerError::Hyper(err)));
 							// don't insert the request back
 						},
 						Poll::Ready(None) => {}, // EOF; don't insert the request back
@@ -719,6 +970,9 @@ impl Future for HttpWorker {
 			}
 		}
 
+		let requests = &me.requests;
+		me.timeouts.retain(|id, _| requests.iter().any(|(i, _)| i == id));
+
 		// Check for messages coming from the [`HttpApi`].
 		match Stream::poll_next(Pin::new(&mut me.from_api), cx) {
 			Poll::Pending => {},
@@ -727,6 +981,9 @@ impl Future for HttpWorker {
 				let future = me.http_client.request(request);
 				debug_assert!(me.requests.iter().all(|(i, _)| *i != id));
 				me.requests.push((id, HttpWorkerRequest::Dispatched(future)));
+				if let Some(timeout) = me.request_timeout {
+					me.timeouts.insert(id, Delay::new(timeout));
+				}
 				cx.waker().wake_by_ref(); // reschedule the task to poll the request
 			},
 		}
@@ -767,7 +1024,7 @@ mod tests {
 	// Using lazy_static to avoid spawning lots of different SharedClients,
 	// as spawning a SharedClient is CPU-intensive and opens lots of fds.
 	lazy_static! {
-		static ref SHARED_CLIENT: SharedClient = SharedClient::new();
+		static ref SHARED_CLIENT: SharedClient = SharedClient::new(Default::default());
 	}
 
 	// Returns an `HttpApi` whose worker is ran in the background, and a `SocketAddr` to an HTTP
@@ -776,8 +1033,11 @@ mod tests {
 		() => {
 			build_api_server!(hyper::Response::new(hyper::Body::from("Hello World!")))
 		};
-		( $response:expr ) => {{
-			let hyper_client = SHARED_CLIENT.clone();
+		( $response:expr ) => {
+			build_api_server!(SHARED_CLIENT.clone(), $response)
+		};
+		( $client:expr, $response:expr ) => {{
+			let hyper_client = $client;
 			let (api, worker) = http(hyper_client.clone());
 
 			let (addr_tx, addr_rx) = std::sync::mpsc::channel();
@@ -858,6 +1118,205 @@ mod tests {
 		assert_eq!(&buf[..n], b"Hello World!");
 	}
 
+	#[test]
+	fn host_filter_matches_hosts_and_subdomains() {
+		let hosts = vec!["example.com".to_string(), "127.0.0.1".to_string(), "::1".to_string()];
+
+		let allow = HttpHostFilter::Allow(hosts.clone());
+		assert!(allow.allows(Some("example.com")));
+		assert!(allow.allows(Some("API.Example.com")));
+		assert!(allow.allows(Some("127.0.0.1")));
+		assert!(allow.allows(Some("[::1]")));
+		assert!(!allow.allows(Some("badexample.com")));
+		assert!(!allow.allows(Some("example.com.evil.org")));
+		assert!(!allow.allows(None));
+
+		let deny = HttpHostFilter::Deny(hosts);
+		assert!(!deny.allows(Some("api.example.com")));
+		assert!(!deny.allows(Some("[::ffff:127.0.0.1]")));
+		assert!(!deny.allows(Some("[0:0:0:0:0:0:0:1]")));
+		assert!(deny.allows(Some("badexample.com")));
+		assert!(deny.allows(None));
+		assert!(!deny.allows_address("127.0.0.1".parse().unwrap()));
+		assert!(!deny.allows_address("::ffff:127.0.0.1".parse().unwrap()));
+		assert!(deny.allows_address("127.0.0.2".parse().unwrap()));
+		assert!(allow.allows_address("127.0.0.2".parse().unwrap()));
+
+		assert!(HttpHostFilter::Any.allows(None));
+	}
+
+	#[test]
+	fn requests_to_not_allowed_hosts_are_invalid() {
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+
+		for host_filter in [
+			HttpHostFilter::Allow(vec!["example.com".into()]),
+			HttpHostFilter::Deny(vec!["127.0.0.1".into()]),
+		] {
+			let client = SharedClient::new(HttpClientConfig { host_filter, ..Default::default() });
+			let (mut api, addr) =
+				build_api_server!(client, hyper::Response::new(hyper::Body::from("Hello World!")));
+
+			let id = api.request_start("GET", &format!("http://{}", addr)).unwrap();
+			assert_eq!(api.request_add_header(id, "Foo", "Bar"), Err(()));
+			assert_eq!(api.request_write_body(id, &[], Some(deadline)), Err(HttpError::Invalid));
+			assert_eq!(api.response_wait(&[id], Some(deadline)), vec![HttpRequestStatus::Invalid]);
+			let mut buf = [0; 128];
+			assert_eq!(
+				api.response_read_body(id, &mut buf, Some(deadline)),
+				Err(HttpError::Invalid)
+			);
+		}
+
+		let client = SharedClient::new(HttpClientConfig {
+			host_filter: HttpHostFilter::Allow(vec!["127.0.0.1".into()]),
+			..Default::default()
+		});
+		let (mut api, addr) =
+			build_api_server!(client, hyper::Response::new(hyper::Body::from("Hello World!")));
+
+		let id = api.request_start("GET", &format!("http://{}", addr)).unwrap();
+		api.request_write_body(id, &[], Some(deadline)).unwrap();
+		assert_eq!(
+			api.response_wait(&[id], Some(deadline)),
+			vec![HttpRequestStatus::Finished(200)]
+		);
+	}
+
+	#[test]
+	fn host_names_resolving_to_denied_addresses_are_not_reached() {
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+
+		let client = SharedClient::new(HttpClientConfig {
+			host_filter: HttpHostFilter::Deny(vec!["127.0.0.1".into(), "::1".into()]),
+			..Default::default()
+		});
+		let (mut api, addr) =
+			build_api_server!(client, hyper::Response::new(hyper::Body::from("Hello World!")));
+
+		let id = api.request_start("GET", &format!("http://localhost:{}", addr.port())).unwrap();
+		api.request_write_body(id, &[], Some(deadline)).unwrap();
+		assert_eq!(api.response_wait(&[id], Some(deadline)), vec![HttpRequestStatus::IoError]);
+	}
+
+	#[test]
+	fn requests_time_out() {
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+
+		let client = SharedClient::new(HttpClientConfig {
+			request_timeout: Some(std::time::Duration::from_millis(100)),
+			..Default::default()
+		});
+		// The body of the response never ends.
+		let (mut api, addr) = build_api_server!(client, {
+			let (sender, body) = hyper::Body::channel();
+			std::mem::forget(sender);
+			hyper::Response::new(body)
+		});
+
+		let id = api.request_start("GET", &format!("http://{}", addr)).unwrap();
+		api.request_write_body(id, &[], Some(deadline)).unwrap();
+		assert_eq!(
+			api.response_wait(&[id], Some(deadline)),
+			vec![HttpRequestStatus::Finished(200)]
+		);
+
+		let mut buf = vec![0; 2048];
+		assert_eq!(api.response_read_body(id, &mut buf, Some(deadline)), Err(HttpError::IoError));
+	}
+
+	#[test]
+	fn response_body_size_is_limited() {
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+
+		for (max_response_body_size, expected) in
+			[(Some(5), Err(HttpError::Invalid)), (Some(12), Ok(12)), (None, Ok(12))]
+		{
+			let client = SharedClient::new(HttpClientConfig {
+				max_response_body_size,
+				..Default::default()
+			});
+			let (mut api, addr) =
+				build_api_server!(client, hyper::Response::new(hyper::Body::from("Hello World!")));
+
+			let id = api.request_start("GET", &format!("http://{}", addr)).unwrap();
+			api.request_write_body(id, &[], Some(deadline)).unwrap();
+			assert_eq!(
+				api.response_wait(&[id], Some(deadline)),
+				vec![HttpRequestStatus::Finished(200)]
+			);
+
+			let mut buf = vec![0; 2048];
+			assert_eq!(api.response_read_body(id, &mut buf, Some(deadline)), expected);
+		}
+	}
+
+	#[test]
+	fn custom_root_certificates_are_trusted() {
+		let deadline = timestamp::now().add(Duration::from_millis(10_000));
+
+		let certificate = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+		let certificate_der = certificate.serialize_der().unwrap();
+		let tls_config = rustls::ServerConfig::builder()
+			.with_safe_defaults()
+			.with_no_client_auth()
+			.with_single_cert(
+				vec![rustls::Certificate(certificate_der.clone())],
+				rustls::PrivateKey(certificate.serialize_private_key_der()),
+			)
+			.unwrap();
+		let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(tls_config));
+
+		let trusting_client = SharedClient::new(HttpClientConfig {
+			root_certificates: vec![certificate_der],
+			..Default::default()
+		});
+		let (mut trusting_api, trusting_worker) = http(trusting_client);
+		let (mut api, worker) = http(SHARED_CLIENT.clone());
+
+		// Runs both workers and an HTTPS server in the background.
+		let (addr_tx, addr_rx) = std::sync::mpsc::channel();
+		std::thread::spawn(move || {
+			let rt = tokio::runtime::Runtime::new().unwrap();
+			rt.spawn(trusting_worker);
+			rt.spawn(worker);
+			rt.block_on(async move {
+				let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+				let _ = addr_tx.send(listener.local_addr().unwrap());
+				loop {
+					let (stream, _) = listener.accept().await.unwrap();
+					let acceptor = acceptor.clone();
+					tokio::spawn(async move {
+						let stream = acceptor.accept(stream).await.map_err(drop)?;
+						hyper::server::conn::Http::new()
+							.serve_connection(
+								stream,
+								hyper::service::service_fn(|_| async {
+									Ok::<_, Infallible>(hyper::Response::new(hyper::Body::from(
+										"Hello World!",
+									)))
+								}),
+							)
+							.await
+							.map_err(drop)
+					});
+				}
+			});
+		});
+		let uri = format!("https://localhost:{}", addr_rx.recv().unwrap().port());
+
+		let id = trusting_api.request_start("GET", &uri).unwrap();
+		trusting_api.request_write_body(id, &[], Some(deadline)).unwrap();
+		assert_eq!(
+			trusting_api.response_wait(&[id], Some(deadline)),
+			vec![HttpRequestStatus::Finished(200)]
+		);
+
+		let id = api.request_start("GET", &uri).unwrap();
+		api.request_write_body(id, &[], Some(deadline)).unwrap();
+		assert_eq!(api.response_wait(&[id], Some(deadline)), vec![HttpRequestStatus::IoError]);
+	}
+
 	#[test]
 	fn request_start_invalid_call() {
 		let (mut api, addr) = build_api_server!();
@@ -1097,7 +1556,7 @@ mod tests {
 
 	#[test]
 	fn shared_http_client_is_only_initialized_on_access() {
-		let shared_client = SharedClient::new();
+		let shared_client = SharedClient::new(Default::default());
 
 		{
 			let mock = Arc::new(TestNetwork());
@@ -1110,9 +1569,9 @@ mod tests {
 		}
 
 		// Check that the http client wasn't initialized, because it wasn't used.
-		assert!(Lazy::into_value(Arc::try_unwrap(shared_client.0).unwrap()).is_err());
+		assert!(Lazy::into_value(Arc::try_unwrap(shared_client.client).unwrap()).is_err());
 
-		let shared_client = SharedClient::new();
+		let shared_client = SharedClient::new(Default::default());
 
 		{
 			let mock = Arc::new(TestNetwork());
@@ -1125,6 +1584,6 @@ mod tests {
 		}
 
 		// Check that the http client initialized, because it was used.
-		assert!(Lazy::into_value(Arc::try_unwrap(shared_client.0).unwrap()).is_ok());
+		assert!(Lazy::into_value(Arc::try_unwrap(shared_client.client).unwrap()).is_ok());
 	}
 }
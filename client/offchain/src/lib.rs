@@ -50,7 +50,7 @@ use threadpool::ThreadPool;
 
 mod api;
 
-pub use api::Db as OffchainDb;
+pub use api::{Db as OffchainDb, HttpClientConfig, HttpHostFilter};
 pub use sp_offchain::{OffchainWorkerApi, STORAGE_PREFIX};
 
 const LOG_TARGET: &str = "offchain-worker";
@@ -67,6 +67,14 @@ pub struct OffchainWorkerOptions {
 	///
 	/// If not enabled, any http request will panic.
 	pub enable_http_requests: bool,
+	/// Configuration of the client used for http requests.
+	pub http_client_config: HttpClientConfig,
+}
+
+impl Default for OffchainWorkerOptions {
+	fn default() -> Self {
+		Self { enable_http_requests: true, http_client_config: Default::default() }
+	}
 }
 
 /// An offchain workers manager.
@@ -81,7 +89,7 @@ pub struct OffchainWorkers<Client, Block: traits::Block> {
 impl<Client, Block: traits::Block> OffchainWorkers<Client, Block> {
 	/// Creates new [`OffchainWorkers`].
 	pub fn new(client: Arc<Client>) -> Self {
-		Self::new_with_options(client, OffchainWorkerOptions { enable_http_requests: true })
+		Self::new_with_options(client, Default::default())
 	}
 
 	/// Creates new [`OffchainWorkers`] using the given `options`.
@@ -93,7 +101,7 @@ impl<Client, Block: traits::Block> OffchainWorkers<Client, Block> {
 				"offchain-worker".into(),
 				num_cpus::get(),
 			)),
-			shared_http_client: api::SharedClient::new(),
+			shared_http_client: api::SharedClient::new(options.http_client_config),
 			enable_http: options.enable_http_requests,
 		}
 	}
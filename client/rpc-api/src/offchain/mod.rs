@@ -33,4 +33,39 @@ pub trait OffchainApi {
 	/// Get offchain local storage under given key and prefix.
 	#[method(name = "offchain_localStorageGet")]
 	fn get_local_storage(&self, kind: StorageKind, key: Bytes) -> RpcResult<Option<Bytes>>;
+
+	/// Returns the keys with prefix from offchain local storage with pagination support.
+	/// Up to `count` keys will be returned.
+	/// If `start_key` is passed, return next keys in storage in lexicographic order.
+	#[method(name = "offchain_localStorageGetKeysPaged")]
+	fn get_local_storage_keys_paged(
+		&self,
+		kind: StorageKind,
+		prefix: Option<Bytes>,
+		count: u32,
+		start_key: Option<Bytes>,
+	) -> RpcResult<Vec<Bytes>>;
+
+	/// Returns the keys with prefix from offchain local storage together with their values,
+	/// with pagination support.
+	/// Up to `count` entries will be returned.
+	/// If `start_key` is passed, return next entries in storage in lexicographic order.
+	#[method(name = "offchain_localStorageGetPairsPaged")]
+	fn get_local_storage_pairs_paged(
+		&self,
+		kind: StorageKind,
+		prefix: Option<Bytes>,
+		count: u32,
+		start_key: Option<Bytes>,
+	) -> RpcResult<Vec<(Bytes, Bytes)>>;
+
+	/// Remove offchain local storage under given key and prefix.
+	#[method(name = "offchain_localStorageClear")]
+	fn clear_local_storage(&self, kind: StorageKind, key: Bytes) -> RpcResult<()>;
+
+	/// Remove all offchain local storage entries whose keys start with the given prefix.
+	///
+	/// Returns the number of removed entries.
+	#[method(name = "offchain_localStorageClearPrefix")]
+	fn clear_local_storage_prefix(&self, kind: StorageKind, prefix: Bytes) -> RpcResult<u32>;
 }
@@ -0,0 +1,241 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! API implementation for `archive`.
+
+use crate::archive::{
+	error::Error as ArchiveError,
+	storage::{ArchiveStorage, ArchiveStorageResult, MethodResult, StorageQuery},
+	ArchiveApiServer,
+};
+use codec::Encode;
+use jsonrpsee::core::{async_trait, RpcResult};
+use sc_client_api::{
+	Backend, BlockBackend, CallExecutor, ChildInfo, ExecutorProvider, ProofProvider, StorageKey,
+	StorageProvider,
+};
+use sp_blockchain::{
+	Backend as BlockChainBackend, Error as BlockChainError, HeaderBackend, HeaderMetadata,
+};
+use sp_core::{hexdisplay::HexDisplay, storage::well_known_keys, traits::CallContext, Bytes};
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT, NumberFor},
+	SaturatedConversion,
+};
+use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+
+/// The configuration of [`Archive`].
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveConfig {
+	/// The maximum number of results returned by a single descendant query.
+	pub max_descendant_responses: usize,
+	/// The maximum number of items queried by a single `archive_unstable_storage` call.
+	pub max_queried_items: usize,
+}
+
+/// The default maximum number of results returned by a single descendant query.
+const MAX_DESCENDANT_RESPONSES: usize = 5;
+/// The default maximum number of items queried by a single `archive_unstable_storage` call.
+const MAX_QUERIED_ITEMS: usize = 8;
+
+impl Default for ArchiveConfig {
+	fn default() -> Self {
+		Self {
+			max_descendant_responses: MAX_DESCENDANT_RESPONSES,
+			max_queried_items: MAX_QUERIED_ITEMS,
+		}
+	}
+}
+
+/// An API for archive RPC calls.
+pub struct Archive<BE, Block: BlockT, Client> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// Backend of the chain.
+	backend: Arc<BE>,
+	/// The configuration of the storage queries.
+	config: ArchiveConfig,
+	/// Phantom member to pin the block type.
+	_phantom: PhantomData<Block>,
+}
+
+impl<BE, Block: BlockT, Client> Archive<BE, Block, Client> {
+	/// Create a new [`Archive`].
+	pub fn new(client: Arc<Client>, backend: Arc<BE>, config: ArchiveConfig) -> Self {
+		Self { client, backend, config, _phantom: PhantomData }
+	}
+}
+
+/// Parse hex-encoded string parameter as raw bytes.
+fn parse_hex_param(param: String) -> Result<Vec<u8>, ArchiveError> {
+	// Methods can accept empty parameters, including the empty key `0x`.
+	if param.is_empty() || param == "0x" {
+		return Ok(Default::default())
+	}
+
+	array_bytes::hex2bytes(&param).map_err(|_| ArchiveError::InvalidParam(param))
+}
+
+#[async_trait]
+impl<BE, Block, Client> ArchiveApiServer<Block::Hash> for Archive<BE, Block, Client>
+where
+	Block: BlockT + 'static,
+	Block::Header: Unpin,
+	BE: Backend<Block> + 'static,
+	Client: BlockBackend<Block>
+		+ ExecutorProvider<Block>
+		+ HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = BlockChainError>
+		+ ProofProvider<Block>
+		+ StorageProvider<Block, BE>
+		+ 'static,
+{
+	fn archive_unstable_body(&self, hash: Block::Hash) -> RpcResult<Option<String>> {
+		let Ok(Some(signed_block)) = self.client.block(hash) else { return Ok(None) };
+
+		let extrinsics = signed_block.block.extrinsics();
+		Ok(Some(format!("0x{:?}", HexDisplay::from(&extrinsics.encode()))))
+	}
+
+	fn archive_unstable_header(&self, hash: Block::Hash) -> RpcResult<Option<String>> {
+		let Ok(Some(header)) = self.client.header(hash) else { return Ok(None) };
+
+		Ok(Some(format!("0x{:?}", HexDisplay::from(&header.encode()))))
+	}
+
+	fn archive_unstable_finalized_height(&self) -> RpcResult<u64> {
+		Ok(self.client.info().finalized_number.saturated_into())
+	}
+
+	fn archive_unstable_hash_by_height(&self, height: u64) -> RpcResult<Vec<String>> {
+		let height: NumberFor<Block> = height.try_into().map_err(|_| {
+			ArchiveError::InvalidParam(format!("Invalid block height: {}", height))
+		})?;
+
+		let finalized_num = self.client.info().finalized_number;
+		if finalized_num >= height {
+			let Ok(Some(hash)) = self.client.hash(height) else { return Ok(vec![]) };
+			return Ok(vec![format!("{:?}", hash)])
+		}
+
+		// Walk back from the leaves that are at least at the requested height.
+		let leaves = self
+			.backend
+			.blockchain()
+			.leaves()
+			.map_err(|error| ArchiveError::FetchLeaves(error.to_string()))?;
+		let mut headers: Vec<_> = leaves
+			.into_iter()
+			.filter_map(|hash| match self.client.header(hash) {
+				Ok(Some(header)) if *header.number() >= height => Some(header),
+				_ => None,
+			})
+			.collect();
+
+		let mut result = Vec::new();
+		let mut visited = HashSet::new();
+		while let Some(header) = headers.pop() {
+			if *header.number() == height {
+				result.push(format!("{:?}", header.hash()));
+				continue
+			}
+
+			// Forks may share a common non-finalized ancestor, visit it only once.
+			let parent_hash = *header.parent_hash();
+			if visited.insert(parent_hash) {
+				if let Ok(Some(parent)) = self.client.header(parent_hash) {
+					headers.push(parent);
+				}
+			}
+		}
+
+		Ok(result)
+	}
+
+	fn archive_unstable_call(
+		&self,
+		hash: Block::Hash,
+		function: String,
+		call_parameters: String,
+	) -> RpcResult<MethodResult> {
+		let call_parameters = Bytes::from(parse_hex_param(call_parameters)?);
+
+		let result = self.client.executor().call(
+			hash,
+			&function,
+			&call_parameters,
+			self.client.execution_extensions().strategies().other,
+			CallContext::Offchain,
+		);
+
+		Ok(match result {
+			Ok(result) => MethodResult::ok(format!("0x{:?}", HexDisplay::from(&result))),
+			Err(error) => MethodResult::err(error.to_string()),
+		})
+	}
+
+	fn archive_unstable_storage(
+		&self,
+		hash: Block::Hash,
+		items: Vec<StorageQuery<String>>,
+		child_trie: Option<String>,
+	) -> RpcResult<ArchiveStorageResult> {
+		let child_trie = child_trie.map(parse_hex_param).transpose()?;
+		let is_prefixed_key = |key: &[u8]| {
+			well_known_keys::is_default_child_storage_key(key) ||
+				well_known_keys::is_child_storage_key(key)
+		};
+
+		// The child key must not be prefixed with ":child_storage:" nor
+		// ":child_storage:default:".
+		if let Some(child_key) = &child_trie {
+			if is_prefixed_key(child_key) {
+				return Err(
+					ArchiveError::InvalidParam(format!("0x{}", hex::encode(child_key))).into()
+				)
+			}
+		}
+
+		let items = items
+			.into_iter()
+			.map(|query| {
+				let key = StorageKey(parse_hex_param(query.key)?);
+				let pagination_start_key = query
+					.pagination_start_key
+					.map(|key| parse_hex_param(key).map(StorageKey))
+					.transpose()?;
+
+				// Keys of the main trie must not be prefixed with ":child_storage:" nor
+				// ":child_storage:default:".
+				if child_trie.is_none() && is_prefixed_key(&key.0) {
+					return Err(ArchiveError::InvalidParam(format!("0x{}", hex::encode(&key.0))))
+				}
+
+				Ok(StorageQuery { key, query_type: query.query_type, pagination_start_key })
+			})
+			.collect::<Result<Vec<_>, ArchiveError>>()?;
+
+		let child_trie = child_trie.map(ChildInfo::new_default_from_vec);
+
+		let storage_client = ArchiveStorage::new(
+			self.client.clone(),
+			self.config.max_descendant_responses,
+			self.config.max_queried_items,
+		);
+		storage_client.handle_query(hash, items, child_trie).map_err(Into::into)
+	}
+}
@@ -0,0 +1,382 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Storage queries and results of the archive methods.
+
+use crate::archive::error::Error as ArchiveError;
+use codec::Decode;
+use sc_client_api::{Backend, ChildInfo, ProofProvider, StorageKey, StorageProvider};
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, HashFor, Header as HeaderT};
+use sp_trie::{LayoutV1, TrieDBBuilder, TrieDBNodeIterator};
+use std::{marker::PhantomData, sync::Arc};
+
+/// The type of the storage query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageQueryType {
+	/// Fetch the value of the provided key.
+	Value,
+	/// Fetch the hash of the value of the provided key.
+	Hash,
+	/// Fetch the closest descendant merkle value.
+	ClosestDescendantMerkleValue,
+	/// Fetch the values of all descendants of they provided key.
+	DescendantsValues,
+	/// Fetch the hashes of the values of all descendants of they provided key.
+	DescendantsHashes,
+}
+
+impl StorageQueryType {
+	/// Returns `true` if the query is a descendant query.
+	pub fn is_descendant_query(&self) -> bool {
+		matches!(self, Self::DescendantsValues | Self::DescendantsHashes)
+	}
+}
+
+/// The storage item to query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageQuery<Key> {
+	/// The provided key.
+	pub key: Key,
+	/// The type of the storage query.
+	#[serde(rename = "type")]
+	pub query_type: StorageQueryType,
+	/// The key after which descendant queries resume iterating.
+	///
+	/// This is ignored for non-descendant queries.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub pagination_start_key: Option<Key>,
+}
+
+/// The type of the storage query result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageResultType {
+	/// Fetch the value of the provided key.
+	Value(String),
+	/// Fetch the hash of the value of the provided key.
+	Hash(String),
+	/// Fetch the closest descendant merkle value.
+	ClosestDescendantMerkleValue(String),
+}
+
+/// The storage result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageResult {
+	/// The hex-encoded key of the result.
+	pub key: String,
+	/// The result of the query.
+	#[serde(flatten)]
+	pub result: StorageResultType,
+}
+
+/// The result of the `archive_unstable_storage` method.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStorageResult {
+	/// The storage results, in the order of the queried items.
+	pub items: Vec<StorageResult>,
+	/// The number of queried items that were not processed because they exceeded
+	/// the limit of items per call.
+	pub discarded_items: usize,
+	/// The descendant queries that stopped after the maximum number of responses.
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub truncated_queries: Vec<TruncatedQuery>,
+}
+
+/// A descendant query that has more results than were returned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TruncatedQuery {
+	/// The hex-encoded key of the query.
+	pub key: String,
+	/// The hex-encoded last returned key, to pass as the pagination start key of the query
+	/// to fetch the remaining results.
+	pub pagination_start_key: String,
+}
+
+/// The result of a runtime call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodResult {
+	/// Whether the runtime call succeeded.
+	pub success: bool,
+	/// The hex-encoded result of a successful call.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub value: Option<String>,
+	/// The reason of a failed call.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+impl MethodResult {
+	/// Construct a successful result.
+	pub fn ok(value: impl Into<String>) -> Self {
+		Self { success: true, value: Some(value.into()), error: None }
+	}
+
+	/// Construct a failed result.
+	pub fn err(error: impl Into<String>) -> Self {
+		Self { success: false, value: None, error: Some(error.into()) }
+	}
+}
+
+/// Format the bytes as hexadecimal string prefixed by `0x`.
+fn hex_string<Data: AsRef<[u8]>>(data: &Data) -> String {
+	format!("0x{}", hex::encode(data))
+}
+
+/// Executes the storage queries of the archive methods.
+pub(crate) struct ArchiveStorage<Client, Block, BE> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// The maximum number of results returned by a single descendant query.
+	max_descendant_responses: usize,
+	/// The maximum number of items queried by a single call.
+	max_queried_items: usize,
+	/// Phantom member to pin the block type.
+	_phantom: PhantomData<(Block, BE)>,
+}
+
+impl<Client, Block, BE> ArchiveStorage<Client, Block, BE> {
+	/// Constructs a new [`ArchiveStorage`].
+	pub fn new(
+		client: Arc<Client>,
+		max_descendant_responses: usize,
+		max_queried_items: usize,
+	) -> Self {
+		Self { client, max_descendant_responses, max_queried_items, _phantom: PhantomData }
+	}
+}
+
+impl<Client, Block, BE> ArchiveStorage<Client, Block, BE>
+where
+	Block: BlockT + 'static,
+	BE: Backend<Block> + 'static,
+	Client: StorageProvider<Block, BE> + ProofProvider<Block> + HeaderBackend<Block> + 'static,
+{
+	/// Execute the provided storage queries at the given block.
+	///
+	/// Queries exceeding the number of items allowed per call are discarded, while descendant
+	/// queries stop after the configured number of responses. The last returned key can then
+	/// be used as the pagination start key to continue the iteration.
+	pub fn handle_query(
+		&self,
+		hash: Block::Hash,
+		mut items: Vec<StorageQuery<StorageKey>>,
+		child_key: Option<ChildInfo>,
+	) -> Result<ArchiveStorageResult, ArchiveError> {
+		let discarded_items = items.len().saturating_sub(self.max_queried_items);
+		items.truncate(self.max_queried_items);
+
+		let mut storage_results = Vec::with_capacity(items.len());
+		let mut truncated_queries = Vec::new();
+		for item in items {
+			match item.query_type {
+				StorageQueryType::Value =>
+					if let Some(value) = self.query_value(hash, &item.key, child_key.as_ref())? {
+						storage_results.push(StorageResult {
+							key: hex_string(&item.key.0),
+							result: StorageResultType::Value(hex_string(&value)),
+						});
+					},
+				StorageQueryType::Hash =>
+					if let Some(value) = self.query_hash(hash, &item.key, child_key.as_ref())? {
+						storage_results.push(StorageResult {
+							key: hex_string(&item.key.0),
+							result: StorageResultType::Hash(hex_string(&value)),
+						});
+					},
+				StorageQueryType::ClosestDescendantMerkleValue =>
+					if let Some(value) =
+						self.query_merkle_value(hash, &item.key, child_key.as_ref())?
+					{
+						storage_results.push(StorageResult {
+							key: hex_string(&item.key.0),
+							result: StorageResultType::ClosestDescendantMerkleValue(hex_string(
+								&value,
+							)),
+						});
+					},
+				StorageQueryType::DescendantsValues | StorageQueryType::DescendantsHashes =>
+					if let Some(truncated) = self.query_descendants(
+						hash,
+						&item,
+						child_key.as_ref(),
+						&mut storage_results,
+					)? {
+						truncated_queries.push(truncated);
+					},
+			}
+		}
+
+		Ok(ArchiveStorageResult { items: storage_results, discarded_items, truncated_queries })
+	}
+
+	/// Fetch the value of the provided key.
+	fn query_value(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> Result<Option<Vec<u8>>, ArchiveError> {
+		let result = match child_key {
+			Some(child_key) => self.client.child_storage(hash, child_key, key),
+			None => self.client.storage(hash, key),
+		};
+
+		result
+			.map(|opt| opt.map(|storage| storage.0))
+			.map_err(|error| ArchiveError::FetchStorage(error.to_string()))
+	}
+
+	/// Fetch the hash of the value of the provided key.
+	fn query_hash(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> Result<Option<Block::Hash>, ArchiveError> {
+		let result = match child_key {
+			Some(child_key) => self.client.child_storage_hash(hash, child_key, key),
+			None => self.client.storage_hash(hash, key),
+		};
+
+		result.map_err(|error| ArchiveError::FetchStorage(error.to_string()))
+	}
+
+	/// Fetch the values or hashes of all descendants of the provided key.
+	///
+	/// Returns the query to continue with if there are more descendants than the maximum
+	/// number of responses.
+	fn query_descendants(
+		&self,
+		hash: Block::Hash,
+		query: &StorageQuery<StorageKey>,
+		child_key: Option<&ChildInfo>,
+		storage_results: &mut Vec<StorageResult>,
+	) -> Result<Option<TruncatedQuery>, ArchiveError> {
+		let mut keys = match child_key {
+			Some(child_key) => self.client.child_storage_keys(
+				hash,
+				child_key.clone(),
+				Some(&query.key),
+				query.pagination_start_key.as_ref(),
+			),
+			None => self.client.storage_keys(
+				hash,
+				Some(&query.key),
+				query.pagination_start_key.as_ref(),
+			),
+		}
+		.map_err(|error| ArchiveError::FetchStorage(error.to_string()))?;
+
+		let mut last_key = None;
+		for key in keys.by_ref().take(self.max_descendant_responses) {
+			let result = match query.query_type {
+				StorageQueryType::DescendantsValues => self
+					.query_value(hash, &key, child_key)?
+					.map(|value| StorageResultType::Value(hex_string(&value))),
+				_ => self
+					.query_hash(hash, &key, child_key)?
+					.map(|value| StorageResultType::Hash(hex_string(&value))),
+			};
+
+			if let Some(result) = result {
+				storage_results.push(StorageResult { key: hex_string(&key.0), result });
+			}
+			last_key = Some(key);
+		}
+
+		if keys.next().is_none() {
+			return Ok(None)
+		}
+		Ok(last_key.map(|last_key| TruncatedQuery {
+			key: hex_string(&query.key.0),
+			pagination_start_key: hex_string(&last_key.0),
+		}))
+	}
+
+	/// Fetch the merkle value of the closest descendant node of the provided key.
+	///
+	/// The merkle value is the hash of the node, or the node itself if its encoding is
+	/// shorter than the hash and therefore inlined in the parent node.
+	///
+	/// The node is found by walking a read proof of the key, since the proof contains
+	/// every trie node visited while looking up the key, including the closest descendant.
+	fn query_merkle_value(
+		&self,
+		hash: Block::Hash,
+		key: &StorageKey,
+		child_key: Option<&ChildInfo>,
+	) -> Result<Option<Vec<u8>>, ArchiveError> {
+		let to_error = |error: sp_blockchain::Error| ArchiveError::FetchStorage(error.to_string());
+
+		let (proof, root) = match child_key {
+			Some(child_key) => {
+				let child_root_key = StorageKey(child_key.prefixed_storage_key().into_inner());
+				let Some(child_root) =
+					self.client.storage(hash, &child_root_key).map_err(to_error)?
+				else {
+					// The child trie does not exist.
+					return Ok(None)
+				};
+				let root = Block::Hash::decode(&mut &child_root.0[..])
+					.map_err(|error| ArchiveError::FetchStorage(error.to_string()))?;
+				let proof = self
+					.client
+					.read_child_proof(hash, child_key, &mut std::iter::once(key.as_ref()))
+					.map_err(to_error)?;
+
+				(proof, root)
+			},
+			None => {
+				let Some(header) = self.client.header(hash).map_err(to_error)? else {
+					return Ok(None)
+				};
+				let proof = self
+					.client
+					.read_proof(hash, &mut std::iter::once(key.as_ref()))
+					.map_err(to_error)?;
+
+				(proof, *header.state_root())
+			},
+		};
+
+		let db = proof.into_memory_db::<HashFor<Block>>();
+		let trie = TrieDBBuilder::<LayoutV1<HashFor<Block>>>::new(&db, &root).build();
+		let to_error = |error: Box<sp_trie::TrieError<LayoutV1<HashFor<Block>>>>| {
+			ArchiveError::FetchStorage(error.to_string())
+		};
+
+		let mut iter = TrieDBNodeIterator::new(&trie).map_err(to_error)?;
+		iter.prefix(key.as_ref()).map_err(to_error)?;
+
+		// The first node under the prefix is the closest descendant of the key.
+		let Some(item) = iter.next() else { return Ok(None) };
+		let (_, node_hash, node) = item.map_err(to_error)?;
+
+		Ok(Some(match node_hash {
+			Some(node_hash) => node_hash.as_ref().to_vec(),
+			None => node.data().to_vec(),
+		}))
+	}
+}
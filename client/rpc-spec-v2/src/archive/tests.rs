@@ -0,0 +1,464 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use super::*;
+use assert_matches::assert_matches;
+use codec::{Decode, Encode};
+use jsonrpsee::{
+	core::error::Error,
+	rpc_params,
+	types::{error::CallError, EmptyServerParams as EmptyParams},
+	RpcModule,
+};
+use sc_block_builder::BlockBuilderProvider;
+use sc_client_api::ChildInfo;
+use sp_consensus::BlockOrigin;
+use sp_runtime::traits::{BlakeTwo256, Hash};
+use std::sync::Arc;
+use substrate_test_runtime::Transfer;
+use substrate_test_runtime_client::{
+	prelude::*, runtime, Backend, BlockBuilderExt, Client, ClientBlockImportExt,
+};
+
+type Header = substrate_test_runtime_client::runtime::Header;
+type Block = substrate_test_runtime_client::runtime::Block;
+const INVALID_HASH: [u8; 32] = [1; 32];
+const KEY: &[u8] = b":mock";
+const VALUE: &[u8] = b"hello world";
+const CHILD_STORAGE_KEY: &[u8] = b"child";
+const CHILD_VALUE: &[u8] = b"child value";
+const MAX_DESCENDANT_RESPONSES: usize = 2;
+const MAX_QUERIED_ITEMS: usize = 4;
+
+fn hex_string<Data: AsRef<[u8]>>(data: &Data) -> String {
+	format!("0x{}", hex::encode(data))
+}
+
+fn setup_api() -> (Arc<Client<Backend>>, RpcModule<Archive<Backend, Block, Client<Backend>>>) {
+	let child_info = ChildInfo::new_default(CHILD_STORAGE_KEY);
+	let builder = TestClientBuilder::new().add_extra_child_storage(
+		&child_info,
+		KEY.to_vec(),
+		CHILD_VALUE.to_vec(),
+	);
+	let backend = builder.backend();
+	let client = Arc::new(builder.build());
+
+	let api = Archive::new(
+		client.clone(),
+		backend,
+		ArchiveConfig {
+			max_descendant_responses: MAX_DESCENDANT_RESPONSES,
+			max_queried_items: MAX_QUERIED_ITEMS,
+		},
+	)
+	.into_rpc();
+
+	(client, api)
+}
+
+#[tokio::test]
+async fn archive_body() {
+	let (mut client, api) = setup_api();
+
+	// Invalid block hash.
+	let invalid_hash = hex_string(&INVALID_HASH);
+	let body: Option<String> = api.call("archive_unstable_body", [invalid_hash]).await.unwrap();
+	assert!(body.is_none());
+
+	// Import a new block with an extrinsic.
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder
+		.push_transfer(runtime::Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 42,
+			nonce: 0,
+		})
+		.unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let expected = hex_string(&block.extrinsics.encode());
+	let body: Option<String> = api.call("archive_unstable_body", [block_hash]).await.unwrap();
+	assert_eq!(body, Some(expected));
+}
+
+#[tokio::test]
+async fn archive_header() {
+	let (mut client, api) = setup_api();
+
+	// Invalid block hash.
+	let invalid_hash = hex_string(&INVALID_HASH);
+	let header: Option<String> = api.call("archive_unstable_header", [invalid_hash]).await.unwrap();
+	assert!(header.is_none());
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let header: Option<String> = api.call("archive_unstable_header", [block_hash]).await.unwrap();
+	let bytes = array_bytes::hex2bytes(&header.unwrap()).unwrap();
+	let header: Header = Decode::decode(&mut &bytes[..]).unwrap();
+	assert_eq!(header, block.header);
+}
+
+#[tokio::test]
+async fn archive_finalized_height() {
+	let (mut client, api) = setup_api();
+
+	let height: u64 =
+		api.call("archive_unstable_finalizedHeight", EmptyParams::new()).await.unwrap();
+	assert_eq!(height, 0);
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+	client.finalize_block(block.header.hash(), None).unwrap();
+
+	let height: u64 =
+		api.call("archive_unstable_finalizedHeight", EmptyParams::new()).await.unwrap();
+	assert_eq!(height, 1);
+}
+
+#[tokio::test]
+async fn archive_hash_by_height() {
+	let (mut client, api) = setup_api();
+
+	// Genesis height.
+	let hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [0]).await.unwrap();
+	assert_eq!(hashes, vec![format!("{:?}", client.genesis_hash())]);
+
+	// Block tree:
+	// genesis -> finalized -> block 2 -> block 3
+	//                      -> block 4 -> block 5
+	//                      -> block 6
+	let finalized = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let finalized_hash = finalized.header.hash();
+	client.import(BlockOrigin::Own, finalized.clone()).await.unwrap();
+	client.finalize_block(finalized_hash, None).unwrap();
+
+	let block_2 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_2_hash = block_2.header.hash();
+	client.import(BlockOrigin::Own, block_2.clone()).await.unwrap();
+
+	let block_3 = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_3_hash = block_3.header.hash();
+	client.import(BlockOrigin::Own, block_3.clone()).await.unwrap();
+
+	// Blocks on the forks require a transfer to have a different hash.
+	let mut block_builder = client.new_block_at(finalized_hash, Default::default(), false).unwrap();
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Alice.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let block_4 = block_builder.build().unwrap().block;
+	let block_4_hash = block_4.header.hash();
+	client.import(BlockOrigin::Own, block_4.clone()).await.unwrap();
+
+	let block_5 = client
+		.new_block_at(block_4_hash, Default::default(), false)
+		.unwrap()
+		.build()
+		.unwrap()
+		.block;
+	let block_5_hash = block_5.header.hash();
+	client.import(BlockOrigin::Own, block_5.clone()).await.unwrap();
+
+	let mut block_builder = client.new_block_at(finalized_hash, Default::default(), false).unwrap();
+	block_builder
+		.push_transfer(Transfer {
+			from: AccountKeyring::Bob.into(),
+			to: AccountKeyring::Ferdie.into(),
+			amount: 41,
+			nonce: 0,
+		})
+		.unwrap();
+	let block_6 = block_builder.build().unwrap().block;
+	let block_6_hash = block_6.header.hash();
+	client.import(BlockOrigin::Own, block_6.clone()).await.unwrap();
+
+	// Finalized height returns only the canonical block.
+	let hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [1]).await.unwrap();
+	assert_eq!(hashes, vec![format!("{:?}", finalized_hash)]);
+
+	// Non-finalized heights return all the forks.
+	let mut hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [2]).await.unwrap();
+	hashes.sort();
+	let mut expected = vec![
+		format!("{:?}", block_2_hash),
+		format!("{:?}", block_4_hash),
+		format!("{:?}", block_6_hash),
+	];
+	expected.sort();
+	assert_eq!(hashes, expected);
+
+	let mut hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [3]).await.unwrap();
+	hashes.sort();
+	let mut expected = vec![format!("{:?}", block_3_hash), format!("{:?}", block_5_hash)];
+	expected.sort();
+	assert_eq!(hashes, expected);
+
+	// Height in the future.
+	let hashes: Vec<String> = api.call("archive_unstable_hashByHeight", [10]).await.unwrap();
+	assert!(hashes.is_empty());
+}
+
+#[tokio::test]
+async fn archive_call() {
+	let (mut client, api) = setup_api();
+	let invalid_hash = hex_string(&INVALID_HASH);
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	// Invalid parameters that cannot be decoded.
+	let err = api
+		.call::<_, serde_json::Value>(
+			"archive_unstable_call",
+			[&block_hash, "BabeApi_current_epoch", "0x0"],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 3001 && err.message().contains("Invalid parameter")
+	);
+
+	// Invalid block hash.
+	let result: MethodResult = api
+		.call("archive_unstable_call", [&invalid_hash, "BabeApi_current_epoch", "0x00"])
+		.await
+		.unwrap();
+	assert!(!result.success);
+
+	let alice_id = AccountKeyring::Alice.to_account_id();
+	let call_parameters = hex_string(&alice_id.encode());
+	let result: MethodResult = api
+		.call(
+			"archive_unstable_call",
+			[&block_hash, "AccountNonceApi_account_nonce", &call_parameters],
+		)
+		.await
+		.unwrap();
+	assert_eq!(result, MethodResult::ok("0x0000000000000000"));
+
+	// The `current_epoch` takes no parameters and not draining the input buffer
+	// will cause the execution to fail.
+	let result: MethodResult = api
+		.call("archive_unstable_call", [&block_hash, "BabeApi_current_epoch", "0x00"])
+		.await
+		.unwrap();
+	assert_matches!(result.error, Some(error) if error.contains("Execution failed"));
+}
+
+#[tokio::test]
+async fn archive_storage_values_and_hashes() {
+	let (mut client, api) = setup_api();
+	let key = hex_string(&KEY);
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_storage_change(KEY.to_vec(), Some(VALUE.to_vec())).unwrap();
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let result: ArchiveStorageResult = api
+		.call(
+			"archive_unstable_storage",
+			rpc_params![
+				&block_hash,
+				vec![
+					StorageQuery {
+						key: key.clone(),
+						query_type: StorageQueryType::Value,
+						pagination_start_key: None,
+					},
+					StorageQuery {
+						key: key.clone(),
+						query_type: StorageQueryType::Hash,
+						pagination_start_key: None,
+					},
+				]
+			],
+		)
+		.await
+		.unwrap();
+
+	let expected_hash = format!("{:?}", BlakeTwo256::hash(VALUE));
+	assert_eq!(
+		result,
+		ArchiveStorageResult {
+			items: vec![
+				StorageResult {
+					key: key.clone(),
+					result: StorageResultType::Value(hex_string(&VALUE)),
+				},
+				StorageResult { key: key.clone(), result: StorageResultType::Hash(expected_hash) },
+			],
+			discarded_items: 0,
+			truncated_queries: vec![],
+		}
+	);
+
+	// Child value set in `setup_api`.
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+	let result: ArchiveStorageResult = api
+		.call(
+			"archive_unstable_storage",
+			rpc_params![
+				&genesis_hash,
+				vec![StorageQuery {
+					key: key.clone(),
+					query_type: StorageQueryType::Value,
+					pagination_start_key: None,
+				}],
+				hex_string(&CHILD_STORAGE_KEY)
+			],
+		)
+		.await
+		.unwrap();
+	assert_eq!(
+		result.items,
+		vec![StorageResult { key, result: StorageResultType::Value(hex_string(&CHILD_VALUE)) }]
+	);
+}
+
+#[tokio::test]
+async fn archive_storage_descendants_paginated() {
+	let (mut client, api) = setup_api();
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	for i in 0..3u8 {
+		builder.push_storage_change(vec![b':', b'm', i], Some(vec![i])).unwrap();
+	}
+	let block = builder.build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let query = |pagination_start_key: Option<String>| StorageQuery {
+		key: hex_string(b":m"),
+		query_type: StorageQueryType::DescendantsValues,
+		pagination_start_key,
+	};
+
+	// The first page is limited by the maximum number of descendant responses.
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, vec![query(None)]])
+		.await
+		.unwrap();
+	let expected: Vec<_> = (0..2u8)
+		.map(|i| StorageResult {
+			key: hex_string(&[b':', b'm', i]),
+			result: StorageResultType::Value(hex_string(&[i])),
+		})
+		.collect();
+	assert_eq!(result.items, expected);
+	let last_key = result.items.last().unwrap().key.clone();
+	assert_eq!(
+		result.truncated_queries,
+		vec![TruncatedQuery { key: hex_string(b":m"), pagination_start_key: last_key.clone() }]
+	);
+
+	// Continue from the last returned key.
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, vec![query(Some(last_key))]])
+		.await
+		.unwrap();
+	assert_eq!(
+		result.items,
+		vec![StorageResult {
+			key: hex_string(&[b':', b'm', 2]),
+			result: StorageResultType::Value(hex_string(&[2])),
+		}]
+	);
+	assert!(result.truncated_queries.is_empty());
+}
+
+#[tokio::test]
+async fn archive_storage_closest_merkle_value() {
+	let (mut client, api) = setup_api();
+
+	let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+	let block_hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block.clone()).await.unwrap();
+
+	let query = |key: &[u8]| StorageQuery {
+		key: hex_string(&key),
+		query_type: StorageQueryType::ClosestDescendantMerkleValue,
+		pagination_start_key: None,
+	};
+
+	// The closest descendant of the empty key is the root node.
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, vec![query(&[])]])
+		.await
+		.unwrap();
+	assert_eq!(
+		result.items,
+		vec![StorageResult {
+			key: "0x".into(),
+			result: StorageResultType::ClosestDescendantMerkleValue(hex_string(
+				&block.header.state_root
+			)),
+		}]
+	);
+
+	// Keys without descendants have no merkle value.
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&block_hash, vec![query(b"\xff\xff")]])
+		.await
+		.unwrap();
+	assert!(result.items.is_empty());
+}
+
+#[tokio::test]
+async fn archive_storage_discarded_items() {
+	let (client, api) = setup_api();
+	let genesis_hash = format!("{:?}", client.genesis_hash());
+
+	let items: Vec<_> = (0..MAX_QUERIED_ITEMS + 2)
+		.map(|_| StorageQuery {
+			key: hex_string(&KEY),
+			query_type: StorageQueryType::Value,
+			pagination_start_key: None,
+		})
+		.collect();
+	let result: ArchiveStorageResult = api
+		.call("archive_unstable_storage", rpc_params![&genesis_hash, items])
+		.await
+		.unwrap();
+	assert_eq!(result.discarded_items, 2);
+
+	// Keys of the main trie must not be prefixed with the child storage prefix.
+	let items = vec![StorageQuery {
+		key: hex_string(&b":child_storage:default:child"),
+		query_type: StorageQueryType::Value,
+		pagination_start_key: None,
+	}];
+	let err = api
+		.call::<_, serde_json::Value>("archive_unstable_storage", rpc_params![&genesis_hash, items])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 3001 && err.message().contains("Invalid parameter")
+	);
+}
@@ -19,7 +19,7 @@
 #![allow(non_snake_case)]
 
 //! API trait of the chain head.
-use crate::chain_head::event::{ChainHeadEvent, FollowEvent, NetworkConfig};
+use crate::chain_head::event::{ChainHeadEvent, FollowEvent, NetworkConfig, StorageDiffResult};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 #[rpc(client, server)]
@@ -105,6 +105,38 @@ pub trait ChainHeadApi<Hash> {
 		network_config: Option<NetworkConfig>,
 	);
 
+	/// Return the storage entries that differ between the states of two pinned blocks.
+	///
+	/// The entries under the `key` prefix that were added, modified or deleted in the
+	/// state of `hash` compared to the state of `previous_hash` are reported in
+	/// lexicographic order of their keys.
+	///
+	/// A single call reports a bounded number of entries and compares a bounded number of
+	/// keys. If either limit is reached, the result contains a `paginationStartKey`, and
+	/// the following entries are obtained by calling the method again with that key as
+	/// `start_key`.
+	///
+	/// Keys prefixed by `:child_storage:` are rejected. A child trie is queried by providing
+	/// its unprefixed key as `child_key`.
+	///
+	/// # Unstable
+	///
+	/// This method is unstable and subject to change in the future.
+	#[subscription(
+		name = "chainHead_unstable_storageDiff",
+		unsubscribe = "chainHead_unstable_stopStorageDiff",
+		item = ChainHeadEvent<StorageDiffResult>,
+	)]
+	fn chain_head_unstable_storage_diff(
+		&self,
+		follow_subscription: String,
+		hash: Hash,
+		previous_hash: Hash,
+		key: String,
+		child_key: Option<String>,
+		start_key: Option<String>,
+	);
+
 	/// Call into the Runtime API at a specified block's state.
 	///
 	/// # Unstable
@@ -23,7 +23,10 @@ use crate::{
 		api::ChainHeadApiServer,
 		chain_head_follow::ChainHeadFollower,
 		error::Error as ChainHeadRpcError,
-		event::{ChainHeadEvent, ChainHeadResult, ErrorEvent, FollowEvent, NetworkConfig},
+		event::{
+			ChainHeadEvent, ChainHeadResult, ErrorEvent, FollowEvent, NetworkConfig,
+			StorageDiffItem, StorageDiffResult, StorageDiffType,
+		},
 		subscription::SubscriptionManagement,
 	},
 	SubscriptionTaskExecutor,
@@ -44,10 +47,16 @@ use sp_api::CallApiAt;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_core::{hexdisplay::HexDisplay, storage::well_known_keys, traits::CallContext, Bytes};
 use sp_runtime::traits::Block as BlockT;
-use std::{marker::PhantomData, sync::Arc};
+use std::{cmp::Ordering, marker::PhantomData, sync::Arc};
 
 pub(crate) const LOG_TARGET: &str = "rpc-spec-v2";
 
+/// The maximum number of entries reported by a single `chainHead_unstable_storageDiff` call.
+pub(crate) const MAX_STORAGE_DIFF_ITEMS: usize = 32;
+
+/// The maximum number of keys compared by a single `chainHead_unstable_storageDiff` call.
+pub(crate) const MAX_STORAGE_DIFF_VISITED_KEYS: usize = 256;
+
 /// An API for chain head RPC calls.
 pub struct ChainHead<BE, Block: BlockT, Client> {
 	/// Substrate client.
@@ -353,6 +362,74 @@ where
 		Ok(())
 	}
 
+	fn chain_head_unstable_storage_diff(
+		&self,
+		mut sink: SubscriptionSink,
+		follow_subscription: String,
+		hash: Block::Hash,
+		previous_hash: Block::Hash,
+		key: String,
+		child_key: Option<String>,
+		start_key: Option<String>,
+	) -> SubscriptionResult {
+		let key = StorageKey(parse_hex_param(&mut sink, key)?);
+
+		let child_key = child_key
+			.map(|child_key| parse_hex_param(&mut sink, child_key))
+			.transpose()?
+			.map(ChildInfo::new_default_from_vec);
+
+		let start_key = start_key
+			.map(|start_key| parse_hex_param(&mut sink, start_key))
+			.transpose()?
+			.map(StorageKey);
+
+		let client = self.client.clone();
+		let subscriptions = self.subscriptions.clone();
+
+		let fut = async move {
+			let Some(handle) = subscriptions.get_subscription(&follow_subscription) else {
+				// Invalid invalid subscription ID.
+				let _ = sink.send(&ChainHeadEvent::<String>::Disjoint);
+				return
+			};
+
+			// Both blocks must be part of the subscription.
+			if !handle.contains_block(&hash) || !handle.contains_block(&previous_hash) {
+				let _ = sink.reject(ChainHeadRpcError::InvalidBlock);
+				return
+			}
+
+			// The keys must not be prefixed with ":child_storage:" nor
+			// ":child_storage:default:".
+			let queried_key =
+				child_key.as_ref().map_or(key.0.as_slice(), |child_key| child_key.storage_key());
+			if well_known_keys::is_default_child_storage_key(queried_key) ||
+				well_known_keys::is_child_storage_key(queried_key)
+			{
+				let _ = sink.reject(ChainHeadRpcError::InvalidParam(
+					"The key must not be prefixed by `:child_storage:`".into(),
+				));
+				return
+			}
+
+			let res = storage_diff(
+				&*client,
+				hash,
+				previous_hash,
+				&key,
+				child_key.as_ref(),
+				start_key.as_ref(),
+			)
+			.map(|result| ChainHeadEvent::Done(ChainHeadResult { result }))
+			.unwrap_or_else(|error| ChainHeadEvent::Error(ErrorEvent { error: error.to_string() }));
+			let _ = sink.send(&res);
+		};
+
+		self.executor.spawn("substrate-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+
 	fn chain_head_unstable_call(
 		&self,
 		mut sink: SubscriptionSink,
@@ -429,3 +506,95 @@ where
 		Ok(())
 	}
 }
+
+/// Compute the entries under the `prefix` that differ between the states of
+/// `previous_hash` and `hash`.
+///
+/// The keys of both states are iterated in lexicographic order, starting after
+/// the `start_key` if provided, and the values are compared by their hashes.
+/// At most [`MAX_STORAGE_DIFF_ITEMS`] entries are returned and at most
+/// [`MAX_STORAGE_DIFF_VISITED_KEYS`] keys are compared, such that unchanged entries
+/// cannot make the call arbitrarily long. The key to resume from is returned when
+/// either limit is reached before the end of the prefix.
+fn storage_diff<Block, BE, Client>(
+	client: &Client,
+	hash: Block::Hash,
+	previous_hash: Block::Hash,
+	prefix: &StorageKey,
+	child_key: Option<&ChildInfo>,
+	start_key: Option<&StorageKey>,
+) -> Result<StorageDiffResult, BlockChainError>
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	Client: StorageProvider<Block, BE>,
+{
+	let storage_keys = |hash| match child_key {
+		Some(child_key) =>
+			client.child_storage_keys(hash, child_key.clone(), Some(prefix), start_key),
+		None => client.storage_keys(hash, Some(prefix), start_key),
+	};
+	let storage_hash = |hash, key: &StorageKey| match child_key {
+		Some(child_key) => client.child_storage_hash(hash, child_key, key),
+		None => client.storage_hash(hash, key),
+	};
+	let storage_value = |key: &StorageKey| {
+		match child_key {
+			Some(child_key) => client.child_storage(hash, child_key, key),
+			None => client.storage(hash, key),
+		}
+		.map(|value| value.map(|value| format!("0x{:?}", HexDisplay::from(&value.0))))
+	};
+
+	let mut keys = storage_keys(hash)?.peekable();
+	let mut previous_keys = storage_keys(previous_hash)?.peekable();
+	let mut items = Vec::new();
+	let mut visited_keys = 0;
+	let mut last_key = None;
+
+	while items.len() < MAX_STORAGE_DIFF_ITEMS && visited_keys < MAX_STORAGE_DIFF_VISITED_KEYS {
+		let ordering = match (keys.peek(), previous_keys.peek()) {
+			(None, None) => break,
+			(Some(_), None) => Ordering::Less,
+			(None, Some(_)) => Ordering::Greater,
+			(Some(key), Some(previous_key)) => key.cmp(previous_key),
+		};
+
+		visited_keys += 1;
+		let (key, diff_type) = match ordering {
+			Ordering::Less => (keys.next(), StorageDiffType::Added),
+			Ordering::Greater => (previous_keys.next(), StorageDiffType::Deleted),
+			Ordering::Equal => {
+				previous_keys.next();
+				(keys.next(), StorageDiffType::Modified)
+			},
+		};
+		let key = key.expect("Key was peeked above; qed");
+
+		let value = match diff_type {
+			StorageDiffType::Added => storage_value(&key)?,
+			StorageDiffType::Deleted => None,
+			StorageDiffType::Modified => {
+				if storage_hash(hash, &key)? == storage_hash(previous_hash, &key)? {
+					last_key = Some(key);
+					continue
+				}
+				storage_value(&key)?
+			},
+		};
+		items.push(StorageDiffItem {
+			key: format!("0x{:?}", HexDisplay::from(&key.0)),
+			diff_type,
+			value,
+		});
+		last_key = Some(key);
+	}
+
+	// Resume after the last compared key if any key was left out.
+	let pagination_start_key = match (keys.peek(), previous_keys.peek()) {
+		(None, None) => None,
+		_ => last_key.map(|key| format!("0x{:?}", HexDisplay::from(&key.0))),
+	};
+
+	Ok(StorageDiffResult { items, pagination_start_key })
+}
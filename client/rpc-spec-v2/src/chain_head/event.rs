@@ -226,6 +226,47 @@ pub struct ChainHeadResult<T> {
 	pub result: T,
 }
 
+/// The type of change of a storage entry between the states of two blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageDiffType {
+	/// The entry exists only in the state of the newer block.
+	Added,
+	/// The entry exists in both states, with different values.
+	Modified,
+	/// The entry exists only in the state of the older block.
+	Deleted,
+}
+
+/// A storage entry that differs between the states of two blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDiffItem {
+	/// The hexadecimal encoded key of the entry.
+	pub key: String,
+	/// The type of change.
+	#[serde(rename = "type")]
+	pub diff_type: StorageDiffType,
+	/// The hexadecimal encoded value of the entry in the state of the newer block.
+	///
+	/// This is `None` for deleted entries.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub value: Option<String>,
+}
+
+/// The entries that differ between the states of two blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageDiffResult {
+	/// The entries that differ, in lexicographic order of their keys.
+	pub items: Vec<StorageDiffItem>,
+	/// The hexadecimal encoded key to provide as `start_key` to obtain the following entries.
+	///
+	/// This is `None` once all the keys under the prefix were compared.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub pagination_start_key: Option<String>,
+}
+
 /// The event generated by the body / call / storage methods.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -462,6 +503,34 @@ mod tests {
 		assert_eq!(event_dec, event);
 	}
 
+	#[test]
+	fn chain_head_storage_diff_event() {
+		let event: ChainHeadEvent<StorageDiffResult> = ChainHeadEvent::Done(ChainHeadResult {
+			result: StorageDiffResult {
+				items: vec![
+					StorageDiffItem {
+						key: "0x1".into(),
+						diff_type: StorageDiffType::Added,
+						value: Some("0x2".into()),
+					},
+					StorageDiffItem {
+						key: "0x3".into(),
+						diff_type: StorageDiffType::Deleted,
+						value: None,
+					},
+				],
+				pagination_start_key: Some("0x3".into()),
+			},
+		});
+
+		let ser = serde_json::to_string(&event).unwrap();
+		let exp = r#"{"event":"done","result":{"items":[{"key":"0x1","type":"added","value":"0x2"},{"key":"0x3","type":"deleted"}],"paginationStartKey":"0x3"}}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: ChainHeadEvent<StorageDiffResult> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
+	}
+
 	#[test]
 	fn chain_head_network_config() {
 		let conf = NetworkConfig {
@@ -39,5 +39,6 @@ pub use api::ChainHeadApiServer;
 pub use chain_head::ChainHead;
 pub use event::{
 	BestBlockChanged, ChainHeadEvent, ChainHeadResult, ErrorEvent, Finalized, FollowEvent,
-	Initialized, NetworkConfig, NewBlock, RuntimeEvent, RuntimeVersionEvent,
+	Initialized, NetworkConfig, NewBlock, RuntimeEvent, RuntimeVersionEvent, StorageDiffItem,
+	StorageDiffResult, StorageDiffType,
 };
@@ -1,4 +1,7 @@
-use crate::chain_head::test_utils::ChainHeadMockClient;
+use crate::chain_head::{
+	chain_head::{MAX_STORAGE_DIFF_ITEMS, MAX_STORAGE_DIFF_VISITED_KEYS},
+	test_utils::ChainHeadMockClient,
+};
 
 use super::*;
 use assert_matches::assert_matches;
@@ -6,6 +9,7 @@ use codec::{Decode, Encode};
 use futures::Future;
 use jsonrpsee::{
 	core::{error::Error, server::rpc_module::Subscription as RpcSubscription},
+	rpc_params,
 	types::{error::CallError, EmptyServerParams as EmptyParams},
 	RpcModule,
 };
@@ -181,7 +185,8 @@ async fn follow_with_runtime() {
 		\"specVersion\":2,\"implVersion\":2,\"apis\":[[\"0xdf6acb689907609b\",4],\
 		[\"0x37e397fc7c91f5e4\",2],[\"0xd2bc9897eed08f15\",3],[\"0x40fe3ad401f8959a\",6],\
 		[\"0xc6e9a76309f39b09\",1],[\"0xdd718d5cc53262d4\",1],[\"0xcbca25e39f142387\",2],\
-		[\"0xf78b278be53f454c\",2],[\"0xab3c0572291feb8b\",1],[\"0xbc9d89904f5b923f\",1]],\
+		[\"0xf78b278be53f454c\",2],[\"0xab3c0572291feb8b\",1],[\"0xfbc577b9d747efd6\",1],\
+		[\"0xbc9d89904f5b923f\",1]],\
 		\"transactionVersion\":1,\"stateVersion\":1}";
 	let runtime: RuntimeVersion = serde_json::from_str(runtime_str).unwrap();
 
@@ -619,6 +624,220 @@ async fn get_storage_wrong_key() {
 	assert_matches!(event, ChainHeadEvent::<Option<String>>::Done(done) if done.result.is_none());
 }
 
+async fn import_block_with_changes(
+	client: &mut Arc<Client<Backend>>,
+	changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+) -> String {
+	let mut builder = client.new_block(Default::default()).unwrap();
+	for (key, value) in changes {
+		builder.push_storage_change(key, value).unwrap();
+	}
+	let block = builder.build().unwrap().block;
+	let hash = format!("{:?}", block.header.hash());
+	client.import(BlockOrigin::Own, block).await.unwrap();
+	hash
+}
+
+#[tokio::test]
+async fn get_storage_diff() {
+	let (mut client, api, mut block_sub, sub_id, block) = setup_api().await;
+	let invalid_hash = format!("0x{:?}", HexDisplay::from(&INVALID_HASH));
+	let prefix = b":diff";
+	let key_a = b":diff:a".to_vec();
+	let key_b = b":diff:b".to_vec();
+	let key_c = b":diff:c".to_vec();
+	let hex = |data: &[u8]| format!("0x{:?}", HexDisplay::from(&data));
+
+	let first_hash = import_block_with_changes(&mut client, vec![
+		(key_a.clone(), Some(b"a".to_vec())),
+		(key_b.clone(), Some(b"b".to_vec())),
+	])
+	.await;
+	let second_hash = import_block_with_changes(&mut client, vec![
+		(key_a.clone(), Some(b"aa".to_vec())),
+		(key_b.clone(), None),
+		(key_c.clone(), Some(b"c".to_vec())),
+		// The key is not under the queried prefix.
+		(KEY.to_vec(), Some(VALUE.to_vec())),
+	])
+	.await;
+	let page_keys: Vec<_> = (0..MAX_STORAGE_DIFF_ITEMS as u32 + 1)
+		.map(|index| [&b":page:"[..], &index.to_be_bytes()[..]].concat())
+		.collect();
+	let visited_keys: Vec<_> = (0..MAX_STORAGE_DIFF_VISITED_KEYS as u32 + 1)
+		.map(|index| [&b":visit:"[..], &index.to_be_bytes()[..]].concat())
+		.collect();
+	let third_hash = import_block_with_changes(
+		&mut client,
+		page_keys
+			.iter()
+			.chain(visited_keys.iter())
+			.map(|key| (key.clone(), Some(VALUE.to_vec())))
+			.collect(),
+	)
+	.await;
+
+	// Ensure the imported blocks are propagated and pinned for this subscription.
+	for _ in 0..3 {
+		assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::NewBlock(_)
+		);
+		assert_matches!(
+			get_next_event::<FollowEvent<String>>(&mut block_sub).await,
+			FollowEvent::BestBlockChanged(_)
+		);
+	}
+
+	// The previous block is not part of the subscription.
+	let err = api
+		.subscribe(
+			"chainHead_unstable_storageDiff",
+			[&sub_id, &second_hash, &invalid_hash, &hex(prefix)],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2001 && err.message() == "Invalid block hash"
+	);
+
+	// Keys added on top of the block from `setup_api`.
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_storageDiff",
+			[&sub_id, &first_hash, &format!("{:?}", block.header.hash()), &hex(prefix)],
+		)
+		.await
+		.unwrap();
+	let event: ChainHeadEvent<StorageDiffResult> = get_next_event(&mut sub).await;
+	let expected = vec![
+		StorageDiffItem {
+			key: hex(&key_a),
+			diff_type: StorageDiffType::Added,
+			value: Some(hex(b"a")),
+		},
+		StorageDiffItem {
+			key: hex(&key_b),
+			diff_type: StorageDiffType::Added,
+			value: Some(hex(b"b")),
+		},
+	];
+	let expected = StorageDiffResult { items: expected, pagination_start_key: None };
+	assert_eq!(event, ChainHeadEvent::Done(ChainHeadResult { result: expected }));
+
+	// Keys modified, deleted and added under the prefix.
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_storageDiff",
+			[&sub_id, &second_hash, &first_hash, &hex(prefix)],
+		)
+		.await
+		.unwrap();
+	let event: ChainHeadEvent<StorageDiffResult> = get_next_event(&mut sub).await;
+	let expected = vec![
+		StorageDiffItem {
+			key: hex(&key_a),
+			diff_type: StorageDiffType::Modified,
+			value: Some(hex(b"aa")),
+		},
+		StorageDiffItem { key: hex(&key_b), diff_type: StorageDiffType::Deleted, value: None },
+		StorageDiffItem {
+			key: hex(&key_c),
+			diff_type: StorageDiffType::Added,
+			value: Some(hex(b"c")),
+		},
+	];
+	let expected = StorageDiffResult { items: expected, pagination_start_key: None };
+	assert_eq!(event, ChainHeadEvent::Done(ChainHeadResult { result: expected }));
+
+	// The number of reported entries is limited.
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_storageDiff",
+			[&sub_id, &third_hash, &second_hash, &hex(b":page")],
+		)
+		.await
+		.unwrap();
+	let event: ChainHeadEvent<StorageDiffResult> = get_next_event(&mut sub).await;
+	let ChainHeadEvent::Done(done) = event else { panic!("Expected done event; qed") };
+	let keys: Vec<_> = done.result.items.iter().map(|item| item.key.clone()).collect();
+	let expected: Vec<_> =
+		page_keys[..MAX_STORAGE_DIFF_ITEMS].iter().map(|key| hex(key)).collect();
+	assert_eq!(keys, expected);
+	assert_eq!(done.result.pagination_start_key.as_ref(), keys.last());
+
+	// The remaining entries are reported after the pagination key.
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_storageDiff",
+			rpc_params![
+				&sub_id,
+				&third_hash,
+				&second_hash,
+				hex(b":page"),
+				None::<String>,
+				done.result.pagination_start_key
+			],
+		)
+		.await
+		.unwrap();
+	let event: ChainHeadEvent<StorageDiffResult> = get_next_event(&mut sub).await;
+	let expected = vec![StorageDiffItem {
+		key: hex(&page_keys[MAX_STORAGE_DIFF_ITEMS]),
+		diff_type: StorageDiffType::Added,
+		value: Some(hex(VALUE)),
+	}];
+	let expected = StorageDiffResult { items: expected, pagination_start_key: None };
+	assert_eq!(event, ChainHeadEvent::Done(ChainHeadResult { result: expected }));
+
+	// Unchanged keys count towards the limit of compared keys.
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_storageDiff",
+			[&sub_id, &third_hash, &third_hash, &hex(b":visit")],
+		)
+		.await
+		.unwrap();
+	let event: ChainHeadEvent<StorageDiffResult> = get_next_event(&mut sub).await;
+	let expected = StorageDiffResult {
+		items: vec![],
+		pagination_start_key: Some(hex(&visited_keys[MAX_STORAGE_DIFF_VISITED_KEYS - 1])),
+	};
+	assert_eq!(event, ChainHeadEvent::Done(ChainHeadResult { result: expected.clone() }));
+
+	let mut sub = api
+		.subscribe(
+			"chainHead_unstable_storageDiff",
+			rpc_params![
+				&sub_id,
+				&third_hash,
+				&third_hash,
+				hex(b":visit"),
+				None::<String>,
+				expected.pagination_start_key
+			],
+		)
+		.await
+		.unwrap();
+	let event: ChainHeadEvent<StorageDiffResult> = get_next_event(&mut sub).await;
+	let expected = StorageDiffResult { items: vec![], pagination_start_key: None };
+	assert_eq!(event, ChainHeadEvent::Done(ChainHeadResult { result: expected }));
+
+	// Child storage keys are rejected.
+	let child_key = [well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX, b"child"].concat();
+	let err = api
+		.subscribe(
+			"chainHead_unstable_storageDiff",
+			[&sub_id, &third_hash, &second_hash, &hex(&child_key)],
+		)
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 2003
+	);
+}
+
+
 #[tokio::test]
 async fn follow_generates_initial_blocks() {
 	let builder = TestClientBuilder::new();
@@ -35,3 +35,24 @@ pub trait TransactionApi<Hash: Clone> {
 	)]
 	fn submit_and_watch(&self, bytes: Bytes);
 }
+
+#[rpc(client, server)]
+pub trait TransactionBroadcastApi<Hash: Clone> {
+	/// Broadcast an extrinsic to the network.
+	///
+	/// The extrinsic is submitted to the transaction pool, which keeps propagating it to
+	/// the peers, and is resubmitted whenever the pool drops it. The operation ends with
+	/// a `finalized` event once the extrinsic is finalized, or with an `invalid` event once
+	/// the runtime considers the extrinsic invalid. Unsubscribing stops the operation.
+	///
+	/// # Note
+	///
+	/// Every broadcast is a subscription of the connection that started it, therefore the
+	/// number of concurrent broadcasts is bounded by the subscription limit of the connection.
+	#[subscription(
+		name = "transaction_unstable_broadcast" => "transaction_unstable_broadcastEvent",
+		unsubscribe = "transaction_unstable_stop",
+		item = TransactionEvent<Hash>,
+	)]
+	fn broadcast(&self, bytes: Bytes);
+}
@@ -0,0 +1,133 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use super::*;
+use assert_matches::assert_matches;
+use codec::Encode;
+use futures::Future;
+use jsonrpsee::{core::error::Error, types::error::CallError, RpcModule};
+use sc_block_builder::BlockBuilderProvider;
+use sc_transaction_pool::{BasicPool, FullChainApi};
+use sc_transaction_pool_api::{ChainEvent, MaintainedTransactionPool, TransactionPool};
+use sp_consensus::BlockOrigin;
+use sp_core::{testing::TaskExecutor, Bytes};
+use std::{sync::Arc, time::Duration};
+use substrate_test_runtime::Transfer;
+use substrate_test_runtime_client::{
+	prelude::*,
+	runtime::{Block, Hash},
+	AccountKeyring, Backend, BlockBuilderExt, Client, ClientBlockImportExt,
+};
+
+type FullTransactionPool = BasicPool<FullChainApi<Client<Backend>, Block>, Block>;
+
+fn transfer(nonce: u64) -> Transfer {
+	Transfer {
+		amount: Default::default(),
+		nonce,
+		from: AccountKeyring::Alice.into(),
+		to: AccountKeyring::Bob.into(),
+	}
+}
+
+fn encoded_xt(nonce: u64) -> Bytes {
+	transfer(nonce).into_signed_tx().encode().into()
+}
+
+async fn run_with_timeout<F: Future>(future: F) -> <F as Future>::Output {
+	tokio::time::timeout(Duration::from_secs(60), future).await.unwrap()
+}
+
+fn setup_api() -> (
+	Arc<Client<Backend>>,
+	Arc<FullTransactionPool>,
+	RpcModule<TransactionBroadcast<FullTransactionPool, Client<Backend>>>,
+) {
+	let client = Arc::new(TestClientBuilder::new().build());
+	let spawner = TaskExecutor::new();
+	let pool = BasicPool::new_full(Default::default(), true.into(), None, spawner, client.clone());
+
+	let api =
+		TransactionBroadcast::new(client.clone(), pool.clone(), Arc::new(TaskExecutor::default()))
+			.into_rpc();
+
+	(client, pool, api)
+}
+
+async fn wait_for_ready(pool: &FullTransactionPool, ready: usize) {
+	run_with_timeout(async {
+		while pool.status().ready != ready {
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+	})
+	.await
+}
+
+#[tokio::test]
+async fn tx_broadcast_invalid_format() {
+	let (_client, _pool, api) = setup_api();
+
+	let err = api
+		.subscribe("transaction_unstable_broadcast", [Bytes(vec![0xff])])
+		.await
+		.unwrap_err();
+	assert_matches!(err,
+		Error::Call(CallError::Custom(ref err)) if err.code() == 1001
+	);
+}
+
+#[tokio::test]
+async fn tx_broadcast_submits_to_pool_and_stops() {
+	let (_client, pool, api) = setup_api();
+
+	let sub = api.subscribe("transaction_unstable_broadcast", [encoded_xt(0)]).await.unwrap();
+	let sub_id = sub.subscription_id().clone();
+	wait_for_ready(&pool, 1).await;
+
+	let stopped: bool = api.call("transaction_unstable_stop", [&sub_id]).await.unwrap();
+	assert!(stopped);
+
+	// The operation is no longer active.
+	let stopped: bool = api.call("transaction_unstable_stop", [&sub_id]).await.unwrap();
+	assert!(!stopped);
+}
+
+#[tokio::test]
+async fn tx_broadcast_ends_on_finalization() {
+	let (mut client, pool, api) = setup_api();
+
+	let mut sub = api.subscribe("transaction_unstable_broadcast", [encoded_xt(0)]).await.unwrap();
+	wait_for_ready(&pool, 1).await;
+
+	let mut builder = client.new_block(Default::default()).unwrap();
+	builder.push_transfer(transfer(0)).unwrap();
+	let block = builder.build().unwrap().block;
+	let hash = block.header.hash();
+	client.import(BlockOrigin::Own, block).await.unwrap();
+	pool.maintain(ChainEvent::NewBestBlock { hash, tree_route: None }).await;
+	client.finalize_block(hash, None).unwrap();
+	pool.maintain(ChainEvent::Finalized { hash, tree_route: Arc::from(vec![]) })
+		.await;
+
+	let (event, _) = run_with_timeout(sub.next::<TransactionEvent<Hash>>()).await.unwrap().unwrap();
+	assert_eq!(event, TransactionEvent::Finalized(TransactionBlock { hash, index: 0 }));
+
+	// The transaction is stale now and broadcasting it again fails.
+	let mut sub = api.subscribe("transaction_unstable_broadcast", [encoded_xt(0)]).await.unwrap();
+	let (event, _) = run_with_timeout(sub.next::<TransactionEvent<Hash>>()).await.unwrap().unwrap();
+	assert_matches!(event, TransactionEvent::Invalid(_));
+}
@@ -38,8 +38,8 @@ use jsonrpsee::{
 	SubscriptionSink,
 };
 use sc_transaction_pool_api::{
-	error::IntoPoolError, BlockHash, TransactionFor, TransactionPool, TransactionSource,
-	TransactionStatus,
+	error::IntoPoolError, BlockHash, EvictionReason, TransactionFor, TransactionPool,
+	TransactionSource, TransactionStatus,
 };
 use std::sync::Arc;
 
@@ -80,7 +80,7 @@ const TX_SOURCE: TransactionSource = TransactionSource::External;
 /// # Note
 ///
 /// This is similar to the old `author` API error code.
-const BAD_FORMAT: i32 = 1001;
+pub(crate) const BAD_FORMAT: i32 = 1001;
 
 #[async_trait]
 impl<Pool, Client> TransactionApiServer<BlockHash<Pool>> for Transaction<Pool, Client>
@@ -200,6 +200,15 @@ impl TransactionState {
 			TransactionStatus::Dropped => Some(TransactionEvent::Invalid(TransactionError {
 				error: "Extrinsic dropped from the pool due to exceeding limits".into(),
 			})),
+			TransactionStatus::Evicted(reason) => {
+				let error = match reason {
+					EvictionReason::SenderQuota =>
+						"Extrinsic evicted from the pool because its sender exceeded the quota",
+					EvictionReason::PriorityLane =>
+						"Extrinsic evicted from the pool to keep the slots reserved for priority lanes",
+				};
+				Some(TransactionEvent::Invalid(TransactionError { error: error.into() }))
+			},
 			TransactionStatus::Invalid => Some(TransactionEvent::Invalid(TransactionError {
 				error: "Extrinsic marked as invalid".into(),
 			})),
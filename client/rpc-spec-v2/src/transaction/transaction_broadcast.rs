@@ -0,0 +1,185 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! API implementation for broadcasting transactions.
+
+use crate::{
+	transaction::{
+		api::TransactionBroadcastApiServer,
+		error::Error,
+		event::{TransactionBlock, TransactionDropped, TransactionError, TransactionEvent},
+	},
+	SubscriptionTaskExecutor,
+};
+use codec::Decode;
+use futures::{future, FutureExt, StreamExt};
+use jsonrpsee::{
+	core::async_trait,
+	types::{
+		error::{CallError, ErrorObject},
+		SubscriptionResult,
+	},
+	SubscriptionSink,
+};
+use sc_client_api::BlockchainEvents;
+use sc_transaction_pool_api::{
+	error::{Error as PoolError, IntoPoolError},
+	BlockHash, TransactionFor, TransactionPool, TransactionSource, TransactionStatus,
+};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{generic, traits::Block as BlockT};
+use std::sync::Arc;
+
+use super::transaction::BAD_FORMAT;
+
+/// Log target for the transaction broadcast.
+const LOG_TARGET: &str = "rpc-spec-v2";
+
+/// An API for transaction RPC calls.
+pub struct TransactionBroadcast<Pool, Client> {
+	/// Substrate client.
+	client: Arc<Client>,
+	/// Transactions pool.
+	pool: Arc<Pool>,
+	/// Executor to spawn the broadcast tasks.
+	executor: SubscriptionTaskExecutor,
+}
+
+impl<Pool, Client> TransactionBroadcast<Pool, Client> {
+	/// Creates a new [`TransactionBroadcast`].
+	pub fn new(client: Arc<Client>, pool: Arc<Pool>, executor: SubscriptionTaskExecutor) -> Self {
+		TransactionBroadcast { client, pool, executor }
+	}
+}
+
+/// Currently we treat all RPC transactions as externals.
+///
+/// Possibly in the future we could allow opt-in for special treatment
+/// of such transactions, so that the block authors can inject
+/// some unique transactions via RPC and have them included in the pool.
+const TX_SOURCE: TransactionSource = TransactionSource::External;
+
+#[async_trait]
+impl<Pool, Client> TransactionBroadcastApiServer<BlockHash<Pool>>
+	for TransactionBroadcast<Pool, Client>
+where
+	Pool: TransactionPool + Sync + Send + 'static,
+	Pool::Hash: Unpin,
+	<Pool::Block as BlockT>::Hash: Unpin,
+	Client: HeaderBackend<Pool::Block> + BlockchainEvents<Pool::Block> + Send + Sync + 'static,
+{
+	fn broadcast(&self, mut sink: SubscriptionSink, bytes: Bytes) -> SubscriptionResult {
+		let decoded_extrinsic = match TransactionFor::<Pool>::decode(&mut &bytes[..]) {
+			Ok(decoded_extrinsic) => decoded_extrinsic,
+			Err(e) => {
+				let err = CallError::Custom(ErrorObject::owned(
+					BAD_FORMAT,
+					format!("Extrinsic has invalid format: {}", e),
+					None::<()>,
+				));
+				let _ = sink.reject(err);
+				return Ok(())
+			},
+		};
+
+		// The broadcast is dropped, and therefore stopped, as soon as the subscription is
+		// closed by the user or by the connection.
+		let broadcast =
+			broadcast_until_finalized(self.client.clone(), self.pool.clone(), decoded_extrinsic);
+		let fut = async move {
+			sink.pipe_from_stream(futures::stream::once(broadcast).boxed()).await;
+		};
+
+		self.executor
+			.spawn("substrate-rpc-transaction-broadcast", Some("rpc"), fut.boxed());
+		Ok(())
+	}
+}
+
+/// Submit the transaction to the pool until it is finalized.
+///
+/// While the transaction is in the pool, it is periodically propagated to peers by the
+/// transactions handler of the network. If the pool drops the transaction, the submission
+/// is retried on top of the next best block. The broadcast ends once the transaction is
+/// finalized or considered invalid, which is reported by the returned event.
+async fn broadcast_until_finalized<Pool, Client>(
+	client: Arc<Client>,
+	pool: Arc<Pool>,
+	xt: TransactionFor<Pool>,
+) -> TransactionEvent<BlockHash<Pool>>
+where
+	Pool: TransactionPool + 'static,
+	Client: HeaderBackend<Pool::Block> + BlockchainEvents<Pool::Block> + 'static,
+{
+	loop {
+		let best_block_hash = client.info().best_hash;
+
+		match pool
+			.submit_and_watch(&generic::BlockId::hash(best_block_hash), TX_SOURCE, xt.clone())
+			.await
+		{
+			Ok(mut stream) =>
+				while let Some(event) = stream.next().await {
+					match event {
+						TransactionStatus::Finalized((hash, index)) =>
+							return TransactionEvent::Finalized(TransactionBlock { hash, index }),
+						// The transaction is included in a block, but the pool is no longer
+						// tracking its finality.
+						TransactionStatus::FinalityTimeout(_) =>
+							return TransactionEvent::Dropped(TransactionDropped {
+								broadcasted: true,
+								error: "Maximum number of finality watchers has been reached"
+									.into(),
+							}),
+						// Resubmitting an invalid transaction would fail again.
+						TransactionStatus::Invalid =>
+							return TransactionEvent::Invalid(TransactionError {
+								error: "Extrinsic marked as invalid".into(),
+							}),
+						// The transaction left the pool and must be submitted again.
+						TransactionStatus::Usurped(_) |
+						TransactionStatus::Dropped |
+						TransactionStatus::Evicted(_) => break,
+						_ => {},
+					}
+				},
+			Err(err) => match err.into_pool_error() {
+				Ok(err @ PoolError::InvalidTransaction(_)) => return Error::Pool(err).into(),
+				err => {
+					log::debug!(
+						target: LOG_TARGET,
+						"Failed to submit the broadcasted transaction at {:?}: {:?}",
+						best_block_hash,
+						err,
+					);
+				},
+			},
+		}
+
+		// Subscribe only while waiting, such that the notifications do not keep
+		// blocks pinned for the whole lifetime of the operation.
+		let mut best_blocks = client
+			.import_notification_stream()
+			.filter(|notification| future::ready(notification.is_new_best));
+		if best_blocks.next().await.is_none() {
+			return TransactionEvent::Error(TransactionError {
+				error: "The node is shutting down".into(),
+			})
+		}
+	}
+}
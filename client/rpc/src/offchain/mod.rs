@@ -22,7 +22,7 @@
 mod tests;
 
 use self::error::Error;
-use jsonrpsee::core::{async_trait, Error as JsonRpseeError, RpcResult};
+use jsonrpsee::core::{async_trait, RpcResult};
 use parking_lot::RwLock;
 /// Re-export the API for backward compatibility.
 pub use sc_rpc_api::offchain::*;
@@ -33,6 +33,9 @@ use sp_core::{
 };
 use std::sync::Arc;
 
+/// Maximum number of entries returned by a single paged offchain storage query.
+const LOCAL_STORAGE_PAGED_MAX_COUNT: u32 = 1000;
+
 /// Offchain API
 #[derive(Debug)]
 pub struct Offchain<T: OffchainStorage> {
@@ -46,6 +49,39 @@ impl<T: OffchainStorage> Offchain<T> {
 	pub fn new(storage: T, deny_unsafe: DenyUnsafe) -> Self {
 		Offchain { storage: Arc::new(RwLock::new(storage)), deny_unsafe }
 	}
+
+	fn entries_paged(
+		&self,
+		kind: StorageKind,
+		prefix: Option<Bytes>,
+		count: u32,
+		start_key: Option<Bytes>,
+	) -> RpcResult<Vec<(Bytes, Bytes)>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		if count > LOCAL_STORAGE_PAGED_MAX_COUNT {
+			return Err(
+				Error::InvalidCount { value: count, max: LOCAL_STORAGE_PAGED_MAX_COUNT }.into()
+			)
+		}
+		let storage_prefix = storage_prefix(kind)?;
+		let key_prefix = prefix.as_deref().unwrap_or_default();
+
+		let entries = self
+			.storage
+			.read()
+			.entries_paged(storage_prefix, key_prefix, start_key.as_deref(), count as usize)
+			.ok_or(Error::IterationUnsupported)?;
+		Ok(entries.into_iter().map(|(key, value)| (key.into(), value.into())).collect())
+	}
+}
+
+/// The database prefix of the given storage kind.
+fn storage_prefix(kind: StorageKind) -> Result<&'static [u8], Error> {
+	match kind {
+		StorageKind::PERSISTENT => Ok(sp_offchain::STORAGE_PREFIX),
+		StorageKind::LOCAL => Err(Error::UnavailableStorageKind),
+	}
 }
 
 #[async_trait]
@@ -53,10 +89,7 @@ impl<T: OffchainStorage + 'static> OffchainApiServer for Offchain<T> {
 	fn set_local_storage(&self, kind: StorageKind, key: Bytes, value: Bytes) -> RpcResult<()> {
 		self.deny_unsafe.check_if_safe()?;
 
-		let prefix = match kind {
-			StorageKind::PERSISTENT => sp_offchain::STORAGE_PREFIX,
-			StorageKind::LOCAL => return Err(JsonRpseeError::from(Error::UnavailableStorageKind)),
-		};
+		let prefix = storage_prefix(kind)?;
 		self.storage.write().set(prefix, &key, &value);
 		Ok(())
 	}
@@ -64,11 +97,62 @@ impl<T: OffchainStorage + 'static> OffchainApiServer for Offchain<T> {
 	fn get_local_storage(&self, kind: StorageKind, key: Bytes) -> RpcResult<Option<Bytes>> {
 		self.deny_unsafe.check_if_safe()?;
 
-		let prefix = match kind {
-			StorageKind::PERSISTENT => sp_offchain::STORAGE_PREFIX,
-			StorageKind::LOCAL => return Err(JsonRpseeError::from(Error::UnavailableStorageKind)),
-		};
-
+		let prefix = storage_prefix(kind)?;
 		Ok(self.storage.read().get(prefix, &key).map(Into::into))
 	}
+
+	fn get_local_storage_keys_paged(
+		&self,
+		kind: StorageKind,
+		prefix: Option<Bytes>,
+		count: u32,
+		start_key: Option<Bytes>,
+	) -> RpcResult<Vec<Bytes>> {
+		let entries = self.entries_paged(kind, prefix, count, start_key)?;
+		Ok(entries.into_iter().map(|(key, _)| key).collect())
+	}
+
+	fn get_local_storage_pairs_paged(
+		&self,
+		kind: StorageKind,
+		prefix: Option<Bytes>,
+		count: u32,
+		start_key: Option<Bytes>,
+	) -> RpcResult<Vec<(Bytes, Bytes)>> {
+		self.entries_paged(kind, prefix, count, start_key)
+	}
+
+	fn clear_local_storage(&self, kind: StorageKind, key: Bytes) -> RpcResult<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let prefix = storage_prefix(kind)?;
+		self.storage.write().remove(prefix, &key);
+		Ok(())
+	}
+
+	fn clear_local_storage_prefix(&self, kind: StorageKind, prefix: Bytes) -> RpcResult<u32> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let storage_prefix = storage_prefix(kind)?;
+		let mut storage = self.storage.write();
+		let mut removed = 0u32;
+		loop {
+			let keys = storage
+				.entries_paged(
+					storage_prefix,
+					&prefix,
+					None,
+					LOCAL_STORAGE_PAGED_MAX_COUNT as usize,
+				)
+				.ok_or(Error::IterationUnsupported)?;
+			if keys.is_empty() {
+				break
+			}
+			for (key, _) in keys {
+				storage.remove(storage_prefix, &key);
+				removed = removed.saturating_add(1);
+			}
+		}
+		Ok(removed)
+	}
 }
@@ -18,6 +18,7 @@
 
 use super::*;
 use assert_matches::assert_matches;
+use jsonrpsee::{core::Error as JsonRpseeError, types::error::CallError};
 use sp_core::{offchain::storage::InMemOffchainStorage, Bytes};
 
 #[test]
@@ -37,9 +38,88 @@ fn local_storage_should_work() {
 	);
 }
 
+#[test]
+fn local_storage_paging_should_work() {
+	let storage = InMemOffchainStorage::default();
+	let offchain = Offchain::new(storage, DenyUnsafe::No);
+	for key in [&b"a1"[..], b"a2", b"a3", b"b1"] {
+		offchain
+			.set_local_storage(StorageKind::PERSISTENT, Bytes(key.to_vec()), Bytes(b"v".to_vec()))
+			.unwrap();
+	}
+	let keys = |keys: &[&[u8]]| keys.iter().map(|k| Bytes(k.to_vec())).collect::<Vec<_>>();
+
+	assert_eq!(
+		offchain
+			.get_local_storage_keys_paged(
+				StorageKind::PERSISTENT,
+				Some(Bytes(b"a".to_vec())),
+				2,
+				None
+			)
+			.unwrap(),
+		keys(&[b"a1", b"a2"]),
+	);
+	assert_eq!(
+		offchain
+			.get_local_storage_keys_paged(
+				StorageKind::PERSISTENT,
+				Some(Bytes(b"a".to_vec())),
+				2,
+				Some(Bytes(b"a2".to_vec()))
+			)
+			.unwrap(),
+		keys(&[b"a3"]),
+	);
+	assert_eq!(
+		offchain
+			.get_local_storage_pairs_paged(
+				StorageKind::PERSISTENT,
+				None,
+				10,
+				Some(Bytes(b"a3".to_vec()))
+			)
+			.unwrap(),
+		vec![(Bytes(b"b1".to_vec()), Bytes(b"v".to_vec()))],
+	);
+	assert_matches!(
+		offchain.get_local_storage_keys_paged(StorageKind::PERSISTENT, None, 1001, None),
+		Err(JsonRpseeError::Call(CallError::Custom(err))) => {
+			assert_eq!(err.message(), "count exceeds maximum value. value: 1001, max: 1000")
+		}
+	);
+}
+
+#[test]
+fn local_storage_clearing_should_work() {
+	let storage = InMemOffchainStorage::default();
+	let offchain = Offchain::new(storage, DenyUnsafe::No);
+	for key in [&b"a1"[..], b"a2", b"b1"] {
+		offchain
+			.set_local_storage(StorageKind::PERSISTENT, Bytes(key.to_vec()), Bytes(b"v".to_vec()))
+			.unwrap();
+	}
+
+	assert_matches!(
+		offchain.clear_local_storage(StorageKind::PERSISTENT, Bytes(b"b1".to_vec())),
+		Ok(())
+	);
+	assert_matches!(
+		offchain.get_local_storage(StorageKind::PERSISTENT, Bytes(b"b1".to_vec())),
+		Ok(None)
+	);
+	assert_matches!(
+		offchain.clear_local_storage_prefix(StorageKind::PERSISTENT, Bytes(b"a".to_vec())),
+		Ok(2)
+	);
+	assert_matches!(
+		offchain.get_local_storage_keys_paged(StorageKind::PERSISTENT, None, 10, None),
+		Ok(keys) if keys.is_empty()
+	);
+}
+
 #[test]
 fn offchain_calls_considered_unsafe() {
-	use jsonrpsee::types::error::CallError;
 	let storage = InMemOffchainStorage::default();
 	let offchain = Offchain::new(storage, DenyUnsafe::Yes);
 	let key = Bytes(b"offchain_storage".to_vec());
@@ -52,7 +132,19 @@ fn offchain_calls_considered_unsafe() {
 		}
 	);
 	assert_matches!(
-		offchain.get_local_storage(StorageKind::PERSISTENT, key),
+		offchain.get_local_storage(StorageKind::PERSISTENT, key.clone()),
+		Err(JsonRpseeError::Call(CallError::Custom(err))) => {
+			assert_eq!(err.message(), "RPC call is unsafe to be called externally")
+		}
+	);
+	assert_matches!(
+		offchain.get_local_storage_keys_paged(StorageKind::PERSISTENT, None, 10, None),
+		Err(JsonRpseeError::Call(CallError::Custom(err))) => {
+			assert_eq!(err.message(), "RPC call is unsafe to be called externally")
+		}
+	);
+	assert_matches!(
+		offchain.clear_local_storage(StorageKind::PERSISTENT, key),
 		Err(JsonRpseeError::Call(CallError::Custom(err))) => {
 			assert_eq!(err.message(), "RPC call is unsafe to be called externally")
 		}
@@ -34,7 +34,7 @@ use sc_client_api::{
 	execution_extensions::ExecutionExtensions, proof_provider::ProofProvider, BadBlocks,
 	BlockBackend, BlockchainEvents, ExecutorProvider, ForkBlocks, StorageProvider, UsageProvider,
 };
-use sc_client_db::{Backend, DatabaseSettings};
+use sc_client_db::{Backend, BlocksPruning, DatabaseSettings};
 use sc_consensus::import_queue::ImportQueue;
 use sc_executor::RuntimeVersionOf;
 use sc_keystore::LocalKeystore;
@@ -55,7 +55,11 @@ use sc_rpc::{
 	system::SystemApiServer,
 	DenyUnsafe, SubscriptionTaskExecutor,
 };
-use sc_rpc_spec_v2::{chain_head::ChainHeadApiServer, transaction::TransactionApiServer};
+use sc_rpc_spec_v2::{
+	archive::ArchiveApiServer,
+	chain_head::ChainHeadApiServer,
+	transaction::{TransactionApiServer, TransactionBroadcastApiServer},
+};
 use sc_telemetry::{telemetry, ConnectionMessage, Telemetry, TelemetryHandle, SUBSTRATE_INFO};
 use sc_transaction_pool_api::MaintainedTransactionPool;
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
@@ -83,29 +87,43 @@ pub type TFullCallExecutor<TBl, TExec> =
 type TFullParts<TBl, TRtApi, TExec> =
 	(TFullClient<TBl, TRtApi, TExec>, Arc<TFullBackend<TBl>>, KeystoreContainer, TaskManager);
 
-/// Construct a local keystore shareable container
-pub struct KeystoreContainer(Arc<LocalKeystore>);
+/// Construct a keystore shareable container
+pub struct KeystoreContainer {
+	keystore: KeystorePtr,
+	local: Option<Arc<LocalKeystore>>,
+}
 
 impl KeystoreContainer {
 	/// Construct KeystoreContainer
 	pub fn new(config: &KeystoreConfig) -> Result<Self, Error> {
-		let keystore = Arc::new(match config {
+		let local = match config {
 			KeystoreConfig::Path { path, password } =>
 				LocalKeystore::open(path.clone(), password.clone())?,
 			KeystoreConfig::InMemory => LocalKeystore::in_memory(),
-		});
+			#[cfg(unix)]
+			KeystoreConfig::Remote { socket } => {
+				let remote = sc_keystore::RemoteKeystore::open(socket.clone())?;
+				return Ok(Self { keystore: Arc::new(remote), local: None })
+			},
+			#[cfg(not(unix))]
+			KeystoreConfig::Remote { .. } =>
+				return Err("Remote keystores are only supported on Unix".into()),
+		};
 
-		Ok(Self(keystore))
+		let local = Arc::new(local);
+		Ok(Self { keystore: local.clone(), local: Some(local) })
 	}
 
 	/// Returns a shared reference to a dynamic `Keystore` trait implementation.
 	pub fn keystore(&self) -> KeystorePtr {
-		self.0.clone()
+		self.keystore.clone()
 	}
 
-	/// Returns a shared reference to the local keystore .
-	pub fn local_keystore(&self) -> Arc<LocalKeystore> {
-		self.0.clone()
+	/// Returns a shared reference to the local keystore.
+	///
+	/// Returns `None` if the keys are kept by a remote signer.
+	pub fn local_keystore(&self) -> Option<Arc<LocalKeystore>> {
+		self.local.clone()
 	}
 }
 
@@ -350,7 +368,13 @@ where
 	TCl: Send + Sync + ProvideRuntimeApi<TBl> + BlockchainEvents<TBl> + 'static,
 	<TCl as ProvideRuntimeApi<TBl>>::Api: sc_offchain::OffchainWorkerApi<TBl>,
 {
-	let offchain_workers = Some(Arc::new(sc_offchain::OffchainWorkers::new(client.clone())));
+	let offchain_workers = Some(Arc::new(sc_offchain::OffchainWorkers::new_with_options(
+		client.clone(),
+		sc_offchain::OffchainWorkerOptions {
+			enable_http_requests: true,
+			http_client_config: config.offchain_worker.http_client.clone(),
+		},
+	)));
 
 	// Inform the offchain worker about new imported blocks
 	if let Some(offchain) = offchain_workers.clone() {
@@ -644,6 +668,13 @@ where
 	)
 	.into_rpc();
 
+	let transaction_broadcast_v2 = sc_rpc_spec_v2::transaction::TransactionBroadcast::new(
+		client.clone(),
+		transaction_pool.clone(),
+		task_executor.clone(),
+	)
+	.into_rpc();
+
 	// Maximum pinned blocks per connection.
 	// This number is large enough to consider immediate blocks,
 	// but it will change to facilitate adequate limits for the pinning API.
@@ -676,8 +707,25 @@ where
 
 	// Part of the RPC v2 spec.
 	rpc_api.merge(transaction_v2).map_err(|e| Error::Application(e.into()))?;
+	rpc_api
+		.merge(transaction_broadcast_v2)
+		.map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(chain_head_v2).map_err(|e| Error::Application(e.into()))?;
 
+	// The archive methods are exposed only by nodes that keep the state and the bodies
+	// of all the canonical blocks around.
+	let is_archive_node = config.state_pruning.as_ref().map_or(false, |mode| mode.is_archive()) &&
+		matches!(config.blocks_pruning, BlocksPruning::KeepAll | BlocksPruning::KeepFinalized);
+	if is_archive_node {
+		let archive_v2 = sc_rpc_spec_v2::archive::Archive::new(
+			client.clone(),
+			backend.clone(),
+			config.rpc_archive,
+		)
+		.into_rpc();
+		rpc_api.merge(archive_v2).map_err(|e| Error::Application(e.into()))?;
+	}
+
 	// Part of the old RPC spec.
 	rpc_api.merge(chain).map_err(|e| Error::Application(e.into()))?;
 	rpc_api.merge(author).map_err(|e| Error::Application(e.into()))?;
@@ -31,11 +31,15 @@ pub use sc_network::{
 	},
 	Multiaddr,
 };
+pub use sc_offchain::{HttpClientConfig as OffchainHttpClientConfig, HttpHostFilter};
+pub use sc_rpc_spec_v2::archive::ArchiveConfig as RpcArchiveConfig;
 
 use prometheus_endpoint::Registry;
 use sc_chain_spec::ChainSpec;
 pub use sc_telemetry::TelemetryEndpoints;
-pub use sc_transaction_pool::Options as TransactionPoolOptions;
+pub use sc_transaction_pool::{
+	Options as TransactionPoolOptions, PoolPriorityLane, PoolSenderLimit,
+};
 use sp_core::crypto::SecretString;
 use std::{
 	io, iter,
@@ -109,6 +113,8 @@ pub struct Configuration {
 	///
 	/// Default: 1024.
 	pub rpc_max_subs_per_conn: Option<usize>,
+	/// Limits of the `archive` RPC methods, which are exposed by archive nodes.
+	pub rpc_archive: RpcArchiveConfig,
 	/// Maximum size of the output buffer capacity for websocket connections.
 	pub ws_max_out_buffer_capacity: Option<usize>,
 	/// Prometheus endpoint configuration. `None` if disabled.
@@ -169,6 +175,11 @@ pub enum KeystoreConfig {
 	},
 	/// In-memory keystore. Recommended for in-browser nodes.
 	InMemory,
+	/// Keystore of a signer listening on a Unix socket, see [`sc_keystore::remote`].
+	Remote {
+		/// The path of the signer's socket.
+		socket: PathBuf,
+	},
 }
 
 impl KeystoreConfig {
@@ -176,7 +187,7 @@ impl KeystoreConfig {
 	pub fn path(&self) -> Option<&Path> {
 		match self {
 			Self::Path { path, .. } => Some(path),
-			Self::InMemory => None,
+			Self::InMemory | Self::Remote { .. } => None,
 		}
 	}
 }
@@ -187,6 +198,8 @@ pub struct OffchainWorkerConfig {
 	pub enabled: bool,
 	/// allow writes from the runtime to the offchain worker database.
 	pub indexing_enabled: bool,
+	/// Configuration of the HTTP client used by offchain workers.
+	pub http_client: OffchainHttpClientConfig,
 }
 
 /// Configuration of the Prometheus endpoint.
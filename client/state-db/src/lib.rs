@@ -46,7 +46,7 @@ mod pruning;
 #[cfg(test)]
 mod test;
 
-use codec::Codec;
+use codec::{Codec, DecodeAll, Encode};
 use log::trace;
 use noncanonical::NonCanonicalOverlay;
 use parking_lot::RwLock;
@@ -54,6 +54,7 @@ use pruning::{HaveBlock, RefWindow};
 use std::{
 	collections::{hash_map::Entry, HashMap},
 	fmt,
+	num::NonZeroU32,
 };
 
 const LOG_TARGET: &str = "state-db";
@@ -62,6 +63,8 @@ const PRUNING_MODE: &[u8] = b"mode";
 const PRUNING_MODE_ARCHIVE: &[u8] = b"archive";
 const PRUNING_MODE_ARCHIVE_CANON: &[u8] = b"archive_canonical";
 const PRUNING_MODE_CONSTRAINED: &[u8] = b"constrained";
+const PRUNING_MODE_CONSTRAINED_CHECKPOINTS: &[u8] = b"constrained_checkpoints";
+const PRUNING_CHECKPOINT_INTERVAL: &[u8] = b"checkpoint_interval";
 pub(crate) const DEFAULT_MAX_BLOCK_CONSTRAINT: u32 = 256;
 
 /// Database value type.
@@ -145,6 +148,8 @@ pub enum StateDbError {
 	BlockUnavailable,
 	/// Block record is missing from the pruning window
 	BlockMissing,
+	/// Pruning checkpoints require a database with reference counting.
+	CheckpointsRequireRefCounting,
 }
 
 impl<E> From<StateDbError> for Error<E> {
@@ -194,6 +199,8 @@ impl fmt::Debug for StateDbError {
 			Self::BlockUnavailable =>
 				write!(f, "Trying to get a block record from db while it is not commit to db yet"),
 			Self::BlockMissing => write!(f, "Block record is missing from the pruning window"),
+			Self::CheckpointsRequireRefCounting =>
+				write!(f, "Pruning checkpoints require a database with reference counting"),
 		}
 	}
 }
@@ -222,6 +229,9 @@ pub struct Constraints {
 	/// Maximum blocks. Defaults to 0 when unspecified, effectively keeping only non-canonical
 	/// states.
 	pub max_blocks: Option<u32>,
+	/// Keep the state of every canonical block whose number is a multiple of the interval,
+	/// regardless of `max_blocks`. Defaults to no checkpoints when unspecified.
+	pub checkpoint_interval: Option<NonZeroU32>,
 }
 
 /// Pruning mode.
@@ -238,7 +248,16 @@ pub enum PruningMode {
 impl PruningMode {
 	/// Create a mode that keeps given number of blocks.
 	pub fn blocks_pruning(n: u32) -> PruningMode {
-		PruningMode::Constrained(Constraints { max_blocks: Some(n) })
+		PruningMode::Constrained(Constraints { max_blocks: Some(n), checkpoint_interval: None })
+	}
+
+	/// Create a mode that keeps given number of blocks, as well as every block whose
+	/// number is a multiple of `interval`.
+	pub fn blocks_pruning_with_checkpoints(n: u32, interval: NonZeroU32) -> PruningMode {
+		PruningMode::Constrained(Constraints {
+			max_blocks: Some(n),
+			checkpoint_interval: Some(interval),
+		})
 	}
 
 	/// Is this an archive (either ArchiveAll or ArchiveCanonical) pruning mode?
@@ -254,7 +273,21 @@ impl PruningMode {
 		match self {
 			PruningMode::ArchiveAll => PRUNING_MODE_ARCHIVE,
 			PruningMode::ArchiveCanonical => PRUNING_MODE_ARCHIVE_CANON,
-			PruningMode::Constrained(_) => PRUNING_MODE_CONSTRAINED,
+			PruningMode::Constrained(Constraints { checkpoint_interval: None, .. }) =>
+				PRUNING_MODE_CONSTRAINED,
+			PruningMode::Constrained(Constraints { checkpoint_interval: Some(_), .. }) =>
+				PRUNING_MODE_CONSTRAINED_CHECKPOINTS,
+		}
+	}
+
+	/// Returns the interval of the pruning checkpoints, if any.
+	///
+	/// The interval is not part of [`Self::id`] and is stored separately, since it cannot
+	/// be changed once blocks have been pruned.
+	pub fn checkpoint_interval(&self) -> Option<NonZeroU32> {
+		match self {
+			PruningMode::Constrained(constraints) => constraints.checkpoint_interval,
+			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		}
 	}
 
@@ -276,7 +309,7 @@ impl Default for PruningMode {
 
 impl Default for Constraints {
 	fn default() -> Self {
-		Self { max_blocks: Some(DEFAULT_MAX_BLOCK_CONSTRAINT) }
+		Self { max_blocks: Some(DEFAULT_MAX_BLOCK_CONSTRAINT), checkpoint_interval: None }
 	}
 }
 
@@ -315,8 +348,14 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDbSync<BlockHash, Key, D> {
 
 		let non_canonical: NonCanonicalOverlay<BlockHash, Key> = NonCanonicalOverlay::new(&db)?;
 		let pruning: Option<RefWindow<BlockHash, Key, D>> = match mode {
-			PruningMode::Constrained(Constraints { max_blocks }) =>
-				Some(RefWindow::new(db, max_blocks.unwrap_or(0), ref_counting)?),
+			// Nodes of checkpoint states are kept by skipping the matching reference count
+			// decrements, which has no equivalent for databases that delete the nodes outright.
+			PruningMode::Constrained(Constraints { checkpoint_interval: Some(_), .. })
+				if ref_counting =>
+				return Err(StateDbError::CheckpointsRequireRefCounting.into()),
+			PruningMode::Constrained(Constraints { max_blocks, checkpoint_interval }) => Some(
+				RefWindow::new(db, max_blocks.unwrap_or(0), ref_counting, checkpoint_interval)?,
+			),
 			PruningMode::ArchiveAll | PruningMode::ArchiveCanonical => None,
 		};
 
@@ -558,10 +597,15 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> StateDb<BlockHash, Key, D> {
 			let mut cs: CommitSet<Key> = Default::default();
 
 			let key = to_meta_key(PRUNING_MODE, &());
-			let value = selected_mode.id().to_owned();
+			let value = selected_mode.id().to_vec();
 
 			cs.meta.inserted.push((key, value));
 
+			if let Some(interval) = selected_mode.checkpoint_interval() {
+				let key = to_meta_key(PRUNING_CHECKPOINT_INTERVAL, &());
+				cs.meta.inserted.push((key, interval.get().encode()));
+			}
+
 			cs
 		} else {
 			Default::default()
@@ -672,6 +716,15 @@ pub enum IsPruned {
 fn fetch_stored_pruning_mode<D: MetaDb>(db: &D) -> Result<Option<PruningMode>, Error<D::Error>> {
 	let meta_key_mode = to_meta_key(PRUNING_MODE, &());
 	if let Some(stored_mode) = db.get_meta(&meta_key_mode).map_err(Error::Db)? {
+		if stored_mode == PRUNING_MODE_CONSTRAINED_CHECKPOINTS {
+			return fetch_stored_checkpoint_interval(db).map(|checkpoint_interval| {
+				Some(PruningMode::Constrained(Constraints {
+					checkpoint_interval: Some(checkpoint_interval),
+					..Default::default()
+				}))
+			})
+		}
+
 		if let Some(mode) = PruningMode::from_id(&stored_mode) {
 			Ok(Some(mode))
 		} else {
@@ -686,6 +739,20 @@ fn fetch_stored_pruning_mode<D: MetaDb>(db: &D) -> Result<Option<PruningMode>, E
 	}
 }
 
+fn fetch_stored_checkpoint_interval<D: MetaDb>(db: &D) -> Result<NonZeroU32, Error<D::Error>> {
+	let meta_key_interval = to_meta_key(PRUNING_CHECKPOINT_INTERVAL, &());
+	db.get_meta(&meta_key_interval)
+		.map_err(Error::Db)?
+		.and_then(|stored_interval| u32::decode_all(&mut stored_interval.as_slice()).ok())
+		.and_then(NonZeroU32::new)
+		.ok_or_else(|| {
+			StateDbError::Metadata(
+				"Invalid or missing value stored for PRUNING_CHECKPOINT_INTERVAL".into(),
+			)
+			.into()
+		})
+}
+
 fn choose_pruning_mode(
 	stored: PruningMode,
 	requested: PruningMode,
@@ -694,19 +761,51 @@ fn choose_pruning_mode(
 		(PruningMode::ArchiveAll, PruningMode::ArchiveAll) => Ok(PruningMode::ArchiveAll),
 		(PruningMode::ArchiveCanonical, PruningMode::ArchiveCanonical) =>
 			Ok(PruningMode::ArchiveCanonical),
-		(PruningMode::Constrained(_), PruningMode::Constrained(requested)) =>
+		// The window size may change, but not the checkpoints.
+		(PruningMode::Constrained(stored), PruningMode::Constrained(requested))
+			if stored.checkpoint_interval == requested.checkpoint_interval =>
 			Ok(PruningMode::Constrained(requested)),
 		(stored, requested) => Err(StateDbError::IncompatiblePruningModes { requested, stored }),
 	}
 }
 
+/// Journals of a database that counts insertions, rewritten for a database that does
+/// reference counting. See [`ref_counted_journals`].
+#[derive(Default, Debug, Clone)]
+pub struct RefCountedJournals<Key: Hash> {
+	/// Rewritten journal records, to replace the records of the source database.
+	pub meta: ChangeSet<Vec<u8>>,
+	/// Number of references the target database must hold for a key of the source database,
+	/// for the keys that are deleted by pending pruning journal records. Other keys are held by
+	/// a single reference.
+	pub references: HashMap<Key, u32>,
+}
+
+/// Prepare the state journals of a database opened without reference counting to be moved
+/// to a database that does reference counting.
+///
+/// The non-canonical and pruning journal records are rewritten with `map_key` applied to all
+/// node keys. The returned references make sure that pruning the pending journal records
+/// releases exactly the nodes that would have been deleted from the source database.
+pub fn ref_counted_journals<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	map_key: impl Fn(&Key) -> Key,
+) -> Result<RefCountedJournals<Key>, Error<D::Error>> {
+	let mut journals = RefCountedJournals::default();
+	noncanonical::map_journal_keys::<BlockHash, Key, D>(db, &map_key, &mut journals.meta)?;
+	journals.references =
+		pruning::map_journal_keys::<BlockHash, Key, D>(db, &map_key, &mut journals.meta)?;
+	Ok(journals)
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::{
 		test::{make_changeset, make_db, TestDb},
-		Constraints, Error, IsPruned, PruningMode, StateDb, StateDbError,
+		Error, IsPruned, PruningMode, StateDb, StateDbError,
 	};
 	use sp_core::H256;
+	use std::num::NonZeroU32;
 
 	fn make_test_db(settings: PruningMode) -> (TestDb, StateDb<H256, H256, TestDb>) {
 		let mut db = make_db(&[91, 921, 922, 93, 94]);
@@ -786,8 +885,7 @@ mod tests {
 
 	#[test]
 	fn block_record_unavailable() {
-		let (mut db, state_db) =
-			make_test_db(PruningMode::Constrained(Constraints { max_blocks: Some(1) }));
+		let (mut db, state_db) = make_test_db(PruningMode::blocks_pruning(1));
 		// import 2 blocks
 		for i in &[5, 6] {
 			db.commit(
@@ -821,13 +919,13 @@ mod tests {
 
 	#[test]
 	fn prune_window_0() {
-		let (db, _) = make_test_db(PruningMode::Constrained(Constraints { max_blocks: Some(0) }));
+		let (db, _) = make_test_db(PruningMode::blocks_pruning(0));
 		assert!(db.data_eq(&make_db(&[21, 3, 922, 94])));
 	}
 
 	#[test]
 	fn prune_window_1() {
-		let (db, sdb) = make_test_db(PruningMode::Constrained(Constraints { max_blocks: Some(1) }));
+		let (db, sdb) = make_test_db(PruningMode::blocks_pruning(1));
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(0), 0), IsPruned::Pruned);
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(1), 1), IsPruned::Pruned);
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(21), 2), IsPruned::Pruned);
@@ -837,7 +935,7 @@ mod tests {
 
 	#[test]
 	fn prune_window_2() {
-		let (db, sdb) = make_test_db(PruningMode::Constrained(Constraints { max_blocks: Some(2) }));
+		let (db, sdb) = make_test_db(PruningMode::blocks_pruning(2));
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(0), 0), IsPruned::Pruned);
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(1), 1), IsPruned::Pruned);
 		assert_eq!(sdb.is_pruned(&H256::from_low_u64_be(21), 2), IsPruned::NotPruned);
@@ -861,7 +959,7 @@ mod tests {
 				)
 				.unwrap(),
 		);
-		let new_mode = PruningMode::Constrained(Constraints { max_blocks: Some(2) });
+		let new_mode = PruningMode::blocks_pruning(2);
 		let state_db_open_result: Result<(_, StateDb<H256, H256, TestDb>), _> =
 			StateDb::open(db.clone(), Some(new_mode), false, false);
 		assert!(state_db_open_result.is_err());
@@ -944,4 +1042,42 @@ mod tests {
 			check_stored_and_requested_mode_compatibility(created, reopened, expected);
 		}
 	}
+
+	#[test]
+	fn checkpoints_mode_compatibility() {
+		let interval = NonZeroU32::new(64).unwrap();
+		let checkpoints = PruningMode::blocks_pruning_with_checkpoints(256, interval);
+		assert_eq!(checkpoints.id(), super::PRUNING_MODE_CONSTRAINED_CHECKPOINTS);
+		assert_eq!(checkpoints.checkpoint_interval(), Some(interval));
+		assert_eq!(PruningMode::blocks_pruning(256).checkpoint_interval(), None);
+
+		for (created, reopened, expected) in [
+			(Some(checkpoints.clone()), None, Ok(checkpoints.clone())),
+			(
+				Some(checkpoints.clone()),
+				Some(PruningMode::blocks_pruning_with_checkpoints(128, interval)),
+				Ok(PruningMode::blocks_pruning_with_checkpoints(128, interval)),
+			),
+			(
+				Some(checkpoints.clone()),
+				Some(PruningMode::blocks_pruning_with_checkpoints(
+					256,
+					NonZeroU32::new(32).unwrap(),
+				)),
+				Err(()),
+			),
+			(Some(checkpoints.clone()), Some(PruningMode::blocks_pruning(256)), Err(())),
+			(Some(PruningMode::blocks_pruning(256)), Some(checkpoints.clone()), Err(())),
+			(Some(PruningMode::ArchiveAll), Some(checkpoints), Err(())),
+		] {
+			check_stored_and_requested_mode_compatibility(created, reopened, expected);
+		}
+	}
+
+	#[test]
+	fn checkpoints_require_ref_counting() {
+		let mode = PruningMode::blocks_pruning_with_checkpoints(256, NonZeroU32::new(64).unwrap());
+		let result = StateDb::<H256, H256, TestDb>::open(make_db(&[]), Some(mode), true, true);
+		assert!(matches!(result, Err(Error::StateDb(StateDbError::CheckpointsRequireRefCounting))));
+	}
 }
@@ -568,6 +568,40 @@ impl<BlockHash: Hash, Key: Hash> NonCanonicalOverlay<BlockHash, Key> {
 	}
 }
 
+/// Rewrite the non-canonical journal records with `map_key` applied to all node keys.
+pub(crate) fn map_journal_keys<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	map_key: impl Fn(&Key) -> Key,
+	journals: &mut ChangeSet<Vec<u8>>,
+) -> Result<(), Error<D::Error>> {
+	let last_canonicalized = db.get_meta(&to_meta_key(LAST_CANONICAL, &())).map_err(Error::Db)?;
+	let mut block = match last_canonicalized {
+		Some(buffer) => <(BlockHash, u64)>::decode(&mut buffer.as_slice())?.1 + 1,
+		None => return Ok(()),
+	};
+	loop {
+		let mut found = false;
+		for index in 0..MAX_BLOCKS_PER_LEVEL {
+			let journal_key = to_journal_key(block, index);
+			if let Some(record) = db.get_meta(&journal_key).map_err(Error::Db)? {
+				let record: JournalRecord<BlockHash, Key> = Decode::decode(&mut record.as_slice())?;
+				let record = JournalRecord {
+					hash: record.hash,
+					parent_hash: record.parent_hash,
+					inserted: record.inserted.into_iter().map(|(k, v)| (map_key(&k), v)).collect(),
+					deleted: record.deleted.iter().map(&map_key).collect(),
+				};
+				journals.inserted.push((journal_key, record.encode()));
+				found = true;
+			}
+		}
+		if !found {
+			return Ok(())
+		}
+		block += 1;
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::{to_journal_key, NonCanonicalOverlay};
@@ -23,14 +23,23 @@
 //! If a node is re-inserted into the window it gets removed from
 //! the death list.
 //! The changes are journaled in the DB.
+//!
+//! When checkpoints are enabled, the state of every block whose number is a multiple
+//! of the checkpoint interval is kept. Pruning a block only releases the nodes that were
+//! inserted after the last checkpoint that precedes it; the references to the other nodes
+//! are held by the checkpoint state and are never released. The journals of the blocks
+//! pruned since the last checkpoint are kept to track these insertions across restarts.
 
 use crate::{
-	noncanonical::LAST_CANONICAL, to_meta_key, CommitSet, Error, Hash, MetaDb, StateDbError,
-	DEFAULT_MAX_BLOCK_CONSTRAINT, LOG_TARGET,
+	noncanonical::LAST_CANONICAL, to_meta_key, ChangeSet, CommitSet, Error, Hash, MetaDb,
+	StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT, LOG_TARGET,
 };
 use codec::{Decode, Encode};
 use log::trace;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::{
+	collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
+	num::NonZeroU32,
+};
 
 pub(crate) const LAST_PRUNED: &[u8] = b"last_pruned";
 const PRUNING_JOURNAL: &[u8] = b"pruning_journal";
@@ -42,6 +51,11 @@ pub struct RefWindow<BlockHash: Hash, Key: Hash, D: MetaDb> {
 	queue: DeathRowQueue<BlockHash, Key, D>,
 	/// Block number that is next to be pruned.
 	base: u64,
+	/// Keep the state of the blocks whose number is a multiple of the interval.
+	checkpoint_interval: Option<NonZeroU32>,
+	/// The number of times each key was inserted by the blocks pruned since the
+	/// last checkpoint.
+	inserted_since_checkpoint: HashMap<Key, u32>,
 }
 
 /// `DeathRowQueue` used to keep track of blocks in the pruning window, there are two flavors:
@@ -136,7 +150,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> DeathRowQueue<BlockHash, Key, D> {
 				// cache.
 				if num == base + cache.len() as u64 && cache.len() < *cache_capacity {
 					trace!(target: LOG_TARGET, "Adding to DB backed cache {:?} (#{})", hash, num);
-					cache.push_back(DeathRow { hash, deleted: deleted.into_iter().collect() });
+					cache.push_back(DeathRow {
+						hash,
+						deleted: deleted.into_iter().collect(),
+						inserted,
+					});
 				}
 				*last = Some(num);
 			},
@@ -152,7 +170,11 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> DeathRowQueue<BlockHash, Key, D> {
 				for k in deleted.iter() {
 					death_index.insert(k.clone(), imported_block);
 				}
-				death_rows.push_back(DeathRow { hash, deleted: deleted.into_iter().collect() });
+				death_rows.push_back(DeathRow {
+					hash,
+					deleted: deleted.into_iter().collect(),
+					inserted: Default::default(),
+				});
 			},
 		}
 	}
@@ -255,8 +277,8 @@ fn load_death_row_from_db<BlockHash: Hash, Key: Hash, D: MetaDb>(
 	let journal_key = to_journal_key(block);
 	match db.get_meta(&journal_key).map_err(Error::Db)? {
 		Some(record) => {
-			let JournalRecord { hash, deleted, .. } = Decode::decode(&mut record.as_slice())?;
-			Ok(Some(DeathRow { hash, deleted: deleted.into_iter().collect() }))
+			let JournalRecord { hash, inserted, deleted } = Decode::decode(&mut record.as_slice())?;
+			Ok(Some(DeathRow { hash, deleted: deleted.into_iter().collect(), inserted }))
 		},
 		None => Ok(None),
 	}
@@ -266,6 +288,8 @@ fn load_death_row_from_db<BlockHash: Hash, Key: Hash, D: MetaDb>(
 struct DeathRow<BlockHash: Hash, Key: Hash> {
 	hash: BlockHash,
 	deleted: HashSet<Key>,
+	/// Keys inserted by the block, only journaled when checkpoints are enabled.
+	inserted: Vec<Key>,
 }
 
 #[derive(Encode, Decode, Default)]
@@ -279,6 +303,15 @@ fn to_journal_key(block: u64) -> Vec<u8> {
 	to_meta_key(PRUNING_JOURNAL, &block)
 }
 
+fn is_checkpoint(block: u64, interval: NonZeroU32) -> bool {
+	block % interval.get() as u64 == 0
+}
+
+/// Returns the number of the last checkpoint that is not after `block`.
+fn last_checkpoint(block: u64, interval: NonZeroU32) -> u64 {
+	block - block % interval.get() as u64
+}
+
 /// The result return by `RefWindow::have_block`
 #[derive(Debug, PartialEq, Eq)]
 pub enum HaveBlock {
@@ -305,6 +338,7 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 		db: D,
 		window_size: u32,
 		count_insertions: bool,
+		checkpoint_interval: Option<NonZeroU32>,
 	) -> Result<RefWindow<BlockHash, Key, D>, Error<D::Error>> {
 		// the block number of the first block in the queue or the next block number if the queue is
 		// empty
@@ -319,6 +353,20 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 				None => None,
 			};
 
+		// the insertions of the blocks pruned since the last checkpoint
+		let mut inserted_since_checkpoint = HashMap::new();
+		if let (Some(interval), Some(last_pruned)) = (checkpoint_interval, base.checked_sub(1)) {
+			for block in last_checkpoint(last_pruned, interval) + 1..base {
+				if let Some(record) = db.get_meta(&to_journal_key(block)).map_err(Error::Db)? {
+					let record: JournalRecord<BlockHash, Key> =
+						Decode::decode(&mut record.as_slice())?;
+					for key in record.inserted {
+						*inserted_since_checkpoint.entry(key).or_default() += 1;
+					}
+				}
+			}
+		}
+
 		let queue = if count_insertions {
 			// Highly scientific crafted number for deciding when to print the warning!
 			//
@@ -347,7 +395,7 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 			DeathRowQueue::new_db_backed(db, base, last, window_size)?
 		};
 
-		Ok(RefWindow { queue, base })
+		Ok(RefWindow { queue, base, checkpoint_interval, inserted_since_checkpoint })
 	}
 
 	pub fn window_size(&self) -> u64 {
@@ -374,6 +422,14 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 
 	// Check if a block is in the pruning window and not be pruned yet
 	pub fn have_block(&self, hash: &BlockHash, number: u64) -> HaveBlock {
+		// the state of canonical checkpoints is kept after they leave the window, but
+		// we don't track which block was canonical
+		if number < self.base &&
+			self.checkpoint_interval
+				.map_or(false, |interval| is_checkpoint(number, interval))
+		{
+			return HaveBlock::Maybe
+		}
 		// if the queue is empty or the block number exceed the pruning window, we definitely
 		// do not have this block
 		if self.is_empty() || number < self.base || number >= self.base + self.window_size() {
@@ -387,9 +443,39 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 		if let Some(pruned) = self.queue.pop_front(self.base)? {
 			trace!(target: "state-db", "Pruning {:?} ({} deleted)", pruned.hash, pruned.deleted.len());
 			let index = self.base;
-			commit.data.deleted.extend(pruned.deleted.into_iter());
+			match self.checkpoint_interval {
+				None => {
+					commit.data.deleted.extend(pruned.deleted.into_iter());
+					commit.meta.deleted.push(to_journal_key(index));
+				},
+				Some(interval) => {
+					// only release the keys inserted after the last checkpoint, the other
+					// ones are part of the checkpoint state
+					for key in pruned.deleted {
+						if let Entry::Occupied(mut entry) =
+							self.inserted_since_checkpoint.entry(key)
+						{
+							*entry.get_mut() -= 1;
+							let key = if *entry.get() == 0 {
+								entry.remove_entry().0
+							} else {
+								entry.key().clone()
+							};
+							commit.data.deleted.push(key);
+						}
+					}
+					if is_checkpoint(index, interval) {
+						self.inserted_since_checkpoint.clear();
+						let first = (index + 1).saturating_sub(interval.get() as u64);
+						commit.meta.deleted.extend((first..=index).map(to_journal_key));
+					} else {
+						for key in pruned.inserted {
+							*self.inserted_since_checkpoint.entry(key).or_default() += 1;
+						}
+					}
+				},
+			}
 			commit.meta.inserted.push((to_meta_key(LAST_PRUNED, &()), index.encode()));
-			commit.meta.deleted.push(to_journal_key(self.base));
 			self.base += 1;
 			Ok(())
 		} else {
@@ -412,7 +498,9 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 			return Err(Error::StateDb(StateDbError::InvalidBlockNumber))
 		}
 		trace!(target: "state-db", "Adding to pruning window: {:?} ({} inserted, {} deleted)", hash, commit.data.inserted.len(), commit.data.deleted.len());
-		let inserted = if matches!(self.queue, DeathRowQueue::Mem { .. }) {
+		let inserted = if matches!(self.queue, DeathRowQueue::Mem { .. }) ||
+			self.checkpoint_interval.is_some()
+		{
 			commit.data.inserted.iter().map(|(k, _)| k.clone()).collect()
 		} else {
 			Default::default()
@@ -425,21 +513,70 @@ impl<BlockHash: Hash, Key: Hash, D: MetaDb> RefWindow<BlockHash, Key, D> {
 	}
 }
 
+/// Rewrite the pruning journal records with `map_key` applied to all node keys.
+///
+/// Returns the number of references that a reference counting database must hold for each
+/// key deleted by the journal records, so that the key is released by the same record that
+/// deletes it when insertions are counted: once for every deletion, plus once if the key is
+/// inserted again after the last one.
+pub(crate) fn map_journal_keys<BlockHash: Hash, Key: Hash, D: MetaDb>(
+	db: &D,
+	map_key: impl Fn(&Key) -> Key,
+	journals: &mut ChangeSet<Vec<u8>>,
+) -> Result<HashMap<Key, u32>, Error<D::Error>> {
+	let mut block = match db.get_meta(&to_meta_key(LAST_PRUNED, &())).map_err(Error::Db)? {
+		Some(buffer) => u64::decode(&mut buffer.as_slice())? + 1,
+		None => 0,
+	};
+	// number of deletions and whether the key is inserted after the last one
+	let mut pending: HashMap<Key, (u32, bool)> = HashMap::new();
+	while let Some(record) = db.get_meta(&to_journal_key(block)).map_err(Error::Db)? {
+		let record: JournalRecord<BlockHash, Key> = Decode::decode(&mut record.as_slice())?;
+		for key in &record.deleted {
+			let (deleted, reinserted) = pending.entry(key.clone()).or_default();
+			*deleted += 1;
+			*reinserted = false;
+		}
+		for key in &record.inserted {
+			if let Some((_, reinserted)) = pending.get_mut(key) {
+				*reinserted = true;
+			}
+		}
+		let record = JournalRecord {
+			hash: record.hash,
+			inserted: record.inserted.iter().map(&map_key).collect(),
+			deleted: record.deleted.iter().map(&map_key).collect(),
+		};
+		journals.inserted.push((to_journal_key(block), record.encode()));
+		block += 1;
+	}
+	Ok(pending
+		.into_iter()
+		.map(|(key, (deleted, reinserted))| (key, deleted + reinserted as u32))
+		.collect())
+}
+
 #[cfg(test)]
 mod tests {
-	use super::{to_journal_key, DeathRowQueue, HaveBlock, JournalRecord, RefWindow, LAST_PRUNED};
+	use super::{
+		map_journal_keys, to_journal_key, DeathRowQueue, HaveBlock, JournalRecord, RefWindow,
+		LAST_PRUNED,
+	};
 	use crate::{
 		noncanonical::LAST_CANONICAL,
 		test::{make_commit, make_db, TestDb},
-		to_meta_key, CommitSet, Error, Hash, StateDbError, DEFAULT_MAX_BLOCK_CONSTRAINT,
+		to_meta_key, ChangeSet, CommitSet, Error, Hash, MetaDb, StateDbError,
+		DEFAULT_MAX_BLOCK_CONSTRAINT,
 	};
-	use codec::Encode;
+	use codec::{Decode, Encode};
 	use sp_core::H256;
+	use std::num::NonZeroU32;
 
 	fn check_journal(pruning: &RefWindow<H256, H256, TestDb>, db: &TestDb) {
 		let count_insertions = matches!(pruning.queue, DeathRowQueue::Mem { .. });
 		let restored: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, count_insertions).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, count_insertions, None)
+				.unwrap();
 		assert_eq!(pruning.base, restored.base);
 		assert_eq!(pruning.queue.get_mem_queue_state(), restored.queue.get_mem_queue_state());
 	}
@@ -448,7 +585,7 @@ mod tests {
 	fn created_from_empty_db() {
 		let db = make_db(&[]);
 		let pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		assert_eq!(pruning.base, 0);
 		let (death_rows, death_index) = pruning.queue.get_mem_queue_state().unwrap();
 		assert!(death_rows.is_empty());
@@ -459,7 +596,7 @@ mod tests {
 	fn prune_empty() {
 		let db = make_db(&[]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = CommitSet::default();
 		assert_eq!(
 			Err(Error::StateDb(StateDbError::BlockUnavailable)),
@@ -475,7 +612,7 @@ mod tests {
 	fn prune_one() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = make_commit(&[4, 5], &[1, 3]);
 		let hash = H256::random();
 		pruning.note_canonical(&hash, 0, &mut commit).unwrap();
@@ -505,7 +642,7 @@ mod tests {
 	fn prune_two() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = make_commit(&[4], &[1]);
 		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
 		db.commit(&commit);
@@ -531,7 +668,7 @@ mod tests {
 	fn prune_two_pending() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = make_commit(&[4], &[1]);
 		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
 		db.commit(&commit);
@@ -554,7 +691,7 @@ mod tests {
 	fn reinserted_survives() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = make_commit(&[], &[2]);
 		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
 		db.commit(&commit);
@@ -586,7 +723,7 @@ mod tests {
 	fn reinserted_survive_pending() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
 		let mut commit = make_commit(&[], &[2]);
 		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
 		db.commit(&commit);
@@ -616,7 +753,7 @@ mod tests {
 	fn reinserted_ignores() {
 		let mut db = make_db(&[1, 2, 3]);
 		let mut pruning: RefWindow<H256, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		let mut commit = make_commit(&[], &[2]);
 		pruning.note_canonical(&H256::random(), 0, &mut commit).unwrap();
 		db.commit(&commit);
@@ -654,7 +791,7 @@ mod tests {
 
 		fn load_pruning_from_db(db: TestDb) -> (usize, u64) {
 			let pruning: RefWindow<u64, H256, TestDb> =
-				RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+				RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 			let (cache, _) = pruning.queue.get_db_backed_queue_state().unwrap();
 			(cache.len(), pruning.base)
 		}
@@ -708,7 +845,7 @@ mod tests {
 	fn db_backed_queue() {
 		let mut db = make_db(&[]);
 		let mut pruning: RefWindow<u64, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		let cache_capacity = DEFAULT_MAX_BLOCK_CONSTRAINT as usize;
 
 		// start as an empty queue
@@ -752,7 +889,7 @@ mod tests {
 
 		// revert the last add that no apply yet
 		// NOTE: do not commit the previous `CommitSet` to db
-		pruning = RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+		pruning = RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		let cache_capacity = DEFAULT_MAX_BLOCK_CONSTRAINT as usize;
 		assert_eq!(pruning.window_size(), cache_capacity as u64 + 10);
 		let (cache, _) = pruning.queue.get_db_backed_queue_state().unwrap();
@@ -773,7 +910,7 @@ mod tests {
 		// load a new queue from db
 		// `cache` is full again but the content of the queue should be the same
 		let pruning: RefWindow<u64, H256, TestDb> =
-			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		assert_eq!(pruning.window_size(), cache_capacity as u64 + 9);
 		let (cache, _) = pruning.queue.get_db_backed_queue_state().unwrap();
 		assert_eq!(cache.len(), cache_capacity);
@@ -786,7 +923,7 @@ mod tests {
 	fn load_block_from_db() {
 		let mut db = make_db(&[]);
 		let mut pruning: RefWindow<u64, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		let cache_capacity = DEFAULT_MAX_BLOCK_CONSTRAINT as usize;
 
 		// import blocks
@@ -823,7 +960,7 @@ mod tests {
 		// load a new queue from db
 		// `cache` should be the same
 		let pruning: RefWindow<u64, H256, TestDb> =
-			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db, DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		assert_eq!(pruning.window_size(), 10);
 		let (cache, _) = pruning.queue.get_db_backed_queue_state().unwrap();
 		assert_eq!(cache.len(), 10);
@@ -836,7 +973,7 @@ mod tests {
 	fn get_block_from_queue() {
 		let mut db = make_db(&[]);
 		let mut pruning: RefWindow<u64, H256, TestDb> =
-			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false).unwrap();
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, None).unwrap();
 		let cache_capacity = DEFAULT_MAX_BLOCK_CONSTRAINT as u64;
 
 		// import blocks and commit to db
@@ -869,4 +1006,115 @@ mod tests {
 		pruning.prune_one(&mut commit).unwrap();
 		db.commit(&commit);
 	}
+
+	#[test]
+	fn checkpoints_keep_state() {
+		let mut db = make_db(&[]);
+		let interval = NonZeroU32::new(2).unwrap();
+		let mut pruning: RefWindow<u64, H256, TestDb> =
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, Some(interval))
+				.unwrap();
+
+		// block 0 and 2 are checkpoints
+		let blocks: [(&[u64], &[u64]); 5] =
+			[(&[1, 2, 3], &[]), (&[4], &[1]), (&[5], &[4]), (&[6], &[2, 5]), (&[7], &[6])];
+		for (number, (inserted, deleted)) in blocks.iter().enumerate() {
+			let mut commit = make_commit(inserted, deleted);
+			pruning.note_canonical(&(number as u64), number as u64, &mut commit).unwrap();
+			db.commit(&commit);
+		}
+
+		fn prune(pruning: &mut RefWindow<u64, H256, TestDb>, db: &mut TestDb) -> Vec<u64> {
+			let mut commit = CommitSet::default();
+			pruning.prune_one(&mut commit).unwrap();
+			db.commit(&commit);
+			let mut deleted: Vec<_> =
+				commit.data.deleted.iter().map(|k| k.to_low_u64_be()).collect();
+			deleted.sort();
+			deleted
+		}
+
+		// keys alive at checkpoint 0 are kept
+		assert_eq!(prune(&mut pruning, &mut db), Vec::<u64>::new());
+		assert_eq!(prune(&mut pruning, &mut db), Vec::<u64>::new());
+		// key 4 was inserted after checkpoint 0 and is not part of checkpoint 2
+		assert_eq!(prune(&mut pruning, &mut db), vec![4]);
+		// keys alive at checkpoints 0 and 2 are kept
+		assert_eq!(prune(&mut pruning, &mut db), Vec::<u64>::new());
+
+		// the insertions since the last checkpoint are restored from the journals
+		let mut pruning: RefWindow<u64, H256, TestDb> =
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, Some(interval))
+				.unwrap();
+		assert_eq!(pruning.base, 4);
+		assert_eq!(prune(&mut pruning, &mut db), vec![6]);
+
+		// the journals of the blocks pruned since the last checkpoint are discarded
+		for number in 0..5 {
+			assert!(db.get_meta(&to_journal_key(number)).unwrap().is_none());
+		}
+	}
+
+	#[test]
+	fn checkpoints_may_have_block() {
+		let mut db = make_db(&[]);
+		let interval = NonZeroU32::new(2).unwrap();
+		let mut pruning: RefWindow<u64, H256, TestDb> =
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, false, Some(interval))
+				.unwrap();
+		let mut commit = make_commit(&[], &[]);
+		for number in 0..4 {
+			pruning.note_canonical(&number, number, &mut commit).unwrap();
+		}
+		db.commit(&commit);
+
+		let mut commit = make_commit(&[], &[]);
+		for _ in 0..3 {
+			pruning.prune_one(&mut commit).unwrap();
+		}
+		db.commit(&commit);
+
+		assert_eq!(pruning.have_block(&0, 0), HaveBlock::Maybe);
+		assert_eq!(pruning.have_block(&1, 1), HaveBlock::No);
+		assert_eq!(pruning.have_block(&2, 2), HaveBlock::Maybe);
+		assert_eq!(pruning.have_block(&3, 3), HaveBlock::Yes);
+	}
+
+	#[test]
+	fn map_journal_keys_counts_references() {
+		let mut db = make_db(&[]);
+		let mut pruning: RefWindow<u64, H256, TestDb> =
+			RefWindow::new(db.clone(), DEFAULT_MAX_BLOCK_CONSTRAINT, true, None).unwrap();
+		let blocks: [(&[u64], &[u64]); 4] =
+			[(&[1], &[]), (&[2], &[1]), (&[1], &[2]), (&[2], &[1, 3])];
+		for (number, (inserted, deleted)) in blocks.iter().enumerate() {
+			let mut commit = make_commit(inserted, deleted);
+			pruning.note_canonical(&(number as u64), number as u64, &mut commit).unwrap();
+			db.commit(&commit);
+		}
+		let mut commit = CommitSet::default();
+		pruning.prune_one(&mut commit).unwrap();
+		db.commit(&commit);
+
+		let map_key = |key: &H256| H256::from_low_u64_be(key.to_low_u64_be() + 100);
+		let mut journals = ChangeSet::default();
+		let references =
+			map_journal_keys::<u64, H256, TestDb>(&db, map_key, &mut journals).unwrap();
+
+		// 1 is deleted twice, 2 is inserted again after its deletion
+		let expected = [(1, 2), (2, 2), (3, 1)]
+			.into_iter()
+			.map(|(key, references)| (H256::from_low_u64_be(key), references))
+			.collect();
+		assert_eq!(references, expected);
+
+		// the pruned record is not rewritten
+		let keys: Vec<_> = journals.inserted.iter().map(|(key, _)| key.clone()).collect();
+		assert_eq!(keys, (1..4).map(to_journal_key).collect::<Vec<_>>());
+		let record: JournalRecord<u64, H256> =
+			Decode::decode(&mut journals.inserted[2].1.as_slice()).unwrap();
+		assert_eq!(record.hash, 3);
+		assert_eq!(record.inserted, vec![H256::from_low_u64_be(102)]);
+		assert_eq!(record.deleted, vec![H256::from_low_u64_be(101), H256::from_low_u64_be(103)]);
+	}
 }
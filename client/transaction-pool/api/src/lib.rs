@@ -72,6 +72,7 @@ impl PoolStatus {
 /// 		- `Invalid`
 /// 		- `Usurped`
 /// 		- `Dropped`
+/// 		- `Evicted`
 /// 	4. Re-entering the pool:
 /// 		- `Retracted`
 /// 	5. Block finalized:
@@ -88,7 +89,7 @@ impl PoolStatus {
 /// Note that there are conditions that may cause transactions to reappear in the pool.
 /// 1. Due to possible forks, the transaction that ends up being in included
 /// in one block, may later re-enter the pool or be marked as invalid.
-/// 2. Transaction `Dropped` at one point, may later re-enter the pool if some other
+/// 2. Transaction `Dropped` or `Evicted` at one point, may later re-enter the pool if some other
 /// transactions are removed.
 /// 3. `Invalid` transaction may become valid at some point in the future.
 /// (Note that runtimes are encouraged to use `UnknownValidity` to inform the pool about
@@ -128,10 +129,22 @@ pub enum TransactionStatus<Hash, BlockHash> {
 	Usurped(Hash),
 	/// Transaction has been dropped from the pool because of the limit.
 	Dropped,
+	/// Transaction has been evicted from the pool to enforce a fairness limit.
+	Evicted(EvictionReason),
 	/// Transaction is no longer valid in the current state.
 	Invalid,
 }
 
+/// Reason for evicting a transaction from the pool, besides the limits of its queues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EvictionReason {
+	/// The sender of the transaction exceeded its quota.
+	SenderQuota,
+	/// The transaction occupied a slot reserved for a priority lane.
+	PriorityLane,
+}
+
 /// The stream of transaction events.
 pub type TransactionStatusStream<Hash, BlockHash> =
 	dyn Stream<Item = TransactionStatus<Hash, BlockHash>> + Send;
@@ -418,5 +431,15 @@ mod tests {
 
 		let event_dec: TransactionStatus<u8, u8> = serde_json::from_str(exp).unwrap();
 		assert_eq!(event_dec, TransactionStatus::Finalized((1, 0)));
+
+		let event: TransactionStatus<u8, u8> =
+			TransactionStatus::Evicted(EvictionReason::SenderQuota);
+		let ser = serde_json::to_string(&event).unwrap();
+
+		let exp = r#"{"evicted":"senderQuota"}"#;
+		assert_eq!(ser, exp);
+
+		let event_dec: TransactionStatus<u8, u8> = serde_json::from_str(exp).unwrap();
+		assert_eq!(event_dec, event);
 	}
 }
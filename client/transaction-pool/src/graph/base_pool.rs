@@ -20,7 +20,13 @@
 //!
 //! For a more full-featured pool, have a look at the `pool` module.
 
-use std::{cmp::Ordering, collections::HashSet, fmt, hash, sync::Arc};
+use std::{
+	cmp::Ordering,
+	collections::{HashMap, HashSet},
+	fmt, hash,
+	sync::Arc,
+	time::Instant,
+};
 
 use crate::LOG_TARGET;
 use log::{debug, trace, warn};
@@ -396,29 +402,7 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 
 		while ready.is_exceeded(self.ready.len(), self.ready.bytes()) {
 			// find the worst transaction
-			let worst = self.ready.fold::<TransactionRef<Hash, Ex>, _>(|worst, current| {
-				let transaction = &current.transaction;
-				worst
-					.map(|worst| {
-						// Here we don't use `TransactionRef`'s ordering implementation because
-						// while it prefers priority like need here, it also prefers older
-						// transactions for inclusion purposes and limit enforcement needs to prefer
-						// newer transactions instead and drop the older ones.
-						match worst.transaction.priority.cmp(&transaction.transaction.priority) {
-							Ordering::Less => worst,
-							Ordering::Equal =>
-								if worst.insertion_id > transaction.insertion_id {
-									transaction.clone()
-								} else {
-									worst
-								},
-							Ordering::Greater => transaction.clone(),
-						}
-					})
-					.or_else(|| Some(transaction.clone()))
-			});
-
-			if let Some(worst) = worst {
+			if let Some(worst) = self.worst_ready(|_| true) {
 				removed.append(&mut self.remove_subtree(&[worst.transaction.hash.clone()]))
 			} else {
 				break
@@ -443,6 +427,187 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		removed
 	}
 
+	/// Makes sure that no single sender occupies more than its quota of the pool.
+	///
+	/// For every sender that exceeds the limit, future transactions are removed first (most
+	/// recently imported ones first), followed by the newest ready transactions of that sender
+	/// and all transactions that depend on them. Returns the removed transactions.
+	pub fn enforce_sender_limit(&mut self, limit: &SenderLimit) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		let mut senders = HashMap::<Vec<u8>, SenderTransactions<Hash>>::new();
+		self.future.fold(|_: Option<()>, current| {
+			if let Some(sender) = limit.sender(&current.transaction.provides) {
+				let hash = current.transaction.hash.clone();
+				senders
+					.entry(sender.to_vec())
+					.or_default()
+					.future
+					.push((current.imported_at, hash));
+			}
+			None
+		});
+		self.ready.fold(|_: Option<()>, current| {
+			let transaction = &current.transaction;
+			if let Some(sender) = limit.sender(&transaction.transaction.provides) {
+				let hash = transaction.transaction.hash.clone();
+				senders
+					.entry(sender.to_vec())
+					.or_default()
+					.ready
+					.push((transaction.insertion_id, hash));
+			}
+			None
+		});
+
+		let mut counts: HashMap<Vec<u8>, usize> = senders
+			.iter()
+			.map(|(sender, txs)| (sender.clone(), txs.future.len() + txs.ready.len()))
+			.collect();
+		let mut removed = vec![];
+
+		for (sender, mut txs) in senders {
+			if counts[&sender] <= limit.count {
+				continue
+			}
+
+			// Unlike the queue limits, prefer the newest transactions here: these are usually
+			// the tail of the sender's chain, so nothing else depends on them.
+			txs.future.sort_by(|a, b| b.0.cmp(&a.0));
+			txs.ready.sort_by(|a, b| b.0.cmp(&a.0));
+			let newest = txs
+				.future
+				.into_iter()
+				.map(|(_, hash)| hash)
+				.chain(txs.ready.into_iter().map(|(_, hash)| hash));
+
+			for worst in newest {
+				if counts[&sender] <= limit.count {
+					break
+				}
+
+				// Already removed together with a transaction it depends on.
+				let mut removed_now = self.remove_subtree(&[worst.clone()]);
+				if removed_now.is_empty() {
+					continue
+				}
+
+				debug!(
+					target: LOG_TARGET,
+					"[{:?}] Sender 0x{} exceeded its quota of {} transactions",
+					worst,
+					HexDisplay::from(&sender),
+					limit.count,
+				);
+				for tx in &removed_now {
+					if let Some(count) = limit.sender(&tx.provides).and_then(|s| counts.get_mut(s))
+					{
+						*count = count.saturating_sub(1);
+					}
+				}
+				removed.append(&mut removed_now);
+			}
+		}
+
+		removed
+	}
+
+	/// Makes sure that the slots reserved for priority lanes stay available.
+	///
+	/// Transactions below the `min_priority` of a lane can occupy at most `ready.count` minus the
+	/// slots reserved by that lane and all lanes of higher priority. The worst transactions
+	/// breaking that rule are removed from the ready queue together with all transactions that
+	/// depend on them. Returns the removed transactions.
+	pub fn enforce_priority_lanes(
+		&mut self,
+		ready: &Limit,
+		lanes: &[PriorityLane],
+	) -> Vec<Arc<Transaction<Hash, Ex>>> {
+		let mut removed = vec![];
+
+		for lane in lanes {
+			let reserved = lanes
+				.iter()
+				.filter(|other| other.min_priority >= lane.min_priority)
+				.fold(0usize, |acc, other| acc.saturating_add(other.reserved));
+			let allowed = ready.count.saturating_sub(reserved);
+			let below_lane = |tx: &Transaction<Hash, Ex>| tx.priority < lane.min_priority;
+
+			let mut below = Vec::new();
+			self.ready.fold(|_: Option<()>, current| {
+				if below_lane(&current.transaction.transaction) {
+					below.push(current.transaction.clone());
+				}
+				None
+			});
+			let mut count = below.len();
+			if count <= allowed {
+				continue
+			}
+
+			// The worst transactions first, see `worst_ready`.
+			below.sort_by(|a, b| {
+				a.transaction
+					.priority
+					.cmp(&b.transaction.priority)
+					.then_with(|| b.insertion_id.cmp(&a.insertion_id))
+			});
+
+			for worst in below {
+				if count <= allowed {
+					break
+				}
+
+				// Already removed together with a transaction it depends on.
+				let mut removed_now = self.remove_subtree(&[worst.transaction.hash.clone()]);
+				if removed_now.is_empty() {
+					continue
+				}
+
+				debug!(
+					target: LOG_TARGET,
+					"[{:?}] Evicted from slots reserved for priority {}",
+					worst.transaction.hash,
+					lane.min_priority,
+				);
+				count =
+					count.saturating_sub(removed_now.iter().filter(|tx| below_lane(tx)).count());
+				removed.append(&mut removed_now);
+			}
+		}
+
+		removed
+	}
+
+	/// Returns the worst ready transaction matching the given filter.
+	fn worst_ready(
+		&mut self,
+		filter: impl Fn(&Transaction<Hash, Ex>) -> bool,
+	) -> Option<TransactionRef<Hash, Ex>> {
+		self.ready.fold::<TransactionRef<Hash, Ex>, _>(|worst, current| {
+			let transaction = &current.transaction;
+			if !filter(&transaction.transaction) {
+				return worst
+			}
+			worst
+				.map(|worst| {
+					// Here we don't use `TransactionRef`'s ordering implementation because
+					// while it prefers priority like need here, it also prefers older
+					// transactions for inclusion purposes and limit enforcement needs to prefer
+					// newer transactions instead and drop the older ones.
+					match worst.transaction.priority.cmp(&transaction.transaction.priority) {
+						Ordering::Less => worst,
+						Ordering::Equal =>
+							if worst.insertion_id > transaction.insertion_id {
+								transaction.clone()
+							} else {
+								worst
+							},
+						Ordering::Greater => transaction.clone(),
+					}
+				})
+				.or_else(|| Some(transaction.clone()))
+		})
+	}
+
 	/// Removes all transactions represented by the hashes and all other transactions
 	/// that depend on them.
 	///
@@ -530,6 +695,57 @@ impl Limit {
 	}
 }
 
+/// Limit of transactions a single sender can have in the pool.
+///
+/// The sender is identified by the first tag a transaction provides, with the trailing
+/// `nonce_len` bytes stripped. This matches the `(sender, nonce)` tags of FRAME's `CheckNonce`.
+#[derive(Debug, Clone)]
+pub struct SenderLimit {
+	/// Maximal number of transactions of a single sender in both queues combined.
+	pub count: usize,
+	/// Number of trailing bytes of the provided tag that encode the nonce.
+	pub nonce_len: usize,
+}
+
+impl SenderLimit {
+	/// Returns the sender of a transaction providing given tags.
+	///
+	/// Transactions that don't provide any tags long enough to contain a sender are not
+	/// subject to the limit.
+	pub fn sender<'a>(&self, provides: &'a [Tag]) -> Option<&'a [u8]> {
+		let tag = provides.first()?;
+		match tag.len().checked_sub(self.nonce_len) {
+			Some(len) if len > 0 => Some(&tag[..len]),
+			_ => None,
+		}
+	}
+}
+
+/// The transactions of a single sender, collected to enforce a [`SenderLimit`].
+struct SenderTransactions<Hash> {
+	/// Hashes of the future transactions, with the time they were imported.
+	future: Vec<(Instant, Hash)>,
+	/// Hashes of the ready transactions, with their insertion id.
+	ready: Vec<(u64, Hash)>,
+}
+
+impl<Hash> Default for SenderTransactions<Hash> {
+	fn default() -> Self {
+		Self { future: Vec::new(), ready: Vec::new() }
+	}
+}
+
+/// Slots of the ready queue reserved for transactions of high priority.
+///
+/// Useful to make sure there is always room for e.g. operational transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriorityLane {
+	/// Minimal priority of transactions that can use the reserved slots.
+	pub min_priority: Priority,
+	/// Number of ready queue slots reserved for the lane.
+	pub reserved: usize,
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1043,4 +1259,115 @@ source: TransactionSource::External, requires: [03, 02], provides: [04], data: [
 		assert_eq!(pool.reject_future_transactions, true);
 		assert_eq!(pool.future.len(), 1);
 	}
+
+	#[test]
+	fn should_enforce_sender_limit() {
+		// given
+		let mut pool = pool();
+		let limit = SenderLimit { count: 2, nonce_len: 1 };
+		// sender 1: two ready and one future transaction
+		pool.import(Transaction {
+			data: vec![1u8],
+			provides: vec![vec![1, 0]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		pool.import(Transaction {
+			data: vec![2u8],
+			hash: 2,
+			requires: vec![vec![1, 0]],
+			provides: vec![vec![1, 1]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		pool.import(Transaction {
+			data: vec![3u8],
+			hash: 3,
+			requires: vec![vec![1, 5]],
+			provides: vec![vec![1, 6]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		// sender 2: single ready transaction
+		pool.import(Transaction {
+			data: vec![4u8],
+			hash: 4,
+			provides: vec![vec![2, 0]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		// no sender
+		pool.import(Transaction { data: vec![5u8], hash: 5, ..DEFAULT_TX.clone() })
+			.unwrap();
+		assert_eq!(pool.ready().count(), 4);
+		assert_eq!(pool.future.len(), 1);
+
+		// when
+		let removed = pool.enforce_sender_limit(&limit);
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![3]);
+		assert_eq!(pool.ready().count(), 4);
+		assert_eq!(pool.future.len(), 0);
+
+		// when
+		let removed = pool.enforce_sender_limit(&SenderLimit { count: 1, nonce_len: 1 });
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<HashSet<_>>(), [1, 4, 5].into());
+	}
+
+	#[test]
+	fn should_keep_slots_reserved_for_priority_lanes() {
+		// given
+		let mut pool = pool();
+		let limit = Limit { count: 4, total_bytes: 1024 };
+		let lanes = vec![
+			PriorityLane { min_priority: 100, reserved: 1 },
+			PriorityLane { min_priority: 10, reserved: 1 },
+		];
+		for hash in 1..=3 {
+			pool.import(Transaction {
+				data: vec![hash as u8],
+				hash,
+				priority: hash,
+				provides: vec![vec![hash as u8]],
+				..DEFAULT_TX.clone()
+			})
+			.unwrap();
+		}
+		pool.import(Transaction {
+			data: vec![4u8],
+			hash: 4,
+			priority: 50,
+			provides: vec![vec![4]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+
+		// when
+		let removed = pool.enforce_priority_lanes(&limit, &lanes);
+
+		// then
+		// only 2 slots are left for transactions below priority 10
+		// and 3 for transactions below priority 100.
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![1]);
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<HashSet<_>>(), [2, 3, 4].into());
+
+		// when
+		pool.import(Transaction {
+			data: vec![5u8],
+			hash: 5,
+			priority: 60,
+			provides: vec![vec![5]],
+			..DEFAULT_TX.clone()
+		})
+		.unwrap();
+		let removed = pool.enforce_priority_lanes(&limit, &lanes);
+
+		// then
+		assert_eq!(removed.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![2]);
+		assert_eq!(pool.ready().map(|tx| tx.hash).collect::<HashSet<_>>(), [3, 4, 5].into());
+	}
 }
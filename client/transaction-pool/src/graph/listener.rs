@@ -21,6 +21,7 @@ use std::{collections::HashMap, fmt::Debug, hash};
 use crate::LOG_TARGET;
 use linked_hash_map::LinkedHashMap;
 use log::{debug, trace};
+use sc_transaction_pool_api::EvictionReason;
 use serde::Serialize;
 use sp_runtime::traits;
 
@@ -96,6 +97,12 @@ impl<H: hash::Hash + traits::Member + Serialize, C: ChainApi> Listener<H, C> {
 		})
 	}
 
+	/// Transaction was evicted from the pool to enforce a fairness limit.
+	pub fn evicted(&mut self, tx: &H, reason: EvictionReason) {
+		debug!(target: LOG_TARGET, "[{:?}] Evicted ({:?})", tx, reason);
+		self.fire(tx, |watcher| watcher.evicted(reason));
+	}
+
 	/// Transaction was removed as invalid.
 	pub fn invalid(&mut self, tx: &H) {
 		debug!(target: LOG_TARGET, "[{:?}] Extrinsic invalid", tx);
@@ -115,10 +115,20 @@ pub struct Options {
 	pub ready: base::Limit,
 	/// Future queue limits.
 	pub future: base::Limit,
+	/// Per-sender limits applied to both queues combined.
+	pub sender: Option<base::SenderLimit>,
+	/// Slots of the ready queue reserved for high priority transactions.
+	pub priority_lanes: Vec<base::PriorityLane>,
 	/// Reject future transactions.
 	pub reject_future_transactions: bool,
 	/// How long the extrinsic is banned for.
 	pub ban_time: Duration,
+	/// Persist transactions to the database and reimport them on startup.
+	///
+	/// Transactions are saved on shutdown and every `persist_interval`, if set.
+	pub persist: bool,
+	/// How often the transactions are persisted, on top of saving them on shutdown.
+	pub persist_interval: Option<Duration>,
 }
 
 impl Default for Options {
@@ -126,8 +136,12 @@ impl Default for Options {
 		Self {
 			ready: base::Limit { count: 8192, total_bytes: 20 * 1024 * 1024 },
 			future: base::Limit { count: 512, total_bytes: 1 * 1024 * 1024 },
+			sender: None,
+			priority_lanes: Vec::new(),
 			reject_future_transactions: false,
 			ban_time: Duration::from_secs(60 * 30),
+			persist: false,
+			persist_interval: None,
 		}
 	}
 }
@@ -25,7 +25,7 @@ use std::{
 use crate::LOG_TARGET;
 use futures::channel::mpsc::{channel, Sender};
 use parking_lot::{Mutex, RwLock};
-use sc_transaction_pool_api::{error, PoolStatus, ReadyTransactions};
+use sc_transaction_pool_api::{error, EvictionReason, PoolStatus, ReadyTransactions};
 use serde::Serialize;
 use sp_runtime::{
 	generic::BlockId,
@@ -227,49 +227,69 @@ impl<B: ChainApi> ValidatedPool<B> {
 	}
 
 	fn enforce_limits(&self) -> HashSet<ExtrinsicHash<B>> {
-		let status = self.pool.read().status();
 		let ready_limit = &self.options.ready;
 		let future_limit = &self.options.future;
 
-		log::debug!(target: LOG_TARGET, "Pool Status: {:?}", status);
-		if ready_limit.is_exceeded(status.ready, status.ready_bytes) ||
-			future_limit.is_exceeded(status.future, status.future_bytes)
-		{
-			log::debug!(
-				target: LOG_TARGET,
-				"Enforcing limits ({}/{}kB ready, {}/{}kB future",
-				ready_limit.count,
-				ready_limit.total_bytes / 1024,
-				future_limit.count,
-				future_limit.total_bytes / 1024,
-			);
-
-			// clean up the pool
-			let removed = {
-				let mut pool = self.pool.write();
-				let removed = pool
-					.enforce_limits(ready_limit, future_limit)
-					.into_iter()
-					.map(|x| x.hash)
-					.collect::<HashSet<_>>();
-				// ban all removed transactions
-				self.rotator.ban(&Instant::now(), removed.iter().copied());
-				removed
-			};
-			if !removed.is_empty() {
-				log::debug!(target: LOG_TARGET, "Enforcing limits: {} dropped", removed.len());
+		// clean up the pool
+		let evicted = {
+			let mut pool = self.pool.write();
+			let mut evicted = Vec::new();
+
+			if let Some(ref sender_limit) = self.options.sender {
+				evicted.extend(
+					pool.enforce_sender_limit(sender_limit)
+						.into_iter()
+						.map(|x| (x.hash, Some(EvictionReason::SenderQuota))),
+				);
 			}
 
-			// run notifications
-			let mut listener = self.listener.write();
-			for h in &removed {
-				listener.dropped(h, None);
+			if !self.options.priority_lanes.is_empty() {
+				evicted.extend(
+					pool.enforce_priority_lanes(ready_limit, &self.options.priority_lanes)
+						.into_iter()
+						.map(|x| (x.hash, Some(EvictionReason::PriorityLane))),
+				);
 			}
 
-			removed
-		} else {
-			Default::default()
+			let status = pool.status();
+			log::debug!(target: LOG_TARGET, "Pool Status: {:?}", status);
+			if ready_limit.is_exceeded(status.ready, status.ready_bytes) ||
+				future_limit.is_exceeded(status.future, status.future_bytes)
+			{
+				log::debug!(
+					target: LOG_TARGET,
+					"Enforcing limits ({}/{}kB ready, {}/{}kB future",
+					ready_limit.count,
+					ready_limit.total_bytes / 1024,
+					future_limit.count,
+					future_limit.total_bytes / 1024,
+				);
+
+				evicted.extend(
+					pool.enforce_limits(ready_limit, future_limit)
+						.into_iter()
+						.map(|x| (x.hash, None)),
+				);
+			}
+
+			// ban all evicted transactions
+			self.rotator.ban(&Instant::now(), evicted.iter().map(|(hash, _)| *hash));
+			evicted
+		};
+		if !evicted.is_empty() {
+			log::debug!(target: LOG_TARGET, "Enforcing limits: {} dropped", evicted.len());
 		}
+
+		// run notifications
+		let mut listener = self.listener.write();
+		for (h, reason) in &evicted {
+			match reason {
+				Some(reason) => listener.evicted(h, *reason),
+				None => listener.dropped(h, None),
+			}
+		}
+
+		evicted.into_iter().map(|(hash, _)| hash).collect()
 	}
 
 	/// Import a single extrinsic and starts to watch their progress in the pool.
@@ -625,6 +645,17 @@ impl<B: ChainApi> ValidatedPool<B> {
 		self.pool.read().futures().map(|tx| (tx.hash, tx.data.clone())).collect()
 	}
 
+	/// Returns the source and extrinsic of all transactions in the pool.
+	///
+	/// Ready transactions come first, in the order they would be included in a block.
+	pub fn extrinsics(&self) -> Vec<(TransactionSource, ExtrinsicFor<B>)> {
+		let pool = self.pool.read();
+		pool.ready()
+			.map(|tx| (tx.source, tx.data.clone()))
+			.chain(pool.futures().map(|tx| (tx.source, tx.data.clone())))
+			.collect()
+	}
+
 	/// Returns pool status.
 	pub fn status(&self) -> PoolStatus {
 		self.pool.read().status()
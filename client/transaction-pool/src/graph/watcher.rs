@@ -19,7 +19,7 @@
 //! Extrinsics status updates.
 
 use futures::Stream;
-use sc_transaction_pool_api::TransactionStatus;
+use sc_transaction_pool_api::{EvictionReason, TransactionStatus};
 use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
 
 /// Extrinsic watcher.
@@ -118,6 +118,12 @@ impl<H: Clone, BH: Clone> Sender<H, BH> {
 		self.is_finalized = true;
 	}
 
+	/// Transaction has been evicted from the pool for the given reason.
+	pub fn evicted(&mut self, reason: EvictionReason) {
+		self.send(TransactionStatus::Evicted(reason));
+		self.is_finalized = true;
+	}
+
 	/// The extrinsic has been broadcast to the given peers.
 	pub fn broadcast(&mut self, peers: Vec<String>) {
 		self.send(TransactionStatus::Broadcast(peers))
@@ -27,6 +27,7 @@ mod enactment_state;
 pub mod error;
 mod graph;
 mod metrics;
+pub mod persistence;
 mod revalidation;
 #[cfg(test)]
 mod tests;
@@ -40,7 +41,10 @@ use futures::{
 	prelude::*,
 };
 pub use graph::{
-	base_pool::Limit as PoolLimit, ChainApi, Options, Pool, Transaction, ValidatedTransaction,
+	base_pool::{
+		Limit as PoolLimit, PriorityLane as PoolPriorityLane, SenderLimit as PoolSenderLimit,
+	},
+	ChainApi, Options, Pool, Transaction, ValidatedTransaction,
 };
 use parking_lot::Mutex;
 use std::{
@@ -369,6 +373,7 @@ where
 		+ sp_runtime::traits::BlockIdTo<Block>
 		+ sc_client_api::ExecutorProvider<Block>
 		+ sc_client_api::UsageProvider<Block>
+		+ sc_client_api::AuxStore
 		+ sp_blockchain::HeaderMetadata<Block, Error = sp_blockchain::Error>
 		+ Send
 		+ Sync
@@ -380,22 +385,35 @@ where
 		options: graph::Options,
 		is_validator: IsValidator,
 		prometheus: Option<&PrometheusRegistry>,
-		spawner: impl SpawnEssentialNamed,
+		spawner: impl SpawnEssentialNamed + 'static,
 		client: Arc<Client>,
 	) -> Arc<Self> {
+		let spawner: Box<dyn SpawnEssentialNamed> = Box::new(spawner);
 		let pool_api = Arc::new(FullChainApi::new(client.clone(), prometheus, &spawner));
+		let persist = options.persist.then_some(options.persist_interval);
 		let pool = Arc::new(Self::with_revalidation_type(
 			options,
 			is_validator,
 			pool_api,
 			prometheus,
 			RevalidationType::Full,
-			spawner,
+			spawner.clone(),
 			client.usage_info().chain.best_number,
 			client.usage_info().chain.best_hash,
 			client.usage_info().chain.finalized_hash,
 		));
 
+		if let Some(interval) = persist {
+			let task = persistence::persistence_task(
+				pool.pool.clone(),
+				client.clone(),
+				BlockId::Hash(client.usage_info().chain.best_hash),
+				interval,
+				pool.metrics.clone(),
+			);
+			spawner.spawn_essential("txpool-persistence", Some("transaction-pool"), task.boxed());
+		}
+
 		// make transaction pool available for off-chain runtime calls.
 		client.execution_extensions().register_transaction_pool(&pool);
 
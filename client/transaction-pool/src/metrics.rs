@@ -49,6 +49,9 @@ pub struct Metrics {
 	pub validations_invalid: Counter<U64>,
 	pub block_transactions_pruned: Counter<U64>,
 	pub block_transactions_resubmitted: Counter<U64>,
+	pub persisted_transactions: Counter<U64>,
+	pub restored_transactions: Counter<U64>,
+	pub restored_transactions_dropped: Counter<U64>,
 }
 
 impl Metrics {
@@ -82,6 +85,27 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			persisted_transactions: register(
+				Counter::new(
+					"substrate_sub_txpool_persisted_transactions",
+					"Total number of transactions saved to the database",
+				)?,
+				registry,
+			)?,
+			restored_transactions: register(
+				Counter::new(
+					"substrate_sub_txpool_restored_transactions",
+					"Total number of saved transactions that were reimported on startup",
+				)?,
+				registry,
+			)?,
+			restored_transactions_dropped: register(
+				Counter::new(
+					"substrate_sub_txpool_restored_transactions_dropped",
+					"Total number of saved transactions that were dropped as invalid on startup",
+				)?,
+				registry,
+			)?,
 		})
 	}
 }
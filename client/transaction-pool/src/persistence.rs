@@ -0,0 +1,288 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Persistence of the transaction pool across node restarts.
+//!
+//! The extrinsics of the pool are saved together with their source to the auxiliary column
+//! of the database. On startup they are revalidated and reimported, everything that became
+//! invalid in the meantime is dropped.
+
+use std::{sync::Arc, time::Duration};
+
+use codec::{Decode, Encode};
+use sc_client_api::backend::AuxStore;
+use sp_runtime::{generic::BlockId, transaction_validity::TransactionSource};
+
+use crate::{
+	graph::{self, ChainApi, ExtrinsicFor},
+	metrics::MetricsLink as PrometheusMetrics,
+	LOG_TARGET,
+};
+
+/// Key under which the pool is stored in the aux column.
+const SNAPSHOT_KEY: &[u8] = b"txpool_snapshot";
+
+/// Version of the stored snapshot format.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// Outcome of [`restore`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Restored {
+	/// Number of transactions reimported to the pool.
+	pub imported: usize,
+	/// Number of transactions dropped, because they failed revalidation.
+	pub dropped: usize,
+}
+
+/// Saves all transactions of the pool to the aux store.
+///
+/// Returns the number of saved transactions.
+pub fn save<PoolApi: ChainApi>(
+	pool: &graph::Pool<PoolApi>,
+	aux: &impl AuxStore,
+) -> sp_blockchain::Result<usize> {
+	let extrinsics = pool.validated_pool().extrinsics();
+	let snapshot = (SNAPSHOT_VERSION, &extrinsics).encode();
+	aux.insert_aux(&[(SNAPSHOT_KEY, &snapshot[..])], &[])?;
+	Ok(extrinsics.len())
+}
+
+/// Reimports transactions saved by [`save`] to the pool.
+///
+/// Transactions are revalidated at the given block and the ones that turn out to be invalid
+/// are dropped. The snapshot is removed from the aux store, so it is only reimported once.
+pub async fn restore<PoolApi: ChainApi>(
+	pool: &graph::Pool<PoolApi>,
+	aux: &impl AuxStore,
+	at: &BlockId<PoolApi::Block>,
+) -> sp_blockchain::Result<Restored> {
+	let snapshot = match aux.get_aux(SNAPSHOT_KEY)? {
+		Some(snapshot) => snapshot,
+		None => return Ok(Default::default()),
+	};
+	aux.insert_aux(&[], &[SNAPSHOT_KEY])?;
+
+	let extrinsics = match <(u32, Vec<(TransactionSource, ExtrinsicFor<PoolApi>)>)>::decode(
+		&mut &snapshot[..],
+	) {
+		Ok((SNAPSHOT_VERSION, extrinsics)) => extrinsics,
+		Ok((version, _)) => {
+			log::warn!(
+				target: LOG_TARGET,
+				"Ignoring saved transactions of unsupported snapshot version {}",
+				version,
+			);
+			return Ok(Default::default())
+		},
+		Err(e) => {
+			log::warn!(target: LOG_TARGET, "Ignoring invalid saved transactions: {}", e);
+			return Ok(Default::default())
+		},
+	};
+
+	let mut restored = Restored::default();
+	let mut extrinsics = extrinsics.into_iter().peekable();
+	// Submit consecutive transactions of the same source together, to keep their order.
+	while let Some((source, xt)) = extrinsics.next() {
+		let mut batch = vec![xt];
+		while let Some((_, xt)) = extrinsics.next_if(|(next, _)| *next == source) {
+			batch.push(xt);
+		}
+
+		let count = batch.len();
+		match pool.submit_at(at, source, batch).await {
+			Ok(results) => {
+				let imported = results.iter().filter(|result| result.is_ok()).count();
+				restored.imported += imported;
+				restored.dropped += count - imported;
+			},
+			Err(e) => {
+				log::warn!(target: LOG_TARGET, "Failed to reimport saved transactions: {}", e);
+				restored.dropped += count;
+			},
+		}
+	}
+
+	Ok(restored)
+}
+
+/// Saves the pool and reports the outcome.
+fn save_and_report<PoolApi: ChainApi>(
+	pool: &graph::Pool<PoolApi>,
+	aux: &impl AuxStore,
+	metrics: &PrometheusMetrics,
+) {
+	match save(pool, aux) {
+		Ok(saved) => {
+			log::debug!(target: LOG_TARGET, "Saved {} transactions", saved);
+			metrics.report(|metrics| metrics.persisted_transactions.inc_by(saved as u64));
+		},
+		Err(e) => log::warn!(target: LOG_TARGET, "Failed to save transactions: {}", e),
+	}
+}
+
+/// Saves the pool once dropped, i.e. when the node shuts down.
+struct SaveOnDrop<PoolApi: ChainApi, Client: AuxStore> {
+	pool: Arc<graph::Pool<PoolApi>>,
+	client: Arc<Client>,
+	metrics: PrometheusMetrics,
+}
+
+impl<PoolApi: ChainApi, Client: AuxStore> Drop for SaveOnDrop<PoolApi, Client> {
+	fn drop(&mut self) {
+		save_and_report(&self.pool, &*self.client, &self.metrics);
+	}
+}
+
+/// Restores the pool and keeps it persisted until the task is dropped.
+///
+/// Besides saving the pool when dropped, it is saved every `interval`, if given.
+pub(crate) async fn persistence_task<PoolApi, Client>(
+	pool: Arc<graph::Pool<PoolApi>>,
+	client: Arc<Client>,
+	at: BlockId<PoolApi::Block>,
+	interval: Option<Duration>,
+	metrics: PrometheusMetrics,
+) where
+	PoolApi: ChainApi + 'static,
+	Client: AuxStore + Send + Sync + 'static,
+{
+	match restore(&pool, &*client, &at).await {
+		Ok(restored) => {
+			log::info!(
+				target: LOG_TARGET,
+				"Reimported {} saved transactions, dropped {} invalid ones",
+				restored.imported,
+				restored.dropped,
+			);
+			metrics.report(|metrics| {
+				metrics.restored_transactions.inc_by(restored.imported as u64);
+				metrics.restored_transactions_dropped.inc_by(restored.dropped as u64);
+			});
+		},
+		Err(e) => log::warn!(target: LOG_TARGET, "Failed to reimport saved transactions: {}", e),
+	}
+
+	let guard = SaveOnDrop { pool, client, metrics };
+	match interval {
+		Some(interval) => loop {
+			futures_timer::Delay::new(interval).await;
+			save_and_report(&guard.pool, &*guard.client, &guard.metrics);
+		},
+		None => futures::future::pending().await,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{pool, uxt, TestApi};
+	use futures::executor::block_on;
+	use parking_lot::Mutex;
+	use std::collections::HashMap;
+	use substrate_test_runtime::{AccountId, Transfer, H256};
+
+	#[derive(Default)]
+	struct TestAux(Mutex<HashMap<Vec<u8>, Vec<u8>>>);
+
+	impl AuxStore for TestAux {
+		fn insert_aux<
+			'a,
+			'b: 'a,
+			'c: 'a,
+			I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+			D: IntoIterator<Item = &'a &'b [u8]>,
+		>(
+			&self,
+			insert: I,
+			delete: D,
+		) -> sp_blockchain::Result<()> {
+			let mut storage = self.0.lock();
+			for (key, value) in insert {
+				storage.insert(key.to_vec(), value.to_vec());
+			}
+			for key in delete {
+				storage.remove(*key);
+			}
+			Ok(())
+		}
+
+		fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+			Ok(self.0.lock().get(key).cloned())
+		}
+	}
+
+	fn transfer(nonce: u64) -> ExtrinsicFor<TestApi> {
+		uxt(Transfer {
+			from: AccountId::from_h256(H256::from_low_u64_be(1)),
+			to: AccountId::from_h256(H256::from_low_u64_be(2)),
+			amount: 5,
+			nonce,
+		})
+	}
+
+	#[test]
+	fn should_restore_saved_transactions() {
+		// given
+		let aux = TestAux::default();
+		let old_pool = pool();
+		for nonce in [0, 1, 3] {
+			block_on(old_pool.submit_one(
+				&BlockId::Number(0),
+				TransactionSource::External,
+				transfer(nonce),
+			))
+			.unwrap();
+		}
+		assert_eq!(old_pool.validated_pool().status().ready, 2);
+		assert_eq!(old_pool.validated_pool().status().future, 1);
+		assert_eq!(save(&old_pool, &aux).unwrap(), 3);
+
+		// when
+		let new_pool = pool();
+		let invalid = new_pool.validated_pool().api().hash_and_length(&transfer(1)).0;
+		new_pool.validated_pool().api().invalidate.lock().insert(invalid);
+		let restored = block_on(restore(&new_pool, &aux, &BlockId::Number(0))).unwrap();
+
+		// then
+		assert_eq!(restored, Restored { imported: 2, dropped: 1 });
+		assert_eq!(new_pool.validated_pool().status().ready, 1);
+		assert_eq!(new_pool.validated_pool().status().future, 1);
+		assert_eq!(aux.get_aux(SNAPSHOT_KEY).unwrap(), None);
+		assert_eq!(
+			block_on(restore(&new_pool, &aux, &BlockId::Number(0))).unwrap(),
+			Restored::default()
+		);
+	}
+
+	#[test]
+	fn should_ignore_unsupported_snapshot() {
+		// given
+		let aux = TestAux::default();
+		let snapshot = (SNAPSHOT_VERSION + 1, vec![(TransactionSource::External, transfer(0))]);
+		aux.insert_aux(&[(SNAPSHOT_KEY, &snapshot.encode()[..])], &[]).unwrap();
+
+		// when
+		let pool = pool();
+		let restored = block_on(restore(&pool, &aux, &BlockId::Number(0))).unwrap();
+
+		// then
+		assert_eq!(restored, Restored::default());
+		assert_eq!(pool.validated_pool().status().ready, 0);
+	}
+}
@@ -113,12 +113,22 @@
 //!
 //! pub type Executive = executive::Executive<Runtime, Block, Context, Runtime, AllPalletsWithSystem, CustomOnRuntimeUpgrade>;
 //! ```
+//!
+//! ### Multi-block migrations
+//!
+//! Migrations that do not fit into a single block can implement
+//! [`SteppedMigration`](frame_support::migrations::SteppedMigration) and be passed to the
+//! `Executive` through a
+//! [`SteppedMigrationsDriver`](frame_support::migrations::SteppedMigrationsDriver). They are
+//! started after the runtime upgrade and progress in `initialize_block` of the following blocks.
+//! Only inherents are applied until all of them are done.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Codec, Encode};
 use frame_support::{
 	dispatch::{DispatchClass, DispatchInfo, GetDispatchInfo, PostDispatchInfo},
+	migrations::MultiStepMigrator,
 	pallet_prelude::InvalidTransaction,
 	traits::{
 		EnsureInherentsAreFirst, ExecuteBlock, OffchainWorker, OnFinalize, OnIdle, OnInitialize,
@@ -132,7 +142,7 @@ use sp_runtime::{
 		self, Applyable, CheckEqual, Checkable, Dispatchable, Header, NumberFor, One,
 		ValidateUnsigned, Zero,
 	},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	transaction_validity::{TransactionSource, TransactionValidity, TransactionValidityError},
 	ApplyExtrinsicResult,
 };
 use sp_std::{marker::PhantomData, prelude::*};
@@ -155,6 +165,9 @@ pub type OriginOf<E, C> = <CallOf<E, C> as Dispatchable>::RuntimeOrigin;
 ///   used to call hooks e.g. `on_initialize`.
 /// - `OnRuntimeUpgrade`: Custom logic that should be called after a runtime upgrade. Modules are
 ///   already called by `AllPalletsWithSystem`. It will be called before all modules will be called.
+/// - `MultiStepMigrator`: Drives the multi-block migrations started after a runtime upgrade, e.g.
+///   [`SteppedMigrationsDriver`](frame_support::migrations::SteppedMigrationsDriver). Only
+///   mandatory extrinsics are applied while they are ongoing.
 pub struct Executive<
 	System,
 	Block,
@@ -162,6 +175,7 @@ pub struct Executive<
 	UnsignedValidator,
 	AllPalletsWithSystem,
 	OnRuntimeUpgrade = (),
+	MultiStepMigrator = (),
 >(
 	PhantomData<(
 		System,
@@ -170,6 +184,7 @@ pub struct Executive<
 		UnsignedValidator,
 		AllPalletsWithSystem,
 		OnRuntimeUpgrade,
+		MultiStepMigrator,
 	)>,
 );
 
@@ -184,8 +199,17 @@ impl<
 			+ OnFinalize<System::BlockNumber>
 			+ OffchainWorker<System::BlockNumber>,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
+		CMultiStepMigrator: MultiStepMigrator,
 	> ExecuteBlock<Block>
-	for Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+	for Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		CMultiStepMigrator,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -202,6 +226,7 @@ where
 			UnsignedValidator,
 			AllPalletsWithSystem,
 			COnRuntimeUpgrade,
+			CMultiStepMigrator,
 		>::execute_block(block);
 	}
 }
@@ -217,9 +242,20 @@ impl<
 			+ OnIdle<System::BlockNumber>
 			+ OnFinalize<System::BlockNumber>
 			+ OffchainWorker<System::BlockNumber>
-			+ frame_support::traits::TryState<System::BlockNumber>,
+			+ frame_support::traits::TryState<System::BlockNumber>
+			+ frame_support::traits::PalletsInfoAccess,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
-	> Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+		CMultiStepMigrator: MultiStepMigrator,
+	>
+	Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		CMultiStepMigrator,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -241,7 +277,7 @@ where
 		state_root_check: bool,
 		signature_check: bool,
 		select: frame_try_runtime::TryStateSelect,
-	) -> Result<Weight, &'static str> {
+	) -> Result<Weight, sp_runtime::TryRuntimeError> {
 		frame_support::log::info!(
 			target: LOG_TARGET,
 			"try-runtime: executing block #{:?} / state root check: {:?} / signature check: {:?} / try-state-select: {:?}",
@@ -267,9 +303,10 @@ where
 			} else {
 				uxt.unchecked_into_checked_i_know_what_i_am_doing(&Default::default())
 			}?;
+			let dispatch_info = xt.get_dispatch_info();
+			Self::ensure_not_suspended(&dispatch_info)?;
 			<frame_system::Pallet<System>>::note_extrinsic(encoded);
 
-			let dispatch_info = xt.get_dispatch_info();
 			let r = Applyable::apply::<UnsignedValidator>(xt, &dispatch_info, encoded_len)?;
 
 			<frame_system::Pallet<System>>::note_applied_extrinsic(&r, dispatch_info);
@@ -299,7 +336,12 @@ where
 			select,
 		)
 		.map_err(|e| {
-			frame_support::log::error!(target: LOG_TARGET, "failure: {:?}", e);
+			frame_support::log::error!(
+				target: LOG_TARGET,
+				"try-state failed at block #{:?}: {}",
+				header.number(),
+				e,
+			);
 			e
 		})?;
 		drop(_guard);
@@ -311,34 +353,70 @@ where
 			let items_zip = header.digest().logs().iter().zip(new_header.digest().logs().iter());
 			for (header_item, computed_item) in items_zip {
 				header_item.check_equal(computed_item);
-				assert!(header_item == computed_item, "Digest item must match that calculated.");
+				frame_support::ensure!(
+					header_item == computed_item,
+					sp_runtime::try_runtime_error!(
+						"Digest item {:?} must match the calculated {:?}",
+						header_item,
+						computed_item,
+					)
+				);
 			}
 
 			if state_root_check {
 				let storage_root = new_header.state_root();
 				header.state_root().check_equal(storage_root);
-				assert!(
+				frame_support::ensure!(
 					header.state_root() == storage_root,
-					"Storage root must match that calculated."
+					sp_runtime::try_runtime_error!(
+						"Storage root {:?} must match the calculated {:?}",
+						header.state_root(),
+						storage_root,
+					)
 				);
 			}
 
-			assert!(
+			frame_support::ensure!(
 				header.extrinsics_root() == new_header.extrinsics_root(),
-				"Transaction trie root must be valid.",
+				sp_runtime::try_runtime_error!(
+					"Transaction trie root {:?} must match the calculated {:?}",
+					header.extrinsics_root(),
+					new_header.extrinsics_root(),
+				)
 			);
 		}
 
 		Ok(frame_system::Pallet::<System>::block_weight().total())
 	}
 
+	/// Run the `try_state` hooks selected by `select` against the current state.
+	///
+	/// The hooks are executed at the current block number and must not alter any storage.
+	pub fn try_state(
+		select: frame_try_runtime::TryStateSelect,
+	) -> Result<(), sp_runtime::TryRuntimeError> {
+		let _guard = frame_support::StorageNoopGuard::default();
+		<AllPalletsWithSystem as frame_support::traits::TryState<System::BlockNumber>>::try_state(
+			frame_system::Pallet::<System>::block_number(),
+			select,
+		)
+	}
+
+	/// The names of all pallets of this runtime, which can be passed to [`Self::try_state`].
+	pub fn try_state_pallets() -> Vec<Vec<u8>> {
+		<AllPalletsWithSystem as frame_support::traits::PalletsInfoAccess>::infos()
+			.into_iter()
+			.map(|info| info.name.as_bytes().to_vec())
+			.collect()
+	}
+
 	/// Execute all `OnRuntimeUpgrade` of this runtime, including the pre and post migration checks.
 	///
 	/// Runs the try-state code both before and after the migration function if `checks` is set to
 	/// `true`. Also, if set to `true`, it runs the `pre_upgrade` and `post_upgrade` hooks.
 	pub fn try_runtime_upgrade(
 		checks: frame_try_runtime::UpgradeCheckSelect,
-	) -> Result<Weight, &'static str> {
+	) -> Result<Weight, sp_runtime::TryRuntimeError> {
 		if checks.try_state() {
 			let _guard = frame_support::StorageNoopGuard::default();
 			<AllPalletsWithSystem as frame_support::traits::TryState<System::BlockNumber>>::try_state(
@@ -347,10 +425,13 @@ where
 			)?;
 		}
 
-		let weight =
+		let mut weight =
 			<(COnRuntimeUpgrade, AllPalletsWithSystem) as OnRuntimeUpgrade>::try_on_runtime_upgrade(
 				checks.pre_and_post(),
 			)?;
+		// Run the whole multi-block migration sequence, so that their checks see the final state.
+		weight = weight
+			.saturating_add(CMultiStepMigrator::try_run_to_completion(checks.pre_and_post())?);
 
 		if checks.try_state() {
 			let _guard = frame_support::StorageNoopGuard::default();
@@ -375,7 +456,17 @@ impl<
 			+ OnFinalize<System::BlockNumber>
 			+ OffchainWorker<System::BlockNumber>,
 		COnRuntimeUpgrade: OnRuntimeUpgrade,
-	> Executive<System, Block, Context, UnsignedValidator, AllPalletsWithSystem, COnRuntimeUpgrade>
+		CMultiStepMigrator: MultiStepMigrator,
+	>
+	Executive<
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllPalletsWithSystem,
+		COnRuntimeUpgrade,
+		CMultiStepMigrator,
+	>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>: Applyable + GetDispatchInfo,
@@ -384,9 +475,11 @@ where
 	OriginOf<Block::Extrinsic, Context>: From<Option<System::AccountId>>,
 	UnsignedValidator: ValidateUnsigned<Call = CallOf<Block::Extrinsic, Context>>,
 {
-	/// Execute all `OnRuntimeUpgrade` of this runtime, and return the aggregate weight.
+	/// Execute all `OnRuntimeUpgrade` of this runtime and start the multi-block migrations, and
+	/// return the aggregate weight.
 	pub fn execute_on_runtime_upgrade() -> Weight {
 		<(COnRuntimeUpgrade, AllPalletsWithSystem) as OnRuntimeUpgrade>::on_runtime_upgrade()
+			.saturating_add(CMultiStepMigrator::on_runtime_upgrade())
 	}
 
 	/// Start the execution of a particular block.
@@ -433,6 +526,15 @@ where
 			DispatchClass::Mandatory,
 		);
 
+		// Multi-block migrations may use whatever is left of the block.
+		let max_weight = <System::BlockWeights as frame_support::traits::Get<_>>::get().max_block;
+		let remaining_weight =
+			max_weight.saturating_sub(<frame_system::Pallet<System>>::block_weight().total());
+		<frame_system::Pallet<System>>::register_extra_weight_unchecked(
+			CMultiStepMigrator::step(remaining_weight),
+			DispatchClass::Mandatory,
+		);
+
 		frame_system::Pallet::<System>::note_finished_initialize();
 	}
 
@@ -557,6 +659,8 @@ where
 				ext=?sp_core::hexdisplay::HexDisplay::from(&encoded)));
 		// Verify that the signature is good.
 		let xt = uxt.check(&Default::default())?;
+		let dispatch_info = xt.get_dispatch_info();
+		Self::ensure_not_suspended(&dispatch_info)?;
 
 		// We don't need to make sure to `note_extrinsic` only after we know it's going to be
 		// executed to prevent it from leaking in storage since at this point, it will either
@@ -566,7 +670,6 @@ where
 		// AUDIT: Under no circumstances may this function panic from here onwards.
 
 		// Decode parameters and dispatch
-		let dispatch_info = xt.get_dispatch_info();
 		let r = Applyable::apply::<UnsignedValidator>(xt, &dispatch_info, encoded_len)?;
 
 		// Mandatory(inherents) are not allowed to fail.
@@ -582,6 +685,24 @@ where
 		Ok(r.map(|_| ()).map_err(|e| e.error))
 	}
 
+	/// Only inherents may be applied while multi-block migrations are ongoing.
+	///
+	/// Other extrinsics are reported as exhausting the block resources, so that they stay in the
+	/// transaction pool until the migrations are done.
+	fn ensure_not_suspended(dispatch_info: &DispatchInfo) -> Result<(), TransactionValidityError> {
+		if dispatch_info.class == DispatchClass::Mandatory {
+			return Ok(())
+		}
+		<frame_system::Pallet<System>>::register_extra_weight_unchecked(
+			CMultiStepMigrator::ongoing_weight(),
+			dispatch_info.class,
+		);
+		if CMultiStepMigrator::ongoing() {
+			return Err(InvalidTransaction::ExhaustsResources.into())
+		}
+		Ok(())
+	}
+
 	fn final_checks(header: &System::Header) {
 		sp_tracing::enter_span!(sp_tracing::Level::TRACE, "final_checks");
 		// remove temporaries
@@ -860,6 +981,7 @@ mod tests {
 			read: 10,
 			write: 100,
 		};
+		pub MaxBlockWeight: Weight = BlockWeights::get().max_block;
 	}
 	impl frame_system::Config for Runtime {
 		type BaseCallFilter = frame_support::traits::Everything;
@@ -962,6 +1084,41 @@ mod tests {
 		CustomOnRuntimeUpgrade,
 	>;
 
+	/// Migrates one item per 100 `ref_time`, ten items in total.
+	struct TenItems;
+	impl frame_support::migrations::SteppedMigration for TenItems {
+		type Cursor = u32;
+
+		fn id() -> &'static str {
+			"ten-items"
+		}
+
+		fn step(
+			cursor: Option<u32>,
+			meter: &mut frame_support::weights::WeightMeter,
+		) -> Result<Option<u32>, frame_support::migrations::SteppedMigrationError> {
+			let mut next = cursor.unwrap_or_default();
+			while next < 10 {
+				if !meter.check_accrue(Weight::from_parts(100, 0)) {
+					return Ok(Some(next))
+				}
+				sp_io::storage::set(&(b":item:", next).encode(), &[]);
+				next += 1;
+			}
+			Ok(None)
+		}
+	}
+
+	type MbmExecutive = super::Executive<
+		Runtime,
+		Block<TestXt>,
+		ChainContext<Runtime>,
+		Runtime,
+		AllPalletsWithSystem,
+		(),
+		frame_support::migrations::SteppedMigrationsDriver<(TenItems,), (), MaxBlockWeight>,
+	>;
+
 	fn extra(nonce: u64, fee: Balance) -> SignedExtra {
 		(
 			frame_system::CheckEra::from(Era::Immortal),
@@ -1402,6 +1559,37 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn multi_block_migrations_suspend_extrinsics() {
+		let xt = TestXt::new(call_transfer(2, 69), sign_extra(1, 0, 0));
+		let inherent = TestXt::new(RuntimeCall::Custom(custom::Call::inherent_call {}), None);
+		let header = |n| {
+			Header::new(n, H256::default(), H256::default(), [69u8; 32].into(), Digest::default())
+		};
+
+		new_test_ext(1).execute_with(|| {
+			RuntimeVersionTestValues::mutate(|v| {
+				*v = sp_version::RuntimeVersion { spec_version: 1, ..Default::default() }
+			});
+
+			// The first block has no room for all items after the upgrade hooks.
+			MbmExecutive::initialize_block(&header(1));
+			assert!(sp_io::storage::exists(&(b":item:", 0u32).encode()));
+			assert!(!sp_io::storage::exists(&(b":item:", 9u32).encode()));
+
+			assert_eq!(
+				MbmExecutive::apply_extrinsic(xt.clone()),
+				Err(InvalidTransaction::ExhaustsResources.into())
+			);
+			assert_eq!(MbmExecutive::apply_extrinsic(inherent), Ok(Ok(())));
+			MbmExecutive::finalize_block();
+
+			MbmExecutive::initialize_block(&header(2));
+			assert!(sp_io::storage::exists(&(b":item:", 9u32).encode()));
+			assert!(MbmExecutive::apply_extrinsic(xt).unwrap().is_ok());
+		});
+	}
+
 	#[test]
 	fn event_from_runtime_upgrade_is_included() {
 		new_test_ext(1).execute_with(|| {
@@ -446,7 +446,6 @@ pub mod pallet {
 		pub keys: Vec<T::AuthorityId>,
 	}
 
-	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
 			GenesisConfig { keys: Default::default() }
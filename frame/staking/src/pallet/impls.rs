@@ -42,6 +42,9 @@ use sp_staking::{
 };
 use sp_std::prelude::*;
 
+#[cfg(any(test, feature = "try-runtime"))]
+use sp_runtime::{try_runtime_error, TryRuntimeError};
+
 use crate::{
 	log, slashing, weights::WeightInfo, ActiveEraInfo, BalanceOf, EraPayout, Exposure, ExposureOf,
 	Forcing, IndividualExposure, MaxWinnersOf, Nominations, PositiveImbalanceOf, RewardDestination,
@@ -1466,7 +1469,7 @@ impl<T: Config> SortedListProvider<T::AccountId> for UseValidatorsMap<T> {
 		0
 	}
 	#[cfg(feature = "try-runtime")]
-	fn try_state() -> Result<(), &'static str> {
+	fn try_state() -> Result<(), TryRuntimeError> {
 		Ok(())
 	}
 
@@ -1543,7 +1546,7 @@ impl<T: Config> SortedListProvider<T::AccountId> for UseNominatorsAndValidatorsM
 	}
 
 	#[cfg(feature = "try-runtime")]
-	fn try_state() -> Result<(), &'static str> {
+	fn try_state() -> Result<(), TryRuntimeError> {
 		Ok(())
 	}
 
@@ -1688,7 +1691,7 @@ impl<T: Config> StakingInterface for Pallet<T> {
 
 #[cfg(any(test, feature = "try-runtime"))]
 impl<T: Config> Pallet<T> {
-	pub(crate) fn do_try_state(_: BlockNumberFor<T>) -> Result<(), &'static str> {
+	pub(crate) fn do_try_state(_: BlockNumberFor<T>) -> Result<(), TryRuntimeError> {
 		ensure!(
 			T::VoterList::iter()
 				.all(|x| <Nominators<T>>::contains_key(&x) || <Validators<T>>::contains_key(&x)),
@@ -1701,15 +1704,24 @@ impl<T: Config> Pallet<T> {
 		Self::check_count()
 	}
 
-	fn check_count() -> Result<(), &'static str> {
+	fn check_count() -> Result<(), TryRuntimeError> {
 		ensure!(
 			<T as Config>::VoterList::count() ==
 				Nominators::<T>::count() + Validators::<T>::count(),
-			"wrong external count"
+			try_runtime_error!(
+				"wrong external count: {} voters, {} nominators, {} validators",
+				<T as Config>::VoterList::count(),
+				Nominators::<T>::count(),
+				Validators::<T>::count(),
+			)
 		);
 		ensure!(
 			<T as Config>::TargetList::count() == Validators::<T>::count(),
-			"wrong external count"
+			try_runtime_error!(
+				"wrong external count: {} targets, {} validators",
+				<T as Config>::TargetList::count(),
+				Validators::<T>::count(),
+			)
 		);
 		ensure!(
 			ValidatorCount::<T>::get() <=
@@ -1719,17 +1731,17 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
-	fn check_ledgers() -> Result<(), &'static str> {
+	fn check_ledgers() -> Result<(), TryRuntimeError> {
 		Bonded::<T>::iter()
 			.map(|(_, ctrl)| Self::ensure_ledger_consistent(ctrl))
 			.collect::<Result<_, _>>()
 	}
 
-	fn check_exposures() -> Result<(), &'static str> {
+	fn check_exposures() -> Result<(), TryRuntimeError> {
 		// a check per validator to ensure the exposure struct is always sane.
 		let era = Self::active_era().unwrap().index;
-		ErasStakers::<T>::iter_prefix_values(era)
-			.map(|expo| {
+		ErasStakers::<T>::iter_prefix(era)
+			.map(|(validator, expo)| {
 				ensure!(
 					expo.total ==
 						expo.own +
@@ -1737,14 +1749,18 @@ impl<T: Config> Pallet<T> {
 								.iter()
 								.map(|e| e.value)
 								.fold(Zero::zero(), |acc, x| acc + x),
-					"wrong total exposure.",
+					try_runtime_error!(
+						"wrong total exposure of validator {:?} in era {}",
+						validator,
+						era,
+					),
 				);
 				Ok(())
 			})
 			.collect::<Result<_, _>>()
 	}
 
-	fn check_nominators() -> Result<(), &'static str> {
+	fn check_nominators() -> Result<(), TryRuntimeError> {
 		// a check per nominator to ensure their entire stake is correctly distributed. Will only
 		// kick-in if the nomination was submitted before the current era.
 		let era = Self::active_era().unwrap().index;
@@ -1764,15 +1780,20 @@ impl<T: Config> Pallet<T> {
 				let mut sum = BalanceOf::<T>::zero();
 				T::SessionInterface::validators()
 					.iter()
-					.map(|v| Self::eras_stakers(era, v))
-					.map(|e| {
+					.map(|v| (v, Self::eras_stakers(era, v)))
+					.map(|(validator, e)| {
 						let individual =
 							e.others.iter().filter(|e| e.who == nominator).collect::<Vec<_>>();
 						let len = individual.len();
 						match len {
 							0 => { /* not supporting this validator at all. */ },
 							1 => sum += individual[0].value,
-							_ => return Err("nominator cannot back a validator more than once."),
+							_ =>
+								return Err(try_runtime_error!(
+									"nominator {:?} backs validator {:?} more than once",
+									nominator,
+									validator,
+								)),
 						};
 						Ok(())
 					})
@@ -1781,17 +1802,26 @@ impl<T: Config> Pallet<T> {
 			.collect::<Result<_, _>>()
 	}
 
-	fn ensure_is_stash(who: &T::AccountId) -> Result<(), &'static str> {
-		ensure!(Self::bonded(who).is_some(), "Not a stash.");
+	fn ensure_is_stash(who: &T::AccountId) -> Result<(), TryRuntimeError> {
+		ensure!(Self::bonded(who).is_some(), try_runtime_error!("{:?} is not a stash", who));
 		Ok(())
 	}
 
-	fn ensure_ledger_consistent(ctrl: T::AccountId) -> Result<(), &'static str> {
+	fn ensure_ledger_consistent(ctrl: T::AccountId) -> Result<(), TryRuntimeError> {
 		// ensures ledger.total == ledger.active + sum(ledger.unlocking).
-		let ledger = Self::ledger(ctrl.clone()).ok_or("Not a controller.")?;
+		let ledger = Self::ledger(ctrl.clone())
+			.ok_or_else(|| try_runtime_error!("{:?} is not a controller", ctrl))?;
 		let real_total: BalanceOf<T> =
 			ledger.unlocking.iter().fold(ledger.active, |a, c| a + c.value);
-		ensure!(real_total == ledger.total, "ledger.total corrupt");
+		ensure!(
+			real_total == ledger.total,
+			try_runtime_error!(
+				"ledger.total of {:?} corrupt: {:?} recorded, {:?} computed",
+				ctrl,
+				ledger.total,
+				real_total,
+			)
+		);
 
 		if !(ledger.active >= T::Currency::minimum_balance() || ledger.active.is_zero()) {
 			log!(warn, "ledger.active less than ED: {:?}, {:?}", ctrl, ledger)
@@ -30,6 +30,7 @@ pub fn expand_outer_config(
 	let mut types = TokenStream::new();
 	let mut fields = TokenStream::new();
 	let mut build_storage_calls = TokenStream::new();
+	let mut build_calls = TokenStream::new();
 	let mut query_genesis_config_part_macros = Vec::new();
 
 	for decl in pallet_decls {
@@ -54,6 +55,7 @@ pub fn expand_outer_config(
 			fields.extend(quote!(#attr pub #field_name: #config,));
 			build_storage_calls
 				.extend(expand_config_build_storage_call(scrate, attr, runtime, decl, field_name));
+			build_calls.extend(expand_config_build_call(scrate, attr, runtime, decl, field_name));
 			query_genesis_config_part_macros.push(quote! {
 				#path::__substrate_genesis_config_check::is_genesis_config_defined!(#pallet_name);
 				#[cfg(feature = "std")]
@@ -67,9 +69,7 @@ pub fn expand_outer_config(
 
 		#types
 
-		#[cfg(any(feature = "std", test))]
 		use #scrate::serde as __genesis_config_serde_import__;
-		#[cfg(any(feature = "std", test))]
 		#[derive(#scrate::serde::Serialize, #scrate::serde::Deserialize, Default)]
 		#[serde(rename_all = "camelCase")]
 		#[serde(deny_unknown_fields)]
@@ -93,6 +93,14 @@ pub fn expand_outer_config(
 				Ok(())
 			}
 		}
+
+		impl #scrate::traits::BuildGenesisConfig for GenesisConfig {
+			fn build(&self) {
+				#build_calls
+
+				<AllPalletsWithSystem as #scrate::traits::OnGenesis>::on_genesis();
+			}
+		}
 	}
 }
 
@@ -108,17 +116,14 @@ fn expand_config_types(
 	match (decl.instance.as_ref(), part_is_generic) {
 		(Some(inst), true) => quote! {
 			#attr
-			#[cfg(any(feature = "std", test))]
 			pub type #config = #path::GenesisConfig<#runtime, #path::#inst>;
 		},
 		(None, true) => quote! {
 			#attr
-			#[cfg(any(feature = "std", test))]
 			pub type #config = #path::GenesisConfig<#runtime>;
 		},
 		(_, false) => quote! {
 			#attr
-			#[cfg(any(feature = "std", test))]
 			pub type #config = #path::GenesisConfig;
 		},
 	}
@@ -144,3 +149,23 @@ fn expand_config_build_storage_call(
 			<#runtime, #instance>::build_module_genesis_storage(&self.#field_name, storage)?;
 	}
 }
+
+fn expand_config_build_call(
+	scrate: &TokenStream,
+	attr: &TokenStream,
+	runtime: &Ident,
+	decl: &Pallet,
+	field_name: &Ident,
+) -> TokenStream {
+	let path = &decl.path;
+	let instance = if let Some(inst) = decl.instance.as_ref() {
+		quote!(#path::#inst)
+	} else {
+		quote!(#path::__InherentHiddenInstance)
+	};
+
+	quote! {
+		#attr
+		<_ as #scrate::traits::GenesisBuild<#runtime, #instance>>::build(&self.#field_name);
+	}
+}
@@ -131,6 +131,13 @@ fn impl_build_storage(
 
 	let builder_blocks = &builders.blocks;
 
+	// The serde derive of the genesis config is bounded on its generic fields.
+	let mut genesis_build_where_clause =
+		where_clause.clone().unwrap_or_else(|| syn::parse_quote!(where));
+	genesis_build_where_clause.predicates.push(syn::parse_quote!(
+		Self: #scrate::sp_runtime::traits::MaybeSerializeDeserialize
+	));
+
 	let build_storage_impl_trait = quote!(
 		#scrate::sp_runtime::BuildModuleGenesisStorage<#runtime_generic, #inherent_instance>
 	);
@@ -171,6 +178,15 @@ fn impl_build_storage(
 				self.assimilate_storage::<#fn_traitinstance> (storage)
 			}
 		}
+
+		#[cfg(feature = "std")]
+		impl #build_storage_impl #scrate::traits::GenesisBuild<#runtime_generic, #inherent_instance>
+			for GenesisConfig #genesis_struct #genesis_build_where_clause
+		{
+			fn build(&self) {
+				#( #builder_blocks )*
+			}
+		}
 	}
 }
 
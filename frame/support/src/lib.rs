@@ -48,7 +48,6 @@ pub use once_cell;
 pub use paste;
 #[doc(hidden)]
 pub use scale_info;
-#[cfg(feature = "std")]
 pub use serde;
 pub use sp_core::{OpaqueMetadata, Void};
 #[doc(hidden)]
@@ -79,6 +78,7 @@ pub mod inherent;
 pub mod error;
 pub mod crypto;
 pub mod dispatch_context;
+pub mod genesis_builder_helper;
 pub mod instances;
 pub mod metadata_ir;
 pub mod migrations;
@@ -820,7 +820,6 @@ macro_rules! assert_error_encoded_size {
 	} => {};
 }
 
-#[cfg(feature = "std")]
 #[doc(hidden)]
 pub use serde::{Deserialize, Serialize};
 
@@ -1453,8 +1452,6 @@ pub mod tests {
 
 /// Prelude to be used alongside pallet macro, for ease of use.
 pub mod pallet_prelude {
-	#[cfg(feature = "std")]
-	pub use crate::traits::GenesisBuild;
 	pub use crate::{
 		dispatch::{
 			DispatchClass, DispatchError, DispatchResult, DispatchResultWithPostInfo, Parameter,
@@ -1471,8 +1468,8 @@ pub mod pallet_prelude {
 			},
 		},
 		traits::{
-			ConstU32, EnsureOrigin, Get, GetDefault, GetStorageVersion, Hooks, IsType,
-			PalletInfoAccess, StorageInfoTrait, StorageVersion, TypedGet,
+			ConstU32, EnsureOrigin, GenesisBuild, Get, GetDefault, GetStorageVersion, Hooks,
+			IsType, PalletInfoAccess, StorageInfoTrait, StorageVersion, TypedGet,
 		},
 		Blake2_128, Blake2_128Concat, Blake2_256, CloneNoBound, DebugNoBound, EqNoBound, Identity,
 		PartialEqNoBound, RuntimeDebug, RuntimeDebugNoBound, Twox128, Twox256, Twox64Concat,
@@ -1489,6 +1486,8 @@ pub mod pallet_prelude {
 		},
 		MAX_MODULE_ERROR_ENCODED_SIZE,
 	};
+	#[cfg(feature = "try-runtime")]
+	pub use sp_runtime::{try_runtime_error, TryRuntimeError};
 	pub use sp_std::marker::PhantomData;
 	pub use sp_weights::Weight;
 }
@@ -16,10 +16,16 @@
 // limitations under the License.
 
 use crate::{
-	traits::{GetStorageVersion, PalletInfoAccess},
-	weights::{RuntimeDbWeight, Weight},
+	storage::{storage_prefix, unhashed},
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, PalletInfoAccess, StorageVersion},
+	weights::{RuntimeDbWeight, Weight, WeightMeter},
 };
+use codec::{Decode, Encode, FullCodec, MaxEncodedLen};
 use impl_trait_for_tuples::impl_for_tuples;
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(feature = "try-runtime")]
+use sp_runtime::TryRuntimeError;
 
 /// Trait used by [`migrate_from_pallet_version_to_storage_version`] to do the actual migration.
 pub trait PalletVersionToStorageVersionHelper {
@@ -67,3 +73,750 @@ pub fn migrate_from_pallet_version_to_storage_version<
 ) -> Weight {
 	Pallets::migrate(db_weight)
 }
+
+/// Wraps an [`OnRuntimeUpgrade`] migration so that it only runs when the on-chain storage version
+/// of `Pallet` equals `FROM`, and bumps it to `TO` afterwards.
+///
+/// When the on-chain version is anything else the inner migration is skipped and a warning is
+/// logged, as this usually means the migration was already applied and can be removed from the
+/// runtime. The try-runtime hooks of `Inner` are only forwarded if the migration is executed, and
+/// `post_upgrade` additionally checks that the on-chain version was set to `TO`.
+///
+/// ```ignore
+/// pub type MigrateV4ToV5<T> = VersionedMigration<
+///     4,
+///     5,
+///     InnerMigrateV4ToV5<T>,
+///     crate::pallet::Pallet<T>,
+///     <T as frame_system::Config>::DbWeight,
+/// >;
+/// ```
+pub struct VersionedMigration<const FROM: u16, const TO: u16, Inner, Pallet, DbWeight>(
+	PhantomData<(Inner, Pallet, DbWeight)>,
+);
+
+/// State handed from `pre_upgrade` to `post_upgrade` by [`VersionedMigration`].
+#[derive(Encode, Decode)]
+pub enum VersionedPostUpgradeData {
+	/// The migration was executed; contains the state returned by the inner `pre_upgrade`.
+	MigrationExecuted(Vec<u8>),
+	/// The on-chain version did not match, nothing was done.
+	Noop,
+}
+
+impl<
+		const FROM: u16,
+		const TO: u16,
+		Inner: OnRuntimeUpgrade,
+		Pallet: GetStorageVersion + PalletInfoAccess,
+		DbWeight: Get<RuntimeDbWeight>,
+	> OnRuntimeUpgrade for VersionedMigration<FROM, TO, Inner, Pallet, DbWeight>
+{
+	fn on_runtime_upgrade() -> Weight {
+		let on_chain_version = Pallet::on_chain_storage_version();
+		if on_chain_version == FROM {
+			log::info!(
+				target: crate::LOG_TARGET,
+				"Pallet {:?} migrating storage version from {:?} to {:?}.",
+				Pallet::name(),
+				FROM,
+				TO,
+			);
+
+			let weight = Inner::on_runtime_upgrade();
+			StorageVersion::new(TO).put::<Pallet>();
+
+			weight.saturating_add(DbWeight::get().reads_writes(1, 1))
+		} else {
+			log::warn!(
+				target: crate::LOG_TARGET,
+				"Pallet {:?} migration {}->{} can be removed; on-chain is already at {:?}.",
+				Pallet::name(),
+				FROM,
+				TO,
+				on_chain_version,
+			);
+			DbWeight::get().reads(1)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+		crate::ensure!(TO > FROM, "VersionedMigration must bump the storage version");
+
+		let data = if Pallet::on_chain_storage_version() == FROM {
+			VersionedPostUpgradeData::MigrationExecuted(Inner::pre_upgrade()?)
+		} else {
+			VersionedPostUpgradeData::Noop
+		};
+		Ok(data.encode())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), TryRuntimeError> {
+		let data = VersionedPostUpgradeData::decode(&mut &state[..])
+			.map_err(|_| "VersionedMigration failed to decode the pre-upgrade state")?;
+
+		match data {
+			VersionedPostUpgradeData::MigrationExecuted(inner) => {
+				let on_chain_version = Pallet::on_chain_storage_version();
+				crate::ensure!(
+					on_chain_version == TO,
+					sp_runtime::try_runtime_error!(
+						"VersionedMigration of {} left storage version {:?}, expected {}",
+						Pallet::name(),
+						on_chain_version,
+						TO,
+					)
+				);
+				Inner::post_upgrade(inner)
+			},
+			VersionedPostUpgradeData::Noop => Ok(()),
+		}
+	}
+}
+
+/// Error of a single [`SteppedMigration::step`].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, MaxEncodedLen, scale_info::TypeInfo)]
+pub enum SteppedMigrationError {
+	/// The remaining weight is not enough for the next step.
+	///
+	/// The step is retried in the next block, unless `required` exceeds what a block can offer.
+	InsufficientWeight {
+		/// Weight needed for the next step.
+		required: Weight,
+	},
+	/// The persisted cursor could not be decoded.
+	InvalidCursor,
+	/// The migration failed and cannot continue.
+	Failed,
+}
+
+/// A migration that is executed in multiple steps, possibly spanning many blocks.
+///
+/// Every call to [`Self::step`] migrates as much as the given [`WeightMeter`] allows and returns a
+/// cursor to continue from. The cursor is persisted between blocks by the
+/// [`SteppedMigrationsDriver`], so a migration never has to keep its own progress in storage.
+pub trait SteppedMigration {
+	/// The cursor that is persisted between two steps.
+	type Cursor: FullCodec + MaxEncodedLen;
+
+	/// Name of the migration, used for logging.
+	fn id() -> &'static str;
+
+	/// Execute the next step of the migration.
+	///
+	/// `cursor` is `None` for the first step. Returns the cursor for the next step, or `None` once
+	/// the migration is complete. All consumed weight must be accounted in `meter`.
+	fn step(
+		cursor: Option<Self::Cursor>,
+		meter: &mut WeightMeter,
+	) -> Result<Option<Self::Cursor>, SteppedMigrationError>;
+
+	/// Execute some pre-checks before the first step of the migration.
+	///
+	/// Same semantics as [`OnRuntimeUpgrade::pre_upgrade`](crate::traits::OnRuntimeUpgrade).
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, TryRuntimeError> {
+		Ok(Vec::new())
+	}
+
+	/// Execute some post-checks after the last step of the migration.
+	///
+	/// Same semantics as [`OnRuntimeUpgrade::post_upgrade`](crate::traits::OnRuntimeUpgrade).
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_state: Vec<u8>) -> Result<(), TryRuntimeError> {
+		Ok(())
+	}
+}
+
+/// An ordered list of [`SteppedMigration`]s, implemented for tuples of them.
+///
+/// Cursors are passed in their encoded form, so that the list can be driven without knowing the
+/// concrete cursor types.
+pub trait SteppedMigrations {
+	/// Number of migrations in the list.
+	fn len() -> u32;
+
+	/// Name of the `n`th migration.
+	fn nth_id(n: u32) -> Option<&'static str>;
+
+	/// Execute the next step of the `n`th migration, see [`SteppedMigration::step`].
+	///
+	/// Returns `None` if there is no `n`th migration.
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>>;
+
+	/// Run the `pre_upgrade` checks of the `n`th migration.
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(n: u32) -> Option<Result<Vec<u8>, TryRuntimeError>>;
+
+	/// Run the `post_upgrade` checks of the `n`th migration.
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(n: u32, state: Vec<u8>) -> Option<Result<(), TryRuntimeError>>;
+}
+
+fn step_encoded<M: SteppedMigration>(
+	cursor: Option<Vec<u8>>,
+	meter: &mut WeightMeter,
+) -> Result<Option<Vec<u8>>, SteppedMigrationError> {
+	let cursor = cursor
+		.map(|c| M::Cursor::decode(&mut &c[..]))
+		.transpose()
+		.map_err(|_| SteppedMigrationError::InvalidCursor)?;
+	M::step(cursor, meter).map(|next| next.map(|c| c.encode()))
+}
+
+#[cfg_attr(all(not(feature = "tuples-96"), not(feature = "tuples-128")), impl_for_tuples(64))]
+#[cfg_attr(all(feature = "tuples-96", not(feature = "tuples-128")), impl_for_tuples(96))]
+#[cfg_attr(feature = "tuples-128", impl_for_tuples(128))]
+#[tuple_types_custom_trait_bound(SteppedMigration)]
+impl SteppedMigrations for Tuple {
+	fn len() -> u32 {
+		let mut len = 0;
+		for_tuples!( #( let _ = Tuple::id(); len += 1; )* );
+		len
+	}
+
+	fn nth_id(n: u32) -> Option<&'static str> {
+		let mut i = 0;
+		for_tuples!( #(
+			if i == n {
+				return Some(Tuple::id())
+			}
+			i += 1;
+		)* );
+		let _ = i;
+		None
+	}
+
+	fn nth_step(
+		n: u32,
+		cursor: Option<Vec<u8>>,
+		meter: &mut WeightMeter,
+	) -> Option<Result<Option<Vec<u8>>, SteppedMigrationError>> {
+		let mut i = 0;
+		for_tuples!( #(
+			if i == n {
+				return Some(step_encoded::<Tuple>(cursor, meter))
+			}
+			i += 1;
+		)* );
+		let _ = (i, cursor, meter);
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_pre_upgrade(n: u32) -> Option<Result<Vec<u8>, TryRuntimeError>> {
+		let mut i = 0;
+		for_tuples!( #(
+			if i == n {
+				return Some(Tuple::pre_upgrade())
+			}
+			i += 1;
+		)* );
+		let _ = i;
+		None
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn nth_post_upgrade(n: u32, state: Vec<u8>) -> Option<Result<(), TryRuntimeError>> {
+		let mut i = 0;
+		for_tuples!( #(
+			if i == n {
+				return Some(Tuple::post_upgrade(state))
+			}
+			i += 1;
+		)* );
+		let _ = (i, state);
+		None
+	}
+}
+
+/// Something that drives multi-block migrations, used by `frame-executive`.
+pub trait MultiStepMigrator {
+	/// Start the migrations. Called once after a runtime upgrade.
+	///
+	/// Returns the weight consumed.
+	fn on_runtime_upgrade() -> Weight;
+
+	/// Whether migrations are ongoing.
+	///
+	/// Only mandatory extrinsics (inherents) are applied while this returns `true`.
+	fn ongoing() -> bool;
+
+	/// The weight of a call to [`Self::ongoing`].
+	fn ongoing_weight() -> Weight;
+
+	/// Progress the ongoing migrations, consuming at most `limit` weight.
+	///
+	/// Returns the weight consumed.
+	fn step(limit: Weight) -> Weight;
+
+	/// Run all migrations to completion in one go, with the `pre_upgrade` and `post_upgrade`
+	/// checks of each migration if `checks` is set.
+	#[cfg(feature = "try-runtime")]
+	fn try_run_to_completion(checks: bool) -> Result<Weight, TryRuntimeError>;
+}
+
+impl MultiStepMigrator for () {
+	fn on_runtime_upgrade() -> Weight {
+		Weight::zero()
+	}
+
+	fn ongoing() -> bool {
+		false
+	}
+
+	fn ongoing_weight() -> Weight {
+		Weight::zero()
+	}
+
+	fn step(_limit: Weight) -> Weight {
+		Weight::zero()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn try_run_to_completion(_checks: bool) -> Result<Weight, TryRuntimeError> {
+		Ok(Weight::zero())
+	}
+}
+
+/// Progress of the multi-block migrations, persisted between blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct MigrationCursor {
+	/// Index of the migration currently executed.
+	pub index: u32,
+	/// Encoded cursor of that migration, `None` if it did not start yet.
+	pub inner: Option<Vec<u8>>,
+}
+
+/// A [`MultiStepMigrator`] that executes `Migrations` one after another across blocks.
+///
+/// The [`MigrationCursor`] is kept in storage under [`SteppedMigrationsDriver::cursor_key`].
+/// If a migration fails, the remaining migrations are abandoned and extrinsics are allowed again;
+/// the failure is logged. A migration that needs more than `MaxBlockWeight` for a single step
+/// fails as well, since no block could ever fit it. A runtime upgrade while migrations are ongoing
+/// keeps the cursor, so the new runtime continues where the old one stopped.
+pub struct SteppedMigrationsDriver<Migrations, DbWeight, MaxBlockWeight>(
+	PhantomData<(Migrations, DbWeight, MaxBlockWeight)>,
+);
+
+impl<
+		Migrations: SteppedMigrations,
+		DbWeight: Get<RuntimeDbWeight>,
+		MaxBlockWeight: Get<Weight>,
+	> SteppedMigrationsDriver<Migrations, DbWeight, MaxBlockWeight>
+{
+	/// The storage key of the [`MigrationCursor`].
+	pub fn cursor_key() -> [u8; 32] {
+		storage_prefix(b"MultiBlockMigrations", b"Cursor")
+	}
+
+	/// The persisted cursor, `None` if no migration is ongoing.
+	pub fn cursor() -> Option<MigrationCursor> {
+		unhashed::get(&Self::cursor_key())
+	}
+}
+
+impl<
+		Migrations: SteppedMigrations,
+		DbWeight: Get<RuntimeDbWeight>,
+		MaxBlockWeight: Get<Weight>,
+	> MultiStepMigrator for SteppedMigrationsDriver<Migrations, DbWeight, MaxBlockWeight>
+{
+	fn on_runtime_upgrade() -> Weight {
+		if Migrations::len() == 0 {
+			return Weight::zero()
+		}
+		if let Some(cursor) = Self::cursor() {
+			log::warn!(
+				target: crate::LOG_TARGET,
+				"Runtime upgraded while migration #{} was ongoing, continuing from its cursor",
+				cursor.index,
+			);
+			return DbWeight::get().reads(1)
+		}
+		log::info!(
+			target: crate::LOG_TARGET,
+			"Starting {} multi-block migrations",
+			Migrations::len(),
+		);
+		unhashed::put(&Self::cursor_key(), &MigrationCursor { index: 0, inner: None });
+		DbWeight::get().reads_writes(1, 1)
+	}
+
+	fn ongoing() -> bool {
+		unhashed::exists(&Self::cursor_key())
+	}
+
+	fn ongoing_weight() -> Weight {
+		DbWeight::get().reads(1)
+	}
+
+	fn step(limit: Weight) -> Weight {
+		// Reading the cursor is unavoidable, even if it exceeds the limit.
+		let read = DbWeight::get().reads(1);
+		let mut cursor = match Self::cursor() {
+			Some(cursor) => cursor,
+			None => return read,
+		};
+		let mut meter = WeightMeter::from_limit(limit.saturating_sub(read));
+		if !meter.check_accrue(DbWeight::get().writes(1)) {
+			return read
+		}
+
+		loop {
+			let consumed = meter.consumed;
+			let result = match Migrations::nth_step(cursor.index, cursor.inner.clone(), &mut meter)
+			{
+				Some(result) => result,
+				None => {
+					log::info!(target: crate::LOG_TARGET, "All multi-block migrations completed");
+					unhashed::kill(&Self::cursor_key());
+					return meter.consumed.saturating_add(read)
+				},
+			};
+			let id = Migrations::nth_id(cursor.index).unwrap_or_default();
+
+			match result {
+				Ok(Some(inner)) => {
+					cursor.inner = Some(inner);
+					// A step that made no progress yields until the next block.
+					if meter.consumed == consumed {
+						break
+					}
+				},
+				Ok(None) => {
+					log::info!(target: crate::LOG_TARGET, "Migration {} completed", id);
+					cursor = MigrationCursor { index: cursor.index.saturating_add(1), inner: None };
+				},
+				// Retried in the next block, unless not even an empty block could fit it.
+				Err(SteppedMigrationError::InsufficientWeight { required })
+					if required.all_lte(MaxBlockWeight::get()) =>
+					break,
+				Err(error) => {
+					log::error!(
+						target: crate::LOG_TARGET,
+						"Migration {} failed with {:?}, abandoning the remaining migrations",
+						id,
+						error,
+					);
+					unhashed::kill(&Self::cursor_key());
+					return meter.consumed.saturating_add(read)
+				},
+			}
+		}
+
+		unhashed::put(&Self::cursor_key(), &cursor);
+		meter.consumed.saturating_add(read)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn try_run_to_completion(checks: bool) -> Result<Weight, TryRuntimeError> {
+		let mut meter = WeightMeter::max_limit();
+		for n in 0..Migrations::len() {
+			let state = match checks {
+				true => {
+					let _guard = crate::StorageNoopGuard::default();
+					Some(Migrations::nth_pre_upgrade(n).ok_or("Unknown migration")??)
+				},
+				false => None,
+			};
+
+			let mut cursor = None;
+			loop {
+				cursor = Migrations::nth_step(n, cursor, &mut meter)
+					.ok_or("Unknown migration")?
+					.map_err(|e| {
+						sp_runtime::try_runtime_error!(
+							"Multi-block migration #{} failed: {:?}",
+							n,
+							e
+						)
+					})?;
+				if cursor.is_none() {
+					break
+				}
+			}
+
+			if let Some(state) = state {
+				let _guard = crate::StorageNoopGuard::default();
+				Migrations::nth_post_upgrade(n, state).ok_or("Unknown migration")??;
+			}
+		}
+		Ok(meter.consumed)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_io::TestExternalities;
+
+	/// Migrates `ITEMS` items, each costing one unit of `ref_time`.
+	struct Items<const ITEMS: u32>;
+	impl<const ITEMS: u32> SteppedMigration for Items<ITEMS> {
+		type Cursor = u32;
+
+		fn id() -> &'static str {
+			"items"
+		}
+
+		fn step(
+			cursor: Option<u32>,
+			meter: &mut WeightMeter,
+		) -> Result<Option<u32>, SteppedMigrationError> {
+			let mut next = cursor.unwrap_or_default();
+			while next < ITEMS {
+				if !meter.check_accrue(Weight::from_parts(1, 0)) {
+					return Ok(Some(next))
+				}
+				unhashed::put(&(b"item", next).encode(), &true);
+				next += 1;
+			}
+			Ok(None)
+		}
+	}
+
+	struct Failing;
+	impl SteppedMigration for Failing {
+		type Cursor = ();
+
+		fn id() -> &'static str {
+			"failing"
+		}
+
+		fn step(_: Option<()>, _: &mut WeightMeter) -> Result<Option<()>, SteppedMigrationError> {
+			Err(SteppedMigrationError::Failed)
+		}
+	}
+
+	/// Needs `WEIGHT` units of `ref_time` for its only step.
+	struct Heavy<const WEIGHT: u64>;
+	impl<const WEIGHT: u64> SteppedMigration for Heavy<WEIGHT> {
+		type Cursor = ();
+
+		fn id() -> &'static str {
+			"heavy"
+		}
+
+		fn step(
+			_: Option<()>,
+			meter: &mut WeightMeter,
+		) -> Result<Option<()>, SteppedMigrationError> {
+			let required = Weight::from_parts(WEIGHT, 0);
+			if !meter.check_accrue(required) {
+				return Err(SteppedMigrationError::InsufficientWeight { required })
+			}
+			unhashed::put(&(b"item", 0u32).encode(), &true);
+			Ok(None)
+		}
+	}
+
+	crate::parameter_types! {
+		pub const MaxBlockWeight: Weight = Weight::from_parts(10, u64::MAX);
+	}
+
+	type Driver<M> = SteppedMigrationsDriver<M, (), MaxBlockWeight>;
+
+	fn migrated(item: u32) -> bool {
+		unhashed::exists(&(b"item", item).encode())
+	}
+
+	#[test]
+	fn tuple_lists_migrations_in_order() {
+		type List = (Items<1>, Failing);
+		assert_eq!(List::len(), 2);
+		assert_eq!(List::nth_id(1), Some("failing"));
+		assert_eq!(List::nth_id(2), None);
+		assert!(List::nth_step(2, None, &mut WeightMeter::max_limit()).is_none());
+	}
+
+	#[test]
+	fn driver_steps_across_blocks() {
+		TestExternalities::default().execute_with(|| {
+			assert!(!Driver::<(Items<5>,)>::ongoing());
+			Driver::<(Items<5>,)>::on_runtime_upgrade();
+			assert!(Driver::<(Items<5>,)>::ongoing());
+
+			assert_eq!(Driver::<(Items<5>,)>::step(Weight::from_parts(3, 0)).ref_time(), 3);
+			assert!(migrated(2) && !migrated(3));
+			assert_eq!(
+				Driver::<(Items<5>,)>::cursor(),
+				Some(MigrationCursor { index: 0, inner: Some(3u32.encode()) })
+			);
+
+			Driver::<(Items<5>,)>::step(Weight::from_parts(3, 0));
+			assert!(migrated(4));
+			assert!(!Driver::<(Items<5>,)>::ongoing());
+		});
+	}
+
+	#[test]
+	fn driver_runs_migrations_one_after_another() {
+		type List = (Items<2>, Items<4>);
+		TestExternalities::default().execute_with(|| {
+			Driver::<List>::on_runtime_upgrade();
+			Driver::<List>::step(Weight::from_parts(3, 0));
+			// The second migration starts in the same block.
+			assert_eq!(
+				Driver::<List>::cursor(),
+				Some(MigrationCursor { index: 1, inner: Some(1u32.encode()) })
+			);
+			Driver::<List>::step(Weight::from_parts(3, 0));
+			assert!(!Driver::<List>::ongoing());
+		});
+	}
+
+	#[test]
+	fn driver_abandons_migrations_after_failure() {
+		type List = (Failing, Items<2>);
+		TestExternalities::default().execute_with(|| {
+			Driver::<List>::on_runtime_upgrade();
+			Driver::<List>::step(Weight::MAX);
+			assert!(!Driver::<List>::ongoing());
+			assert!(!migrated(0));
+		});
+	}
+
+	#[test]
+	fn driver_retries_step_that_fits_into_a_block() {
+		TestExternalities::default().execute_with(|| {
+			Driver::<(Heavy<5>,)>::on_runtime_upgrade();
+			Driver::<(Heavy<5>,)>::step(Weight::from_parts(3, 0));
+			assert!(Driver::<(Heavy<5>,)>::ongoing());
+			assert!(!migrated(0));
+
+			Driver::<(Heavy<5>,)>::step(MaxBlockWeight::get());
+			assert!(!Driver::<(Heavy<5>,)>::ongoing());
+			assert!(migrated(0));
+		});
+	}
+
+	#[test]
+	fn driver_fails_step_that_exceeds_max_block() {
+		TestExternalities::default().execute_with(|| {
+			Driver::<(Heavy<11>,)>::on_runtime_upgrade();
+			Driver::<(Heavy<11>,)>::step(Weight::from_parts(3, 0));
+			assert!(!Driver::<(Heavy<11>,)>::ongoing());
+			assert!(!migrated(0));
+		});
+	}
+
+	#[test]
+	fn driver_keeps_cursor_on_runtime_upgrade() {
+		TestExternalities::default().execute_with(|| {
+			Driver::<(Items<5>,)>::on_runtime_upgrade();
+			Driver::<(Items<5>,)>::step(Weight::from_parts(3, 0));
+
+			Driver::<(Items<5>,)>::on_runtime_upgrade();
+			assert_eq!(
+				Driver::<(Items<5>,)>::cursor(),
+				Some(MigrationCursor { index: 0, inner: Some(3u32.encode()) })
+			);
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn try_run_to_completion_works() {
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(
+				Driver::<(Items<3>, Items<5>)>::try_run_to_completion(true),
+				Ok(Weight::from_parts(8, 0))
+			);
+			assert!(migrated(4));
+			assert_eq!(
+				Driver::<(Items<1>, Failing)>::try_run_to_completion(false),
+				Err(sp_runtime::try_runtime_error!("Multi-block migration #1 failed: Failed"))
+			);
+		});
+	}
+
+	struct Pallet;
+	impl PalletInfoAccess for Pallet {
+		fn index() -> usize {
+			0
+		}
+		fn name() -> &'static str {
+			"Pallet"
+		}
+		fn module_name() -> &'static str {
+			"pallet"
+		}
+		fn crate_version() -> crate::traits::CrateVersion {
+			crate::traits::CrateVersion::new(1, 0, 0)
+		}
+	}
+	impl GetStorageVersion for Pallet {
+		fn current_storage_version() -> StorageVersion {
+			StorageVersion::new(1)
+		}
+		fn on_chain_storage_version() -> StorageVersion {
+			StorageVersion::get::<Self>()
+		}
+	}
+
+	/// Writes a single item, costing one unit of `ref_time`.
+	struct MigrateItem;
+	impl OnRuntimeUpgrade for MigrateItem {
+		fn on_runtime_upgrade() -> Weight {
+			unhashed::put(&(b"item", 0u32).encode(), &true);
+			Weight::from_parts(1, 0)
+		}
+	}
+
+	type MigrateV0ToV1 = VersionedMigration<0, 1, MigrateItem, Pallet, ()>;
+
+	#[test]
+	fn versioned_migration_runs_and_bumps_version() {
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(MigrateV0ToV1::on_runtime_upgrade(), Weight::from_parts(1, 0));
+			assert!(migrated(0));
+			assert_eq!(Pallet::on_chain_storage_version(), 1);
+		});
+	}
+
+	#[test]
+	fn versioned_migration_skips_on_version_mismatch() {
+		TestExternalities::default().execute_with(|| {
+			StorageVersion::new(1).put::<Pallet>();
+			assert_eq!(MigrateV0ToV1::on_runtime_upgrade(), Weight::zero());
+			assert!(!migrated(0));
+			assert_eq!(Pallet::on_chain_storage_version(), 1);
+		});
+	}
+
+	#[cfg(feature = "try-runtime")]
+	#[test]
+	fn versioned_migration_try_runtime_hooks_work() {
+		TestExternalities::default().execute_with(|| {
+			let state = MigrateV0ToV1::pre_upgrade().unwrap();
+			MigrateV0ToV1::on_runtime_upgrade();
+			assert_eq!(MigrateV0ToV1::post_upgrade(state), Ok(()));
+
+			// Already migrated: the hooks are a no-op.
+			let state = MigrateV0ToV1::pre_upgrade().unwrap();
+			assert_eq!(state, VersionedPostUpgradeData::Noop.encode());
+			MigrateV0ToV1::on_runtime_upgrade();
+			assert_eq!(MigrateV0ToV1::post_upgrade(state), Ok(()));
+
+			// The version check fails if the version was not bumped.
+			StorageVersion::new(0).put::<Pallet>();
+			let state = MigrateV0ToV1::pre_upgrade().unwrap();
+			assert!(MigrateV0ToV1::post_upgrade(state).is_err());
+		});
+	}
+
+	#[test]
+	fn driver_without_migrations_never_starts() {
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(Driver::<()>::on_runtime_upgrade(), Weight::zero());
+			assert!(!Driver::<()>::ongoing());
+		});
+	}
+}
@@ -22,6 +22,7 @@
 
 pub use frame_support::traits::{TryStateSelect, UpgradeCheckSelect};
 use frame_support::weights::Weight;
+use sp_std::prelude::*;
 
 sp_api::decl_runtime_apis! {
 	/// Runtime api for testing the execution of a runtime upgrade.
@@ -49,5 +50,19 @@ sp_api::decl_runtime_apis! {
 			signature_check: bool,
 			try_state: TryStateSelect,
 		) -> Weight;
+
+		/// Execute the `try_state` hooks selected by `select` against the current state.
+		///
+		/// Contrary to [`Self::execute_block`], a failing check is returned instead of panicking,
+		/// so that the caller can keep checking the remaining pallets.
+		#[api_version(2)]
+		fn try_state(select: TryStateSelect) -> Result<(), sp_runtime::TryRuntimeError>;
+
+		/// The names of all pallets of the runtime, as per `construct_runtime!`, that can be
+		/// selected in [`Self::try_state`].
+		///
+		/// Pallets without a `try_state` hook are included as well; their checks trivially pass.
+		#[api_version(2)]
+		fn try_state_pallets() -> Vec<Vec<u8>>;
 	}
 }
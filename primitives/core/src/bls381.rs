@@ -0,0 +1,569 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simple BLS12-381 API.
+//!
+//! Signatures are in G1 and public keys carry their G2 point together with the matching G1 point
+//! (a "double" public key), so that a single signature can be verified cheaply with a DLEQ proof
+//! while signatures on the same message can still be aggregated and verified against the sum of
+//! the G2 points.
+//!
+//! This module is experimental, the format of keys and signatures may still change.
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime_interface::pass_by::PassByInner;
+
+#[cfg(feature = "serde")]
+use crate::crypto::Ss58Codec;
+use crate::crypto::{
+	ByteArray, CryptoType, CryptoTypeId, Derive, Public as TraitPublic, UncheckedFrom,
+};
+#[cfg(feature = "full_crypto")]
+use crate::crypto::{DeriveError, DeriveJunction, Pair as TraitPair, SecretStringError};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(all(not(feature = "std"), feature = "serde"))]
+use sp_std::alloc::{format, string::String};
+#[cfg(feature = "full_crypto")]
+use sp_std::vec::Vec;
+use w3f_bls::{
+	DoublePublicKey, DoubleSignature, Message, PublicKey, SerializableToBytes,
+	Signature as SingleSignature, TinyBLS381,
+};
+#[cfg(feature = "full_crypto")]
+use w3f_bls::{DoublePublicKeyScheme, Keypair, SecretKey};
+
+/// An identifier used to match public keys against BLS12-381 keys
+pub const CRYPTO_ID: CryptoTypeId = CryptoTypeId(*b"bls8");
+
+/// The byte length of a public key, its G1 point followed by its G2 point.
+pub const PUBLIC_KEY_SERIALIZED_SIZE: usize =
+	<DoublePublicKey<TinyBLS381> as SerializableToBytes>::SERIALIZED_BYTES_SIZE;
+
+/// The byte length of a signature, its G1 point followed by its DLEQ proof.
+pub const SIGNATURE_SERIALIZED_SIZE: usize =
+	<DoubleSignature<TinyBLS381> as SerializableToBytes>::SERIALIZED_BYTES_SIZE;
+
+/// The byte length of an aggregate signature, a single G1 point.
+pub const AGGREGATE_SIGNATURE_SERIALIZED_SIZE: usize =
+	<SingleSignature<TinyBLS381> as SerializableToBytes>::SERIALIZED_BYTES_SIZE;
+
+/// The context all messages are signed in.
+const SIGNING_CTX: &[u8] = b"substrate";
+
+/// A secret seed, hashed to the secret key.
+#[cfg(feature = "full_crypto")]
+type Seed = [u8; 32];
+
+/// A BLS12-381 public key.
+#[cfg_attr(feature = "full_crypto", derive(Hash))]
+#[derive(
+	Clone,
+	Copy,
+	Encode,
+	Decode,
+	PassByInner,
+	MaxEncodedLen,
+	TypeInfo,
+	Eq,
+	PartialEq,
+	PartialOrd,
+	Ord,
+)]
+pub struct Public(pub [u8; PUBLIC_KEY_SERIALIZED_SIZE]);
+
+impl Public {
+	/// A new instance from the given `data`.
+	///
+	/// NOTE: No checking goes on to ensure this is a real public key. Only use it if
+	/// you are certain that the array actually is a pubkey. GIGO!
+	pub fn from_raw(data: [u8; PUBLIC_KEY_SERIALIZED_SIZE]) -> Self {
+		Self(data)
+	}
+}
+
+impl ByteArray for Public {
+	const LEN: usize = PUBLIC_KEY_SERIALIZED_SIZE;
+}
+
+impl TraitPublic for Public {}
+
+impl Derive for Public {}
+
+impl AsRef<[u8]> for Public {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+impl AsMut<[u8]> for Public {
+	fn as_mut(&mut self) -> &mut [u8] {
+		&mut self.0[..]
+	}
+}
+
+impl TryFrom<&[u8]> for Public {
+	type Error = ();
+
+	fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+		if data.len() != Self::LEN {
+			return Err(())
+		}
+		let mut r = [0u8; Self::LEN];
+		r.copy_from_slice(data);
+		Ok(Self::unchecked_from(r))
+	}
+}
+
+#[cfg(feature = "full_crypto")]
+impl From<Pair> for Public {
+	fn from(x: Pair) -> Self {
+		x.public()
+	}
+}
+
+impl UncheckedFrom<[u8; PUBLIC_KEY_SERIALIZED_SIZE]> for Public {
+	fn unchecked_from(x: [u8; PUBLIC_KEY_SERIALIZED_SIZE]) -> Self {
+		Public(x)
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for Public {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}", self.to_ss58check())
+	}
+}
+
+impl sp_std::fmt::Debug for Public {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		let s = self.to_ss58check();
+		write!(f, "{} ({}...)", crate::hexdisplay::HexDisplay::from(&self.as_ref()), &s[0..8])
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Public {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_str(&self.to_ss58check())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Public {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		Public::from_ss58check(&String::deserialize(deserializer)?)
+			.map_err(|e| de::Error::custom(format!("{:?}", e)))
+	}
+}
+
+/// A BLS12-381 signature with a DLEQ proof, so it can be verified without pairings.
+#[cfg_attr(feature = "full_crypto", derive(Hash))]
+#[derive(Clone, Encode, Decode, MaxEncodedLen, PassByInner, TypeInfo, PartialEq, Eq)]
+pub struct Signature(pub [u8; SIGNATURE_SERIALIZED_SIZE]);
+
+impl TryFrom<&[u8]> for Signature {
+	type Error = ();
+
+	fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+		Signature::from_slice(data).ok_or(())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Signature {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_str(&array_bytes::bytes2hex("", self.as_ref()))
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Signature {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let signature_hex = array_bytes::hex2bytes(&String::deserialize(deserializer)?)
+			.map_err(|e| de::Error::custom(format!("{:?}", e)))?;
+		Signature::try_from(signature_hex.as_ref())
+			.map_err(|e| de::Error::custom(format!("{:?}", e)))
+	}
+}
+
+impl Default for Signature {
+	fn default() -> Self {
+		Signature([0u8; SIGNATURE_SERIALIZED_SIZE])
+	}
+}
+
+impl From<Signature> for [u8; SIGNATURE_SERIALIZED_SIZE] {
+	fn from(v: Signature) -> [u8; SIGNATURE_SERIALIZED_SIZE] {
+		v.0
+	}
+}
+
+impl AsRef<[u8]> for Signature {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+impl AsMut<[u8]> for Signature {
+	fn as_mut(&mut self) -> &mut [u8] {
+		&mut self.0[..]
+	}
+}
+
+impl sp_std::fmt::Debug for Signature {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "{}", crate::hexdisplay::HexDisplay::from(&self.0))
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl UncheckedFrom<[u8; SIGNATURE_SERIALIZED_SIZE]> for Signature {
+	fn unchecked_from(data: [u8; SIGNATURE_SERIALIZED_SIZE]) -> Signature {
+		Signature(data)
+	}
+}
+
+impl Signature {
+	/// A new instance from the given slice that should be `SIGNATURE_SERIALIZED_SIZE` bytes long.
+	///
+	/// NOTE: No checking goes on to ensure this is a real signature. Only use it if
+	/// you are certain that the array actually is a signature. GIGO!
+	pub fn from_slice(data: &[u8]) -> Option<Self> {
+		if data.len() != SIGNATURE_SERIALIZED_SIZE {
+			return None
+		}
+		let mut r = [0u8; SIGNATURE_SERIALIZED_SIZE];
+		r.copy_from_slice(data);
+		Some(Signature(r))
+	}
+
+	/// Verify this signature on `message` by `public`.
+	///
+	/// This doesn't require `full_crypto`, so signatures can be verified in the runtime.
+	pub fn verify<M: AsRef<[u8]>>(&self, message: M, public: &Public) -> bool {
+		let (signature, public) = match (
+			DoubleSignature::<TinyBLS381>::from_bytes(&self.0),
+			DoublePublicKey::<TinyBLS381>::from_bytes(&public.0),
+		) {
+			(Ok(signature), Ok(public)) => (signature, public),
+			_ => return false,
+		};
+		signature.verify(&Message::new(SIGNING_CTX, message.as_ref()), &public)
+	}
+}
+
+/// An aggregate of BLS12-381 signatures on the same message.
+#[derive(Clone, Copy, Encode, Decode, MaxEncodedLen, PassByInner, TypeInfo, PartialEq, Eq)]
+pub struct AggregateSignature(pub [u8; AGGREGATE_SIGNATURE_SERIALIZED_SIZE]);
+
+impl AggregateSignature {
+	/// Aggregate `signatures` into a single one.
+	///
+	/// Returns `None` if there are no signatures or any of them can't be decoded. The signatures
+	/// are not verified.
+	pub fn aggregate<'a>(signatures: impl IntoIterator<Item = &'a Signature>) -> Option<Self> {
+		let mut aggregate = None;
+		for signature in signatures {
+			let point = DoubleSignature::<TinyBLS381>::from_bytes(&signature.0).ok()?.0;
+			aggregate = Some(aggregate.map_or(point, |sum| sum + point));
+		}
+
+		let bytes = SingleSignature::<TinyBLS381>(aggregate?).to_bytes();
+		let mut r = [0u8; AGGREGATE_SIGNATURE_SERIALIZED_SIZE];
+		r.copy_from_slice(&bytes);
+		Some(AggregateSignature(r))
+	}
+
+	/// Verify that this is the aggregate of signatures on `message` by all of `publics`.
+	///
+	/// Returns `false` if there are no public keys. Each of `publics` must come with a proof
+	/// that its owner knows the secret key, e.g. from registering it with a signature, otherwise
+	/// a rogue key can be crafted to forge an aggregate signature for a set of keys.
+	pub fn verify<'a, M: AsRef<[u8]>>(
+		&self,
+		message: M,
+		publics: impl IntoIterator<Item = &'a Public>,
+	) -> bool {
+		let mut aggregate = None;
+		for public in publics {
+			let point = match DoublePublicKey::<TinyBLS381>::from_bytes(&public.0) {
+				Ok(public) => public.1,
+				Err(_) => return false,
+			};
+			aggregate = Some(aggregate.map_or(point, |sum| sum + point));
+		}
+
+		match (aggregate, SingleSignature::<TinyBLS381>::from_bytes(&self.0)) {
+			(Some(public), Ok(signature)) =>
+				signature.verify(&Message::new(SIGNING_CTX, message.as_ref()), &PublicKey(public)),
+			_ => false,
+		}
+	}
+}
+
+impl AsRef<[u8]> for AggregateSignature {
+	fn as_ref(&self) -> &[u8] {
+		&self.0[..]
+	}
+}
+
+impl sp_std::fmt::Debug for AggregateSignature {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "{}", crate::hexdisplay::HexDisplay::from(&self.0))
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+/// Derive a single hard junction.
+#[cfg(feature = "full_crypto")]
+fn derive_hard_junction(secret_seed: &Seed, cc: &[u8; 32]) -> Seed {
+	("BLS12381HDKD", secret_seed, cc).using_encoded(sp_core_hashing::blake2_256)
+}
+
+/// A key pair.
+#[cfg(feature = "full_crypto")]
+#[derive(Clone)]
+pub struct Pair {
+	public: Public,
+	seed: Seed,
+	keypair: Keypair<TinyBLS381>,
+}
+
+#[cfg(feature = "full_crypto")]
+impl TraitPair for Pair {
+	type Public = Public;
+	type Seed = Seed;
+	type Signature = Signature;
+
+	/// Make a new key pair from secret seed material. The slice must be 32 bytes long or it
+	/// will return `None`.
+	///
+	/// You should never need to use this; generate(), generate_with_phrase
+	fn from_seed_slice(seed_slice: &[u8]) -> Result<Pair, SecretStringError> {
+		let seed = Seed::try_from(seed_slice).map_err(|_| SecretStringError::InvalidSeedLength)?;
+		let secret = SecretKey::<TinyBLS381>::from_seed(&seed);
+		let public = secret.into_public();
+		let keypair = Keypair { secret, public };
+
+		let public = Public::try_from(&keypair.into_double_public_key().to_bytes()[..])
+			.expect("Double public keys are `PUBLIC_KEY_SERIALIZED_SIZE` bytes; qed");
+		Ok(Pair { public, seed, keypair })
+	}
+
+	/// Derive a child key from a series of given junctions.
+	fn derive<Iter: Iterator<Item = DeriveJunction>>(
+		&self,
+		path: Iter,
+		_seed: Option<Seed>,
+	) -> Result<(Pair, Option<Seed>), DeriveError> {
+		let mut acc = self.seed;
+		for j in path {
+			match j {
+				DeriveJunction::Soft(_cc) => return Err(DeriveError::SoftKeyInPath),
+				DeriveJunction::Hard(cc) => acc = derive_hard_junction(&acc, &cc),
+			}
+		}
+		Ok((Self::from_seed(&acc), Some(acc)))
+	}
+
+	/// Get the public key.
+	fn public(&self) -> Public {
+		self.public
+	}
+
+	/// Sign a message.
+	fn sign(&self, message: &[u8]) -> Signature {
+		let mut keypair = self.keypair.clone();
+		let signature =
+			DoublePublicKeyScheme::sign(&mut keypair, &Message::new(SIGNING_CTX, message));
+		Signature::from_slice(&signature.to_bytes())
+			.expect("Double signatures are `SIGNATURE_SERIALIZED_SIZE` bytes; qed")
+	}
+
+	/// Verify a signature on a message. Returns true if the signature is good.
+	fn verify<M: AsRef<[u8]>>(sig: &Self::Signature, message: M, pubkey: &Self::Public) -> bool {
+		sig.verify(message, pubkey)
+	}
+
+	/// Verify a signature on a message. Returns true if the signature is good.
+	///
+	/// This doesn't use the type system to ensure that `sig` and `pubkey` are the correct
+	/// size. Use it only if you're coming from byte buffers and need the speed.
+	fn verify_weak<P: AsRef<[u8]>, M: AsRef<[u8]>>(sig: &[u8], message: M, pubkey: P) -> bool {
+		match (Signature::from_slice(sig), Public::try_from(pubkey.as_ref())) {
+			(Some(sig), Ok(pubkey)) => sig.verify(message, &pubkey),
+			_ => false,
+		}
+	}
+
+	/// Return a vec filled with raw data.
+	fn to_raw_vec(&self) -> Vec<u8> {
+		self.seed.to_vec()
+	}
+}
+
+#[cfg(feature = "full_crypto")]
+impl Pair {
+	/// Get the seed for this key.
+	pub fn seed(&self) -> Seed {
+		self.seed
+	}
+}
+
+impl CryptoType for Public {
+	#[cfg(feature = "full_crypto")]
+	type Pair = Pair;
+}
+
+impl CryptoType for Signature {
+	#[cfg(feature = "full_crypto")]
+	type Pair = Pair;
+}
+
+#[cfg(feature = "full_crypto")]
+impl CryptoType for Pair {
+	type Pair = Pair;
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::crypto::DEV_PHRASE;
+
+	#[test]
+	fn serialized_sizes_match_w3f_bls() {
+		assert_eq!(PUBLIC_KEY_SERIALIZED_SIZE, 144);
+		assert_eq!(SIGNATURE_SERIALIZED_SIZE, 112);
+		assert_eq!(AGGREGATE_SIGNATURE_SERIALIZED_SIZE, 48);
+	}
+
+	#[test]
+	fn default_phrase_should_be_used() {
+		assert_eq!(
+			Pair::from_string("//Alice///password", None).unwrap().public(),
+			Pair::from_string(&format!("{}//Alice", DEV_PHRASE), Some("password"))
+				.unwrap()
+				.public(),
+		);
+	}
+
+	#[test]
+	fn derive_hard_should_work() {
+		let pair = Pair::from_seed(&[1u8; 32]);
+		let derive = |p: &Pair, cc| p.derive([DeriveJunction::Hard(cc)].into_iter(), None);
+
+		let (derived, seed) = derive(&pair, [0u8; 32]).unwrap();
+		assert_eq!(Pair::from_seed(&seed.unwrap()).public(), derived.public());
+		assert_ne!(derived.public(), pair.public());
+		assert_eq!(derive(&pair, [0u8; 32]).unwrap().0.public(), derived.public());
+		assert_ne!(derive(&pair, [1u8; 32]).unwrap().0.public(), derived.public());
+		assert!(matches!(
+			pair.derive([DeriveJunction::soft(1)].into_iter(), None),
+			Err(DeriveError::SoftKeyInPath)
+		));
+	}
+
+	#[test]
+	fn generated_pair_should_work() {
+		let (pair, _) = Pair::generate();
+		let public = pair.public();
+		let message = b"Something important";
+		let signature = pair.sign(&message[..]);
+		assert!(Pair::verify(&signature, &message[..], &public));
+		assert!(!Pair::verify(&signature, b"Something else", &public));
+		assert!(!Pair::verify(&signature, &message[..], &Pair::generate().0.public()));
+		assert!(Pair::verify_weak(&signature.0[..], &message[..], public));
+		assert!(!Pair::verify(&Signature::default(), &message[..], &public));
+	}
+
+	#[test]
+	fn seeded_pair_should_be_deterministic() {
+		let pair = Pair::from_seed(b"12345678901234567890123456789012");
+		assert_eq!(pair.seed(), *b"12345678901234567890123456789012");
+		assert_eq!(pair.public(), Pair::from_seed_slice(&pair.to_raw_vec()).unwrap().public());
+		assert_eq!(pair.public(), pair.derive(None.into_iter(), None).unwrap().0.public());
+		assert!(Pair::from_seed_slice(&[0u8; 31]).is_err());
+	}
+
+	#[test]
+	fn ss58check_roundtrip_works() {
+		let public = Pair::from_seed(&[7u8; 32]).public();
+		let s = public.to_ss58check();
+		assert_eq!(Public::from_ss58check(&s), Ok(public));
+	}
+
+	#[test]
+	fn signature_serialization_works() {
+		let pair = Pair::from_seed(&[7u8; 32]);
+		let signature = pair.sign(b"Something important");
+		let serialized = serde_json::to_string(&signature).unwrap();
+		let deserialized: Signature = serde_json::from_str(&serialized).unwrap();
+		assert_eq!(deserialized, signature);
+		assert_eq!(Signature::decode(&mut &signature.encode()[..]).unwrap(), signature);
+	}
+
+	#[test]
+	fn aggregate_signature_should_work() {
+		let message = b"BEEFY commitment";
+		let pairs: Vec<_> = (0u8..4).map(|i| Pair::from_seed(&[i; 32])).collect();
+		let publics: Vec<_> = pairs.iter().map(|p| p.public()).collect();
+		let signatures: Vec<_> = pairs.iter().map(|p| p.sign(&message[..])).collect();
+
+		let aggregate = AggregateSignature::aggregate(&signatures).unwrap();
+		assert!(aggregate.verify(&message[..], &publics));
+		assert!(!aggregate.verify(b"Another commitment", &publics));
+		assert!(!aggregate.verify(&message[..], &publics[1..]));
+		assert!(!aggregate.verify(&message[..], &[]));
+
+		let partial = AggregateSignature::aggregate(&signatures[..2]).unwrap();
+		assert!(partial.verify(&message[..], &publics[..2]));
+		assert_ne!(partial, aggregate);
+
+		assert!(AggregateSignature::aggregate(&[]).is_none());
+		assert!(AggregateSignature::aggregate(&[Signature::default()]).is_none());
+	}
+}
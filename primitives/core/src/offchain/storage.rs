@@ -87,4 +87,26 @@ impl OffchainStorage for InMemOffchainStorage {
 			_ => false,
 		}
 	}
+
+	fn entries_paged(
+		&self,
+		prefix: &[u8],
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		count: usize,
+	) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+		let mut entries: Vec<_> = self
+			.storage
+			.iter()
+			.filter_map(|(key, value)| {
+				key.strip_prefix(prefix)
+					.filter(|key| key.starts_with(key_prefix))
+					.filter(|key| start_key.map_or(true, |start| *key > start))
+					.map(|key| (key.to_vec(), value.clone()))
+			})
+			.collect();
+		entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+		entries.truncate(count);
+		Some(entries)
+	}
 }
@@ -115,6 +115,16 @@ impl OffchainStorage for TestPersistentOffchainDB {
 	) -> bool {
 		self.persistent.write().compare_and_set(prefix, key, old_value, new_value)
 	}
+
+	fn entries_paged(
+		&self,
+		prefix: &[u8],
+		key_prefix: &[u8],
+		start_key: Option<&[u8]>,
+		count: usize,
+	) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+		self.persistent.read().entries_paged(prefix, key_prefix, start_key, count)
+	}
 }
 
 /// Internal state of the externalities.
@@ -18,7 +18,7 @@
 /// A wrapper around `kvdb::Database` that implements `sp_database::Database` trait
 use ::kvdb::{DBTransaction, KeyValueDB};
 
-use crate::{error, Change, ColumnId, Database, Transaction};
+use crate::{error, Change, ColumnId, Database, PairsIter, Transaction};
 
 struct DbAdapter<D: KeyValueDB + 'static>(D);
 
@@ -115,4 +115,20 @@ impl<D: KeyValueDB, H: Clone + AsRef<[u8]>> Database<H> for DbAdapter<D> {
 	fn contains(&self, col: ColumnId, key: &[u8]) -> bool {
 		handle_err(self.0.has_key(col, key))
 	}
+
+	fn iter_prefix<'a>(
+		&'a self,
+		col: ColumnId,
+		prefix: &'a [u8],
+		start: Option<&'a [u8]>,
+	) -> Option<PairsIter<'a>> {
+		// `KeyValueDB` can't seek, so skip the keys before `start`.
+		Some(Box::new(
+			self.0
+				.iter_with_prefix(col, prefix)
+				.map(handle_err)
+				.map(|(key, value)| (key.to_vec(), value))
+				.skip_while(move |(key, _)| start.map_or(false, |start| key.as_slice() < start)),
+		))
+	}
 }
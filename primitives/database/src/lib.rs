@@ -115,8 +115,24 @@ pub trait Database<H: Clone + AsRef<[u8]>>: Send + Sync {
 	///
 	/// Not all database implementations use a prefix for keys, so this function may be a noop.
 	fn sanitize_key(&self, _key: &mut Vec<u8>) {}
+
+	/// Iterate over all key-value pairs in `col` whose key starts with `prefix`, in
+	/// lexicographic key order, starting at the first key not less than `start` if given.
+	///
+	/// Returns `None` if the database can not iterate over the column, which is the default.
+	fn iter_prefix<'a>(
+		&'a self,
+		_col: ColumnId,
+		_prefix: &'a [u8],
+		_start: Option<&'a [u8]>,
+	) -> Option<PairsIter<'a>> {
+		None
+	}
 }
 
+/// An iterator over key-value pairs of a database column.
+pub type PairsIter<'a> = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>;
+
 impl<H> std::fmt::Debug for dyn Database<H> {
 	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
 		write!(f, "Database")
@@ -17,7 +17,7 @@
 
 //! In-memory implementation of `Database`
 
-use crate::{error, Change, ColumnId, Database, Transaction};
+use crate::{error, Change, ColumnId, Database, PairsIter, Transaction};
 use parking_lot::RwLock;
 use std::collections::{hash_map::Entry, HashMap};
 
@@ -73,6 +73,25 @@ where
 		let s = self.0.read();
 		s.get(&col).and_then(|c| c.get(key).map(|(_, v)| v.clone()))
 	}
+
+	fn iter_prefix<'a>(
+		&'a self,
+		col: ColumnId,
+		prefix: &'a [u8],
+		start: Option<&'a [u8]>,
+	) -> Option<PairsIter<'a>> {
+		let s = self.0.read();
+		let mut pairs: Vec<_> = s
+			.get(&col)
+			.into_iter()
+			.flatten()
+			.filter(|(k, _)| k.starts_with(prefix))
+			.filter(|(k, _)| start.map_or(true, |start| k.as_slice() >= start))
+			.map(|(k, (_, v))| (k.clone(), v.clone()))
+			.collect();
+		pairs.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+		Some(Box::new(pairs.into_iter()))
+	}
 }
 
 impl MemDb {
@@ -0,0 +1,44 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Substrate genesis config builder
+//!
+//! This crate contains [`GenesisBuilder`], a runtime-api to be implemented by runtimes, in order
+//! to make it possible to build the genesis state from a JSON representation of the runtime
+//! genesis config, without the native `GenesisConfig` type being available to the node.
+
+/// The result type alias, used in build methods. `Err` contains formatted error message.
+pub type Result = core::result::Result<(), sp_runtime::RuntimeString>;
+
+sp_api::decl_runtime_apis! {
+	/// API to interact with the genesis config of the runtime.
+	pub trait GenesisBuilder {
+		/// Creates the default genesis config of the runtime and returns it as a JSON blob.
+		///
+		/// This function instantiates the default genesis config struct of the runtime and
+		/// serializes it into a JSON blob. It can be used by the node as a base for a patch.
+		fn create_default_config() -> sp_std::vec::Vec<u8>;
+
+		/// Builds the genesis state from the given JSON blob of the runtime genesis config.
+		///
+		/// The JSON must contain the full genesis config, any missing field is an error. The
+		/// resulting state is written into the storage the call is executed against.
+		fn build_config(json: sp_std::vec::Vec<u8>) -> Result;
+	}
+}
@@ -17,6 +17,8 @@
 
 //! Types that should only be used for testing!
 
+#[cfg(feature = "bls-experimental")]
+use sp_core::bls381;
 use sp_core::{
 	crypto::{ByteArray, KeyTypeId, Pair},
 	ecdsa, ed25519, sr25519,
@@ -190,6 +192,30 @@ impl Keystore for MemoryKeystore {
 		Ok(sig)
 	}
 
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_public_keys(&self, key_type: KeyTypeId) -> Vec<bls381::Public> {
+		self.public_keys::<bls381::Pair>(key_type)
+	}
+
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_generate_new(
+		&self,
+		key_type: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<bls381::Public, Error> {
+		self.generate_new::<bls381::Pair>(key_type, seed)
+	}
+
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &bls381::Public,
+		msg: &[u8],
+	) -> Result<Option<bls381::Signature>, Error> {
+		self.sign::<bls381::Pair>(key_type, public, msg)
+	}
+
 	fn insert(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
 		self.keys
 			.write()
@@ -305,4 +331,23 @@ mod tests {
 		let res = store.ecdsa_sign_prehashed(ECDSA, &pair.public(), &msg).unwrap();
 		assert!(res.is_some());
 	}
+	#[test]
+	#[cfg(feature = "bls-experimental")]
+	fn bls381_generate_and_sign_works() {
+		use codec::Decode;
+		use sp_core::testing::BLS381;
+
+		let store = MemoryKeystore::new();
+
+		let public = store.bls381_generate_new(BLS381, None).unwrap();
+		assert_eq!(store.bls381_public_keys(BLS381), vec![public]);
+
+		let msg = b"this should be signed";
+		let sig = store.bls381_sign(BLS381, &public, msg).unwrap().unwrap();
+		assert!(bls381::Pair::verify(&sig, msg, &public));
+
+		let encoded = store.sign_with(BLS381, bls381::CRYPTO_ID, public.as_ref(), msg).unwrap();
+		let sig = bls381::Signature::decode(&mut &encoded.unwrap()[..]).unwrap();
+		assert!(bls381::Pair::verify(&sig, msg, &public));
+	}
 }
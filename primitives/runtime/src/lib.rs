@@ -24,7 +24,7 @@
 pub use codec;
 #[doc(hidden)]
 pub use scale_info;
-#[cfg(feature = "std")]
+#[cfg(feature = "serde")]
 #[doc(hidden)]
 pub use serde;
 #[doc(hidden)]
@@ -48,6 +48,8 @@ use sp_core::{
 	hash::{H256, H512},
 	sr25519,
 };
+#[cfg(all(not(feature = "std"), feature = "serde"))]
+use sp_std::alloc::format;
 use sp_std::prelude::*;
 
 use codec::{Decode, Encode, MaxEncodedLen};
@@ -122,7 +124,7 @@ pub type EncodedJustification = Vec<u8>;
 
 /// Collection of justifications for a given block, multiple justifications may
 /// be provided by different consensus engines for the same block.
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct Justifications(Vec<Justification>);
 
@@ -179,7 +181,7 @@ impl From<Justification> for Justifications {
 use traits::{Lazy, Verify};
 
 use crate::traits::IdentifyAccount;
-#[cfg(feature = "std")]
+#[cfg(feature = "serde")]
 pub use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 /// Complex storage builder stuff.
@@ -235,7 +237,7 @@ impl BuildStorage for () {
 pub type ConsensusEngineId = [u8; 4];
 
 /// Signature verify that can work with any known signature types.
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Eq, PartialEq, Clone, Encode, Decode, MaxEncodedLen, RuntimeDebug, TypeInfo)]
 pub enum MultiSignature {
 	/// An Ed25519 signature.
@@ -299,7 +301,7 @@ impl TryFrom<MultiSignature> for ecdsa::Signature {
 
 /// Public key for any known crypto algorithm.
 #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MultiSigner {
 	/// An Ed25519 identity.
 	Ed25519(ed25519::Public),
@@ -427,7 +429,7 @@ impl Verify for MultiSignature {
 
 /// Signature verify that can work with any known signature types..
 #[derive(Eq, PartialEq, Clone, Default, Encode, Decode, RuntimeDebug)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AnySignature(H512);
 
 impl Verify for AnySignature {
@@ -475,7 +477,7 @@ pub type DispatchResultWithInfo<T> = sp_std::result::Result<T, DispatchErrorWith
 
 /// Reason why a pallet call failed.
 #[derive(Eq, Clone, Copy, Encode, Decode, Debug, TypeInfo, MaxEncodedLen)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ModuleError {
 	/// Module index, matching the metadata module index.
 	pub index: u8,
@@ -483,7 +485,7 @@ pub struct ModuleError {
 	pub error: [u8; MAX_MODULE_ERROR_ENCODED_SIZE],
 	/// Optional error message.
 	#[codec(skip)]
-	#[cfg_attr(feature = "std", serde(skip_deserializing))]
+	#[cfg_attr(feature = "serde", serde(skip_deserializing))]
 	pub message: Option<&'static str>,
 }
 
@@ -495,7 +497,7 @@ impl PartialEq for ModuleError {
 
 /// Errors related to transactional storage layers.
 #[derive(Eq, PartialEq, Clone, Copy, Encode, Decode, Debug, TypeInfo, MaxEncodedLen)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TransactionalError {
 	/// Too many transactional layers have been spawned.
 	LimitReached,
@@ -520,12 +522,12 @@ impl From<TransactionalError> for DispatchError {
 
 /// Reason why a dispatch call failed.
 #[derive(Eq, Clone, Copy, Encode, Decode, Debug, TypeInfo, PartialEq, MaxEncodedLen)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DispatchError {
 	/// Some error occurred.
 	Other(
 		#[codec(skip)]
-		#[cfg_attr(feature = "std", serde(skip_deserializing))]
+		#[cfg_attr(feature = "serde", serde(skip_deserializing))]
 		&'static str,
 	),
 	/// Failed to lookup some data.
@@ -603,7 +605,7 @@ impl From<crate::traits::BadOrigin> for DispatchError {
 
 /// Description of what went wrong when trying to complete an operation on a token.
 #[derive(Eq, PartialEq, Clone, Copy, Encode, Decode, Debug, TypeInfo, MaxEncodedLen)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TokenError {
 	/// Funds are unavailable.
 	FundsUnavailable,
@@ -739,6 +741,97 @@ where
 	}
 }
 
+/// Error returned by the try-runtime hooks, e.g. `try_state`, `pre_upgrade` and `post_upgrade`.
+///
+/// Contrary to a plain `&'static str` this can carry context about what exactly broke, see
+/// [`try_runtime_error!`]. Any `&'static str` and [`DispatchError`] can be converted into it, so
+/// `ensure!` and `?` keep working in the hooks.
+#[derive(Eq, PartialEq, Clone, Encode, Decode, TypeInfo)]
+pub enum TryRuntimeError {
+	/// A dispatch error, e.g. returned by a call done as part of the checks.
+	Dispatch(DispatchError),
+	/// A description of the failure.
+	Other(RuntimeString),
+}
+
+impl TryRuntimeError {
+	/// Create a [`TryRuntimeError::Other`] from format arguments.
+	///
+	/// Prefer the [`try_runtime_error!`] macro.
+	pub fn from_fmt(args: sp_std::fmt::Arguments) -> Self {
+		#[cfg(feature = "std")]
+		let message = std::fmt::format(args);
+		#[cfg(not(feature = "std"))]
+		let message = {
+			let mut writer = sp_std::Writer::default();
+			let _ = sp_std::fmt::write(&mut writer, args);
+			writer.into_inner()
+		};
+
+		Self::Other(RuntimeString::Owned(message))
+	}
+}
+
+fn runtime_str(message: &RuntimeString) -> &str {
+	sp_std::str::from_utf8(message.as_ref()).unwrap_or("<invalid utf8>")
+}
+
+/// Create a [`TryRuntimeError`] with a formatted message, like `format!`.
+///
+/// ```
+/// # use sp_runtime::try_runtime_error;
+/// let who = 42u64;
+/// let err = try_runtime_error!("account {} has no providers", who);
+/// assert_eq!(err.to_string(), "account 42 has no providers");
+/// ```
+#[macro_export]
+macro_rules! try_runtime_error {
+	($($arg:tt)*) => {
+		$crate::TryRuntimeError::from_fmt(format_args!($($arg)*))
+	};
+}
+
+// Not using `RuntimeDebug`, the message should still be readable when the error is turned into a
+// panic in wasm.
+impl sp_std::fmt::Debug for TryRuntimeError {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		match self {
+			Self::Dispatch(err) => f.debug_tuple("Dispatch").field(err).finish(),
+			Self::Other(message) => f.debug_tuple("Other").field(&runtime_str(message)).finish(),
+		}
+	}
+}
+
+impl sp_std::fmt::Display for TryRuntimeError {
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		match self {
+			Self::Dispatch(err) => write!(f, "{:?}", err),
+			Self::Other(message) => f.write_str(runtime_str(message)),
+		}
+	}
+}
+
+impl From<&'static str> for TryRuntimeError {
+	fn from(message: &'static str) -> Self {
+		Self::Other(RuntimeString::Borrowed(message))
+	}
+}
+
+impl From<RuntimeString> for TryRuntimeError {
+	fn from(message: RuntimeString) -> Self {
+		Self::Other(message)
+	}
+}
+
+impl From<DispatchError> for TryRuntimeError {
+	fn from(err: DispatchError) -> Self {
+		Self::Dispatch(err)
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryRuntimeError {}
+
 /// This type specifies the outcome of dispatching a call to a module.
 ///
 /// In case of failure an error specific to the module is returned.
@@ -872,7 +965,7 @@ impl sp_std::fmt::Debug for OpaqueExtrinsic {
 	}
 }
 
-#[cfg(feature = "std")]
+#[cfg(feature = "serde")]
 impl ::serde::Serialize for OpaqueExtrinsic {
 	fn serialize<S>(&self, seq: S) -> Result<S::Ok, S::Error>
 	where
@@ -882,7 +975,7 @@ impl ::serde::Serialize for OpaqueExtrinsic {
 	}
 }
 
-#[cfg(feature = "std")]
+#[cfg(feature = "serde")]
 impl<'a> ::serde::Deserialize<'a> for OpaqueExtrinsic {
 	fn deserialize<D>(de: D) -> Result<Self, D::Error>
 	where
@@ -988,6 +1081,20 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn try_runtime_error_keeps_context() {
+		let err = try_runtime_error!("pool {} has {} members", 3, 7);
+		assert_eq!(err.to_string(), "pool 3 has 7 members");
+		assert_eq!(format!("{:?}", err), "Other(\"pool 3 has 7 members\")");
+
+		let decoded = TryRuntimeError::decode(&mut &err.encode()[..]).unwrap();
+		assert_eq!(decoded, err);
+
+		let err: TryRuntimeError = DispatchError::BadOrigin.into();
+		assert_eq!(err.to_string(), "BadOrigin");
+		assert_eq!(TryRuntimeError::from("foo").to_string(), "foo");
+	}
+
 	#[test]
 	fn dispatch_error_equality() {
 		use DispatchError::*;
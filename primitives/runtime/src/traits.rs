@@ -28,7 +28,7 @@ use crate::{
 	DispatchResult,
 };
 use impl_trait_for_tuples::impl_for_tuples;
-#[cfg(feature = "std")]
+#[cfg(feature = "serde")]
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sp_application_crypto::AppCrypto;
 pub use sp_arithmetic::traits::{
@@ -716,7 +716,7 @@ pub trait Hash:
 
 /// Blake2-256 Hash implementation.
 #[derive(PartialEq, Eq, Clone, RuntimeDebug, TypeInfo)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BlakeTwo256;
 
 impl Hasher for BlakeTwo256 {
@@ -743,7 +743,7 @@ impl Hash for BlakeTwo256 {
 
 /// Keccak-256 Hash implementation.
 #[derive(PartialEq, Eq, Clone, RuntimeDebug, TypeInfo)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Keccak256;
 
 impl Hasher for Keccak256 {
@@ -824,11 +824,14 @@ sp_core::impl_maybe_marker!(
 
 	/// A type that implements Hash when in std environment.
 	trait MaybeHash: sp_std::hash::Hash;
+);
 
-	/// A type that implements Serialize when in std environment.
+sp_core::impl_maybe_marker_std_or_serde!(
+	/// A type that implements Serialize when in std environment or serde feature is activated.
 	trait MaybeSerialize: Serialize;
 
-	/// A type that implements Serialize, DeserializeOwned and Debug when in std environment.
+	/// A type that implements Serialize, DeserializeOwned and Debug when in std environment or serde
+	/// feature is activated.
 	trait MaybeSerializeDeserialize: DeserializeOwned, Serialize;
 );
 
@@ -1760,7 +1763,7 @@ macro_rules! impl_opaque_keys_inner {
 /// }
 /// ```
 #[macro_export]
-#[cfg(feature = "std")]
+#[cfg(feature = "serde")]
 macro_rules! impl_opaque_keys {
 	{
 		$( #[ $attr:meta ] )*
@@ -1790,7 +1793,7 @@ macro_rules! impl_opaque_keys {
 }
 
 #[macro_export]
-#[cfg(not(feature = "std"))]
+#[cfg(not(feature = "serde"))]
 #[doc(hidden)]
 macro_rules! impl_opaque_keys {
 	{
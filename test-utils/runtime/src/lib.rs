@@ -25,6 +25,8 @@ pub mod system;
 
 use codec::{Decode, Encode, Error, Input, MaxEncodedLen};
 use scale_info::TypeInfo;
+#[cfg(not(feature = "std"))]
+use sp_std::alloc::format;
 use sp_std::{marker::PhantomData, prelude::*};
 
 use sp_application_crypto::{ecdsa, ed25519, sr25519, RuntimeAppPublic};
@@ -166,7 +168,6 @@ pub enum Extrinsic {
 	Store(Vec<u8>),
 }
 
-#[cfg(feature = "std")]
 impl serde::Serialize for Extrinsic {
 	fn serialize<S>(&self, seq: S) -> Result<S::Ok, S::Error>
 	where
@@ -177,7 +178,6 @@ impl serde::Serialize for Extrinsic {
 }
 
 // rustc can't deduce this trait bound https://github.com/rust-lang/rust/issues/48214
-#[cfg(feature = "std")]
 impl<'a> serde::Deserialize<'a> for Extrinsic {
 	fn deserialize<D>(de: D) -> Result<Self, D::Error>
 	where
@@ -1002,6 +1002,16 @@ cfg_if! {
 				}
 			}
 
+			impl sp_genesis_builder::GenesisBuilder<Block> for Runtime {
+				fn create_default_config() -> Vec<u8> {
+					system::create_default_config()
+				}
+
+				fn build_config(json: Vec<u8>) -> sp_genesis_builder::Result {
+					system::build_config(json)
+				}
+			}
+
 			impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Index> for Runtime {
 				fn account_nonce(_account: AccountId) -> Index {
 					0
@@ -1245,6 +1255,16 @@ cfg_if! {
 				}
 			}
 
+			impl sp_genesis_builder::GenesisBuilder<Block> for Runtime {
+				fn create_default_config() -> Vec<u8> {
+					system::create_default_config()
+				}
+
+				fn build_config(json: Vec<u8>) -> sp_genesis_builder::Result {
+					system::build_config(json)
+				}
+			}
+
 			impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Index> for Runtime {
 				fn account_nonce(_account: AccountId) -> Index {
 					0
@@ -25,7 +25,11 @@ use crate::{
 use codec::{Decode, Encode, KeyedVec};
 use frame_support::storage;
 use sp_core::storage::well_known_keys;
-use sp_io::{hashing::blake2_256, storage::root as storage_root, trie};
+use sp_io::{
+	hashing::{blake2_256, twox_128},
+	storage::root as storage_root,
+	trie,
+};
 use sp_runtime::{
 	generic,
 	traits::Header as _,
@@ -73,7 +77,7 @@ mod pallet {
 	pub type Authorities<T> = StorageValue<_, Vec<AuthorityId>, ValueQuery>;
 
 	#[pallet::genesis_config]
-	#[cfg_attr(feature = "std", derive(Default))]
+	#[derive(Default)]
 	pub struct GenesisConfig {
 		pub authorities: Vec<AuthorityId>,
 	}
@@ -86,6 +90,46 @@ mod pallet {
 	}
 }
 
+/// Genesis config of the runtime, as exposed through the `GenesisBuilder` runtime api.
+#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RuntimeGenesisConfig {
+	/// Raw sr25519 public keys of the initial authorities.
+	pub authorities: Vec<[u8; 32]>,
+	/// Raw sr25519 public keys of the endowed accounts, with their balance.
+	pub balances: Vec<([u8; 32], u64)>,
+}
+
+impl RuntimeGenesisConfig {
+	/// Writes the genesis state into the storage.
+	pub fn build(&self) {
+		for (who, balance) in &self.balances {
+			storage::hashed::put(&blake2_256, &balance_of_key(AccountId::from_raw(*who)), balance);
+		}
+		let authorities: Vec<AuthorityId> = self
+			.authorities
+			.iter()
+			.map(|who| sp_core::sr25519::Public::from_raw(*who).into())
+			.collect();
+		storage::unhashed::put(&twox_128(b"sys:auth"), &authorities);
+		<Authorities<Runtime>>::put(authorities);
+	}
+}
+
+/// Returns the JSON of the default [`RuntimeGenesisConfig`].
+pub fn create_default_config() -> Vec<u8> {
+	serde_json::to_vec(&RuntimeGenesisConfig::default())
+		.expect("serialization of the default config never fails; qed")
+}
+
+/// Builds the genesis state from the JSON of a [`RuntimeGenesisConfig`].
+pub fn build_config(json: Vec<u8>) -> sp_genesis_builder::Result {
+	let config = serde_json::from_slice::<RuntimeGenesisConfig>(&json)
+		.map_err(|e| sp_runtime::format_runtime_string!("Invalid genesis config: {}", e))?;
+	config.build();
+	Ok(())
+}
+
 pub fn balance_of_key(who: AccountId) -> Vec<u8> {
 	who.to_keyed_vec(BALANCE_OF)
 }
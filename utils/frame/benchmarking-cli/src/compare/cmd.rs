@@ -0,0 +1,220 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use comfy_table::Table;
+use log::{info, warn};
+
+use sc_cli::{CliConfiguration, Result, SharedParams};
+
+use super::summary::{load, Metric, ProofSize, Summary};
+
+/// Compare two sets of benchmark results and detect regressions.
+///
+/// Each input can either be the JSON output of `benchmark pallet --json-file` or a weight file
+/// that was generated by `benchmark pallet --output`. The worst case of each benchmark, with all
+/// components at their maximal value, is compared per metric.
+///
+/// Returns an error if any metric of any benchmark increased by more than `--threshold`.
+#[derive(Debug, Parser)]
+pub struct CompareCmd {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	/// The baseline results, e.g. the weight file of the target branch.
+	#[arg(value_name = "OLD")]
+	pub old: PathBuf,
+
+	/// The results to check for regressions.
+	#[arg(value_name = "NEW")]
+	pub new: PathBuf,
+
+	/// Maximal allowed increase of any metric.
+	///
+	/// 10% means that a benchmark fails once a metric increases by more than 10%.
+	#[arg(long, default_value_t = 10.0, value_name = "PERCENT")]
+	pub threshold: f64,
+
+	/// Also print benchmarks that did not change.
+	#[arg(long)]
+	pub all: bool,
+}
+
+/// The change of a single metric between two results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Change {
+	old: u128,
+	new: u128,
+}
+
+impl Change {
+	/// The relative change in percent.
+	///
+	/// Infinite if the metric was zero before but is not anymore.
+	fn percent(&self) -> f64 {
+		if self.old == 0 {
+			return if self.new == 0 { 0.0 } else { f64::INFINITY }
+		}
+		(self.new as f64 - self.old as f64) / self.old as f64 * 100.0
+	}
+
+	/// Whether the change is an increase larger than `threshold` percent.
+	fn exceeds(&self, threshold: f64) -> bool {
+		self.percent() > threshold
+	}
+
+	fn to_cell(self) -> String {
+		if self.old == self.new {
+			format!("{}", self.new)
+		} else {
+			format!("{} -> {} ({:+.2}%)", self.old, self.new, self.percent())
+		}
+	}
+}
+
+impl CompareCmd {
+	/// Compare the results and print a summary of the changes.
+	pub fn run(&self) -> Result<()> {
+		self.validate_args()?;
+		let (old, old_proof_size) = load(&self.old)?;
+		let (new, new_proof_size) = load(&self.new)?;
+
+		let metrics = comparable_metrics(old_proof_size, new_proof_size);
+		if !metrics.contains(&Metric::ProofSize) {
+			warn!(
+				"Not comparing the proof size, since it is {:?} in {} but {:?} in {}",
+				old_proof_size,
+				self.old.display(),
+				new_proof_size,
+				self.new.display()
+			);
+		}
+
+		let regressions = self.print_summary(&old, &new, &metrics);
+		if regressions > 0 {
+			return Err(format!(
+				"{} benchmarks regressed by more than {:.2}%",
+				regressions, self.threshold
+			)
+			.into())
+		}
+		info!("No benchmark regressed by more than {:.2}%", self.threshold);
+		Ok(())
+	}
+
+	/// Prints a table of all changed benchmarks and returns the number of regressions.
+	fn print_summary(&self, old: &Summary, new: &Summary, metrics: &[Metric]) -> usize {
+		let mut table = Table::new();
+		let mut header = vec!["Pallet".to_string(), "Extrinsic".to_string()];
+		header.extend(metrics.iter().map(ToString::to_string));
+		header.push("Result".into());
+		table.set_header(header);
+
+		let mut regressions = 0;
+		for (id, new_cost) in new.iter() {
+			let Some(old_cost) = old.get(id) else {
+				warn!("Benchmark {}::{} is new and has nothing to compare to", id.0, id.1);
+				continue
+			};
+			let changes = metrics
+				.iter()
+				.map(|metric| Change { old: old_cost.get(*metric), new: new_cost.get(*metric) })
+				.collect::<Vec<_>>();
+			let regressed = changes.iter().any(|c| c.exceeds(self.threshold));
+			if regressed {
+				regressions += 1;
+			} else if !self.all && changes.iter().all(|c| c.old == c.new) {
+				continue
+			}
+
+			let mut row = vec![id.0.clone(), id.1.clone()];
+			row.extend(changes.iter().map(|c| c.to_cell()));
+			row.push(if regressed { "❌ Regressed" } else { "✅ Ok" }.into());
+			table.add_row(row);
+		}
+		for id in old.keys().filter(|id| !new.contains_key(*id)) {
+			warn!("Benchmark {}::{} was removed", id.0, id.1);
+		}
+
+		info!(
+			"\n{}\nCompared {} benchmarks, {} regressed by more than {:.2}%.",
+			table,
+			new.keys().filter(|id| old.contains_key(*id)).count(),
+			regressions,
+			self.threshold
+		);
+		regressions
+	}
+
+	/// Validates the CLI arguments.
+	fn validate_args(&self) -> Result<()> {
+		if self.threshold < 0.0 {
+			return Err("The --threshold argument must not be negative".into())
+		}
+		Ok(())
+	}
+}
+
+/// The metrics that can be compared between two summaries.
+///
+/// A recorded proof size is not comparable to an estimated one, and is thus left out.
+fn comparable_metrics(old: ProofSize, new: ProofSize) -> Vec<Metric> {
+	Metric::ALL
+		.into_iter()
+		.filter(|metric| *metric != Metric::ProofSize || old == new)
+		.collect()
+}
+
+impl CliConfiguration for CompareCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
+
+#[cfg(test)]
+mod test_compare {
+	use super::{comparable_metrics, Change, Metric, ProofSize};
+
+	#[test]
+	fn change_percent_works() {
+		assert_eq!(Change { old: 100, new: 110 }.percent(), 10.0);
+		assert_eq!(Change { old: 100, new: 50 }.percent(), -50.0);
+		assert_eq!(Change { old: 0, new: 0 }.percent(), 0.0);
+		assert!(Change { old: 0, new: 1 }.percent().is_infinite());
+	}
+
+	#[test]
+	fn change_exceeds_threshold() {
+		assert!(!Change { old: 100, new: 110 }.exceeds(10.0));
+		assert!(Change { old: 100, new: 111 }.exceeds(10.0));
+		assert!(!Change { old: 100, new: 0 }.exceeds(10.0));
+		assert!(Change { old: 0, new: 1 }.exceeds(10.0));
+	}
+
+	#[test]
+	fn proof_size_is_only_compared_between_same_kinds() {
+		assert_eq!(comparable_metrics(ProofSize::Recorded, ProofSize::Recorded), Metric::ALL);
+		assert_eq!(comparable_metrics(ProofSize::Estimated, ProofSize::Estimated), Metric::ALL);
+		assert_eq!(
+			comparable_metrics(ProofSize::Estimated, ProofSize::Recorded),
+			[Metric::RefTime, Metric::Reads, Metric::Writes]
+		);
+	}
+}
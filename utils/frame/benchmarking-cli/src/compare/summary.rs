@@ -0,0 +1,380 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reduces benchmark results and weight files to the worst case cost of each benchmark.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use frame_benchmarking::{
+	Analysis, BenchmarkBatchSplitResults, BenchmarkResult, BenchmarkSelector,
+};
+use sc_cli::Result;
+
+/// Identifies a benchmark by the name of its pallet and extrinsic.
+pub type BenchmarkId = (String, String);
+
+/// The worst case cost of each benchmark.
+pub type Summary = BTreeMap<BenchmarkId, Cost>;
+
+/// How the proof size of a [`Summary`] was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofSize {
+	/// Recorded while running the benchmarks, as found in the JSON results.
+	Recorded,
+	/// Estimated from the storage info, as found in weight files.
+	Estimated,
+}
+
+/// A metric of a benchmark that can be compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+	RefTime,
+	ProofSize,
+	Reads,
+	Writes,
+}
+
+impl Metric {
+	/// All metrics in the order they are reported.
+	pub const ALL: [Metric; 4] =
+		[Metric::RefTime, Metric::ProofSize, Metric::Reads, Metric::Writes];
+}
+
+impl std::fmt::Display for Metric {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let name = match self {
+			Metric::RefTime => "Ref Time [ps]",
+			Metric::ProofSize => "Proof Size [B]",
+			Metric::Reads => "Reads",
+			Metric::Writes => "Writes",
+		};
+		write!(f, "{}", name)
+	}
+}
+
+/// The cost of a benchmark, or the cost per unit of one of its components.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cost {
+	/// Execution time in picoseconds.
+	pub ref_time: u128,
+	/// Proof size in bytes.
+	pub proof_size: u128,
+	/// Number of storage reads.
+	pub reads: u128,
+	/// Number of storage writes.
+	pub writes: u128,
+}
+
+impl Cost {
+	/// Returns the value of the given metric.
+	pub fn get(&self, metric: Metric) -> u128 {
+		match metric {
+			Metric::RefTime => self.ref_time,
+			Metric::ProofSize => self.proof_size,
+			Metric::Reads => self.reads,
+			Metric::Writes => self.writes,
+		}
+	}
+
+	fn get_mut(&mut self, metric: Metric) -> &mut u128 {
+		match metric {
+			Metric::RefTime => &mut self.ref_time,
+			Metric::ProofSize => &mut self.proof_size,
+			Metric::Reads => &mut self.reads,
+			Metric::Writes => &mut self.writes,
+		}
+	}
+}
+
+/// A linear cost model of a benchmark.
+#[derive(Debug, Default)]
+struct Model {
+	/// The cost independent of any component.
+	base: Cost,
+	/// The cost per unit of each component.
+	slopes: BTreeMap<String, Cost>,
+	/// The maximal value of each component.
+	max: BTreeMap<String, u32>,
+}
+
+impl Model {
+	/// Evaluates the model with each component at its maximal value.
+	fn worst_case(&self) -> Cost {
+		let mut cost = self.base;
+		for (name, slope) in self.slopes.iter() {
+			let max = self.max.get(name).copied().unwrap_or_default() as u128;
+			for metric in Metric::ALL {
+				let value = cost.get_mut(metric);
+				*value = value.saturating_add(slope.get(metric).saturating_mul(max));
+			}
+		}
+		cost
+	}
+}
+
+/// Loads a summary from either a `weights.rs` file or the JSON output of `benchmark pallet`.
+///
+/// Files ending with `.rs` are parsed as weight files, everything else as JSON. Also returns how
+/// the proof size of the summary was obtained.
+pub fn load(path: &Path) -> Result<(Summary, ProofSize)> {
+	let content = fs::read_to_string(path)
+		.map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+
+	if path.extension().map_or(false, |e| e == "rs") {
+		let summary =
+			from_weights_file(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+		Ok((summary, ProofSize::Estimated))
+	} else {
+		let batches: Vec<BenchmarkBatchSplitResults> = serde_json::from_str(&content)
+			.map_err(|e| format!("Could not parse {} as JSON: {}", path.display(), e))?;
+		Ok((from_json(&batches), ProofSize::Recorded))
+	}
+}
+
+/// Builds a summary from raw benchmark results by using a linear regression.
+///
+/// The proof size is the *recorded* one since the storage info needed for the estimation is not
+/// part of the results. It can thus only be compared to other [`ProofSize::Recorded`] summaries.
+pub fn from_json(batches: &[BenchmarkBatchSplitResults]) -> Summary {
+	let mut summary = Summary::new();
+	for batch in batches {
+		let id = (
+			String::from_utf8_lossy(&batch.pallet).into_owned(),
+			String::from_utf8_lossy(&batch.benchmark).into_owned(),
+		);
+		let mut model = Model::default();
+		for (results, metric, selector) in [
+			(&batch.time_results, Metric::RefTime, BenchmarkSelector::ExtrinsicTime),
+			(&batch.db_results, Metric::ProofSize, BenchmarkSelector::ProofSize),
+			(&batch.db_results, Metric::Reads, BenchmarkSelector::Reads),
+			(&batch.db_results, Metric::Writes, BenchmarkSelector::Writes),
+		] {
+			let Some(analysis) = Analysis::min_squares_iqr(results, selector) else {
+				log::warn!("Could not analyze the {} of {}::{}", metric, id.0, id.1);
+				continue
+			};
+			*model.base.get_mut(metric) = analysis.base;
+			for (name, slope) in analysis.names.iter().zip(analysis.slopes.iter()) {
+				*model.slopes.entry(name.clone()).or_default().get_mut(metric) = *slope;
+			}
+			track_max_components(&mut model.max, results);
+		}
+		summary.insert(id, model.worst_case());
+	}
+	summary
+}
+
+/// Records the maximal value of each component in `results`.
+fn track_max_components(max: &mut BTreeMap<String, u32>, results: &[BenchmarkResult]) {
+	for (param, value) in results.iter().flat_map(|r| r.components.iter()) {
+		let entry = max.entry(param.to_string()).or_default();
+		*entry = (*entry).max(*value);
+	}
+}
+
+/// Builds a summary from a weight file that was generated by `benchmark pallet`.
+///
+/// Only the first definition of each weight function is considered, which is the one using
+/// `T::DbWeight` in the default template.
+pub fn from_weights_file(content: &str) -> std::result::Result<Summary, String> {
+	let pallet = content
+		.lines()
+		.find_map(|l| l.trim().strip_prefix("//! Autogenerated weights for"))
+		.map(|p| p.trim().trim_matches('`').to_string())
+		.ok_or("Could not find the `Autogenerated weights for` header")?;
+
+	let mut summary = Summary::new();
+	let mut ranges = BTreeMap::new();
+	let mut current: Option<(String, Model)> = None;
+	for line in content.lines().map(str::trim) {
+		if let Some((name, model)) = current.as_mut() {
+			if line == "}" {
+				let id = (pallet.clone(), name.clone());
+				summary.entry(id).or_insert_with(|| model.worst_case());
+				current = None;
+			} else if !line.starts_with("//") {
+				parse_weight_term(line, model)
+					.map_err(|e| format!("Could not parse `{}` in `{}`: {}", line, name, e))?;
+			}
+		} else if let Some(range) = line.strip_prefix("/// The range of component `") {
+			let (name, range) = parse_component_range(range)
+				.ok_or_else(|| format!("Could not parse the component range in `{}`", line))?;
+			ranges.insert(name, range);
+		} else if let Some(signature) = line.strip_prefix("fn ") {
+			// Declarations in the `WeightInfo` trait have no body.
+			if !signature.ends_with('{') {
+				continue
+			}
+			let name = signature.split('(').next().unwrap_or_default().trim().to_string();
+			let model = Model { max: std::mem::take(&mut ranges), ..Default::default() };
+			current = Some((name, model));
+		}
+	}
+
+	Ok(summary)
+}
+
+/// Parses the remainder of a ``/// The range of component `x` is `[min, max]`.`` comment.
+fn parse_component_range(range: &str) -> Option<(String, u32)> {
+	let (name, rest) = range.split_once('`')?;
+	let max = rest.split_once(", ")?.1.split_once(']')?.0;
+	Some((name.to_string(), parse_number(max)?.try_into().ok()?))
+}
+
+/// Parses a single term of a weight function and adds it to the model.
+///
+/// Understands the expressions that `template.hbs` produces, for example:
+/// - `Weight::from_parts(1_000, 0)`
+/// - `.saturating_add(Weight::from_parts(10, 0).saturating_mul(c.into()))`
+/// - `.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(c.into())))`
+fn parse_weight_term(line: &str, model: &mut Model) -> std::result::Result<(), &'static str> {
+	let term = line.strip_prefix(".saturating_add(").unwrap_or(line);
+	let cost = match term.split_once(".saturating_mul(") {
+		Some((_, component)) => {
+			let component =
+				component.split_once(".into()").ok_or("Expected `.into()` after component")?.0;
+			model.slopes.entry(component.to_string()).or_default()
+		},
+		None => &mut model.base,
+	};
+
+	if let Some(args) = term.strip_prefix("Weight::from_parts(") {
+		let (ref_time, proof_size) = args
+			.split_once(')')
+			.and_then(|(args, _)| args.split_once(','))
+			.ok_or("Expected two arguments")?;
+		cost.ref_time += parse_number(ref_time).ok_or("Invalid ref time")? as u128;
+		cost.proof_size += parse_number(proof_size).ok_or("Invalid proof size")? as u128;
+	} else if let Some(reads) = term.split_once(".reads(").map(|(_, r)| r) {
+		cost.reads += parse_number(reads).ok_or("Invalid reads")? as u128;
+	} else if let Some(writes) = term.split_once(".writes(").map(|(_, w)| w) {
+		cost.writes += parse_number(writes).ok_or("Invalid writes")? as u128;
+	} else {
+		return Err("Unknown term")
+	}
+	Ok(())
+}
+
+/// Parses the leading number of `s`, ignoring opening parentheses, underscores and a type suffix.
+fn parse_number(s: &str) -> Option<u64> {
+	let digits: String = s
+		.trim_start_matches(|c: char| c == '(' || c.is_whitespace())
+		.chars()
+		.take_while(|c| c.is_ascii_digit() || *c == '_')
+		.filter(|c| *c != '_')
+		.collect();
+	digits.parse().ok()
+}
+
+#[cfg(test)]
+mod test_summary {
+	use super::*;
+	use frame_benchmarking::BenchmarkParameter;
+
+	const WEIGHTS: &str = r#"
+//! Autogenerated weights for `pallet_example`
+
+pub trait WeightInfo {
+	fn simple() -> Weight;
+	fn linear(c: u32, ) -> Weight;
+}
+
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: System Account (r:1 w:1)
+	fn simple() -> Weight {
+		// Minimum execution time: 9_000_000 picoseconds.
+		Weight::from_parts(10_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// The range of component `c` is `[0, 100]`.
+	fn linear(c: u32, _d: u32, ) -> Weight {
+		Weight::from_parts(5_000, 0)
+			.saturating_add(Weight::from_parts(0, 1_000))
+			// Standard Error: 1_234
+			.saturating_add(Weight::from_parts(200, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 50).saturating_mul(c.into()))
+	}
+}
+
+impl WeightInfo for () {
+	fn simple() -> Weight {
+		Weight::from_parts(1, 1)
+	}
+}
+"#;
+
+	fn id(benchmark: &str) -> BenchmarkId {
+		("pallet_example".into(), benchmark.into())
+	}
+
+	#[test]
+	fn weights_file_is_parsed() {
+		let summary = from_weights_file(WEIGHTS).unwrap();
+
+		assert_eq!(summary.len(), 2);
+		assert_eq!(
+			summary[&id("simple")],
+			Cost { ref_time: 10_000_000, proof_size: 3593, reads: 1, writes: 1 }
+		);
+		assert_eq!(
+			summary[&id("linear")],
+			Cost {
+				ref_time: 5_000 + 200 * 100,
+				proof_size: 1_000 + 50 * 100,
+				reads: 102,
+				writes: 300
+			}
+		);
+	}
+
+	#[test]
+	fn weights_file_without_header_errors() {
+		assert!(from_weights_file("fn simple() -> Weight {\n}").is_err());
+	}
+
+	#[test]
+	fn json_results_are_analyzed() {
+		let result = |c: u32| BenchmarkResult {
+			components: vec![(BenchmarkParameter::c, c)],
+			extrinsic_time: 10 + 2 * c as u128,
+			reads: 1 + c,
+			writes: 1,
+			proof_size: 100,
+			..Default::default()
+		};
+		let results = (0..=10).map(result).collect::<Vec<_>>();
+		let batch = BenchmarkBatchSplitResults {
+			pallet: b"pallet_example".to_vec(),
+			instance: b"Example".to_vec(),
+			benchmark: b"linear".to_vec(),
+			time_results: results.clone(),
+			db_results: results,
+		};
+
+		let summary = from_json(&[batch]);
+
+		assert_eq!(
+			summary[&id("linear")],
+			// The extrinsic time is given in nanoseconds.
+			Cost { ref_time: 30_000, proof_size: 100, reads: 11, writes: 1 }
+		);
+	}
+}
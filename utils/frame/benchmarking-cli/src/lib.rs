@@ -18,6 +18,7 @@
 //! Contains the root [`BenchmarkCmd`] command and exports its sub-commands.
 
 mod block;
+mod compare;
 mod extrinsic;
 mod machine;
 mod overhead;
@@ -26,6 +27,7 @@ mod shared;
 mod storage;
 
 pub use block::BlockCmd;
+pub use compare::CompareCmd;
 pub use extrinsic::{ExtrinsicBuilder, ExtrinsicCmd, ExtrinsicFactory};
 pub use machine::{MachineCmd, SUBSTRATE_REFERENCE_HARDWARE};
 pub use overhead::OverheadCmd;
@@ -46,6 +48,7 @@ pub enum BenchmarkCmd {
 	Block(BlockCmd),
 	Machine(MachineCmd),
 	Extrinsic(ExtrinsicCmd),
+	Compare(CompareCmd),
 }
 
 /// Unwraps a [`BenchmarkCmd`] into its concrete sub-command.
@@ -62,6 +65,7 @@ macro_rules! unwrap_cmd {
 			BenchmarkCmd::Block($cmd) => $code,
 			BenchmarkCmd::Machine($cmd) => $code,
 			BenchmarkCmd::Extrinsic($cmd) => $code,
+			BenchmarkCmd::Compare($cmd) => $code,
 		}
 	}
 }
@@ -85,6 +85,9 @@ impl FromStr for PovEstimationMode {
 pub(crate) type PovModesMap =
 	HashMap<(Vec<u8>, Vec<u8>), HashMap<(String, String), PovEstimationMode>>;
 
+/// Maps (pallet, benchmark) -> (storage key prefix -> size of the largest value read)
+pub(crate) type ReadSizesMap = HashMap<(Vec<u8>, Vec<u8>), HashMap<Vec<u8>, u32>>;
+
 // This takes multiple benchmark batches and combines all the results where the pallet, instance,
 // and benchmark are the same.
 fn combine_batches(
@@ -302,6 +305,7 @@ impl PalletCmd {
 		// Maps (pallet, extrinsic) to its component ranges.
 		let mut component_ranges = HashMap::<(Vec<u8>, Vec<u8>), Vec<ComponentRange>>::new();
 		let pov_modes = Self::parse_pov_modes(&benchmarks_to_run)?;
+		let mut read_sizes = ReadSizesMap::new();
 
 		for (pallet, extrinsic, components, _) in benchmarks_to_run.clone() {
 			log::info!(
@@ -430,6 +434,17 @@ impl PalletCmd {
 						.map_err(|e| format!("Failed to decode benchmark results: {:?}", e))??;
 
 					batches_db.extend(batch);
+
+					// Always take them, so they do not leak into the next benchmark.
+					let benchmark_read_sizes = state.take_read_value_sizes();
+					if self.suggest_pov_modes {
+						let sizes =
+							read_sizes.entry((pallet.clone(), extrinsic.clone())).or_default();
+						for (prefix, size) in benchmark_read_sizes {
+							let max_size = sizes.entry(prefix).or_default();
+							*max_size = (*max_size).max(size);
+						}
+					}
 				}
 				// Finally run a bunch of loops to get extrinsic timing information.
 				for r in 0..self.external_repeat {
@@ -491,7 +506,7 @@ impl PalletCmd {
 		// Combine all of the benchmark results, so that benchmarks of the same pallet/function
 		// are together.
 		let batches = combine_batches(batches, batches_db);
-		self.output(&batches, &storage_info, &component_ranges, pov_modes)
+		self.output(&batches, &storage_info, &component_ranges, pov_modes, read_sizes)
 	}
 
 	fn output(
@@ -500,11 +515,12 @@ impl PalletCmd {
 		storage_info: &[StorageInfo],
 		component_ranges: &HashMap<(Vec<u8>, Vec<u8>), Vec<ComponentRange>>,
 		pov_modes: PovModesMap,
+		read_sizes: ReadSizesMap,
 	) -> Result<()> {
 		// Jsonify the result and write it to a file or stdout if desired.
 		if !self.jsonify(&batches)? {
 			// Print the summary only if `jsonify` did not write to stdout.
-			self.print_summary(&batches, &storage_info, pov_modes.clone())
+			self.print_summary(&batches, &storage_info, pov_modes.clone(), &read_sizes)
 		}
 
 		// Create the weights.rs file.
@@ -514,6 +530,7 @@ impl PalletCmd {
 				&storage_info,
 				&component_ranges,
 				pov_modes,
+				&read_sizes,
 				self.default_pov_mode,
 				output_path,
 				self,
@@ -556,7 +573,7 @@ impl PalletCmd {
 			})
 			.collect();
 
-		self.output(batches, &[], &component_ranges, Default::default())
+		self.output(batches, &[], &component_ranges, Default::default(), Default::default())
 	}
 
 	/// Jsonifies the passed batches and writes them to stdout or into a file.
@@ -584,6 +601,7 @@ impl PalletCmd {
 		batches: &[BenchmarkBatchSplitResults],
 		storage_info: &[StorageInfo],
 		pov_modes: PovModesMap,
+		read_sizes: &ReadSizesMap,
 	) {
 		for batch in batches.iter() {
 			// Print benchmark metadata
@@ -608,12 +626,17 @@ impl PalletCmd {
 					.get(&(batch.pallet.clone(), batch.benchmark.clone()))
 					.cloned()
 					.unwrap_or_default();
+				let read_sizes = read_sizes
+					.get(&(batch.pallet.clone(), batch.benchmark.clone()))
+					.cloned()
+					.unwrap_or_default();
 
 				let comments = writer::process_storage_results(
 					&mut storage_per_prefix,
 					&batch.db_results,
 					storage_info,
 					&pov_mode,
+					&read_sizes,
 					self.default_pov_mode,
 					self.worst_case_map_values,
 					self.additional_trie_layers,
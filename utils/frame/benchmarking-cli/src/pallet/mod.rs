@@ -111,6 +111,13 @@ pub struct PalletCmd {
 	#[arg(long, default_value("max-encoded-len"), value_enum)]
 	pub default_pov_mode: command::PovEstimationMode,
 
+	/// Compare the size of the values read by each benchmark to their `MaxEncodedLen` estimate and
+	/// suggest a PoV estimation mode per storage item.
+	///
+	/// The evidence is added as comment to the storage items in the summary and weight files.
+	#[arg(long)]
+	pub suggest_pov_modes: bool,
+
 	/// Set the heap pages while running benchmarks. If not set, the default value from the client
 	/// is used.
 	#[arg(long)]
@@ -28,7 +28,7 @@ use itertools::Itertools;
 use serde::Serialize;
 
 use crate::{
-	pallet::command::{ComponentRange, PovEstimationMode, PovModesMap},
+	pallet::command::{ComponentRange, PovEstimationMode, PovModesMap, ReadSizesMap},
 	shared::UnderscoreHelper,
 	PalletCmd,
 };
@@ -42,6 +42,10 @@ use sp_runtime::traits::Zero;
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const TEMPLATE: &str = include_str!("./template.hbs");
 
+/// A storage item is considered over-estimated if its `MaxEncodedLen` is more than this factor
+/// larger than the largest value that was read.
+const POV_OVERESTIMATION_FACTOR: u32 = 10;
+
 // This is the final structure we will pass to the Handlebars template.
 #[derive(Serialize, Default, Debug, Clone)]
 struct TemplateData {
@@ -135,6 +139,7 @@ fn map_results(
 	storage_info: &[StorageInfo],
 	component_ranges: &HashMap<(Vec<u8>, Vec<u8>), Vec<ComponentRange>>,
 	pov_modes: PovModesMap,
+	read_sizes: &ReadSizesMap,
 	default_pov_mode: PovEstimationMode,
 	analysis_choice: &AnalysisChoice,
 	pov_analysis_choice: &AnalysisChoice,
@@ -161,6 +166,7 @@ fn map_results(
 			storage_info,
 			&component_ranges,
 			pov_modes.clone(),
+			read_sizes,
 			default_pov_mode,
 			analysis_choice,
 			pov_analysis_choice,
@@ -191,6 +197,7 @@ fn get_benchmark_data(
 	// Per extrinsic component ranges.
 	component_ranges: &HashMap<(Vec<u8>, Vec<u8>), Vec<ComponentRange>>,
 	pov_modes: PovModesMap,
+	read_sizes: &ReadSizesMap,
 	default_pov_mode: PovEstimationMode,
 	analysis_choice: &AnalysisChoice,
 	pov_analysis_choice: &AnalysisChoice,
@@ -286,11 +293,16 @@ fn get_benchmark_data(
 		.get(&(batch.pallet.clone(), batch.benchmark.clone()))
 		.cloned()
 		.unwrap_or_default();
+	let read_sizes = read_sizes
+		.get(&(batch.pallet.clone(), batch.benchmark.clone()))
+		.cloned()
+		.unwrap_or_default();
 	let comments = process_storage_results(
 		&mut storage_per_prefix,
 		&batch.db_results,
 		storage_info,
 		&pov_mode,
+		&read_sizes,
 		default_pov_mode,
 		worst_case_map_values,
 		additional_trie_layers,
@@ -379,6 +391,7 @@ pub(crate) fn write_results(
 	storage_info: &[StorageInfo],
 	component_ranges: &HashMap<(Vec<u8>, Vec<u8>), Vec<ComponentRange>>,
 	pov_modes: PovModesMap,
+	read_sizes: &ReadSizesMap,
 	default_pov_mode: PovEstimationMode,
 	path: &PathBuf,
 	cmd: &PalletCmd,
@@ -445,6 +458,7 @@ pub(crate) fn write_results(
 		storage_info,
 		component_ranges,
 		pov_modes,
+		read_sizes,
 		default_pov_mode,
 		&analysis_choice,
 		&pov_analysis_choice,
@@ -502,12 +516,16 @@ pub(crate) fn write_results(
 /// from the pallets, and creates comments with information about the storage keys touched during
 /// each benchmark.
 ///
+/// If `read_sizes` contains the size of the largest value that was read for a key prefix, it also
+/// compares it to the `MaxEncodedLen` of the storage item and suggests a PoV estimation mode.
+///
 /// It returns informational comments for human consumption.
 pub(crate) fn process_storage_results(
 	storage_per_prefix: &mut HashMap<Vec<u8>, Vec<BenchmarkResult>>,
 	results: &[BenchmarkResult],
 	storage_info: &[StorageInfo],
 	pov_modes: &HashMap<(String, String), PovEstimationMode>,
+	read_sizes: &HashMap<Vec<u8>, u32>,
 	default_pov_mode: PovEstimationMode,
 	worst_case_map_values: u32,
 	additional_trie_layers: u8,
@@ -661,7 +679,24 @@ pub(crate) fn process_storage_results(
 							reads,
 							writes,
 						);
-						comments.push(comment)
+						comments.push(comment);
+
+						if let Some(read_size) = read_sizes.get(&prefix) {
+							let (suggested_mode, verdict) =
+								suggest_pov_mode(key_info.max_size, *read_size);
+							let comment = format!(
+								"PoV: {} {} (measured: {}, max_size: {:?}, {}, suggested mode: {:?})",
+								String::from_utf8(key_info.pallet_name.clone())
+									.expect("encoded from string"),
+								String::from_utf8(key_info.storage_name.clone())
+									.expect("encoded from string"),
+								read_size,
+								key_info.max_size,
+								verdict,
+								suggested_mode,
+							);
+							comments.push(comment);
+						}
 					},
 					None => {
 						let comment = format!(
@@ -730,6 +765,26 @@ pub(crate) fn process_storage_results(
 	comments
 }
 
+/// Suggests a PoV estimation mode for a storage item given its `max_size` and the size of the
+/// largest value that was read from it.
+///
+/// Returns the suggested mode together with the reasoning.
+fn suggest_pov_mode(max_size: Option<u32>, read_size: u32) -> (PovEstimationMode, String) {
+	match max_size {
+		None => (PovEstimationMode::Measured, "unbounded".into()),
+		Some(max_size) if read_size > max_size => (
+			PovEstimationMode::Measured,
+			format!("under-estimated by {} bytes", read_size - max_size),
+		),
+		Some(max_size) if max_size > read_size.max(1).saturating_mul(POV_OVERESTIMATION_FACTOR) =>
+			(
+				PovEstimationMode::Measured,
+				format!("over-estimated by {}x", max_size / read_size.max(1)),
+			),
+		Some(_) => (PovEstimationMode::MaxEncodedLen, "accurate".into()),
+	}
+}
+
 /// The PoV overhead when reading a key the first time out of a map with `max_values` entries.
 fn single_read_pov_overhead(max_values: Option<u32>, worst_case_map_values: u32) -> u32 {
 	let max_values = max_values.unwrap_or(worst_case_map_values);
@@ -948,6 +1003,7 @@ mod test {
 			&storage_info,
 			&Default::default(),
 			test_pov_mode(),
+			&Default::default(),
 			PovEstimationMode::MaxEncodedLen,
 			&AnalysisChoice::default(),
 			&AnalysisChoice::MedianSlopes,
@@ -1005,6 +1061,7 @@ mod test {
 			&storage_info,
 			&Default::default(),
 			test_pov_mode(),
+			&Default::default(),
 			PovEstimationMode::MaxEncodedLen,
 			&AnalysisChoice::default(),
 			&AnalysisChoice::MedianSlopes,
@@ -1062,6 +1119,7 @@ mod test {
 			&storage_info,
 			&Default::default(),
 			test_pov_mode(),
+			&Default::default(),
 			PovEstimationMode::MaxEncodedLen,
 			&AnalysisChoice::default(),
 			&AnalysisChoice::MedianSlopes,
@@ -1117,6 +1175,7 @@ mod test {
 			&storage_info,
 			&Default::default(),
 			test_pov_mode(),
+			&Default::default(),
 			PovEstimationMode::MaxEncodedLen,
 			&AnalysisChoice::default(),
 			&AnalysisChoice::MedianSlopes,
@@ -1174,6 +1233,7 @@ mod test {
 			&storage_info,
 			&Default::default(),
 			test_pov_mode(),
+			&Default::default(),
 			PovEstimationMode::Ignored,
 			&AnalysisChoice::default(),
 			&AnalysisChoice::MedianSlopes,
@@ -1202,6 +1262,7 @@ mod test {
 			&test_storage_info(),
 			&Default::default(),
 			Default::default(),
+			&Default::default(),
 			PovEstimationMode::MaxEncodedLen,
 			&AnalysisChoice::default(),
 			&AnalysisChoice::MedianSlopes,
@@ -1249,6 +1310,7 @@ mod test {
 			&test_storage_info(),
 			&Default::default(),
 			Default::default(),
+			&Default::default(),
 			PovEstimationMode::MaxEncodedLen,
 			&AnalysisChoice::default(),
 			&AnalysisChoice::MedianSlopes,
@@ -1264,6 +1326,7 @@ mod test {
 			&test_storage_info(),
 			&Default::default(),
 			Default::default(),
+			&Default::default(),
 			PovEstimationMode::MaxEncodedLen,
 			&AnalysisChoice::default(),
 			&AnalysisChoice::MedianSlopes,
@@ -1297,6 +1360,7 @@ mod test {
 			&test_storage_info(),
 			&Default::default(),
 			Default::default(),
+			&Default::default(),
 			PovEstimationMode::MaxEncodedLen,
 			&AnalysisChoice::default(),
 			&AnalysisChoice::MedianSlopes,
@@ -1321,6 +1385,53 @@ mod test {
 		}
 	}
 
+	#[test]
+	fn suggest_pov_mode_works() {
+		use PovEstimationMode::*;
+
+		assert_eq!(suggest_pov_mode(None, 10), (Measured, "unbounded".into()));
+		assert_eq!(suggest_pov_mode(Some(8), 10), (Measured, "under-estimated by 2 bytes".into()));
+		assert_eq!(suggest_pov_mode(Some(10), 10), (MaxEncodedLen, "accurate".into()));
+		assert_eq!(suggest_pov_mode(Some(100), 10), (MaxEncodedLen, "accurate".into()));
+		assert_eq!(suggest_pov_mode(Some(101), 10), (Measured, "over-estimated by 10x".into()));
+		assert_eq!(suggest_pov_mode(Some(64), 0), (Measured, "over-estimated by 64x".into()));
+	}
+
+	#[test]
+	fn read_sizes_are_reported_in_comments() {
+		let data = test_data(b"first", b"first", BenchmarkParameter::a, 10, 3);
+		let read_sizes = [(b"bounded".to_vec(), 2)].into_iter().collect();
+
+		let comments = process_storage_results(
+			&mut Default::default(),
+			&data.db_results,
+			&test_storage_info(),
+			&Default::default(),
+			&read_sizes,
+			PovEstimationMode::MaxEncodedLen,
+			1_000_000,
+			0,
+		);
+		assert!(comments.contains(
+			&"PoV: bounded bounded (measured: 2, max_size: Some(32), over-estimated by 16x, \
+			suggested mode: Measured)"
+				.to_string()
+		));
+
+		// Nothing is reported without read sizes.
+		let comments = process_storage_results(
+			&mut Default::default(),
+			&data.db_results,
+			&test_storage_info(),
+			&Default::default(),
+			&Default::default(),
+			PovEstimationMode::MaxEncodedLen,
+			1_000_000,
+			0,
+		);
+		assert!(comments.iter().all(|c| !c.starts_with("PoV:")));
+	}
+
 	#[test]
 	fn easy_log_16_works() {
 		assert_eq!(easy_log_16(0), 0);
@@ -35,7 +35,9 @@ use sp_core::{
 pub use sp_io::TestExternalities;
 use sp_runtime::{traits::Block as BlockT, StateVersion};
 use std::{
+	collections::BTreeMap,
 	fs,
+	io::{BufWriter, Read, Seek, SeekFrom, Write},
 	num::NonZeroUsize,
 	ops::{Deref, DerefMut},
 	path::{Path, PathBuf},
@@ -55,7 +57,19 @@ const DEFAULT_WS_ENDPOINT: &str = "wss://rpc.polkadot.io:443";
 const DEFAULT_VALUE_DOWNLOAD_BATCH: usize = 4096;
 // NOTE: increasing this value does not seem to impact speed all that much.
 const DEFAULT_KEY_DOWNLOAD_PAGE: u32 = 1000;
-/// The snapshot that we store on disk.
+/// The magic bytes every versioned snapshot file starts with.
+const SNAPSHOT_MAGIC: [u8; 8] = *b"rext-snp";
+/// The version of the snapshot format written by this crate.
+const SNAPSHOT_VERSION: u16 = 1;
+/// The zstd compression level of the snapshot chunks.
+const SNAPSHOT_COMPRESSION_LEVEL: i32 = 3;
+/// The length of the key prefix by which top keys are grouped into chunks, i.e. the length of a
+/// hashed pallet prefix.
+const SNAPSHOT_CHUNK_PREFIX_LEN: usize = 16;
+
+/// The state loaded from a snapshot.
+///
+/// This is also the legacy on-disk format, which was a single SCALE encoded blob of this struct.
 #[derive(Decode, Encode)]
 struct Snapshot<B: BlockT> {
 	state_version: StateVersion,
@@ -64,6 +78,188 @@ struct Snapshot<B: BlockT> {
 	child: ChildKeyValues,
 }
 
+/// The header of a versioned snapshot file, describing its contents.
+///
+/// On disk, a snapshot is laid out as:
+///
+/// 1. The magic bytes `rext-snp`.
+/// 2. The format version, as a little endian `u16`.
+/// 3. The zstd compressed chunks, each being a SCALE encoded `Vec<(StorageKey, StorageData)>`.
+/// 4. The SCALE encoded header.
+/// 5. The length of the SCALE encoded header, as a little endian `u32`.
+///
+/// The header comes last, so that the chunks can be written as soon as they are compressed.
+#[derive(Debug, Clone, Decode, Encode)]
+pub struct SnapshotHeader<Hash> {
+	/// The block hash at which the state was scraped.
+	pub block_hash: Hash,
+	/// The state version of the scraped state.
+	pub state_version: StateVersion,
+	/// The spec name of the runtime at `block_hash`.
+	pub spec_name: String,
+	/// The spec version of the runtime at `block_hash`.
+	pub spec_version: u32,
+	/// The pallets that were scraped. Empty if the entire state was scraped.
+	pub pallets: Vec<String>,
+	/// The chunks of the snapshot, in the order they are stored.
+	pub chunks: Vec<SnapshotChunk>,
+}
+
+/// The content of a [`SnapshotChunk`].
+#[derive(Debug, Clone, Decode, Encode, PartialEq, Eq)]
+pub enum ChunkKind {
+	/// The top trie key-values starting with the given prefix.
+	Top(Vec<u8>),
+	/// All key-values of the given child trie.
+	Child(ChildInfo),
+}
+
+impl ChunkKind {
+	/// The storage prefix of this chunk, as seen from the top trie.
+	pub fn prefix(&self) -> Vec<u8> {
+		match self {
+			Self::Top(prefix) => prefix.clone(),
+			Self::Child(info) => info.prefixed_storage_key().into_inner(),
+		}
+	}
+}
+
+/// A single compressed chunk of a snapshot file.
+#[derive(Debug, Clone, Decode, Encode)]
+pub struct SnapshotChunk {
+	/// What the chunk contains.
+	pub kind: ChunkKind,
+	/// The offset of the compressed data, relative to the end of the format version.
+	pub offset: u64,
+	/// The length of the compressed data.
+	pub compressed_len: u64,
+	/// The number of keys in the chunk.
+	pub keys: u32,
+}
+
+/// Whether `key` is a well-known key, such as `:code`, which is always loaded from a snapshot.
+fn is_well_known_key(key: &[u8]) -> bool {
+	key.starts_with(b":") && !is_default_child_storage_key(key)
+}
+
+/// Whether `key` should be loaded given the `hashed_prefixes` filter. An empty filter selects
+/// everything.
+fn is_key_selected(key: &[u8], hashed_prefixes: &[Vec<u8>]) -> bool {
+	hashed_prefixes.is_empty() ||
+		is_well_known_key(key) ||
+		hashed_prefixes.iter().any(|p| key.starts_with(p))
+}
+
+/// Write the given state to `out` in the versioned snapshot format, one chunk at a time. The
+/// chunks of `header` are overwritten.
+fn write_snapshot<Hash: Encode>(
+	mut out: impl Write,
+	mut header: SnapshotHeader<Hash>,
+	top: TopKeyValues,
+	child: ChildKeyValues,
+) -> Result<(), &'static str> {
+	let mut top_chunks = BTreeMap::<Vec<u8>, Vec<KeyValue>>::new();
+	for (k, v) in top {
+		let prefix = k.0[..k.0.len().min(SNAPSHOT_CHUNK_PREFIX_LEN)].to_vec();
+		top_chunks.entry(prefix).or_default().push((k, v));
+	}
+	let chunks = top_chunks
+		.into_iter()
+		.map(|(prefix, kv)| (ChunkKind::Top(prefix), kv))
+		.chain(child.into_iter().map(|(info, kv)| (ChunkKind::Child(info), kv)));
+
+	out.write_all(&SNAPSHOT_MAGIC).map_err(|_| "fs::write failed")?;
+	out.write_all(&SNAPSHOT_VERSION.to_le_bytes()).map_err(|_| "fs::write failed")?;
+
+	let mut offset = 0u64;
+	header.chunks.clear();
+	for (kind, kv) in chunks {
+		let compressed = zstd::stream::encode_all(&*kv.encode(), SNAPSHOT_COMPRESSION_LEVEL)
+			.map_err(|_| "snapshot chunk compression failed")?;
+		out.write_all(&compressed).map_err(|_| "fs::write failed")?;
+		header.chunks.push(SnapshotChunk {
+			kind,
+			offset,
+			compressed_len: compressed.len() as u64,
+			keys: kv.len() as u32,
+		});
+		offset += compressed.len() as u64;
+	}
+
+	let encoded_header = header.encode();
+	out.write_all(&encoded_header).map_err(|_| "fs::write failed")?;
+	out.write_all(&(encoded_header.len() as u32).to_le_bytes())
+		.map_err(|_| "fs::write failed")?;
+	out.flush().map_err(|_| "fs::write failed")
+}
+
+/// Read the header of the versioned snapshot in `file`, leaving the cursor at the start of the
+/// chunks.
+///
+/// The lengths stored in the file are checked against its size before anything is allocated, so
+/// the chunks of the returned header can be read without further checks.
+///
+/// Returns `Ok(None)` if the file is not a versioned snapshot, and thus presumably in the legacy
+/// format.
+fn read_snapshot_header<Hash: Decode>(
+	file: &mut fs::File,
+) -> Result<Option<SnapshotHeader<Hash>>, &'static str> {
+	let mut magic = [0u8; SNAPSHOT_MAGIC.len()];
+	if file.read_exact(&mut magic).is_err() || magic != SNAPSHOT_MAGIC {
+		return Ok(None)
+	}
+
+	let mut version = [0u8; 2];
+	file.read_exact(&mut version).map_err(|_| "fs::read failed.")?;
+	let version = u16::from_le_bytes(version);
+	if version != SNAPSHOT_VERSION {
+		error!(
+			target: LOG_TARGET,
+			"snapshot format version {} is not supported, expected {}", version, SNAPSHOT_VERSION,
+		);
+		return Err("unsupported snapshot version")
+	}
+
+	let data_start = file.stream_position().map_err(|_| "fs::seek failed.")?;
+	let file_len = file.metadata().map_err(|_| "fs::metadata failed.")?.len();
+	// the chunks and the header are followed by the length of the header.
+	let data_and_header_len = file_len
+		.checked_sub(data_start + 4)
+		.ok_or("snapshot is truncated")?;
+
+	let mut len = [0u8; 4];
+	file.seek(SeekFrom::End(-4)).map_err(|_| "fs::seek failed.")?;
+	file.read_exact(&mut len).map_err(|_| "fs::read failed.")?;
+	let header_len = u64::from(u32::from_le_bytes(len));
+	let data_len = data_and_header_len
+		.checked_sub(header_len)
+		.ok_or("snapshot header length exceeds the file")?;
+
+	let mut header = vec![0u8; header_len as usize];
+	file.seek(SeekFrom::Start(data_start + data_len)).map_err(|_| "fs::seek failed.")?;
+	file.read_exact(&mut header).map_err(|_| "fs::read failed.")?;
+	let header: SnapshotHeader<Hash> =
+		Decode::decode(&mut &*header).map_err(|_| "snapshot header decode failed")?;
+
+	let chunk_in_bounds = |chunk: &SnapshotChunk| {
+		chunk.offset.checked_add(chunk.compressed_len).map_or(false, |end| end <= data_len)
+	};
+	if !header.chunks.iter().all(chunk_in_bounds) {
+		return Err("snapshot chunk exceeds the file")
+	}
+
+	file.seek(SeekFrom::Start(data_start)).map_err(|_| "fs::seek failed.")?;
+	Ok(Some(header))
+}
+
+/// Read the header of the snapshot at `path`, without loading any of its state.
+///
+/// Snapshots in the legacy format have no header, and are reported as an error.
+pub fn inspect_snapshot<B: BlockT>(path: &Path) -> Result<SnapshotHeader<B::Hash>, &'static str> {
+	let mut file = fs::File::open(path).map_err(|_| "fs::open failed.")?;
+	read_snapshot_header(&mut file)?.ok_or("snapshot is in the legacy format and has no header")
+}
+
 /// An externalities that acts exactly the same as [`sp_io::TestExternalities`] but has a few extra
 /// bits and pieces to it, and can be loaded remotely.
 pub struct RemoteExternalities<B: BlockT> {
@@ -267,6 +463,8 @@ pub struct Builder<B: BlockT> {
 	///
 	/// Overwrite only with care.
 	overwrite_state_version: Option<StateVersion>,
+	/// The *hashed* prefixes to load from a snapshot. Everything is loaded if empty.
+	snapshot_hashed_prefixes: Vec<Vec<u8>>,
 }
 
 // NOTE: ideally we would use `DefaultNoBound` here, but not worth bringing in frame-support for
@@ -278,6 +476,7 @@ impl<B: BlockT> Default for Builder<B> {
 			hashed_key_values: Default::default(),
 			hashed_blacklist: Default::default(),
 			overwrite_state_version: None,
+			snapshot_hashed_prefixes: Default::default(),
 		}
 	}
 }
@@ -889,14 +1088,14 @@ where
 	///
 	/// Must be called after `init_remote_client`.
 	async fn load_remote_and_maybe_save(&mut self) -> Result<TestExternalities, &'static str> {
-		let state_version =
+		let runtime_version =
 			StateApi::<B::Hash>::runtime_version(self.as_online().rpc_client(), None)
 				.await
 				.map_err(|e| {
 					error!(target: LOG_TARGET, "Error = {:?}", e);
 					"rpc runtime_version failed."
-				})
-				.map(|v| v.state_version())?;
+				})?;
+		let state_version = runtime_version.state_version();
 		let mut pending_ext = TestExternalities::new_with_code_and_state(
 			Default::default(),
 			Default::default(),
@@ -906,32 +1105,88 @@ where
 		let child_kv = self.load_child_remote(&top_kv, &mut pending_ext).await?;
 
 		if let Some(path) = self.as_online().state_snapshot.clone().map(|c| c.path) {
-			let snapshot = Snapshot::<B> {
-				state_version,
-				top: top_kv,
-				child: child_kv,
+			let header = SnapshotHeader {
 				block_hash: self
 					.as_online()
 					.at
 					.expect("set to `Some` in `init_remote_client`; must be called before; qed"),
+				state_version,
+				spec_name: runtime_version.spec_name.to_string(),
+				spec_version: runtime_version.spec_version,
+				pallets: self.as_online().pallets.clone(),
+				chunks: Default::default(),
 			};
-			let encoded = snapshot.encode();
-			log::info!(
-				target: LOG_TARGET,
-				"writing snapshot of {} bytes to {:?}",
-				encoded.len(),
-				path
-			);
-			std::fs::write(path, encoded).map_err(|_| "fs::write failed")?;
+			log::info!(target: LOG_TARGET, "writing snapshot to {:?}", path);
+			let file = fs::File::create(path).map_err(|_| "fs::create failed")?;
+			write_snapshot(BufWriter::new(file), header, top_kv, child_kv)?;
 		}
 
 		Ok(pending_ext)
 	}
 
+	/// Load the snapshot at `path`, only keeping the keys selected by `snapshot_hashed_prefixes`.
+	///
+	/// Legacy snapshots are loaded as a whole and filtered afterwards, whereas the chunks of a
+	/// versioned snapshot that are not selected are never read.
 	fn load_snapshot(&mut self, path: PathBuf) -> Result<Snapshot<B>, &'static str> {
 		info!(target: LOG_TARGET, "loading data from snapshot {:?}", path);
-		let bytes = fs::read(path).map_err(|_| "fs::read failed.")?;
-		Decode::decode(&mut &*bytes).map_err(|_| "decode failed")
+		let mut file = fs::File::open(path).map_err(|_| "fs::open failed.")?;
+		let prefixes = &self.snapshot_hashed_prefixes;
+
+		let header = match read_snapshot_header::<B::Hash>(&mut file)? {
+			Some(header) => header,
+			None => {
+				info!(target: LOG_TARGET, "snapshot has no header, loading it in the legacy format");
+				let mut bytes = Vec::new();
+				file.rewind().map_err(|_| "fs::seek failed.")?;
+				file.read_to_end(&mut bytes).map_err(|_| "fs::read failed.")?;
+				let mut snapshot: Snapshot<B> =
+					Decode::decode(&mut &*bytes).map_err(|_| "decode failed")?;
+				snapshot.top.retain(|(k, _)| is_key_selected(&k.0, prefixes));
+				snapshot
+					.child
+					.retain(|(info, _)| is_key_selected(&info.prefixed_storage_key(), prefixes));
+				return Ok(snapshot)
+			},
+		};
+
+		let data_start = file.stream_position().map_err(|_| "fs::seek failed.")?;
+		let mut snapshot = Snapshot::<B> {
+			state_version: header.state_version,
+			block_hash: header.block_hash,
+			top: Default::default(),
+			child: Default::default(),
+		};
+		for chunk in header.chunks {
+			// a prefix longer than the chunk's one, e.g. of a single storage item, selects the
+			// chunk as well.
+			let chunk_prefix = chunk.kind.prefix();
+			if !is_key_selected(&chunk_prefix, prefixes) &&
+				!prefixes.iter().any(|p| p.starts_with(&chunk_prefix))
+			{
+				continue
+			}
+
+			// bounded by the file size, see `read_snapshot_header`.
+			let mut compressed = vec![0u8; chunk.compressed_len as usize];
+			file.seek(SeekFrom::Start(data_start + chunk.offset))
+				.map_err(|_| "fs::seek failed.")?;
+			file.read_exact(&mut compressed).map_err(|_| "fs::read failed.")?;
+			let raw = zstd::stream::decode_all(&*compressed)
+				.map_err(|_| "snapshot chunk decompression failed")?;
+			let mut kv: Vec<KeyValue> =
+				Decode::decode(&mut &*raw).map_err(|_| "snapshot chunk decode failed")?;
+
+			match chunk.kind {
+				ChunkKind::Top(_) => {
+					kv.retain(|(k, _)| is_key_selected(&k.0, prefixes));
+					snapshot.top.extend(kv);
+				},
+				ChunkKind::Child(info) => snapshot.child.push((info, kv)),
+			}
+		}
+
+		Ok(snapshot)
 	}
 
 	async fn do_load_remote(&mut self) -> Result<RemoteExternalities<B>, &'static str> {
@@ -1050,6 +1305,25 @@ where
 		self
 	}
 
+	/// Only load the given pallets from a snapshot.
+	///
+	/// Well-known keys such as `:code` are always loaded. This has no effect on what is scraped
+	/// from a remote node, see [`OnlineConfig::pallets`] for that.
+	pub fn snapshot_pallets(mut self, pallets: Vec<String>) -> Self {
+		self.snapshot_hashed_prefixes
+			.extend(pallets.iter().map(|p| twox_128(p.as_bytes()).to_vec()));
+		self
+	}
+
+	/// Only load the keys starting with the given *hashed* prefixes from a snapshot.
+	///
+	/// Child tries are selected by their prefixed storage key, thus passing
+	/// [`DEFAULT_CHILD_STORAGE_KEY_PREFIX`] selects all of them.
+	pub fn snapshot_hashed_prefixes(mut self, prefixes: Vec<Vec<u8>>) -> Self {
+		self.snapshot_hashed_prefixes.extend(prefixes);
+		self
+	}
+
 	/// The state version to use.
 	pub fn overwrite_state_version(mut self, version: StateVersion) -> Self {
 		self.overwrite_state_version = Some(version);
@@ -1133,6 +1407,104 @@ mod tests {
 			.expect("Can't read state snapshot file")
 			.execute_with(|| assert!(sp_io::storage::get(&some_key).is_none()));
 	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn can_load_versioned_snapshot_partially() {
+		init_logger();
+		let path = std::env::temp_dir().join("can_load_versioned_snapshot_partially");
+
+		let foo_key = [twox_128(b"Foo").to_vec(), vec![1]].concat();
+		let bar_key = [twox_128(b"Bar").to_vec(), vec![2]].concat();
+		let child_info = ChildInfo::new_default(b"child");
+		let top = vec![
+			(StorageKey(b":code".to_vec()), StorageData(vec![0u8; 1024])),
+			(StorageKey(foo_key.clone()), StorageData(vec![1])),
+			(StorageKey(bar_key.clone()), StorageData(vec![2])),
+		];
+		let child = vec![(child_info.clone(), vec![(StorageKey(vec![3]), StorageData(vec![3]))])];
+		let header = SnapshotHeader {
+			block_hash: Hash::repeat_byte(42),
+			state_version: StateVersion::V1,
+			spec_name: "test".into(),
+			spec_version: 7,
+			pallets: vec![],
+			chunks: vec![],
+		};
+		write_snapshot(fs::File::create(&path).unwrap(), header, top, child).unwrap();
+
+		let header = inspect_snapshot::<Block>(&path).unwrap();
+		assert_eq!(header.block_hash, Hash::repeat_byte(42));
+		assert_eq!(header.spec_version, 7);
+		// top chunks are sorted by prefix, followed by the child tries.
+		let mut top_chunks =
+			vec![b":code".to_vec(), twox_128(b"Bar").to_vec(), twox_128(b"Foo").to_vec()];
+		top_chunks.sort();
+		assert_eq!(
+			header.chunks.iter().map(|c| c.kind.clone()).collect::<Vec<_>>(),
+			top_chunks
+				.into_iter()
+				.map(ChunkKind::Top)
+				.chain(std::iter::once(ChunkKind::Child(child_info.clone())))
+				.collect::<Vec<_>>(),
+		);
+		assert!(inspect_snapshot::<Block>(Path::new("test_data/proxy_test")).is_err());
+
+		let offline =
+			|| Mode::Offline(OfflineConfig { state_snapshot: SnapshotConfig::new(&path) });
+		Builder::<Block>::new().mode(offline()).build().await.unwrap().execute_with(|| {
+			assert!(sp_io::storage::get(b":code").is_some());
+			assert_eq!(sp_io::storage::get(&foo_key), Some(vec![1].into()));
+			assert_eq!(sp_io::storage::get(&bar_key), Some(vec![2].into()));
+			assert!(sp_io::default_child_storage::get(b"child", &[3]).is_some());
+		});
+
+		Builder::<Block>::new()
+			.mode(offline())
+			.snapshot_pallets(vec!["Foo".to_owned()])
+			.build()
+			.await
+			.unwrap()
+			.execute_with(|| {
+				assert!(sp_io::storage::get(b":code").is_some());
+				assert_eq!(sp_io::storage::get(&foo_key), Some(vec![1].into()));
+				assert!(sp_io::storage::get(&bar_key).is_none());
+				assert!(sp_io::default_child_storage::get(b"child", &[3]).is_none());
+			});
+
+		fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn corrupt_snapshot_lengths_are_rejected() {
+		let path = std::env::temp_dir().join("corrupt_snapshot_lengths_are_rejected");
+		let prelude = [&SNAPSHOT_MAGIC[..], &SNAPSHOT_VERSION.to_le_bytes()].concat();
+
+		fs::write(&path, [&prelude[..], &u32::MAX.to_le_bytes()].concat()).unwrap();
+		assert_eq!(
+			inspect_snapshot::<Block>(&path).unwrap_err(),
+			"snapshot header length exceeds the file"
+		);
+
+		let header = SnapshotHeader {
+			block_hash: Hash::repeat_byte(42),
+			state_version: StateVersion::V1,
+			spec_name: "test".into(),
+			spec_version: 7,
+			pallets: vec![],
+			chunks: vec![SnapshotChunk {
+				kind: ChunkKind::Top(vec![]),
+				offset: 0,
+				compressed_len: u64::MAX,
+				keys: 1,
+			}],
+		}
+		.encode();
+		let len = (header.len() as u32).to_le_bytes();
+		fs::write(&path, [&prelude[..], &header, &len].concat()).unwrap();
+		assert_eq!(inspect_snapshot::<Block>(&path).unwrap_err(), "snapshot chunk exceeds the file");
+
+		fs::remove_file(path).unwrap();
+	}
 }
 
 #[cfg(all(test, feature = "remote-test"))]
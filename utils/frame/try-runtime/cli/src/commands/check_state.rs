@@ -0,0 +1,500 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	build_executor, full_extensions, state_machine_call, try_runtime_api_version, SharedParams,
+	State, LOG_TARGET,
+};
+use frame_try_runtime::TryStateSelect;
+use parity_scale_codec::{Codec, Decode, Encode};
+use sc_executor::{sp_wasm_interface::HostFunctions, WasmExecutor};
+use sp_core::{
+	storage::ChildInfo,
+	testing::TaskExecutor,
+	traits::{CallContext, RuntimeCode},
+	Hasher,
+};
+use sp_runtime::{
+	traits::{Block as BlockT, NumberFor},
+	StateVersion, TryRuntimeError,
+};
+use sp_state_machine::{
+	backend::BackendRuntimeCode, Backend, IterArgs, OverlayedChanges, StateMachine,
+	StateMachineStats, StorageIterator, StorageKey, StorageValue, UsageInfo,
+};
+use std::{
+	fmt::Debug,
+	marker::PhantomData,
+	path::PathBuf,
+	str::FromStr,
+	sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+	time::Instant,
+};
+
+/// Configurations of the [`crate::Command::CheckState`].
+#[derive(Debug, Clone, clap::Parser)]
+pub struct CheckStateCmd {
+	/// The state type to use.
+	#[command(subcommand)]
+	pub state: State,
+
+	/// The pallets to check, as per pallet names in `construct_runtime!()` (e.g.
+	/// `Staking,System`).
+	///
+	/// All pallets of the runtime are checked if none are given.
+	#[arg(long, value_delimiter = ',')]
+	pub pallets: Vec<String>,
+
+	/// The number of pallets to check in parallel.
+	///
+	/// Defaults to the number of available cores.
+	#[arg(long)]
+	pub jobs: Option<usize>,
+
+	/// Write the JSON report to the given file instead of stdout.
+	#[arg(long)]
+	pub report: Option<PathBuf>,
+}
+
+/// The outcome of the `try_state` hook of a single pallet.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PalletReport {
+	/// The name of the pallet.
+	pub pallet: String,
+	/// Whether the checks of the pallet passed.
+	pub success: bool,
+	/// The error returned by the checks, if any.
+	pub error: Option<String>,
+	/// Wall-clock time spent in the runtime call, in milliseconds.
+	pub duration_ms: u64,
+	/// Number of reads from the state backend.
+	pub reads: u32,
+	/// Number of storage keys written to the overlay. Should always be zero.
+	pub writes: u32,
+}
+
+/// The report emitted by [`crate::Command::CheckState`].
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CheckStateReport {
+	/// The hash of the block whose state was checked.
+	pub block_hash: String,
+	/// Number of pallets whose checks passed.
+	pub passed: usize,
+	/// Number of pallets whose checks failed.
+	pub failed: usize,
+	/// The per-pallet outcomes, in the order of the runtime.
+	pub pallets: Vec<PalletReport>,
+}
+
+pub(crate) async fn check_state<Block, HostFns>(
+	shared: SharedParams,
+	command: CheckStateCmd,
+) -> sc_cli::Result<()>
+where
+	Block: BlockT + serde::de::DeserializeOwned,
+	Block::Hash: FromStr + serde::de::DeserializeOwned,
+	<Block::Hash as FromStr>::Err: Debug,
+	Block::Header: serde::de::DeserializeOwned,
+	NumberFor<Block>: FromStr,
+	<NumberFor<Block> as FromStr>::Err: Debug,
+	HostFns: HostFunctions,
+{
+	let executor = build_executor::<HostFns>(&shared);
+	let mut ext = command.state.into_ext::<Block, HostFns>(&shared, &executor, None, true).await?;
+
+	let api_version = try_runtime_api_version::<Block, HostFns>(&executor, &mut ext).unwrap_or(0);
+	if api_version < 2 {
+		return Err(format!(
+			"the runtime implements version {} of the TryRuntime api, but check-state needs \
+			 version 2 or later for 'TryRuntime_try_state'",
+			api_version,
+		)
+		.into())
+	}
+
+	let (_, encoded) = state_machine_call::<Block, HostFns>(
+		&ext,
+		&executor,
+		"TryRuntime_try_state_pallets",
+		&[],
+		Default::default(),
+	)?;
+	let available: Vec<Vec<u8>> = Decode::decode(&mut &*encoded)
+		.map_err(|e| format!("failed to decode pallet names: {:?}", e))?;
+	let pallets = select_pallets(available, &command.pallets)?;
+
+	let jobs = command
+		.jobs
+		.or_else(|| std::thread::available_parallelism().ok().map(Into::into))
+		.unwrap_or(1)
+		.clamp(1, pallets.len().max(1));
+	log::info!(target: LOG_TARGET, "checking {} pallets with {} jobs", pallets.len(), jobs);
+
+	// The externalities themselves can't be shared between threads, but their backend can.
+	let backend = &ext.backend;
+	let next = AtomicUsize::new(0);
+	let mut reports = std::thread::scope(|s| {
+		let workers = (0..jobs)
+			.map(|_| {
+				s.spawn(|| {
+					let runtime_code_backend = BackendRuntimeCode::new(backend);
+					let runtime_code = runtime_code_backend
+						.runtime_code()
+						.expect("`:code` is set by `into_ext`; qed");
+					let mut reports = Vec::new();
+					loop {
+						let index = next.fetch_add(1, Ordering::Relaxed);
+						let Some(pallet) = pallets.get(index) else { break };
+						let report = check_pallet(backend, &executor, &runtime_code, pallet);
+						match report.error {
+							None => log::info!(
+								target: LOG_TARGET,
+								"✅ {} passed in {} ms",
+								report.pallet,
+								report.duration_ms,
+							),
+							Some(ref error) => log::error!(
+								target: LOG_TARGET,
+								"❌ {} failed: {}",
+								report.pallet,
+								error,
+							),
+						}
+						reports.push((index, report));
+					}
+					reports
+				})
+			})
+			.collect::<Vec<_>>();
+		workers
+			.into_iter()
+			.flat_map(|worker| worker.join().expect("check-state worker panicked"))
+			.collect::<Vec<_>>()
+	});
+	reports.sort_by_key(|(index, _)| *index);
+
+	let pallets = reports.into_iter().map(|(_, report)| report).collect::<Vec<_>>();
+	let failed = pallets.iter().filter(|report| !report.success).count();
+	let report = CheckStateReport {
+		block_hash: format!("{:?}", ext.block_hash),
+		passed: pallets.len() - failed,
+		failed,
+		pallets,
+	};
+
+	let json = serde_json::to_string_pretty(&report)
+		.map_err(|e| format!("failed to serialize the report: {:?}", e))?;
+	match command.report {
+		Some(path) => std::fs::write(&path, json)
+			.map_err(|e| format!("failed to write the report to {:?}: {:?}", path, e))?,
+		None => println!("{}", json),
+	}
+
+	if failed > 0 {
+		return Err(format!("{} of {} pallets failed try-state", failed, report.pallets.len()).into())
+	}
+	Ok(())
+}
+
+/// The pallets of `available` named in `requested`, or all of them if none are requested.
+///
+/// Fails if any of the `requested` pallets is not part of the runtime.
+fn select_pallets(available: Vec<Vec<u8>>, requested: &[String]) -> Result<Vec<Vec<u8>>, String> {
+	if requested.is_empty() {
+		return Ok(available)
+	}
+
+	let unknown = requested
+		.iter()
+		.filter(|pallet| !available.iter().any(|p| p == pallet.as_bytes()))
+		.map(String::as_str)
+		.collect::<Vec<_>>();
+	if !unknown.is_empty() {
+		let available =
+			available.iter().map(|p| String::from_utf8_lossy(p)).collect::<Vec<_>>().join(", ");
+		return Err(format!(
+			"unknown pallets: {}. The runtime has the following pallets: {}",
+			unknown.join(", "),
+			available,
+		))
+	}
+	Ok(requested.iter().map(|p| p.as_bytes().to_vec()).collect())
+}
+
+/// Run the `try_state` hook of `pallet` on top of `backend`.
+fn check_pallet<H, B, HostFns>(
+	backend: &B,
+	executor: &WasmExecutor<HostFns>,
+	runtime_code: &RuntimeCode,
+	pallet: &[u8],
+) -> PalletReport
+where
+	H: Hasher,
+	H::Out: Codec + Ord + 'static,
+	B: Backend<H>,
+	HostFns: HostFunctions,
+{
+	let backend = CountingBackend::new(backend);
+	let mut changes = OverlayedChanges::default();
+	let call_data = TryStateSelect::Only(vec![pallet.to_vec()]).encode();
+
+	let started = Instant::now();
+	let result = StateMachine::new(
+		&backend,
+		&mut changes,
+		executor,
+		"TryRuntime_try_state",
+		&call_data,
+		full_extensions(),
+		runtime_code,
+		TaskExecutor::new(),
+		CallContext::Offchain,
+	)
+	.execute(sp_state_machine::ExecutionStrategy::AlwaysWasm);
+	let duration_ms = started.elapsed().as_millis() as u64;
+
+	let error = match result {
+		Ok(encoded) => match <Result<(), TryRuntimeError>>::decode(&mut &*encoded) {
+			Ok(Ok(())) => None,
+			Ok(Err(e)) => Some(e.to_string()),
+			Err(e) => Some(format!("failed to decode the result: {:?}", e)),
+		},
+		Err(e) => Some(format!("failed to execute 'TryRuntime_try_state': {}", e)),
+	};
+	let writes = changes.changes().count() +
+		changes.children().map(|(changes, _)| changes.count()).sum::<usize>();
+
+	PalletReport {
+		pallet: String::from_utf8_lossy(pallet).into_owned(),
+		success: error.is_none(),
+		error,
+		duration_ms,
+		reads: backend.reads.load(Ordering::Relaxed),
+		writes: writes as u32,
+	}
+}
+
+/// A [`Backend`] that counts the reads going through it.
+struct CountingBackend<'a, H, B> {
+	inner: &'a B,
+	reads: AtomicU32,
+	_phantom: PhantomData<H>,
+}
+
+impl<'a, H, B> Debug for CountingBackend<'a, H, B> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("CountingBackend").field("reads", &self.reads).finish()
+	}
+}
+
+impl<'a, H, B> CountingBackend<'a, H, B> {
+	fn new(inner: &'a B) -> Self {
+		Self { inner, reads: AtomicU32::new(0), _phantom: PhantomData }
+	}
+
+	fn tally_read(&self) {
+		self.reads.fetch_add(1, Ordering::Relaxed);
+	}
+}
+
+/// The raw iterator of [`CountingBackend`], counting every step as a read.
+struct CountingIter<'a, H, B: Backend<H>>
+where
+	H: Hasher,
+{
+	inner: B::RawIter,
+	_phantom: PhantomData<&'a B>,
+}
+
+impl<'a, H: Hasher, B: Backend<H>> StorageIterator<H> for CountingIter<'a, H, B> {
+	type Backend = CountingBackend<'a, H, B>;
+	type Error = B::Error;
+
+	fn next_key(&mut self, backend: &Self::Backend) -> Option<Result<StorageKey, Self::Error>> {
+		backend.tally_read();
+		self.inner.next_key(backend.inner)
+	}
+
+	fn next_pair(
+		&mut self,
+		backend: &Self::Backend,
+	) -> Option<Result<(StorageKey, StorageValue), Self::Error>> {
+		backend.tally_read();
+		self.inner.next_pair(backend.inner)
+	}
+
+	fn was_complete(&self) -> bool {
+		self.inner.was_complete()
+	}
+}
+
+impl<'a, H, B> Backend<H> for CountingBackend<'a, H, B>
+where
+	H: Hasher,
+	B: Backend<H>,
+{
+	type Error = B::Error;
+	type Transaction = B::Transaction;
+	type TrieBackendStorage = B::TrieBackendStorage;
+	type RawIter = CountingIter<'a, H, B>;
+
+	fn storage(&self, key: &[u8]) -> Result<Option<StorageValue>, Self::Error> {
+		self.tally_read();
+		self.inner.storage(key)
+	}
+
+	fn storage_hash(&self, key: &[u8]) -> Result<Option<H::Out>, Self::Error> {
+		self.tally_read();
+		self.inner.storage_hash(key)
+	}
+
+	fn child_storage(
+		&self,
+		child_info: &ChildInfo,
+		key: &[u8],
+	) -> Result<Option<StorageValue>, Self::Error> {
+		self.tally_read();
+		self.inner.child_storage(child_info, key)
+	}
+
+	fn child_storage_hash(
+		&self,
+		child_info: &ChildInfo,
+		key: &[u8],
+	) -> Result<Option<H::Out>, Self::Error> {
+		self.tally_read();
+		self.inner.child_storage_hash(child_info, key)
+	}
+
+	fn next_storage_key(&self, key: &[u8]) -> Result<Option<StorageKey>, Self::Error> {
+		self.tally_read();
+		self.inner.next_storage_key(key)
+	}
+
+	fn next_child_storage_key(
+		&self,
+		child_info: &ChildInfo,
+		key: &[u8],
+	) -> Result<Option<StorageKey>, Self::Error> {
+		self.tally_read();
+		self.inner.next_child_storage_key(child_info, key)
+	}
+
+	fn storage_root<'b>(
+		&self,
+		delta: impl Iterator<Item = (&'b [u8], Option<&'b [u8]>)>,
+		state_version: StateVersion,
+	) -> (H::Out, Self::Transaction)
+	where
+		H::Out: Ord,
+	{
+		self.inner.storage_root(delta, state_version)
+	}
+
+	fn child_storage_root<'b>(
+		&self,
+		child_info: &ChildInfo,
+		delta: impl Iterator<Item = (&'b [u8], Option<&'b [u8]>)>,
+		state_version: StateVersion,
+	) -> (H::Out, bool, Self::Transaction)
+	where
+		H::Out: Ord,
+	{
+		self.inner.child_storage_root(child_info, delta, state_version)
+	}
+
+	fn raw_iter(&self, args: IterArgs) -> Result<Self::RawIter, Self::Error> {
+		Ok(CountingIter { inner: self.inner.raw_iter(args)?, _phantom: PhantomData })
+	}
+
+	fn register_overlay_stats(&self, stats: &StateMachineStats) {
+		self.inner.register_overlay_stats(stats)
+	}
+
+	fn usage_info(&self) -> UsageInfo {
+		self.inner.usage_info()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{storage::Storage, Blake2Hasher};
+	use sp_state_machine::InMemoryBackend;
+
+	fn names(names: &[&str]) -> Vec<Vec<u8>> {
+		names.iter().map(|n| n.as_bytes().to_vec()).collect()
+	}
+
+	#[test]
+	fn select_pallets_defaults_to_all() {
+		let available = names(&["System", "Balances"]);
+		assert_eq!(select_pallets(available.clone(), &[]), Ok(available));
+	}
+
+	#[test]
+	fn select_pallets_keeps_requested_order() {
+		let requested = vec!["Staking".to_string(), "System".to_string()];
+		assert_eq!(
+			select_pallets(names(&["System", "Balances", "Staking"]), &requested),
+			Ok(names(&["Staking", "System"]))
+		);
+	}
+
+	#[test]
+	fn select_pallets_rejects_unknown_pallets() {
+		let requested = vec!["System".to_string(), "Stacking".to_string()];
+		assert_eq!(
+			select_pallets(names(&["System", "Staking"]), &requested),
+			Err("unknown pallets: Stacking. The runtime has the following pallets: System, Staking"
+				.to_string())
+		);
+	}
+
+	#[test]
+	fn counting_backend_counts_every_read() {
+		let child_info = ChildInfo::new_default(b"child");
+		let storage = Storage {
+			top: vec![(b"a".to_vec(), b"1".to_vec()), (b"b".to_vec(), b"2".to_vec())]
+				.into_iter()
+				.collect(),
+			children_default: vec![(
+				child_info.storage_key().to_vec(),
+				sp_core::storage::StorageChild {
+					data: vec![(b"c".to_vec(), b"3".to_vec())].into_iter().collect(),
+					child_info: child_info.clone(),
+				},
+			)]
+			.into_iter()
+			.collect(),
+		};
+		let inner = InMemoryBackend::<Blake2Hasher>::from((storage, StateVersion::V1));
+		let backend = CountingBackend::new(&inner);
+
+		assert_eq!(backend.storage(b"a").unwrap(), Some(b"1".to_vec()));
+		assert!(backend.storage_hash(b"b").unwrap().is_some());
+		assert_eq!(backend.child_storage(&child_info, b"c").unwrap(), Some(b"3".to_vec()));
+		assert_eq!(backend.next_storage_key(b"a").unwrap(), Some(b"b".to_vec()));
+		assert_eq!(backend.reads.load(Ordering::Relaxed), 4);
+
+		let mut args = IterArgs::default();
+		args.prefix = Some(b"a");
+		assert_eq!(backend.keys(args).unwrap().count(), 1);
+		// One read per key, plus the one that finds the end of the prefix.
+		assert_eq!(backend.reads.load(Ordering::Relaxed), 6);
+	}
+}
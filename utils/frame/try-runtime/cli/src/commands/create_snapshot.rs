@@ -16,22 +16,31 @@
 // limitations under the License.
 
 use crate::{build_executor, LiveState, SharedParams, State, LOG_TARGET};
+use remote_externalities::{inspect_snapshot, ChunkKind};
 use sc_executor::sp_wasm_interface::HostFunctions;
+use sp_core::hexdisplay::HexDisplay;
 use sp_runtime::traits::{Block as BlockT, NumberFor};
-use std::{fmt::Debug, str::FromStr};
+use std::{fmt::Debug, path::Path, str::FromStr};
 use substrate_rpc_client::{ws_client, StateApi};
 
 /// Configurations of the [`crate::Command::CreateSnapshot`].
 #[derive(Debug, Clone, clap::Parser)]
+#[command(mut_arg("uri", |arg| arg.required(false).required_unless_present("inspect")))]
 pub struct CreateSnapshotCmd {
 	/// The source of the snapshot. Must be a remote node.
+	///
+	/// Not needed with `--inspect`.
 	#[clap(flatten)]
-	pub from: LiveState,
+	pub from: Option<LiveState>,
 
 	/// The snapshot path to write to.
 	///
 	/// If not provided `<spec-name>-<spec-version>@<block-hash>.snap` will be used.
 	pub snapshot_path: Option<String>,
+
+	/// Instead of creating a snapshot, list the contents of the existing one at `snapshot_path`.
+	#[arg(long, requires = "snapshot_path")]
+	pub inspect: bool,
 }
 
 /// inner command for `Command::CreateSnapshot`.
@@ -49,6 +58,13 @@ where
 	HostFns: HostFunctions,
 {
 	let snapshot_path = command.snapshot_path;
+	let from = match command.from {
+		Some(from) if !command.inspect => from,
+		_ => {
+			let path = snapshot_path.expect("`--inspect` requires a snapshot path; qed");
+			return inspect::<Block>(Path::new(&path))
+		},
+	};
 	if !matches!(shared.runtime, crate::Runtime::Existing) {
 		return Err("creating a snapshot is only possible with --runtime existing.".into())
 	}
@@ -56,13 +72,13 @@ where
 	let path = match snapshot_path {
 		Some(path) => path,
 		None => {
-			let rpc = ws_client(&command.from.uri).await.unwrap();
+			let rpc = ws_client(&from.uri).await.unwrap();
 			let remote_spec = StateApi::<Block::Hash>::runtime_version(&rpc, None).await.unwrap();
 			let path_str = format!(
 				"{}-{}@{}.snap",
 				remote_spec.spec_name.to_lowercase(),
 				remote_spec.spec_version,
-				command.from.at.clone().unwrap_or("latest".to_owned())
+				from.at.clone().unwrap_or("latest".to_owned())
 			);
 			log::info!(target: LOG_TARGET, "snapshot path not provided (-s), using '{}'", path_str);
 			path_str.into()
@@ -70,9 +86,37 @@ where
 	};
 
 	let executor = build_executor::<HostFns>(&shared);
-	let _ = State::Live(command.from)
+	let _ = State::Live(from)
 		.into_ext::<Block, HostFns>(&shared, &executor, Some(path.into()), false)
 		.await?;
 
 	Ok(())
 }
+
+/// Print the header of the snapshot at `path`.
+fn inspect<Block: BlockT>(path: &Path) -> sc_cli::Result<()> {
+	let header = inspect_snapshot::<Block>(path)?;
+	println!("block hash:    {:?}", header.block_hash);
+	println!("state version: {:?}", header.state_version);
+	println!("runtime:       {}-{}", header.spec_name, header.spec_version);
+	if header.pallets.is_empty() {
+		println!("pallets:       all");
+	} else {
+		println!("pallets:       {}", header.pallets.join(", "));
+	}
+	println!("{} chunks:", header.chunks.len());
+	for chunk in header.chunks {
+		let kind = match chunk.kind {
+			ChunkKind::Top(_) => "top",
+			ChunkKind::Child(_) => "child",
+		};
+		println!(
+			"  {:<5} 0x{:<64} {:>10} keys {:>12} bytes",
+			kind,
+			HexDisplay::from(&chunk.kind.prefix()),
+			chunk.keys,
+			chunk.compressed_len,
+		);
+	}
+	Ok(())
+}
@@ -148,7 +148,7 @@ where
 			state_ext,
 			&executor,
 			"TryRuntime_execute_block",
-			(block, command.state_root_check, command.try_state.clone()).encode().as_ref(),
+			(block, command.state_root_check, false, command.try_state.clone()).encode().as_ref(),
 			full_extensions(),
 			shared
 				.export_proof
@@ -159,7 +159,7 @@ where
 		if let Err(why) = result {
 			log::error!(
 				target: LOG_TARGET,
-				"failed to execute block {:?} due to {:?}",
+				"failed to execute block {:?} due to {}",
 				number,
 				why
 			);
@@ -15,9 +15,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod check_state;
 pub mod create_snapshot;
 pub mod execute_block;
 pub mod fast_forward;
 pub mod follow_chain;
 pub mod offchain_worker;
 pub mod on_runtime_upgrade;
+pub mod replay;
@@ -0,0 +1,364 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{build_executor, full_extensions, Runtime, SharedParams, LOG_TARGET};
+use parity_scale_codec::{Decode, Encode};
+use sc_client_api::{blockchain::Backend as _, Backend as _, HeaderBackend};
+use sc_client_db::{BlocksPruning, DatabaseSettings, DatabaseSource};
+use sc_executor::{sp_wasm_interface::HostFunctions, WasmExecutor};
+use sp_core::{
+	hashing::blake2_256,
+	testing::TaskExecutor,
+	traits::{CallContext, RuntimeCode, WrappedRuntimeCode},
+};
+use sp_runtime::{
+	traits::{Block as BlockT, HashFor, Header as HeaderT, SaturatedConversion},
+	StateVersion,
+};
+use sp_state_machine::{Backend, OverlayedChanges, StateMachine, StorageTransactionCache};
+use sp_weights::{constants::WEIGHT_REF_TIME_PER_MILLIS, Weight};
+use std::{fmt::Debug, path::PathBuf, time::Instant};
+
+/// The RocksDB cache size in MiB used to open the database.
+const DATABASE_CACHE_SIZE: usize = 128;
+
+/// Configurations of the [`crate::Command::Replay`].
+///
+/// This will always call into `TryRuntime_execute_block`, with state-root and signature checks
+/// disabled, on top of the state of the parent of each block, as found in the database.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ReplayCmd {
+	/// The path to the database of a node, e.g. `<base-path>/chains/<chain>/db/full` or
+	/// `<base-path>/chains/<chain>/paritydb/full`.
+	///
+	/// The database is opened read-only and can thus be in use by a running node. It must still
+	/// hold the state of the parent of every replayed block, e.g. be an archive node.
+	#[arg(long)]
+	pub database_path: PathBuf,
+
+	/// The number of the first block to replay.
+	#[arg(long)]
+	pub from: u64,
+
+	/// The number of the last block to replay, inclusive. Defaults to `from`.
+	#[arg(long)]
+	pub to: Option<u64>,
+
+	/// Which try-state targets to execute after each block.
+	///
+	/// Expected values:
+	/// - `all`
+	/// - `none`
+	/// - A comma separated list of pallets, as per pallet names in `construct_runtime!()` (e.g.
+	///   `Staking, System`).
+	/// - `rr-[x]` where `[x]` is a number. Then, the given number of pallets are checked in a
+	///   round-robin fashion.
+	#[arg(long, default_value = "none")]
+	pub try_state: frame_try_runtime::TryStateSelect,
+
+	/// Write a JSON report of every replayed block to the given file.
+	#[arg(long)]
+	pub report: Option<PathBuf>,
+}
+
+/// The outcome of replaying a single block.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BlockReport {
+	/// The number of the block.
+	pub number: u64,
+	/// The hash of the block.
+	pub hash: String,
+	/// The error that occurred while executing the block, if any.
+	pub error: Option<String>,
+	/// The `ref_time` of the weight consumed by the block.
+	pub ref_time: u64,
+	/// The `proof_size` of the weight consumed by the block.
+	pub proof_size: u64,
+	/// The weight consumed by the block, converted to milliseconds.
+	pub weight_ms: f64,
+	/// Wall-clock time spent executing the block, in milliseconds.
+	pub duration_ms: f64,
+	/// The state root in the header of the block.
+	pub expected_state_root: String,
+	/// The state root after executing the block with the given runtime.
+	pub state_root: String,
+	/// Whether `state_root` differs from `expected_state_root`.
+	pub diverged: bool,
+}
+
+pub(crate) async fn replay<Block, HostFns>(
+	shared: SharedParams,
+	command: ReplayCmd,
+) -> sc_cli::Result<()>
+where
+	Block: BlockT,
+	HostFns: HostFunctions,
+{
+	let executor = build_executor::<HostFns>(&shared);
+	let backend = sc_client_db::Backend::<Block>::new_read_only(DatabaseSettings {
+		trie_cache_maximum_size: None,
+		state_pruning: None,
+		source: DatabaseSource::Auto {
+			paritydb_path: command.database_path.clone(),
+			rocksdb_path: command.database_path.clone(),
+			cache_size: DATABASE_CACHE_SIZE,
+		},
+		blocks_pruning: BlocksPruning::KeepAll,
+	})?;
+
+	let new_code = match shared.runtime {
+		Runtime::Path(ref path) =>
+			Some(std::fs::read(path).map_err(|e| {
+				format!("error while reading runtime file from {:?}: {:?}", path, e)
+			})?),
+		Runtime::Existing => None,
+	};
+
+	let to = command.to.unwrap_or(command.from);
+	// the state version of the last runtime seen, by its code hash.
+	let mut state_version: Option<(Vec<u8>, StateVersion)> = None;
+	let mut reports = Vec::new();
+	for number in command.from..=to {
+		let hash = backend
+			.blockchain()
+			.hash(number.saturated_into())?
+			.ok_or_else(|| format!("block #{} not found in the database", number))?;
+		let header = backend
+			.blockchain()
+			.header(hash)?
+			.ok_or_else(|| format!("header of block #{} not found in the database", number))?;
+		let extrinsics = backend
+			.blockchain()
+			.body(hash)?
+			.ok_or_else(|| format!("body of block #{} not found in the database", number))?;
+		let state = backend.state_at(*header.parent_hash()).map_err(|e| {
+			format!("state of the parent of block #{} is not available: {}", number, e)
+		})?;
+
+		let code = match new_code {
+			Some(ref code) => code.clone(),
+			None => state
+				.storage(sp_core::storage::well_known_keys::CODE)?
+				.ok_or_else(|| format!("no `:code` in the parent state of block #{}", number))?,
+		};
+		let code_fetcher = WrappedRuntimeCode(code.as_slice().into());
+		let runtime_code = RuntimeCode {
+			code_fetcher: &code_fetcher,
+			heap_pages: None,
+			hash: blake2_256(&code).to_vec(),
+		};
+
+		// the runtime, and thus its state version, may change with every runtime upgrade.
+		let state_version = match state_version {
+			Some((ref code_hash, state_version)) if *code_hash == runtime_code.hash =>
+				state_version,
+			_ => {
+				let version =
+					runtime_state_version::<Block, _, HostFns>(&state, &executor, &runtime_code)?;
+				state_version = Some((runtime_code.hash.clone(), version));
+				version
+			},
+		};
+
+		// A digest item gets added when the runtime is processing the block, so we need to pop
+		// the last one to be consistent with what a gossiped block would contain.
+		let expected_state_root = *header.state_root();
+		let mut header = header;
+		header.digest_mut().pop();
+		let block = Block::new(header, extrinsics);
+
+		// for now, hardcoded for the sake of simplicity. We might customize them one day.
+		let state_root_check = false;
+		let signature_check = false;
+		let payload =
+			(block, state_root_check, signature_check, command.try_state.clone()).encode();
+
+		let started = Instant::now();
+		let result = call::<Block, _, HostFns>(
+			&state,
+			&executor,
+			&runtime_code,
+			"TryRuntime_execute_block",
+			&payload,
+		);
+		let duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+		let (weight, state_root, error) = match result {
+			Ok((changes, encoded)) => {
+				let state_root = changes.storage_root(
+					&state,
+					&mut StorageTransactionCache::default(),
+					state_version,
+				);
+				match Weight::decode(&mut &*encoded) {
+					Ok(weight) => (weight, state_root, None),
+					Err(e) => (
+						Weight::zero(),
+						state_root,
+						Some(format!("failed to decode the weight: {:?}", e)),
+					),
+				}
+			},
+			Err(e) => (Weight::zero(), Default::default(), Some(e.to_string())),
+		};
+
+		let report = BlockReport {
+			number,
+			hash: format!("{:?}", hash),
+			ref_time: weight.ref_time(),
+			proof_size: weight.proof_size(),
+			weight_ms: weight.ref_time() as f64 / WEIGHT_REF_TIME_PER_MILLIS as f64,
+			duration_ms,
+			expected_state_root: format!("{:?}", expected_state_root),
+			state_root: format!("{:?}", state_root),
+			diverged: error.is_none() && state_root != expected_state_root,
+			error,
+		};
+		match report.error {
+			Some(ref error) =>
+				log::error!(target: LOG_TARGET, "❌ block #{} failed: {}", number, error),
+			None => log::info!(
+				target: LOG_TARGET,
+				"{} block #{}: weight {:.2} ms, actual {:.2} ms, state root {}",
+				if report.diverged { "⚠️" } else { "✅" },
+				number,
+				report.weight_ms,
+				report.duration_ms,
+				if report.diverged {
+					format!("diverged ({} != {})", report.state_root, report.expected_state_root)
+				} else {
+					"matches".to_string()
+				},
+			),
+		}
+		reports.push(report);
+	}
+
+	if let Some(path) = command.report {
+		let json = serde_json::to_string_pretty(&reports)
+			.map_err(|e| format!("failed to serialize the report: {:?}", e))?;
+		std::fs::write(&path, json)
+			.map_err(|e| format!("failed to write the report to {:?}: {:?}", path, e))?;
+	}
+
+	let failed = reports.iter().filter(|r| r.error.is_some()).count();
+	let diverged = reports.iter().filter(|r| r.diverged).count();
+	log::info!(
+		target: LOG_TARGET,
+		"replayed {} blocks: {} failed, {} diverged from the on-chain state root",
+		reports.len(),
+		failed,
+		diverged,
+	);
+
+	if failed > 0 {
+		return Err(format!("{} of {} blocks failed to execute", failed, reports.len()).into())
+	}
+	Ok(())
+}
+
+/// The state version of the given runtime, as per its `Core_version`.
+fn runtime_state_version<Block: BlockT, S: Backend<HashFor<Block>>, HostFns: HostFunctions>(
+	state: &S,
+	executor: &WasmExecutor<HostFns>,
+	runtime_code: &RuntimeCode,
+) -> sc_cli::Result<StateVersion> {
+	let (_, version) =
+		call::<Block, _, HostFns>(state, executor, runtime_code, "Core_version", &[])?;
+	let version = sp_version::RuntimeVersion::decode(&mut &*version)
+		.map_err(|e| format!("failed to decode runtime version: {:?}", e))?;
+	Ok(version.state_version())
+}
+
+/// Call `method` of the given runtime on top of `state`.
+fn call<Block: BlockT, S: Backend<HashFor<Block>>, HostFns: HostFunctions>(
+	state: &S,
+	executor: &WasmExecutor<HostFns>,
+	runtime_code: &RuntimeCode,
+	method: &'static str,
+	data: &[u8],
+) -> sc_cli::Result<(OverlayedChanges, Vec<u8>)> {
+	let mut changes = Default::default();
+	let encoded_results = StateMachine::new(
+		state,
+		&mut changes,
+		executor,
+		method,
+		data,
+		full_extensions(),
+		runtime_code,
+		TaskExecutor::new(),
+		CallContext::Offchain,
+	)
+	.execute(sp_state_machine::ExecutionStrategy::AlwaysWasm)
+	.map_err(|e| format!("failed to execute '{}': {}", method, e))?;
+
+	Ok((changes, encoded_results))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+	use sp_runtime::testing::{Block as RawBlock, ExtrinsicWrapper};
+
+	type Block = RawBlock<ExtrinsicWrapper<u64>>;
+	type HostFns = sp_io::SubstrateHostFunctions;
+
+	fn shared() -> SharedParams {
+		SharedParams::parse_from(["try-runtime", "--runtime", "existing"])
+	}
+
+	fn replay_cmd(database_path: PathBuf) -> ReplayCmd {
+		ReplayCmd {
+			database_path,
+			from: 1,
+			to: None,
+			try_state: frame_try_runtime::TryStateSelect::None,
+			report: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn replay_does_not_create_a_missing_database() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("db");
+
+		assert!(replay::<Block, HostFns>(shared(), replay_cmd(path.clone())).await.is_err());
+		assert!(!path.exists());
+	}
+
+	#[tokio::test]
+	async fn replay_reports_missing_blocks() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("paritydb");
+		// an empty database, as created by a node.
+		sc_client_db::Backend::<Block>::new(
+			DatabaseSettings {
+				trie_cache_maximum_size: None,
+				state_pruning: None,
+				source: DatabaseSource::ParityDb { path: path.clone() },
+				blocks_pruning: BlocksPruning::KeepAll,
+			},
+			0,
+		)
+		.unwrap();
+
+		let error = replay::<Block, HostFns>(shared(), replay_cmd(path)).await.unwrap_err();
+		assert_eq!(error.to_string(), "Invalid input: block #1 not found in the database");
+	}
+}
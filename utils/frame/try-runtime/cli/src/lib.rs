@@ -82,6 +82,9 @@
 //! * [`Command::FollowChain`]: continuously execute the blocks of a remote chain on top of a given
 //!   runtime.
 //! * [`Command::CreateSnapshot`]: Create a snapshot file from a remote node.
+//! * [`Command::CheckState`]: run the `try_state` hooks of all pallets independently and report the
+//!   outcome of each.
+//! * [`Command::Replay`]: re-execute a range of blocks read from the database of a local node.
 //!
 //! Finally, To make sure there are no errors regarding this, always run any `try-runtime` command
 //! with `executor=trace` logging targets, which will specify which runtime is being used per api
@@ -134,10 +137,10 @@
 //!
 //! ```ignore
 //! #[cfg(feature = "try-runtime")]
-//! fn pre_upgrade() -> Result<Vec<u8>, &'static str> {}
+//! fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {}
 //!
 //! #[cfg(feature = "try-runtime")]
-//! fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {}
+//! fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {}
 //! ```
 //!
 //! (The pallet macro syntax will support this simply as a part of `#[pallet::hooks]`).
@@ -153,7 +156,7 @@
 //!
 //! ```ignore
 //! #[cfg(feature = "try-runtime")]
-//! fn try_state(_: BlockNumber) -> Result<(), &'static str> {}
+//! fn try_state(_: BlockNumber) -> Result<(), sp_runtime::TryRuntimeError> {}
 //! ```
 //!
 //! which is called on numerous code paths in the try-runtime tool. These checks should ensure that
@@ -314,6 +317,11 @@
 //!     snap -s node-268@latest.snap
 //! ```
 //!
+//! The snapshot is stored in zstd compressed chunks, one per pallet and child trie. Its header
+//! (block hash, runtime version and chunk sizes) can be listed with
+//! `create-snapshot --inspect node-268@latest.snap`, and a subset of the pallets can be loaded
+//! with `snap -s node-268@latest.snap --pallet Staking --pallet System`.
+//!
 //! * Execute the latest finalized block with the given runtime.
 //!
 //! ```bash
@@ -457,8 +465,32 @@ pub enum Command {
 	///    extrinsics; both things should be defined in your node CLI handling level
 	FastForward(commands::fast_forward::FastForwardCmd),
 
-	/// Create a new snapshot file.
+	/// Create a new snapshot file, or inspect an existing one.
 	CreateSnapshot(commands::create_snapshot::CreateSnapshotCmd),
+
+	/// Run the `try_state` hooks of every pallet against some state.
+	///
+	/// This uses a custom runtime api call, namely "TryRuntime_try_state", once per pallet. Unlike
+	/// the `try-state` checks of the other commands, a failing pallet does not stop the others
+	/// from being checked. A JSON report of the outcome, runtime and storage accesses of each
+	/// pallet is emitted.
+	///
+	/// See [`frame_try_runtime::TryRuntime`] and [`commands::check_state::CheckStateCmd`] for more
+	/// information.
+	CheckState(commands::check_state::CheckStateCmd),
+
+	/// Re-execute a range of blocks from the database of a local node.
+	///
+	/// This uses a custom runtime api call, namely "TryRuntime_execute_block", on top of the state
+	/// of the parent of each block, as found in the database. No RPC connection is needed, and the
+	/// database is never written to.
+	///
+	/// For each block, the consumed weight is reported next to the actual execution time, along
+	/// with whether the resulting state root diverges from the one in the block header.
+	///
+	/// See [`frame_try_runtime::TryRuntime`] and [`commands::replay::ReplayCmd`] for more
+	/// information.
+	Replay(commands::replay::ReplayCmd),
 }
 
 #[derive(Debug, Clone)]
@@ -602,6 +634,13 @@ pub enum State {
 	Snap {
 		#[arg(short, long)]
 		snapshot_path: PathBuf,
+
+		/// A pallet to load from the snapshot. Can be provided multiple times. If empty, the
+		/// entire snapshot is loaded.
+		///
+		/// Well-known keys such as `:code` are always loaded.
+		#[arg(short, long, num_args = 1..)]
+		pallet: Vec<String>,
 	},
 
 	/// Use a live chain as the source of runtime state.
@@ -628,10 +667,11 @@ impl State {
 		<Block::Hash as FromStr>::Err: Debug,
 	{
 		let builder = match self {
-			State::Snap { snapshot_path } =>
-				Builder::<Block>::new().mode(Mode::Offline(OfflineConfig {
+			State::Snap { snapshot_path, pallet } => Builder::<Block>::new()
+				.mode(Mode::Offline(OfflineConfig {
 					state_snapshot: SnapshotConfig::new(snapshot_path),
-				})),
+				}))
+				.snapshot_pallets(pallet.clone()),
 			State::Live(LiveState { pallet, uri, at, child_tree }) => {
 				let at = match at {
 					Some(at_str) => Some(hash_of::<Block>(at_str)?),
@@ -782,6 +822,14 @@ impl TryRuntimeCmd {
 					cmd.clone(),
 				)
 				.await,
+			Command::CheckState(cmd) =>
+				commands::check_state::check_state::<Block, HostFns>(
+					self.shared.clone(),
+					cmd.clone(),
+				)
+				.await,
+			Command::Replay(cmd) =>
+				commands::replay::replay::<Block, HostFns>(self.shared.clone(), cmd.clone()).await,
 		}
 	}
 }
@@ -844,6 +892,15 @@ fn ensure_try_runtime<Block: BlockT, HostFns: HostFunctions>(
 	executor: &WasmExecutor<HostFns>,
 	ext: &mut TestExternalities,
 ) -> bool {
+	try_runtime_api_version::<Block, HostFns>(executor, ext).is_some()
+}
+
+/// The version of the `TryRuntime` api of the runtime in `ext`, `None` if it is not compiled with
+/// `try-runtime`.
+pub(crate) fn try_runtime_api_version<Block: BlockT, HostFns: HostFunctions>(
+	executor: &WasmExecutor<HostFns>,
+	ext: &mut TestExternalities,
+) -> Option<u32> {
 	use sp_api::RuntimeApiInfo;
 	let final_code = ext
 		.execute_with(|| sp_io::storage::get(well_known_keys::CODE))
@@ -852,9 +909,7 @@ fn ensure_try_runtime<Block: BlockT, HostFns: HostFunctions>(
 		&mut &*executor.read_runtime_version(&final_code, &mut ext.ext()).unwrap(),
 	)
 	.unwrap();
-	final_version
-		.api_version(&<dyn frame_try_runtime::TryRuntime<Block>>::ID)
-		.is_some()
+	final_version.api_version(&<dyn frame_try_runtime::TryRuntime<Block>>::ID)
 }
 
 /// Execute the given `method` and `data` on top of `ext`, returning the results (encoded) and the